@@ -0,0 +1,604 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::decimal::Decimal;
+use crate::types::BongoError;
+
+pub const MICROS_PER_SECOND: i64 = 1_000_000;
+pub const MICROS_PER_DAY: i64 = 24 * 60 * 60 * MICROS_PER_SECOND;
+
+///
+/// Returns the amount of days between 1970-01-01 and the given date of the proleptic Gregorian
+/// calendar.
+///
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    // years start in March, so that the leap day is the last day of a year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+///
+/// Returns the year, month and day of the date that is `days` days after 1970-01-01.
+///
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+///
+/// Parses a number of at most `max_digits` digits.
+///
+fn parse_digits(s: &str, max_digits: usize) -> Option<u32> {
+    match !s.is_empty() && s.len() <= max_digits && s.chars().all(|c| c.is_ascii_digit()) {
+        true => s.parse().ok(),
+        false => None,
+    }
+}
+
+///
+/// Formats a time of day, omitting trailing zeros of the fraction of a second.
+///
+fn fmt_time_of_day(f: &mut fmt::Formatter<'_>, micros: i64) -> fmt::Result {
+    let seconds = micros / MICROS_PER_SECOND;
+    write!(f, "{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)?;
+    match micros % MICROS_PER_SECOND {
+        0 => Ok(()),
+        fraction => write!(f, ".{}", format!("{:06}", fraction).trim_end_matches('0')),
+    }
+}
+
+///
+/// `Date` is a day of the proleptic Gregorian calendar between the years 1 and 9999.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    // days since 1970-01-01
+    days: i32,
+}
+
+impl Date {
+    ///
+    /// Creates a date from its year, month and day. Returns `None` if the date does not exist or
+    /// the year is not between 1 and 9999.
+    ///
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Option<Self> {
+        if !(1..=9999).contains(&year) || !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { days: days_from_civil(year, month, day) as i32 })
+    }
+
+    ///
+    /// Creates the date that is `days` days after 1970-01-01. Returns `None` if the date is not
+    /// between the years 1 and 9999.
+    ///
+    pub fn from_days(days: i64) -> Option<Self> {
+        match (days_from_civil(1, 1, 1)..=days_from_civil(9999, 12, 31)).contains(&days) {
+            true => Some(Self { days: days as i32 }),
+            false => None,
+        }
+    }
+
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    ///
+    /// Returns the year, month and day of the date.
+    ///
+    pub fn ymd(&self) -> (i64, u32, u32) {
+        civil_from_days(self.days as i64)
+    }
+
+    pub fn checked_add_days(&self, days: i64) -> Option<Self> {
+        Self::from_days((self.days as i64).checked_add(days)?)
+    }
+
+    ///
+    /// Adds `months` months to the date. The day is reduced to the last day of the resulting month
+    /// if that month is shorter, e.g. one month after 2024-01-31 is 2024-02-29.
+    ///
+    pub fn checked_add_months(&self, months: i64) -> Option<Self> {
+        let (year, month, day) = self.ymd();
+        let months = (year * 12 + month as i64 - 1).checked_add(months)?;
+        let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u32 + 1);
+        Self::from_ymd(year, month, day.min(days_in_month(year, month)))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = BongoError;
+
+    ///
+    /// Parses a date of the form `YYYY-MM-DD`.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('-');
+        let date = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(year), Some(month), Some(day), None) => {
+                match (parse_digits(year, 4), parse_digits(month, 2), parse_digits(day, 2)) {
+                    (Some(year), Some(month), Some(day)) => Self::from_ymd(year as i64, month, day),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        date.ok_or_else(|| BongoError::SqlRuntimeError(format!("'{}' is not a valid date.", s)))
+    }
+}
+
+///
+/// `Time` is a time of day with a precision of microseconds.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    // microseconds since midnight
+    micros: i64,
+}
+
+impl Time {
+    ///
+    /// Creates the time that is `micros` microseconds after midnight. Returns `None` if that is
+    /// not a time of the same day.
+    ///
+    pub fn from_micros(micros: i64) -> Option<Self> {
+        match (0..MICROS_PER_DAY).contains(&micros) {
+            true => Some(Self { micros }),
+            false => None,
+        }
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    ///
+    /// Returns the hour, minute and second of the time. The second contains the fraction as
+    /// microseconds.
+    ///
+    pub fn hms_micro(&self) -> (u32, u32, i64) {
+        let minutes = self.micros / (60 * MICROS_PER_SECOND);
+        ((minutes / 60) as u32, (minutes % 60) as u32, self.micros % (60 * MICROS_PER_SECOND))
+    }
+
+    ///
+    /// Adds `micros` microseconds to the time, wrapping around midnight.
+    ///
+    pub fn wrapping_add_micros(&self, micros: i64) -> Self {
+        Self { micros: (self.micros + micros % MICROS_PER_DAY).rem_euclid(MICROS_PER_DAY) }
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_time_of_day(f, self.micros)
+    }
+}
+
+impl FromStr for Time {
+    type Err = BongoError;
+
+    ///
+    /// Parses a time of the form `HH:MM[:SS[.ffffff]]`.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BongoError::SqlRuntimeError(format!("'{}' is not a valid time.", s));
+
+        let (hms, fraction) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+        let mut parts = hms.split(':');
+        let (hour, minute, second) = match (parts.next(), parts.next(), parts.next().unwrap_or("0"), parts.next()) {
+            (Some(hour), Some(minute), second, None) => (
+                parse_digits(hour, 2).filter(|hour| *hour < 24).ok_or_else(invalid)?,
+                parse_digits(minute, 2).filter(|minute| *minute < 60).ok_or_else(invalid)?,
+                parse_digits(second, 2).filter(|second| *second < 60).ok_or_else(invalid)?,
+            ),
+            _ => return Err(invalid()),
+        };
+        let fraction = match fraction {
+            "" if !s.contains('.') => 0,
+            fraction => parse_digits(fraction, 6).ok_or_else(invalid)? as i64 * 10i64.pow(6 - fraction.len() as u32),
+        };
+
+        let seconds = (hour * 3600 + minute * 60 + second) as i64;
+        Ok(Self { micros: seconds * MICROS_PER_SECOND + fraction })
+    }
+}
+
+///
+/// `Timestamp` is a date and a time of day in UTC.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    // microseconds since 1970-01-01 00:00:00
+    micros: i64,
+}
+
+impl Timestamp {
+    pub fn new(date: Date, time: Time) -> Self {
+        Self { micros: date.days as i64 * MICROS_PER_DAY + time.micros }
+    }
+
+    ///
+    /// Creates the timestamp that is `micros` microseconds after 1970-01-01 00:00:00. Returns
+    /// `None` if its date is not between the years 1 and 9999.
+    ///
+    pub fn from_micros(micros: i64) -> Option<Self> {
+        Date::from_days(micros.div_euclid(MICROS_PER_DAY))?;
+        Some(Self { micros })
+    }
+
+    ///
+    /// Returns the current time of the system.
+    ///
+    pub fn now() -> Self {
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        Self { micros: since_epoch.as_micros() as i64 }
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    pub fn date(&self) -> Date {
+        Date { days: self.micros.div_euclid(MICROS_PER_DAY) as i32 }
+    }
+
+    pub fn time(&self) -> Time {
+        Time { micros: self.micros.rem_euclid(MICROS_PER_DAY) }
+    }
+
+    ///
+    /// Adds an interval to the timestamp. The months of the interval are added first, then its
+    /// days and then its microseconds.
+    ///
+    pub fn checked_add(&self, interval: &Interval) -> Option<Self> {
+        let date = self.date().checked_add_months(interval.months as i64)?.checked_add_days(interval.days as i64)?;
+        Self::from_micros(Self::new(date, self.time()).micros.checked_add(interval.micros)?)
+    }
+
+    ///
+    /// Returns the interval from `other` to this timestamp in days and microseconds.
+    ///
+    pub fn checked_sub(&self, other: &Self) -> Option<Interval> {
+        let micros = self.micros.checked_sub(other.micros)?;
+        Interval::new(0, (micros / MICROS_PER_DAY) as i32, micros % MICROS_PER_DAY)
+    }
+}
+
+impl From<Date> for Timestamp {
+    fn from(date: Date) -> Self {
+        Self::new(date, Time { micros: 0 })
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.date())?;
+        fmt_time_of_day(f, self.time().micros)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = BongoError;
+
+    ///
+    /// Parses a timestamp of the form `YYYY-MM-DD[( |T)HH:MM[:SS[.ffffff]][Z|(+|-)HH[:MM]]]`.
+    /// Timestamps with a time zone offset are converted to UTC.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BongoError::SqlRuntimeError(format!("'{}' is not a valid timestamp.", s));
+
+        let s = s.trim();
+        let (date, time) = s.split_once([' ', 'T']).unwrap_or((s, "00:00"));
+        let date = Date::from_str(date).map_err(|_| invalid())?;
+
+        let (time, offset) = match time.trim().find(['Z', 'z', '+', '-']) {
+            Some(pos) => time.trim().split_at(pos),
+            None => (time, ""),
+        };
+        let time = Time::from_str(time).map_err(|_| invalid())?;
+        let offset = match offset {
+            "" | "Z" | "z" => 0,
+            offset => {
+                let sign = if offset.starts_with('-') { -1 } else { 1 };
+                let (hours, minutes) = offset[1..].split_once(':').unwrap_or((&offset[1..], "0"));
+                match (parse_digits(hours, 2).filter(|hours| *hours <= 14), parse_digits(minutes, 2).filter(|minutes| *minutes < 60)) {
+                    (Some(hours), Some(minutes)) => sign * (hours * 60 + minutes) as i64 * 60 * MICROS_PER_SECOND,
+                    _ => return Err(invalid()),
+                }
+            }
+        };
+
+        Self::from_micros(Self::new(date, time).micros - offset).ok_or_else(invalid)
+    }
+}
+
+///
+/// `Interval` is a span of time of months, days and microseconds. The lengths of months and days
+/// depend on the date they are added to, e.g. one month after 2024-02-01 is 29 days later.
+///
+/// Intervals are compared by their lengths assuming 30 days per month, e.g. `1 month` and
+/// `30 days` are equal.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+impl Interval {
+    ///
+    /// Creates an interval. Returns `None` if its microseconds are more than a day.
+    ///
+    pub fn new(months: i32, days: i32, micros: i64) -> Option<Self> {
+        match micros.unsigned_abs() < MICROS_PER_DAY as u64 {
+            true => Some(Self { months, days, micros }),
+            false => None,
+        }
+    }
+
+    ///
+    /// Creates an interval of `days` days and `micros` microseconds. Whole days in `micros` are
+    /// added to the days, so that it can be created by `new`.
+    ///
+    fn from_days_and_micros(months: i64, days: i64, micros: i64) -> Option<Self> {
+        let days = days.checked_add(micros / MICROS_PER_DAY)?;
+        Self::new(i32::try_from(months).ok()?, i32::try_from(days).ok()?, micros % MICROS_PER_DAY)
+    }
+
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Self::from_days_and_micros(
+            self.months as i64 + other.months as i64,
+            self.days as i64 + other.days as i64,
+            self.micros + other.micros,
+        )
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, factor: i64) -> Option<Self> {
+        Self::from_days_and_micros(
+            (self.months as i64).checked_mul(factor)?,
+            (self.days as i64).checked_mul(factor)?,
+            self.micros.checked_mul(factor)?,
+        )
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Self::new(self.months.checked_neg()?, self.days.checked_neg()?, -self.micros)
+    }
+
+    ///
+    /// Returns the length of the interval in microseconds assuming 30 days per month.
+    ///
+    fn length(&self) -> i128 {
+        (self.months as i128 * 30 + self.days as i128) * MICROS_PER_DAY as i128 + self.micros as i128
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.length() == other.length()
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.length().cmp(&other.length())
+    }
+}
+
+impl Hash for Interval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.length().hash(state)
+    }
+}
+
+impl fmt::Display for Interval {
+    ///
+    /// Formats the interval as e.g. `1 year 2 months 3 days 04:05:06`, which can be parsed again.
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |val: i32| if val.abs() == 1 { "" } else { "s" };
+        let mut parts = vec![];
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years != 0 {
+            parts.push(format!("{} year{}", years, plural(years)));
+        }
+        if months != 0 {
+            parts.push(format!("{} month{}", months, plural(months)));
+        }
+        if self.days != 0 {
+            parts.push(format!("{} day{}", self.days, plural(self.days)));
+        }
+        write!(f, "{}", parts.join(" "))?;
+
+        if self.micros != 0 || parts.is_empty() {
+            let sign = if self.micros < 0 { "-" } else { "" };
+            write!(f, "{}{}", if parts.is_empty() { "" } else { " " }, sign)?;
+            fmt_time_of_day(f, self.micros.abs())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = BongoError;
+
+    ///
+    /// Parses an interval of the form `[<quantity> <unit> ...] [[-]HH:MM[:SS[.ffffff]]]`, e.g.
+    /// `1 year 2 months -3 days 04:05`. The units are `year`, `month`, `week`, `day`, `hour`,
+    /// `minute` and `second` or their plurals. Seconds can have a fraction.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BongoError::SqlRuntimeError(format!("'{}' is not a valid interval.", s));
+
+        let (mut months, mut days, mut micros) = (0i64, 0i64, 0i64);
+        let mut words = s.split_whitespace().peekable();
+        if words.peek().is_none() {
+            return Err(invalid());
+        }
+        while let Some(quantity) = words.next() {
+            if quantity.contains(':') && words.peek().is_none() {
+                let (sign, time) = match quantity.strip_prefix('-') {
+                    Some(time) => (-1, time),
+                    None => (1, quantity),
+                };
+                micros += sign * Time::from_str(time).map_err(|_| invalid())?.micros;
+                continue;
+            }
+
+            let unit = words.next().ok_or_else(invalid)?.to_lowercase();
+            let unit = unit.strip_suffix('s').unwrap_or(&unit);
+            if unit == "second" {
+                let seconds = Decimal::from_str(quantity)
+                    .ok()
+                    .filter(|seconds| seconds.scale() <= 6)
+                    .and_then(|seconds| seconds.rescale(6))
+                    .and_then(|seconds| i64::try_from(seconds.mantissa()).ok())
+                    .ok_or_else(invalid)?;
+                micros = micros.checked_add(seconds).ok_or_else(invalid)?;
+                continue;
+            }
+
+            let quantity = quantity.parse::<i64>().map_err(|_| invalid())?;
+            let (field, factor) = match unit {
+                "year" => (&mut months, 12),
+                "month" => (&mut months, 1),
+                "week" => (&mut days, 7),
+                "day" => (&mut days, 1),
+                "hour" => (&mut micros, 3600 * MICROS_PER_SECOND),
+                "minute" => (&mut micros, 60 * MICROS_PER_SECOND),
+                _ => return Err(invalid()),
+            };
+            *field = quantity.checked_mul(factor).and_then(|val| field.checked_add(val)).ok_or_else(invalid)?;
+        }
+
+        Self::from_days_and_micros(months, days, micros).ok_or_else(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::datetime::{Date, Interval, Time, Timestamp};
+
+    fn date(s: &str) -> Date {
+        Date::from_str(s).unwrap()
+    }
+
+    fn ts(s: &str) -> Timestamp {
+        Timestamp::from_str(s).unwrap()
+    }
+
+    fn interval(s: &str) -> Interval {
+        Interval::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display_dates() {
+        for s in ["1970-01-01", "2024-02-29", "0001-01-01", "9999-12-31", "1969-12-31"] {
+            assert_eq!(s, date(s).to_string());
+        }
+        assert_eq!(0, date("1970-01-01").days());
+        assert_eq!(-1, date("1969-12-31").days());
+        assert_eq!(19_782, date("2024-02-29").days());
+        assert!(Date::from_str("2023-02-29").is_err());
+        assert!(Date::from_str("2024-13-01").is_err());
+        assert!(Date::from_str("0000-01-01").is_err());
+        assert!(Date::from_str("2024-1").is_err());
+        assert!(date("2024-01-31") < date("2024-02-01"));
+    }
+
+    #[test]
+    fn parse_and_display_times_and_timestamps() {
+        assert_eq!("08:30:00", Time::from_str("8:30").unwrap().to_string());
+        assert_eq!("23:59:59.5", Time::from_str("23:59:59.500").unwrap().to_string());
+        assert!(Time::from_str("24:00").is_err());
+        assert!(Time::from_str("12:00:00.1234567").is_err());
+
+        assert_eq!("2024-03-01 12:00:00", ts("2024-03-01 12:00").to_string());
+        assert_eq!("2024-03-01 00:00:00", ts("2024-03-01").to_string());
+        assert_eq!(ts("2024-03-01 10:00:00"), ts("2024-03-01T12:00:00+02:00"));
+        assert_eq!(ts("2024-03-01 00:30:00"), ts("2024-02-29 21:00-03:30"));
+        assert_eq!(ts("2024-03-01 10:00:00"), ts("2024-03-01 10:00:00Z"));
+        assert_eq!("1969-12-31 23:59:59.999999", Timestamp::from_micros(-1).unwrap().to_string());
+        assert!(Timestamp::from_str("2024-03-01 10:00+25").is_err());
+    }
+
+    #[test]
+    fn interval_arithmetic() {
+        assert_eq!("1 year 2 months 3 days 04:05:06", interval("14 months 3 days 4 hours 5 minutes 6 seconds").to_string());
+        assert_eq!("-1 day -00:00:01.5", interval("-1 day -1.5 seconds").to_string());
+        assert_eq!("00:00:00", interval("0 days").to_string());
+        assert_eq!(interval("1 month"), interval("30 days"));
+        assert_eq!(interval("1 day 02:00"), interval("26 hours"));
+        assert!(interval("1 week") > interval("6 days 23:59"));
+        assert!(Interval::from_str("1 fortnight").is_err());
+        assert!(Interval::from_str("").is_err());
+
+        assert_eq!(ts("2024-02-29 10:00"), ts("2024-01-31 10:00").checked_add(&interval("1 month")).unwrap());
+        assert_eq!(ts("2025-03-01 01:30"), ts("2024-02-29 23:30").checked_add(&interval("1 year 2 hours")).unwrap());
+        assert_eq!(interval("1 day 01:00"), ts("2024-03-02 11:00").checked_sub(&ts("2024-03-01 10:00")).unwrap());
+        assert_eq!(interval("2 days 4 hours"), interval("1 day 2 hours").checked_mul(2).unwrap());
+        assert_eq!(None, ts("9999-12-31 23:00").checked_add(&interval("1 hour")));
+        assert_eq!("00:30:00", Time::from_str("23:30").unwrap().wrapping_add_micros(interval("1 hour").micros()).to_string());
+    }
+}
@@ -0,0 +1,344 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::BongoError;
+
+///
+/// The maximum amount of digits of a `Decimal`. All decimals with this amount of digits fit into
+/// the `i128` mantissa.
+///
+pub const MAX_PRECISION: u32 = 38;
+
+///
+/// `Decimal` is an exact decimal number. Its value is `mantissa * 10^-scale`, so `scale` is the
+/// amount of digits after the decimal point.
+///
+/// Decimals are compared by their values, e.g. `1.5` and `1.50` are equal. The mantissa never has
+/// more than `MAX_PRECISION` digits, operations whose result would be larger return `None`.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+///
+/// Returns `10^exp` for an `exp` of at most `MAX_PRECISION`.
+///
+fn pow10(exp: u32) -> i128 {
+    10i128.pow(exp)
+}
+
+///
+/// Divides `val` by `divisor` and rounds the quotient half away from zero.
+///
+fn div_round(val: i128, divisor: i128) -> Option<i128> {
+    let quotient = val.checked_div(divisor)?;
+    let remainder = val % divisor;
+    if remainder.unsigned_abs() >= divisor.unsigned_abs() - remainder.unsigned_abs() {
+        // the remainder is at least half of the divisor
+        return quotient.checked_add(if (val < 0) == (divisor < 0) { 1 } else { -1 });
+    }
+    Some(quotient)
+}
+
+impl Decimal {
+    ///
+    /// Creates the decimal `mantissa * 10^-scale`. Returns `None` if the mantissa has more than
+    /// `MAX_PRECISION` digits or `scale` is larger than `MAX_PRECISION`.
+    ///
+    pub fn new(mantissa: i128, scale: u32) -> Option<Self> {
+        if scale > MAX_PRECISION || mantissa.unsigned_abs() >= pow10(MAX_PRECISION) as u128 {
+            return None;
+        }
+        Some(Self { mantissa, scale })
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    ///
+    /// Returns the amount of digits before the decimal point, which is 0 if the absolute value is
+    /// less than 1.
+    ///
+    pub fn int_digits(&self) -> u32 {
+        let int_part = (self.mantissa / pow10(self.scale)).unsigned_abs();
+        match int_part {
+            0 => 0,
+            int_part => int_part.ilog10() + 1,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    ///
+    /// Returns the decimal with the same value and `scale` digits after the decimal point. If
+    /// `scale` is less than the current scale, the value is rounded half away from zero.
+    ///
+    pub fn rescale(&self, scale: u32) -> Option<Self> {
+        if scale > MAX_PRECISION {
+            return None;
+        }
+        let mantissa = match scale.cmp(&self.scale) {
+            Ordering::Equal => self.mantissa,
+            Ordering::Greater => self.mantissa.checked_mul(pow10(scale - self.scale))?,
+            Ordering::Less => div_round(self.mantissa, pow10(self.scale - scale))?,
+        };
+        Self::new(mantissa, scale)
+    }
+
+    ///
+    /// Returns both decimals with the larger of their scales.
+    ///
+    fn aligned(&self, other: &Self) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+        Some((self.rescale(scale)?.mantissa, other.rescale(scale)?.mantissa, scale))
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let (left, right, scale) = self.aligned(other)?;
+        Self::new(left.checked_add(right)?, scale)
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        let (left, right, scale) = self.aligned(other)?;
+        Self::new(left.checked_sub(right)?, scale)
+    }
+
+    ///
+    /// Multiplies two decimals. The scale of the product is the sum of both scales, but at most
+    /// `MAX_PRECISION`.
+    ///
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        let mantissa = self.mantissa.checked_mul(other.mantissa)?;
+        let scale = self.scale + other.scale;
+        match scale > MAX_PRECISION {
+            true => Self::new(div_round(mantissa, pow10(scale - MAX_PRECISION))?, MAX_PRECISION),
+            false => Self::new(mantissa, scale),
+        }
+    }
+
+    ///
+    /// Divides two decimals. The quotient is rounded half away from zero to 6 more digits after
+    /// the decimal point than the larger scale of both decimals. Returns `None` if `other` is zero.
+    ///
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        let scale = (self.scale.max(other.scale) + 6).min(MAX_PRECISION);
+        // self / other = (self.mantissa * 10^(scale + other.scale - self.scale) / other.mantissa) * 10^-scale
+        let dividend = self.mantissa.checked_mul(10i128.checked_pow(scale + other.scale - self.scale)?)?;
+        Self::new(div_round(dividend, other.mantissa)?, scale)
+    }
+
+    ///
+    /// Returns the remainder of the division of two decimals, which has the sign of `self`.
+    /// Returns `None` if `other` is zero.
+    ///
+    pub fn checked_rem(&self, other: &Self) -> Option<Self> {
+        let (left, right, scale) = self.aligned(other)?;
+        Self::new(left.checked_rem(right)?, scale)
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Self::new(-self.mantissa, self.scale)
+    }
+
+    ///
+    /// Converts a floating point number to the nearest decimal with `scale` digits after the
+    /// decimal point. Returns `None` if the value is not finite or too large.
+    ///
+    pub fn from_f64(val: f64, scale: u32) -> Option<Self> {
+        let mantissa = (val * 10f64.powi(scale as i32)).round();
+        if !mantissa.is_finite() || mantissa.abs() >= 1e38 {
+            return None;
+        }
+        Self::new(mantissa as i128, scale)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    ///
+    /// Returns the value as integer if the decimal has no fractional digits and fits into an `i64`.
+    ///
+    pub fn to_i64(&self) -> Option<i64> {
+        let divisor = pow10(self.scale);
+        match self.mantissa % divisor {
+            0 => i64::try_from(self.mantissa / divisor).ok(),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns the integer part and the fractional part scaled to `MAX_PRECISION` digits. Both
+    /// parts have the sign of the decimal, so decimals can be compared by these parts without
+    /// overflows.
+    ///
+    fn parts(&self) -> (i128, i128) {
+        let divisor = pow10(self.scale);
+        (self.mantissa / divisor, (self.mantissa % divisor) * pow10(MAX_PRECISION - self.scale))
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(val: i64) -> Self {
+        Self { mantissa: val as i128, scale: 0 }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parts().cmp(&other.parts())
+    }
+}
+
+impl Hash for Decimal {
+    ///
+    /// Equal decimals with different scales have the same hash, because trailing zeros after the
+    /// decimal point are ignored.
+    ///
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (mut mantissa, mut scale) = (self.mantissa, self.scale);
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        mantissa.hash(state);
+        scale.hash(state);
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+
+        let digits = format!("{:0>width$}", digits, width = self.scale as usize + 1);
+        let (int_part, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        write!(f, "{}{}.{}", sign, int_part, fraction)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = BongoError;
+
+    ///
+    /// Parses a decimal of the form `[-]digits[.digits]`. The scale of the decimal is the amount of
+    /// digits after the decimal point.
+    ///
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BongoError::SqlRuntimeError(format!("'{}' is not a valid decimal number.", s));
+        let too_large = || BongoError::SqlRuntimeError(format!(
+            "The decimal number '{}' has more than {} digits.", s, MAX_PRECISION
+        ));
+
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, s),
+        };
+        let (int_part, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if int_part.is_empty() && fraction.is_empty()
+            || !int_part.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let scale = u32::try_from(fraction.len()).map_err(|_| too_large())?;
+        let mut mantissa: i128 = 0;
+        for digit in int_part.chars().chain(fraction.chars()) {
+            mantissa = mantissa.checked_mul(10)
+                .and_then(|mantissa| mantissa.checked_add(digit.to_digit(10).unwrap() as i128))
+                .ok_or_else(too_large)?;
+        }
+        Self::new(if negative { -mantissa } else { mantissa }, scale).ok_or_else(too_large)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::str::FromStr;
+
+    use crate::decimal::Decimal;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn hash(val: &Decimal) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        val.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        for s in ["0", "12.50", "-0.05", "123456789012345678901234567890.12345678", "-7"] {
+            assert_eq!(s, dec(s).to_string());
+        }
+        assert_eq!("0.5", dec(".5").to_string());
+        assert_eq!(2, dec("12.50").scale());
+        assert!(Decimal::from_str("1.2.3").is_err());
+        assert!(Decimal::from_str("1e5").is_err());
+        assert!(Decimal::from_str("-").is_err());
+        assert!(Decimal::from_str(&"9".repeat(39)).is_err());
+    }
+
+    #[test]
+    fn compare_and_hash_by_value() {
+        assert_eq!(dec("1.5"), dec("1.500"));
+        assert_eq!(hash(&dec("1.5")), hash(&dec("1.500")));
+        assert_eq!(hash(&dec("10")), hash(&dec("10.0")));
+        assert!(dec("-0.25") < dec("-0.2"));
+        assert!(dec("-1.5") < dec("0.1"));
+        assert!(dec("99999999999999999999999999999999999999") > dec("0.00000000000000000000000000000000000001"));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(dec("3.75"), dec("1.5").checked_add(&dec("2.25")).unwrap());
+        assert_eq!(dec("-0.75"), dec("1.5").checked_sub(&dec("2.25")).unwrap());
+        assert_eq!("3.375", dec("1.5").checked_mul(&dec("2.25")).unwrap().to_string());
+        assert_eq!("0.3333333", dec("1.0").checked_div(&dec("3")).unwrap().to_string());
+        assert_eq!("-0.6666667", dec("-2.0").checked_div(&dec("3")).unwrap().to_string());
+        assert_eq!(dec("-0.5"), dec("-3.5").checked_rem(&dec("1.5")).unwrap());
+        assert_eq!(None, dec("1").checked_div(&dec("0")));
+        assert_eq!(None, dec(&"9".repeat(38)).checked_add(&dec("1")));
+
+        assert_eq!("1.26", dec("1.255").rescale(2).unwrap().to_string());
+        assert_eq!("-1.26", dec("-1.255").rescale(2).unwrap().to_string());
+        assert_eq!("1.2500", dec("1.25").rescale(4).unwrap().to_string());
+        assert_eq!(Some(5), dec("5.00").to_i64());
+        assert_eq!(None, dec("5.01").to_i64());
+        assert_eq!(3, dec("-123.4").int_digits());
+        assert_eq!(0, dec("0.4").int_digits());
+    }
+}
@@ -2,4 +2,6 @@ pub mod bongo_request;
 pub mod bongo_result;
 pub mod bytes_on_disc;
 pub mod conversions;
+pub mod datetime;
+pub mod decimal;
 pub mod types;
@@ -1,5 +1,9 @@
 use std::cmp::Ordering;
 use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::str::FromStr;
 
 use object::read::ReadRef;
 use serde::{Deserialize, Serialize};
@@ -8,6 +12,8 @@ use sqlparser::parser::ParserError;
 
 use crate::bytes_on_disc::{AsDiscBytes, FromDiscBytes};
 use crate::conversions::TryConvertAllExt;
+use crate::datetime::{Date, Interval, Time, Timestamp};
+use crate::decimal::{Decimal, MAX_PRECISION};
 use crate::types::BongoError::InternalError;
 
 ///
@@ -45,6 +51,17 @@ pub enum BongoError {
     // Error when serialising the messages passed between BongoServer and client
     DeserializerError,
     InvalidArgumentError(String),
+    // A table is locked by a transaction of another connection and did not become available in time
+    TableLockedError(String),
+    // A row violates a NOT NULL or CHECK constraint of the table it is stored in
+    ConstraintViolationError(String),
+    // A row has the same values as another row in the columns of a PRIMARY KEY or UNIQUE constraint
+    UniqueViolationError(String),
+    // A row references a row of another table that does not exist or a referenced row cannot be
+    // deleted or updated because of a FOREIGN KEY constraint
+    ForeignKeyViolationError(String),
+    // The files of a database have been written in a format this version of BongoDB cannot read
+    UnsupportedFormatError(String),
 }
 
 ///
@@ -64,15 +81,73 @@ impl From<ParserError> for BongoError {
 ///
 /// `BongoLiteral` represents all literals supported by BongoDB.
 ///
-/// Each variant contains data that represents an instance of this datatype in Rust.
+/// Each variant contains data that represents an instance of this datatype in Rust. `Int` is used
+/// for all integer types, `Float` for `REAL` and `DOUBLE PRECISION` and `Varchar` for `VARCHAR(n)`
+/// and `TEXT`. `Interval` is the result of date and time arithmetic and cannot be stored in a column.
 ///
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Hash, Clone)]
+/// Literals are serialized by the position of their variant, so new variants have to be appended.
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum BongoLiteral {
     Int(i64),
     Bool(bool),
     // Varchar can only fit in the BongoDataType::Varchar(size) if String::len() <= size
     Varchar(String),
     Null,
+    // floating point values are always finite, so that they can be compared and hashed
+    Float(f64),
+    Decimal(Decimal),
+    Date(Date),
+    Time(Time),
+    // timestamps are stored in UTC
+    Timestamp(Timestamp),
+    Interval(Interval),
+    Blob(Vec<u8>),
+}
+
+impl PartialEq for BongoLiteral {
+    ///
+    /// Literals are equal if they are of the same variant and have equal values. Use `compare` to
+    /// compare numbers of different variants.
+    ///
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BongoLiteral::Int(l), BongoLiteral::Int(r)) => l == r,
+            (BongoLiteral::Bool(l), BongoLiteral::Bool(r)) => l == r,
+            (BongoLiteral::Varchar(l), BongoLiteral::Varchar(r)) => l == r,
+            (BongoLiteral::Null, BongoLiteral::Null) => true,
+            (BongoLiteral::Float(l), BongoLiteral::Float(r)) => l == r,
+            (BongoLiteral::Decimal(l), BongoLiteral::Decimal(r)) => l == r,
+            (BongoLiteral::Date(l), BongoLiteral::Date(r)) => l == r,
+            (BongoLiteral::Time(l), BongoLiteral::Time(r)) => l == r,
+            (BongoLiteral::Timestamp(l), BongoLiteral::Timestamp(r)) => l == r,
+            (BongoLiteral::Interval(l), BongoLiteral::Interval(r)) => l == r,
+            (BongoLiteral::Blob(l), BongoLiteral::Blob(r)) => l == r,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for BongoLiteral {}
+
+impl Hash for BongoLiteral {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            BongoLiteral::Int(val) => val.hash(state),
+            BongoLiteral::Bool(val) => val.hash(state),
+            BongoLiteral::Varchar(val) => val.hash(state),
+            BongoLiteral::Null => {}
+            // 0.0 and -0.0 are equal
+            BongoLiteral::Float(val) => (if *val == 0.0 { 0.0 } else { *val }).to_bits().hash(state),
+            BongoLiteral::Decimal(val) => val.hash(state),
+            BongoLiteral::Date(val) => val.hash(state),
+            BongoLiteral::Time(val) => val.hash(state),
+            BongoLiteral::Timestamp(val) => val.hash(state),
+            BongoLiteral::Interval(val) => val.hash(state),
+            BongoLiteral::Blob(val) => val.hash(state),
+        }
+    }
 }
 
 impl BongoLiteral {
@@ -90,6 +165,57 @@ impl BongoLiteral {
             self
         )))
     }
+
+    ///
+    /// Compares two literals by their values. Unlike `PartialOrd` this compares numbers of
+    /// different variants, e.g. `Int(1)` and `Float(1.0)` are equal. An `Int` or a `Decimal` is
+    /// compared exactly to a `Decimal`, a `Float` is compared to the closest floating point value
+    /// of the other number. A `Date` is compared to a `Timestamp` as the timestamp at the start of
+    /// the day. A string is compared to a `Date`, `Time` or `Timestamp` as the value it is parsed
+    /// to, like strings are converted when they are stored in columns of these types.
+    ///
+    /// Returns `None` if the literals are not comparable.
+    ///
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (BongoLiteral::Date(_) | BongoLiteral::Time(_) | BongoLiteral::Timestamp(_), BongoLiteral::Varchar(r)) => {
+                self.compare(&self.parse_like(r)?)
+            }
+            (BongoLiteral::Varchar(l), BongoLiteral::Date(_) | BongoLiteral::Time(_) | BongoLiteral::Timestamp(_)) => {
+                other.parse_like(l)?.compare(other)
+            }
+            (BongoLiteral::Int(l), BongoLiteral::Decimal(r)) => Some(Decimal::from(*l).cmp(r)),
+            (BongoLiteral::Decimal(l), BongoLiteral::Int(r)) => Some(l.cmp(&Decimal::from(*r))),
+            (BongoLiteral::Float(_), _) | (_, BongoLiteral::Float(_)) => self.as_f64()?.partial_cmp(&other.as_f64()?),
+            (BongoLiteral::Date(l), BongoLiteral::Timestamp(r)) => Some(Timestamp::from(*l).cmp(r)),
+            (BongoLiteral::Timestamp(l), BongoLiteral::Date(r)) => Some(l.cmp(&Timestamp::from(*r))),
+            _ => self.partial_cmp(other),
+        }
+    }
+
+    ///
+    /// Parses `val` as a literal of the same date or time variant as this literal.
+    ///
+    fn parse_like(&self, val: &str) -> Option<BongoLiteral> {
+        match self {
+            BongoLiteral::Date(_) => Date::from_str(val).ok().map(BongoLiteral::Date),
+            BongoLiteral::Time(_) => Time::from_str(val).ok().map(BongoLiteral::Time),
+            BongoLiteral::Timestamp(_) => Timestamp::from_str(val).ok().map(BongoLiteral::Timestamp),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns the value of a number as floating point value.
+    ///
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            BongoLiteral::Int(val) => Some(*val as f64),
+            BongoLiteral::Float(val) => Some(*val),
+            BongoLiteral::Decimal(val) => Some(val.to_f64()),
+            _ => None,
+        }
+    }
 }
 
 impl PartialOrd for BongoLiteral {
@@ -119,6 +245,41 @@ impl PartialOrd for BongoLiteral {
                 }
                 return None;
             }
+            BongoLiteral::Float(l) => {
+                if let BongoLiteral::Float(r) = other {
+                    return l.partial_cmp(r);
+                }
+            }
+            BongoLiteral::Decimal(l) => {
+                if let BongoLiteral::Decimal(r) = other {
+                    return l.partial_cmp(r);
+                }
+            }
+            BongoLiteral::Date(l) => {
+                if let BongoLiteral::Date(r) = other {
+                    return l.partial_cmp(r);
+                }
+            }
+            BongoLiteral::Time(l) => {
+                if let BongoLiteral::Time(r) = other {
+                    return l.partial_cmp(r);
+                }
+            }
+            BongoLiteral::Timestamp(l) => {
+                if let BongoLiteral::Timestamp(r) = other {
+                    return l.partial_cmp(r);
+                }
+            }
+            BongoLiteral::Interval(l) => {
+                if let BongoLiteral::Interval(r) = other {
+                    return l.partial_cmp(r);
+                }
+            }
+            BongoLiteral::Blob(l) => {
+                if let BongoLiteral::Blob(r) = other {
+                    return l.partial_cmp(r);
+                }
+            }
         }
         return None;
     }
@@ -135,10 +296,56 @@ impl<D: AsRef<BongoDataType>> AsDiscBytes<&D> for BongoLiteral {
         match self {
             BongoLiteral::Int(val) => {
                 let mut bytes = vec![true as u8];
-                bytes.append(&mut val.to_be_bytes().to_vec());
+                // the value fits into the type, because the data type can store it
+                match def.as_ref() {
+                    BongoDataType::SmallInt => bytes.extend_from_slice(&(*val as i16).to_be_bytes()),
+                    BongoDataType::Int => bytes.extend_from_slice(&(*val as i32).to_be_bytes()),
+                    _ => bytes.extend_from_slice(&val.to_be_bytes()),
+                }
+
+                Ok(bytes)
+            }
+            BongoLiteral::Float(val) => {
+                let mut bytes = vec![true as u8];
+                match def.as_ref() {
+                    BongoDataType::Real => bytes.extend_from_slice(&(*val as f32).to_be_bytes()),
+                    _ => bytes.extend_from_slice(&val.to_be_bytes()),
+                }
+
+                Ok(bytes)
+            }
+            BongoLiteral::Decimal(val) => {
+                // the scale is defined by the data type
+                let mut bytes = vec![true as u8];
+                bytes.extend_from_slice(&val.mantissa().to_be_bytes());
+
+                Ok(bytes)
+            }
+            BongoLiteral::Date(val) => {
+                let mut bytes = vec![true as u8];
+                bytes.extend_from_slice(&val.days().to_be_bytes());
+
+                Ok(bytes)
+            }
+            BongoLiteral::Time(val) => {
+                let mut bytes = vec![true as u8];
+                bytes.extend_from_slice(&val.micros().to_be_bytes());
 
                 Ok(bytes)
             }
+            BongoLiteral::Timestamp(val) => {
+                let mut bytes = vec![true as u8];
+                bytes.extend_from_slice(&val.micros().to_be_bytes());
+
+                Ok(bytes)
+            }
+            BongoLiteral::Interval(_) => Err(BongoError::InternalError(
+                "Intervals cannot be converted to bytes.".to_string(),
+            )),
+            // only the reference to the value is stored in the row, see `BongoDataType::is_out_of_line`
+            BongoLiteral::Blob(_) => Err(BongoError::InternalError(
+                "Values of out of line data types cannot be converted to bytes.".to_string(),
+            )),
             BongoLiteral::Bool(val) => Ok(vec![true as u8, *val as u8]),
             BongoLiteral::Null => {
                 let size = def.as_ref().disc_size();
@@ -151,6 +358,9 @@ impl<D: AsRef<BongoDataType>> AsDiscBytes<&D> for BongoLiteral {
 
                 Ok(bytes)
             }
+            BongoLiteral::Varchar(_) if def.as_ref().is_out_of_line() => Err(BongoError::InternalError(
+                "Values of out of line data types cannot be converted to bytes.".to_string(),
+            )),
             BongoLiteral::Varchar(val) => {
                 let disc_size = def.as_ref().disc_size();
 
@@ -183,14 +393,34 @@ impl<D: AsRef<BongoDataType>> FromDiscBytes<D> for BongoLiteral {
 
         let payload = &bytes[1..];
 
+        // unwraps safe because we know the size of the slice because of checks before
         return match def.as_ref() {
-            BongoDataType::Int => {
-                // safe because we know the size of the slice because of checks before
-                Ok(BongoLiteral::Int(i64::from_be_bytes(
-                    payload.try_into().unwrap(),
-                )))
+            BongoDataType::SmallInt => Ok(BongoLiteral::Int(i16::from_be_bytes(payload.try_into().unwrap()) as i64)),
+            BongoDataType::Int => Ok(BongoLiteral::Int(i32::from_be_bytes(payload.try_into().unwrap()) as i64)),
+            BongoDataType::BigInt => Ok(BongoLiteral::Int(i64::from_be_bytes(payload.try_into().unwrap()))),
+            BongoDataType::Real => Ok(BongoLiteral::Float(f32::from_be_bytes(payload.try_into().unwrap()) as f64)),
+            BongoDataType::Double => Ok(BongoLiteral::Float(f64::from_be_bytes(payload.try_into().unwrap()))),
+            BongoDataType::Decimal(_, scale) => {
+                match Decimal::new(i128::from_be_bytes(payload.try_into().unwrap()), *scale as u32) {
+                    Some(val) => Ok(BongoLiteral::Decimal(val)),
+                    None => Err(BongoError::InternalError(
+                        "Cannot read literal from bytes due to corrupted format.".to_string(),
+                    )),
+                }
             }
+            BongoDataType::Date => Date::from_days(i32::from_be_bytes(payload.try_into().unwrap()) as i64)
+                .map(BongoLiteral::Date)
+                .ok_or_else(|| BongoError::InternalError("Cannot read literal from bytes due to corrupted format.".to_string())),
+            BongoDataType::Time => Time::from_micros(i64::from_be_bytes(payload.try_into().unwrap()))
+                .map(BongoLiteral::Time)
+                .ok_or_else(|| BongoError::InternalError("Cannot read literal from bytes due to corrupted format.".to_string())),
+            BongoDataType::Timestamp => Timestamp::from_micros(i64::from_be_bytes(payload.try_into().unwrap()))
+                .map(BongoLiteral::Timestamp)
+                .ok_or_else(|| BongoError::InternalError("Cannot read literal from bytes due to corrupted format.".to_string())),
             BongoDataType::Bool => Ok(BongoLiteral::Bool(payload[0] != 0)), // convert to bool
+            BongoDataType::Text | BongoDataType::Blob => Err(BongoError::InternalError(
+                "Values of out of line data types cannot be read from bytes.".to_string(),
+            )),
             BongoDataType::Varchar(_) => match payload.iter().position(|b| b == &0xFFu8) {
                 None => Err(BongoError::InternalError(
                     "Cannot read literal from bytes due to corrupted format.".to_string(),
@@ -221,6 +451,108 @@ impl TryFrom<BongoLiteral> for i64 {
     }
 }
 
+impl TryFrom<BongoLiteral> for i32 {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Int(v) if i32::try_from(v).is_ok() => Ok(v as i32),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to i32".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for i16 {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Int(v) if i16::try_from(v).is_ok() => Ok(v as i16),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to i16".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for f64 {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        if let BongoLiteral::Float(v) = literal {
+            Ok(v)
+        } else {
+            Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to f64".to_string(),
+            ))
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for f32 {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        if let BongoLiteral::Float(v) = literal {
+            Ok(v as f32)
+        } else {
+            Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to f32".to_string(),
+            ))
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Decimal {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        if let BongoLiteral::Decimal(v) = literal {
+            Ok(v)
+        } else {
+            Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Decimal".to_string(),
+            ))
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Date {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        if let BongoLiteral::Date(v) = literal {
+            Ok(v)
+        } else {
+            Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Date".to_string(),
+            ))
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Time {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        if let BongoLiteral::Time(v) = literal {
+            Ok(v)
+        } else {
+            Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Time".to_string(),
+            ))
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Timestamp {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        if let BongoLiteral::Timestamp(v) = literal {
+            Ok(v)
+        } else {
+            Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Timestamp".to_string(),
+            ))
+        }
+    }
+}
+
 impl TryFrom<BongoLiteral> for String {
     type Error = BongoError;
     fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
@@ -234,6 +566,19 @@ impl TryFrom<BongoLiteral> for String {
     }
 }
 
+impl TryFrom<BongoLiteral> for Vec<u8> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        if let BongoLiteral::Blob(v) = literal {
+            Ok(v)
+        } else {
+            Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Vec<u8>".to_string(),
+            ))
+        }
+    }
+}
+
 impl TryFrom<BongoLiteral> for bool {
     type Error = BongoError;
     fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
@@ -260,6 +605,108 @@ impl TryFrom<BongoLiteral> for Option<i64> {
     }
 }
 
+impl TryFrom<BongoLiteral> for Option<i32> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Null => Ok(None),
+            literal => i32::try_from(literal).map(Some).map_err(|_| BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<i32>".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Option<i16> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Null => Ok(None),
+            literal => i16::try_from(literal).map(Some).map_err(|_| BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<i16>".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Option<f64> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Float(v) => Ok(Some(v)),
+            BongoLiteral::Null => Ok(None),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<f64>".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Option<f32> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Float(v) => Ok(Some(v as f32)),
+            BongoLiteral::Null => Ok(None),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<f32>".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Option<Decimal> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Decimal(v) => Ok(Some(v)),
+            BongoLiteral::Null => Ok(None),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<Decimal>".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Option<Date> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Date(v) => Ok(Some(v)),
+            BongoLiteral::Null => Ok(None),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<Date>".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Option<Time> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Time(v) => Ok(Some(v)),
+            BongoLiteral::Null => Ok(None),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<Time>".to_string(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<BongoLiteral> for Option<Timestamp> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Timestamp(v) => Ok(Some(v)),
+            BongoLiteral::Null => Ok(None),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<Timestamp>".to_string(),
+            )),
+        }
+    }
+}
+
 impl TryFrom<BongoLiteral> for Option<String> {
     type Error = BongoError;
     fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
@@ -273,6 +720,19 @@ impl TryFrom<BongoLiteral> for Option<String> {
     }
 }
 
+impl TryFrom<BongoLiteral> for Option<Vec<u8>> {
+    type Error = BongoError;
+    fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            BongoLiteral::Blob(v) => Ok(Some(v)),
+            BongoLiteral::Null => Ok(None),
+            _ => Err(BongoError::InternalError(
+                "Could not convert BongoLiteral to Option<Vec<u8>>".to_string(),
+            )),
+        }
+    }
+}
+
 impl TryFrom<BongoLiteral> for Option<bool> {
     type Error = BongoError;
     fn try_from(literal: BongoLiteral) -> Result<Self, Self::Error> {
@@ -289,12 +749,29 @@ impl TryFrom<BongoLiteral> for Option<bool> {
 ///
 /// `BongoDataType` represents all data types supported by BongoDB.
 ///
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// Data types are serialized by the position of their variant, so new variants have to be
+/// appended. `BigInt` is the first variant, because `INT` columns of earlier versions of BongoDB
+/// store 64-bit integers.
+///
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub enum BongoDataType {
-    Int,
+    BigInt,
     Bool,
     // VARCHARs are required to have a fixed size
     Varchar(usize),
+    SmallInt,
+    Int,
+    Real,
+    Double,
+    // precision and scale
+    Decimal(usize, usize),
+    Date,
+    Time,
+    // `TIMESTAMP WITH TIME ZONE` is stored as `TIMESTAMP` in UTC
+    Timestamp,
+    // strings and byte sequences of any length, which are stored out of line
+    Text,
+    Blob,
 }
 
 impl AsRef<BongoDataType> for BongoDataType {
@@ -304,28 +781,147 @@ impl AsRef<BongoDataType> for BongoDataType {
 }
 
 impl BongoDataType {
+    ///
+    /// Returns whether `lit` can be stored in a column of this type without converting it.
+    ///
     pub fn can_store(&self, lit: &BongoLiteral) -> bool {
-        return match lit {
-            BongoLiteral::Int(_) => {
-                matches!(self, BongoDataType::Int)
+        return match (self, lit) {
+            (_, BongoLiteral::Null) => true,
+            (BongoDataType::SmallInt, BongoLiteral::Int(val)) => i16::try_from(*val).is_ok(),
+            (BongoDataType::Int, BongoLiteral::Int(val)) => i32::try_from(*val).is_ok(),
+            (BongoDataType::BigInt, BongoLiteral::Int(_)) => true,
+            (BongoDataType::Bool, BongoLiteral::Bool(_)) => true,
+            (BongoDataType::Varchar(cap), BongoLiteral::Varchar(s)) => &s.len() <= cap,
+            (BongoDataType::Real, BongoLiteral::Float(val)) => val.is_finite() && (*val as f32) as f64 == *val,
+            (BongoDataType::Double, BongoLiteral::Float(val)) => val.is_finite(),
+            (BongoDataType::Decimal(precision, scale), BongoLiteral::Decimal(val)) => {
+                val.scale() as usize == *scale && val.int_digits() as usize <= precision - scale
             }
-            BongoLiteral::Bool(_) => {
-                matches!(self, BongoDataType::Bool)
+            (BongoDataType::Date, BongoLiteral::Date(_)) => true,
+            (BongoDataType::Time, BongoLiteral::Time(_)) => true,
+            (BongoDataType::Timestamp, BongoLiteral::Timestamp(_)) => true,
+            (BongoDataType::Text, BongoLiteral::Varchar(_)) => true,
+            (BongoDataType::Blob, BongoLiteral::Blob(_)) => true,
+            _ => false,
+        };
+    }
+
+    ///
+    /// Converts `lit` to the literal that is stored for it in a column of this type.
+    ///
+    /// Numbers can be converted to all numeric types. They are rounded half away from zero to
+    /// the digits after the decimal point the type can store, e.g. `1.255` is stored as `1.26` in
+    /// a `DECIMAL(5, 2)` column and `2.5` as `3` in an `INT` column.
+    ///
+    /// Strings are parsed for `DATE`, `TIME` and `TIMESTAMP` columns. A `Date` is stored as the
+    /// start of the day in a `TIMESTAMP` column, of a `Timestamp` only the date is stored in a
+    /// `DATE` column and only the time in a `TIME` column.
+    ///
+    /// Strings are stored as their UTF-8 bytes in a `BLOB` column unless they start with `\x`,
+    /// in which case the rest of the string is parsed as hexadecimal digits like in PostgreSQL.
+    ///
+    /// An error is returned if the value does not fit into the type or cannot be converted.
+    ///
+    pub fn convert(&self, lit: BongoLiteral) -> Result<BongoLiteral, BongoError> {
+        let converted = match (self, &lit) {
+            (BongoDataType::SmallInt | BongoDataType::Int | BongoDataType::BigInt, BongoLiteral::Float(val)) => {
+                let val = val.round();
+                // i64::MAX is rounded up to 2^63 as floating point value
+                match val >= i64::MIN as f64 && val < i64::MAX as f64 {
+                    true => Some(BongoLiteral::Int(val as i64)),
+                    false => None,
+                }
+            }
+            (BongoDataType::SmallInt | BongoDataType::Int | BongoDataType::BigInt, BongoLiteral::Decimal(val)) => {
+                val.rescale(0).and_then(|val| val.to_i64()).map(BongoLiteral::Int)
+            }
+            (BongoDataType::Real, BongoLiteral::Int(_) | BongoLiteral::Float(_) | BongoLiteral::Decimal(_)) => {
+                lit.as_f64().map(|val| BongoLiteral::Float(val as f32 as f64))
+            }
+            (BongoDataType::Double, BongoLiteral::Int(_) | BongoLiteral::Decimal(_)) => lit.as_f64().map(BongoLiteral::Float),
+            (BongoDataType::Decimal(_, scale), BongoLiteral::Int(val)) => {
+                Decimal::from(*val).rescale(*scale as u32).map(BongoLiteral::Decimal)
+            }
+            (BongoDataType::Decimal(_, scale), BongoLiteral::Float(val)) => {
+                Decimal::from_f64(*val, *scale as u32).map(BongoLiteral::Decimal)
             }
-            BongoLiteral::Varchar(s) => match self {
-                BongoDataType::Varchar(cap) => &s.len() <= cap,
-                _ => false,
+            (BongoDataType::Decimal(_, scale), BongoLiteral::Decimal(val)) => {
+                val.rescale(*scale as u32).map(BongoLiteral::Decimal)
+            }
+            (BongoDataType::Date, BongoLiteral::Varchar(val)) => Date::from_str(val).ok().map(BongoLiteral::Date),
+            (BongoDataType::Date, BongoLiteral::Timestamp(val)) => Some(BongoLiteral::Date(val.date())),
+            (BongoDataType::Time, BongoLiteral::Varchar(val)) => Time::from_str(val).ok().map(BongoLiteral::Time),
+            (BongoDataType::Time, BongoLiteral::Timestamp(val)) => Some(BongoLiteral::Time(val.time())),
+            (BongoDataType::Timestamp, BongoLiteral::Varchar(val)) => Timestamp::from_str(val).ok().map(BongoLiteral::Timestamp),
+            (BongoDataType::Timestamp, BongoLiteral::Date(val)) => Some(BongoLiteral::Timestamp(Timestamp::from(*val))),
+            (BongoDataType::Blob, BongoLiteral::Varchar(val)) => match val.strip_prefix("\\x") {
+                Some(hex) => parse_hex(hex).map(BongoLiteral::Blob),
+                None => Some(BongoLiteral::Blob(val.as_bytes().to_vec())),
             },
-            BongoLiteral::Null => true,
+            _ => Some(lit.clone()),
         };
+
+        match converted {
+            Some(converted) if self.can_store(&converted) => Ok(converted),
+            _ => Err(BongoError::SqlRuntimeError(format!(
+                "The value '{:?}' cannot be stored in a column of the type {}.", lit, self
+            ))),
+        }
+    }
+
+    ///
+    /// Returns whether this is one of the integer types `SMALLINT`, `INT` and `BIGINT`.
+    ///
+    pub fn is_integer(&self) -> bool {
+        matches!(self, BongoDataType::SmallInt | BongoDataType::Int | BongoDataType::BigInt)
+    }
+
+    ///
+    /// Returns whether values of this type are stored outside of the fixed size rows. The row then
+    /// only holds a reference to the value, which takes up `disc_size` bytes.
+    ///
+    pub fn is_out_of_line(&self) -> bool {
+        matches!(self, BongoDataType::Text | BongoDataType::Blob)
     }
 
     pub fn disc_size(&self) -> usize {
         // items are saved with one extra byte. The first byte is the information whether it is a null value
         match self {
-            BongoDataType::Int => 8 + 1,
+            BongoDataType::SmallInt => 2 + 1,
+            BongoDataType::Int => 4 + 1,
+            BongoDataType::BigInt => 8 + 1,
+            BongoDataType::Real => 4 + 1,
+            BongoDataType::Double => 8 + 1,
+            // the mantissa, the scale is defined by the data type
+            BongoDataType::Decimal(..) => 16 + 1,
+            // days since 1970-01-01
+            BongoDataType::Date => 4 + 1,
+            // microseconds since midnight and since 1970-01-01 00:00:00
+            BongoDataType::Time | BongoDataType::Timestamp => 8 + 1,
             BongoDataType::Bool => 1 + 1,
             BongoDataType::Varchar(size) => size + 1 + 1, // one 0xFF at the end as terminator
+            // the position and the length of the value outside of the row
+            BongoDataType::Text | BongoDataType::Blob => 8 + 8 + 1,
+        }
+    }
+}
+
+impl fmt::Display for BongoDataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BongoDataType::SmallInt => write!(f, "SMALLINT"),
+            BongoDataType::Int => write!(f, "INT"),
+            BongoDataType::BigInt => write!(f, "BIGINT"),
+            BongoDataType::Real => write!(f, "REAL"),
+            BongoDataType::Double => write!(f, "DOUBLE PRECISION"),
+            BongoDataType::Decimal(precision, scale) => write!(f, "DECIMAL({}, {})", precision, scale),
+            BongoDataType::Date => write!(f, "DATE"),
+            BongoDataType::Time => write!(f, "TIME"),
+            BongoDataType::Timestamp => write!(f, "TIMESTAMP"),
+            BongoDataType::Bool => write!(f, "BOOLEAN"),
+            BongoDataType::Varchar(size) => write!(f, "VARCHAR({})", size),
+            BongoDataType::Text => write!(f, "TEXT"),
+            BongoDataType::Blob => write!(f, "BLOB"),
         }
     }
 }
@@ -347,17 +943,54 @@ impl TryFrom<&DataType> for BongoDataType {
                     Some(size) => Ok(BongoDataType::Varchar(*size as usize)),
                 };
             }
-            DataType::TinyInt(_) | DataType::SmallInt(_) | DataType::Int(_) => {
-                Ok(BongoDataType::Int)
+            DataType::TinyInt(_) | DataType::SmallInt(_) => Ok(BongoDataType::SmallInt),
+            DataType::Int(_) => Ok(BongoDataType::Int),
+            DataType::BigInt(_) => Ok(BongoDataType::BigInt),
+            DataType::Real => Ok(BongoDataType::Real),
+            // FLOAT(p) is a REAL if p binary digits fit into a REAL
+            DataType::Float(Some(precision)) if *precision <= 24 => Ok(BongoDataType::Real),
+            DataType::Float(_) | DataType::Double => Ok(BongoDataType::Double),
+            DataType::Decimal(precision, scale) => {
+                let precision = precision.unwrap_or(MAX_PRECISION as u64);
+                let scale = scale.unwrap_or(0);
+                if precision == 0 || precision > MAX_PRECISION as u64 || scale > precision {
+                    return Err(BongoError::UnsupportedFeatureError(format!(
+                        "The precision of a DECIMAL must be between 1 and {} and its scale must not be \
+                        larger than its precision.", MAX_PRECISION
+                    )));
+                }
+                Ok(BongoDataType::Decimal(precision as usize, scale as usize))
             }
             DataType::Boolean => Ok(BongoDataType::Bool),
+            DataType::Date => Ok(BongoDataType::Date),
+            DataType::Time => Ok(BongoDataType::Time),
+            // the parser accepts `WITH TIME ZONE` and drops it
+            DataType::Timestamp => Ok(BongoDataType::Timestamp),
+            // the size of a CLOB or BLOB is not limited
+            DataType::Text | DataType::String | DataType::Clob(_) => Ok(BongoDataType::Text),
+            DataType::Blob(_) | DataType::Bytea => Ok(BongoDataType::Blob),
             _ => Err(BongoError::UnsupportedFeatureError(String::from(
-                "BongoDB only supports the datatypes INT, VARCHAR(n) and BOOLEAN.",
+                "BongoDB only supports the datatypes SMALLINT, INT, BIGINT, REAL, DOUBLE PRECISION, \
+                DECIMAL(p, s), VARCHAR(n), TEXT, BLOB, BOOLEAN, DATE, TIME and TIMESTAMP.",
             ))),
         };
     }
 }
 
+///
+/// Parses a string of hexadecimal digits into the bytes it represents.
+///
+pub fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
 ///
 /// `ColumnDef` represents the definition of a column in an SQL CREATE TABLE statement.
 ///
@@ -365,13 +998,26 @@ impl TryFrom<&DataType> for BongoDataType {
 ///
 /// In the statement `CREATE TABLE table_1 (col_1 INT, col_2 BOOLEAN);`
 /// two `ColumnDef`s are specified:
-/// `BongoColDef { name: "col_1".to_string(), data_type: BongoDataType::Int },`
-/// `BongoColDef { name: "col_2".to_string(), data_type: BongoDataType::Bool },`
+/// `BongoColDef { name: "col_1".to_string(), data_type: BongoDataType::Int, not_null: false, default: None, serial: false },`
+/// `BongoColDef { name: "col_2".to_string(), data_type: BongoDataType::Bool, not_null: false, default: None, serial: false },`
 ///
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct ColumnDef {
     pub name: String,
     pub data_type: BongoDataType,
+    ///
+    /// `not_null` is true if the column has a `NOT NULL` constraint, i.e. it cannot store `NULL`.
+    ///
+    pub not_null: bool,
+    ///
+    /// `default` is the value given by `DEFAULT`, which a column gets if no value is given for it.
+    ///
+    pub default: Option<BongoLiteral>,
+    ///
+    /// `serial` is true for `SERIAL` and `AUTO_INCREMENT` columns, which get the next value of a
+    /// counter of the table if no value is given for them.
+    ///
+    pub serial: bool,
 }
 
 impl<T: AsRef<[ColumnDef]>> GetColNamesExt for T {
@@ -405,13 +1051,33 @@ impl AsRef<BongoDataType> for ColumnDef {
 /// Tries to convert an `&SqlParserColDef` of the used `sqlparser`-library into an object of the custom
 /// `ColumnDef` type paying attention to what features are supported by BongoDB.
 ///
+/// The column options, e.g. `NOT NULL` and `DEFAULT`, are not converted. They are left to the
+/// parser of BongoServer, which can evaluate the expressions they contain.
+///
 impl TryFrom<&SqlParserColDef> for ColumnDef {
     type Error = BongoError;
 
     fn try_from(value: &SqlParserColDef) -> Result<Self, Self::Error> {
+        // the serial types are integer types whose values are generated like in PostgreSQL
+        let serial_type = match &value.data_type {
+            DataType::Custom(name) => match name.to_string().to_uppercase().as_str() {
+                "SMALLSERIAL" | "SERIAL2" => Some(BongoDataType::SmallInt),
+                "SERIAL" | "SERIAL4" => Some(BongoDataType::Int),
+                "BIGSERIAL" | "SERIAL8" => Some(BongoDataType::BigInt),
+                _ => None,
+            },
+            _ => None,
+        };
+
         Ok(ColumnDef {
             name: String::from(&value.name.value),
-            data_type: BongoDataType::try_from(&value.data_type)?,
+            data_type: match &serial_type {
+                Some(data_type) => data_type.clone(),
+                None => BongoDataType::try_from(&value.data_type)?,
+            },
+            not_null: serial_type.is_some(),
+            default: None,
+            serial: serial_type.is_some(),
         })
     }
 }
@@ -458,9 +1124,18 @@ impl<D: AsRef<BongoDataType>> FromDiscBytes<&[D]> for Row {
 
 #[cfg(test)]
 mod tests {
+    use std::cmp::Ordering;
+    use std::str::FromStr;
+
     use crate::bytes_on_disc::{AsDiscBytes, FromDiscBytes};
+    use crate::datetime::{Date, Time, Timestamp};
+    use crate::decimal::Decimal;
     use crate::types::{BongoDataType, BongoLiteral};
 
+    fn dec(s: &str) -> BongoLiteral {
+        BongoLiteral::Decimal(Decimal::from_str(s).unwrap())
+    }
+
     #[test]
     fn datatype_can_store() {
         assert!(BongoDataType::Int.can_store(&BongoLiteral::Int(5)));
@@ -486,13 +1161,13 @@ mod tests {
         // note: the content of null values is ignored except the first byte
         assert_eq!(
             BongoLiteral::Null
-                .as_disc_bytes(&BongoDataType::Int)
+                .as_disc_bytes(&BongoDataType::BigInt)
                 .unwrap()[0],
             0
         );
         assert_eq!(
             BongoLiteral::Null
-                .as_disc_bytes(&BongoDataType::Int)
+                .as_disc_bytes(&BongoDataType::BigInt)
                 .unwrap()
                 .len(),
             9
@@ -536,13 +1211,13 @@ mod tests {
     fn bongo_lit_as_and_from_disc_bytes_int() {
         assert_eq!(
             BongoLiteral::Int(42)
-                .as_disc_bytes(&BongoDataType::Int)
+                .as_disc_bytes(&BongoDataType::BigInt)
                 .unwrap()[0],
             1
         );
         assert_eq!(
             BongoLiteral::Int(42)
-                .as_disc_bytes(&BongoDataType::Int)
+                .as_disc_bytes(&BongoDataType::BigInt)
                 .unwrap()
                 .len(),
             9
@@ -555,8 +1230,8 @@ mod tests {
             .is_err());
 
         let original = BongoLiteral::Int(42);
-        let bytes = original.as_disc_bytes(&BongoDataType::Int).unwrap();
-        let literal = BongoLiteral::from_disc_bytes(&bytes, &BongoDataType::Int).unwrap();
+        let bytes = original.as_disc_bytes(&BongoDataType::BigInt).unwrap();
+        let literal = BongoLiteral::from_disc_bytes(&bytes, &BongoDataType::BigInt).unwrap();
 
         assert_eq!(original, literal)
     }
@@ -642,4 +1317,160 @@ mod tests {
             BongoLiteral::from_disc_bytes(&bytes, &BongoDataType::Varchar(container_size)).unwrap();
         assert_eq!(original, literal);
     }
+
+    #[test]
+    fn numeric_datatypes_can_store() {
+        assert!(BongoDataType::SmallInt.can_store(&BongoLiteral::Int(-32768)));
+        assert!(!BongoDataType::SmallInt.can_store(&BongoLiteral::Int(32768)));
+        assert!(BongoDataType::Int.can_store(&BongoLiteral::Int(i32::MAX as i64)));
+        assert!(!BongoDataType::Int.can_store(&BongoLiteral::Int(i32::MAX as i64 + 1)));
+        assert!(BongoDataType::BigInt.can_store(&BongoLiteral::Int(i64::MIN)));
+        assert!(!BongoDataType::BigInt.can_store(&BongoLiteral::Float(1.0)));
+
+        assert!(BongoDataType::Real.can_store(&BongoLiteral::Float(0.5)));
+        assert!(!BongoDataType::Real.can_store(&BongoLiteral::Float(0.1)));
+        assert!(BongoDataType::Double.can_store(&BongoLiteral::Float(0.1)));
+        assert!(!BongoDataType::Double.can_store(&BongoLiteral::Float(f64::INFINITY)));
+
+        assert!(BongoDataType::Decimal(5, 2).can_store(&dec("123.45")));
+        assert!(!BongoDataType::Decimal(5, 2).can_store(&dec("1234.50")));
+        assert!(!BongoDataType::Decimal(5, 2).can_store(&dec("1.5")));
+        assert!(!BongoDataType::Decimal(5, 2).can_store(&BongoLiteral::Int(1)));
+    }
+
+    #[test]
+    fn convert_numbers() {
+        assert_eq!(BongoLiteral::Int(3), BongoDataType::Int.convert(BongoLiteral::Float(2.5)).unwrap());
+        assert_eq!(BongoLiteral::Int(-2), BongoDataType::SmallInt.convert(dec("-1.50")).unwrap());
+        assert!(BongoDataType::SmallInt.convert(BongoLiteral::Int(40000)).is_err());
+        assert!(BongoDataType::BigInt.convert(BongoLiteral::Float(1e19)).is_err());
+
+        assert_eq!(BongoLiteral::Float(2.0), BongoDataType::Double.convert(BongoLiteral::Int(2)).unwrap());
+        assert_eq!(BongoLiteral::Float(0.1f32 as f64), BongoDataType::Real.convert(dec("0.1")).unwrap());
+        assert!(BongoDataType::Real.convert(BongoLiteral::Float(1e300)).is_err());
+
+        assert_eq!(dec("1.26"), BongoDataType::Decimal(5, 2).convert(dec("1.255")).unwrap());
+        assert_eq!("7.00", match BongoDataType::Decimal(5, 2).convert(BongoLiteral::Int(7)).unwrap() {
+            BongoLiteral::Decimal(val) => val.to_string(),
+            val => panic!("unexpected value {:?}", val),
+        });
+        assert_eq!(dec("0.33"), BongoDataType::Decimal(5, 2).convert(BongoLiteral::Float(1.0 / 3.0)).unwrap());
+        assert!(BongoDataType::Decimal(5, 2).convert(BongoLiteral::Int(1000)).is_err());
+
+        assert_eq!(BongoLiteral::Null, BongoDataType::Decimal(5, 2).convert(BongoLiteral::Null).unwrap());
+        assert!(BongoDataType::Int.convert(BongoLiteral::Varchar("1".to_string())).is_err());
+        assert!(BongoDataType::Bool.convert(BongoLiteral::Int(1)).is_err());
+    }
+
+    #[test]
+    fn compare_numbers() {
+        assert_eq!(Some(Ordering::Equal), BongoLiteral::Int(1).compare(&BongoLiteral::Float(1.0)));
+        assert_eq!(Some(Ordering::Equal), BongoLiteral::Int(1).compare(&dec("1.00")));
+        assert_eq!(Some(Ordering::Less), dec("0.1").compare(&BongoLiteral::Float(0.5)));
+        assert_eq!(Some(Ordering::Greater), BongoLiteral::Int(i64::MAX).compare(&dec("9223372036854775806.5")));
+        assert_eq!(None, BongoLiteral::Int(1).compare(&BongoLiteral::Bool(true)));
+
+        // only literals of the same variant are equal
+        assert_ne!(BongoLiteral::Int(1), BongoLiteral::Float(1.0));
+        assert_eq!(dec("1.5"), dec("1.50"));
+        assert_eq!(BongoLiteral::Float(0.0), BongoLiteral::Float(-0.0));
+    }
+
+    #[test]
+    fn compare_dates_and_times_with_strings() {
+        let date = BongoLiteral::Date(Date::from_str("2024-01-31").unwrap());
+        let time = BongoLiteral::Time(Time::from_str("08:30").unwrap());
+        let timestamp = BongoLiteral::Timestamp(Timestamp::from_str("2024-01-31 10:00").unwrap());
+        let varchar = |s: &str| BongoLiteral::Varchar(s.to_string());
+
+        assert_eq!(Some(Ordering::Equal), date.compare(&varchar("2024-01-31")));
+        assert_eq!(Some(Ordering::Greater), varchar("2024-02-01").compare(&date));
+        assert_eq!(Some(Ordering::Less), time.compare(&varchar("08:30:01")));
+        assert_eq!(Some(Ordering::Greater), timestamp.compare(&varchar("2024-01-31")));
+        assert_eq!(None, date.compare(&varchar("not a date")));
+    }
+
+    #[test]
+    fn bongo_lit_as_and_from_disc_bytes_numbers() {
+        let cases = [
+            (BongoDataType::SmallInt, BongoLiteral::Int(-1234), 3),
+            (BongoDataType::Int, BongoLiteral::Int(i32::MIN as i64), 5),
+            (BongoDataType::BigInt, BongoLiteral::Int(i64::MAX), 9),
+            (BongoDataType::Real, BongoLiteral::Float(-2.75), 5),
+            (BongoDataType::Double, BongoLiteral::Float(0.1), 9),
+            (BongoDataType::Decimal(10, 3), dec("-1234567.891"), 17),
+            (BongoDataType::Decimal(10, 3), BongoLiteral::Null, 17),
+        ];
+
+        for (d_type, original, size) in cases {
+            let bytes = original.as_disc_bytes(&d_type).unwrap();
+            assert_eq!(size, bytes.len());
+            assert_eq!(size, d_type.disc_size());
+            assert_eq!(original, BongoLiteral::from_disc_bytes(&bytes, &d_type).unwrap());
+        }
+
+        assert!(BongoLiteral::Int(40000).as_disc_bytes(&BongoDataType::SmallInt).is_err());
+        assert!(BongoLiteral::Float(1.0).as_disc_bytes(&BongoDataType::Int).is_err());
+    }
+
+    #[test]
+    fn dates_and_times() {
+        let date = BongoLiteral::Date(Date::from_str("2024-02-29").unwrap());
+        let time = BongoLiteral::Time(Time::from_str("13:45:10.25").unwrap());
+        let timestamp = BongoLiteral::Timestamp(Timestamp::from_str("1900-12-31 23:59:59").unwrap());
+        let cases = [
+            (BongoDataType::Date, date.clone(), 5),
+            (BongoDataType::Time, time.clone(), 9),
+            (BongoDataType::Timestamp, timestamp.clone(), 9),
+            (BongoDataType::Timestamp, BongoLiteral::Null, 9),
+        ];
+        for (d_type, original, size) in cases {
+            let bytes = original.as_disc_bytes(&d_type).unwrap();
+            assert_eq!(size, bytes.len());
+            assert_eq!(size, d_type.disc_size());
+            assert_eq!(original, BongoLiteral::from_disc_bytes(&bytes, &d_type).unwrap());
+        }
+
+        let varchar = |s: &str| BongoLiteral::Varchar(s.to_string());
+        assert_eq!(Ok(date.clone()), BongoDataType::Date.convert(varchar("2024-02-29")));
+        assert_eq!(Ok(time.clone()), BongoDataType::Time.convert(varchar("13:45:10.25")));
+        assert_eq!(Ok(timestamp), BongoDataType::Timestamp.convert(varchar("1901-01-01 01:59:59+02:00")));
+        let midnight = BongoDataType::Timestamp.convert(date.clone()).unwrap();
+        assert_eq!(Ok(date.clone()), BongoDataType::Date.convert(midnight.clone()));
+        assert!(BongoDataType::Date.convert(varchar("2024-02-30")).is_err());
+        assert!(BongoDataType::Date.convert(BongoLiteral::Int(5)).is_err());
+        assert!(!BongoDataType::Time.can_store(&date));
+
+        assert_eq!(Some(Ordering::Equal), date.compare(&midnight));
+        assert_eq!(Some(Ordering::Less), midnight.compare(&BongoLiteral::Timestamp(Timestamp::from_str("2024-02-29 00:01").unwrap())));
+        assert_eq!(None, date.compare(&time));
+        assert_eq!(Some(Ordering::Equal), date.compare(&varchar("2024-02-29")));
+    }
+
+    #[test]
+    fn text_and_blob() {
+        let text = BongoLiteral::Varchar("x".repeat(1000));
+        let blob = BongoLiteral::Blob(vec![0, 255, 16]);
+        assert!(BongoDataType::Text.can_store(&text));
+        assert!(BongoDataType::Blob.can_store(&blob));
+        assert!(!BongoDataType::Text.can_store(&blob));
+        assert!(!BongoDataType::Varchar(10).can_store(&text));
+
+        // only NULL is stored inside of the row
+        for d_type in [BongoDataType::Text, BongoDataType::Blob] {
+            assert!(d_type.is_out_of_line());
+            let bytes = BongoLiteral::Null.as_disc_bytes(&d_type).unwrap();
+            assert_eq!(d_type.disc_size(), bytes.len());
+            assert_eq!(BongoLiteral::Null, BongoLiteral::from_disc_bytes(&bytes, &d_type).unwrap());
+        }
+        assert!(text.as_disc_bytes(&BongoDataType::Text).is_err());
+        assert!(blob.as_disc_bytes(&BongoDataType::Blob).is_err());
+
+        let varchar = |s: &str| BongoLiteral::Varchar(s.to_string());
+        assert_eq!(Ok(blob.clone()), BongoDataType::Blob.convert(varchar("\\x00ff10")));
+        assert_eq!(Ok(BongoLiteral::Blob(b"abc".to_vec())), BongoDataType::Blob.convert(varchar("abc")));
+        assert!(BongoDataType::Blob.convert(varchar("\\x0g")).is_err());
+        assert!(BongoDataType::Text.convert(BongoLiteral::Int(1)).is_err());
+        assert_eq!(Some(Ordering::Less), blob.compare(&BongoLiteral::Blob(vec![1])));
+    }
 }
@@ -8,7 +8,7 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::Data::{Enum, Struct, Union};
 use syn::Fields::{Named, Unit, Unnamed};
-use syn::{DeriveInput, Field};
+use syn::{DeriveInput, Field, Ident};
 
 pub fn create_drop_table(input: DeriveInput) -> TokenStream {
     let struct_ident = input.ident;
@@ -68,6 +68,11 @@ pub fn create_drop_table(input: DeriveInput) -> TokenStream {
     let (field_idents, field_types) =
         extract_data_from_fields(&named_fields, &named_fields_persistent_attr);
 
+    let primary_key_idents: Vec<String> = get_fields_with_attribute("PrimaryKey", &named_fields)
+        .iter()
+        .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+        .collect();
+
     let mut cols = String::new();
 
     for (ident, ty) in field_idents.iter().zip(field_types.iter()) {
@@ -82,24 +87,26 @@ pub fn create_drop_table(input: DeriveInput) -> TokenStream {
             .clone();
             cols.push_str(
                 format!(
-                    "{} {}, ",
+                    "{} {}{}, ",
                     ident,
                     match map_type_to_sql_type(&ty) {
                         Ok(string) => string,
                         Err(ts) => return ts,
-                    }
+                    },
+                    primary_key_suffix(ident, &primary_key_idents)
                 )
                 .as_str(),
             )
         } else {
             cols.push_str(
                 format!(
-                    "{} {}, ",
+                    "{} {}{}, ",
                     ident,
                     match map_type_to_sql_type(ty) {
                         Ok(string) => string,
                         Err(ts) => return ts,
-                    }
+                    },
+                    primary_key_suffix(ident, &primary_key_idents)
                 )
                 .as_str(),
             )
@@ -121,3 +128,15 @@ pub fn create_drop_table(input: DeriveInput) -> TokenStream {
     )
     .into()
 }
+
+///
+/// Returns the column constraint that declares `ident` as primary key, if the field has the
+/// `PrimaryKey` attribute, so that the server enforces the uniqueness of the key.
+///
+fn primary_key_suffix(ident: &Ident, primary_key_idents: &[String]) -> &'static str {
+    if primary_key_idents.contains(&ident.to_string()) {
+        " PRIMARY KEY"
+    } else {
+        ""
+    }
+}
@@ -50,8 +50,17 @@ pub fn map_type_to_sql_type(ty: &Type) -> Result<String, TokenStream> {
     .as_str()
     {
         "String" => Ok("VARCHAR(255)".to_string()),
-        "i64" => Ok("INT".to_string()),
+        "i16" => Ok("SMALLINT".to_string()),
+        "i32" => Ok("INT".to_string()),
+        "i64" => Ok("BIGINT".to_string()),
+        "f32" => Ok("REAL".to_string()),
+        "f64" => Ok("DOUBLE PRECISION".to_string()),
         "bool" => Ok("BOOLEAN".to_string()),
+        "Date" => Ok("DATE".to_string()),
+        "Time" => Ok("TIME".to_string()),
+        "Timestamp" => Ok("TIMESTAMP".to_string()),
+        // only `Vec<u8>` can be converted from a literal
+        "Vec" => Ok("BLOB".to_string()),
         _ => Err(syn::Error::new(ty.span(), "Type not supported.")
             .to_compile_error()
             .into()),
@@ -6,7 +6,7 @@ use syn::{parse_macro_input, DeriveInput};
 mod derives;
 mod helpers;
 
-#[proc_macro_derive(CreateDropTable, attributes(TableName, Persistent))]
+#[proc_macro_derive(CreateDropTable, attributes(TableName, Persistent, PrimaryKey))]
 pub fn derive_create_drop_table(input: TokenStream) -> TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
     derives::create_drop_table::create_drop_table(derive_input)
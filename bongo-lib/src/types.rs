@@ -1,2 +1,4 @@
 // re-exports
+pub use bongo_core::datetime::{Date, Interval, Time, Timestamp};
+pub use bongo_core::decimal::Decimal;
 pub use bongo_core::types::{BongoError, BongoLiteral, Row};
@@ -0,0 +1,424 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use bongo_core::decimal::Decimal;
+use bongo_core::types::{BongoError, BongoLiteral, Row};
+
+use crate::btree::compare_keys;
+use crate::join::resolve_col;
+use crate::sort::SortKey;
+use crate::statement::{Aggregate, AggregateFunc, BinOp, Expr, NullLogic, Order, Select, SelectItem};
+
+///
+/// `GroupQuery` is a `Select` statement with `GROUP BY`, `HAVING` or aggregate functions whose
+/// column names have been resolved against the columns of the joined rows.
+///
+/// Each group of rows is combined to one row, whose columns are the columns of `GROUP BY` followed
+/// by the aggregates that are selected or used in `HAVING`. `HAVING`, `ORDER BY` and the select
+/// items refer to the columns of these combined rows.
+///
+#[derive(Debug)]
+pub struct GroupQuery {
+    ///
+    /// The positions of the columns of `GROUP BY` in the joined rows.
+    ///
+    keys: Vec<usize>,
+    ///
+    /// The aggregates and the positions of the aggregated columns in the joined rows.
+    ///
+    aggregates: Vec<(Aggregate, Option<usize>)>,
+    ///
+    /// The names of the columns of the combined rows. Aggregates are named like they are written
+    /// in SQL with resolved column names, e.g. `SUM(table_1.col_1)`.
+    ///
+    cols: Vec<String>,
+    ///
+    /// The condition given by `HAVING` whose aggregates have been replaced by their columns.
+    ///
+    having: Option<Expr>,
+    ///
+    /// The computed select items, which refer to the columns of the combined rows. Their values are
+    /// appended to the combined rows.
+    ///
+    computed: Vec<Expr>,
+    ///
+    /// The positions of the selected columns in the combined rows with the computed values appended.
+    ///
+    pub projection: Vec<usize>,
+    ///
+    /// The keys to order the combined rows by.
+    ///
+    pub order: Vec<SortKey>,
+}
+
+impl GroupQuery {
+    ///
+    /// Resolves `select` against the columns `cols` of the joined rows. Returns `None` if the rows
+    /// of the statement are not combined.
+    ///
+    pub fn new(select: &Select, cols: &[String]) -> Result<Option<Self>, BongoError> {
+        if !select.is_aggregation() {
+            return Ok(None);
+        }
+
+        // unwrap safe, because all columns are resolved among `cols` before
+        let position = |col: &str| cols.iter().position(|c| c == col).unwrap();
+
+        let mut group_cols = vec![];
+        for name in &select.group_by {
+            let col = resolve_col(name, cols)?;
+            if !group_cols.contains(&col) {
+                group_cols.push(col);
+            }
+        }
+
+        let mut aggregates = vec![];
+        let mut add_aggregate = |aggregate: &Aggregate| -> Result<String, BongoError> {
+            let aggregate = Aggregate {
+                func: aggregate.func,
+                col: aggregate.col.as_ref().map(|col| resolve_col(col, cols)).transpose()?,
+            };
+            let name = aggregate.to_string();
+            if !aggregates.iter().any(|(other, _)| other == &aggregate) {
+                let col = aggregate.col.as_deref().map(position);
+                aggregates.push((aggregate, col));
+            }
+            Ok(name)
+        };
+
+        // `None` stands for the next computed select item
+        let mut selected = vec![];
+        let mut computed = vec![];
+        for item in &select.cols {
+            match item {
+                SelectItem::ColumnName(name) => selected.push(Some(Self::grouped_col(name, cols, &group_cols)?)),
+                SelectItem::Aggregate(aggregate) => selected.push(Some(add_aggregate(aggregate)?)),
+                SelectItem::Expr { expr, .. } => {
+                    computed.push(Self::resolve_expr(expr, cols, &group_cols, &mut add_aggregate)?);
+                    selected.push(None);
+                }
+                SelectItem::Wildcard | SelectItem::QualifiedWildcard(_) => {
+                    return Err(BongoError::UnsupportedFeatureError(
+                        "Wildcards cannot be selected together with GROUP BY or aggregate functions.".to_string()
+                    ));
+                }
+            }
+        }
+
+        let having = select.having.as_ref()
+            .map(|having| Self::resolve_expr(having, cols, &group_cols, &mut add_aggregate))
+            .transpose()?;
+
+        let keys = group_cols.iter().map(|col| position(col)).collect();
+        let group_row_cols = group_cols.into_iter()
+            .chain(aggregates.iter().map(|(aggregate, _)| aggregate.to_string()))
+            .collect::<Vec<String>>();
+        let group_position = |col: &str| group_row_cols.iter().position(|c| c == col).unwrap();
+
+        let mut next_computed = group_row_cols.len();
+        let projection = selected.iter()
+            .map(|col| match col {
+                Some(col) => group_position(col),
+                None => {
+                    next_computed += 1;
+                    next_computed - 1
+                }
+            })
+            .collect();
+
+        let mut order = vec![];
+        for Order { col, asc, nulls_first } in &select.order {
+            let col = match select.alias_position(col) {
+                Some(i) => group_row_cols.len() + i,
+                None => group_position(&Self::grouped_col(col, cols, &group_row_cols)?),
+            };
+            order.push(SortKey { col, asc: *asc, nulls_first: *nulls_first });
+        }
+
+        Ok(Some(Self { keys, aggregates, cols: group_row_cols, having, computed, projection, order }))
+    }
+
+    ///
+    /// Resolves the expression `expr` of `HAVING` or of a computed select item against the columns
+    /// of the combined rows. Its aggregates are added by `add_aggregate`, which returns the name of
+    /// their column.
+    ///
+    fn resolve_expr<F>(expr: &Expr, cols: &[String], group_cols: &[String], add_aggregate: &mut F) -> Result<Expr, BongoError>
+        where F: FnMut(&Aggregate) -> Result<String, BongoError> {
+        let expr = expr.clone().replace_aggregates(&mut |aggregate| Ok(Expr::Identifier(add_aggregate(&aggregate)?)))?;
+        expr.rename_identifiers(&|name| match name.contains('(') {
+            // aggregates have already been resolved
+            true => Ok(name.to_string()),
+            false => Self::grouped_col(name, cols, group_cols),
+        })
+    }
+
+    ///
+    /// Resolves the column `name` among `cols` and checks that it is one of the columns `group_cols`
+    /// by which the rows are grouped.
+    ///
+    fn grouped_col(name: &str, cols: &[String], group_cols: &[String]) -> Result<String, BongoError> {
+        let col = resolve_col(name, cols)?;
+        match group_cols.contains(&col) {
+            true => Ok(col),
+            false => Err(BongoError::SqlRuntimeError(format!(
+                "Column '{name}' must appear in GROUP BY or be used in an aggregate function."
+            ))),
+        }
+    }
+
+    ///
+    /// Combines the joined `rows` to one row per group and returns the combined rows for which
+    /// `HAVING` is true with the `NullLogic` `logic` in the order in which the groups first appear in `rows`.
+    ///
+    /// Without `GROUP BY` all rows form a single group, so exactly one row is returned even if
+    /// `rows` is empty.
+    ///
+    pub fn group(&self, rows: Vec<Row>, logic: NullLogic) -> Result<Vec<Row>, BongoError> {
+        let mut groups: Vec<(Row, Vec<Accumulator>)> = vec![];
+        let mut positions: HashMap<Row, usize> = HashMap::new();
+
+        if self.keys.is_empty() {
+            groups.push((vec![], self.accumulators()));
+            positions.insert(vec![], 0);
+        }
+
+        for row in rows {
+            let key = self.keys.iter().map(|i| row[*i].clone()).collect::<Row>();
+            let i = match positions.get(&key) {
+                Some(i) => *i,
+                None => {
+                    positions.insert(key.clone(), groups.len());
+                    groups.push((key, self.accumulators()));
+                    groups.len() - 1
+                }
+            };
+
+            for (accumulator, (_, col)) in groups[i].1.iter_mut().zip(&self.aggregates) {
+                accumulator.add(col.map(|col| &row[col]))?;
+            }
+        }
+
+        let mut result = vec![];
+        for (mut group_row, accumulators) in groups {
+            for accumulator in accumulators {
+                group_row.push(accumulator.finish()?);
+            }
+
+            if self.having.is_none() || self.having.as_ref().unwrap().eval(&group_row, &self.cols, logic)? {
+                let values = self.computed.iter()
+                    .map(|expr| expr.value(&group_row, &self.cols, logic))
+                    .collect::<Result<Row, BongoError>>()?;
+                group_row.extend(values);
+                result.push(group_row);
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn accumulators(&self) -> Vec<Accumulator> {
+        self.aggregates.iter().map(|(aggregate, _)| Accumulator::new(aggregate.func)).collect()
+    }
+
+    ///
+    /// Returns a description of the grouping, e.g. `Aggregate: COUNT(*) grouped by table_1.col_2`.
+    ///
+    pub fn describe(&self) -> Vec<String> {
+        let (group_cols, aggregates) = self.cols.split_at(self.keys.len());
+        let mut lines = vec![match group_cols.is_empty() {
+            true => format!("Aggregate: {}", aggregates.join(", ")),
+            false => format!("Aggregate: {} grouped by {}", aggregates.join(", "), group_cols.join(", ")),
+        }];
+        if let Some(having) = &self.having {
+            lines.push(format!("Having: {}", having));
+        }
+        lines
+    }
+}
+
+///
+/// `Accumulator` holds the intermediate value of an aggregate function for a group of rows.
+/// NULL values are ignored by all aggregate functions except `COUNT(*)`.
+///
+#[derive(Debug)]
+enum Accumulator {
+    Count(i64),
+    Sum(BongoLiteral),
+    ///
+    /// Integers and decimals are summed up in `sum`, which cannot overflow for any realistic amount
+    /// of integers, floating point values in `float_sum`. The average of integers is truncated to
+    /// an integer.
+    ///
+    Avg { sum: Decimal, float_sum: f64, count: i64, kind: AvgKind },
+    Min(BongoLiteral),
+    Max(BongoLiteral),
+}
+
+///
+/// The type of the values whose average is computed.
+///
+#[derive(Debug)]
+enum AvgKind {
+    Int,
+    Float,
+    Decimal,
+}
+
+impl Accumulator {
+    fn new(func: AggregateFunc) -> Self {
+        match func {
+            AggregateFunc::Count => Accumulator::Count(0),
+            AggregateFunc::Sum => Accumulator::Sum(BongoLiteral::Null),
+            AggregateFunc::Avg => Accumulator::Avg { sum: Decimal::from(0), float_sum: 0.0, count: 0, kind: AvgKind::Int },
+            AggregateFunc::Min => Accumulator::Min(BongoLiteral::Null),
+            AggregateFunc::Max => Accumulator::Max(BongoLiteral::Null),
+        }
+    }
+
+    ///
+    /// Adds the value of the aggregated column of a row. `val` is `None` for `COUNT(*)`.
+    ///
+    fn add(&mut self, val: Option<&BongoLiteral>) -> Result<(), BongoError> {
+        let val = match val {
+            None => {
+                if let Accumulator::Count(count) = self {
+                    *count += 1;
+                }
+                return Ok(());
+            }
+            Some(BongoLiteral::Null) => return Ok(()),
+            Some(val) => val,
+        };
+
+        match (self, val) {
+            (Accumulator::Count(count), _) => *count += 1,
+            (Accumulator::Sum(sum), BongoLiteral::Int(_) | BongoLiteral::Float(_) | BongoLiteral::Decimal(_)) => {
+                *sum = match &*sum {
+                    BongoLiteral::Null => val.clone(),
+                    sum => BinOp::Plus.apply_arithmetic(sum, val).map_err(|_| {
+                        BongoError::SqlRuntimeError("Overflow in SUM.".to_string())
+                    })?,
+                };
+            }
+            (Accumulator::Avg { sum, count, kind, .. }, BongoLiteral::Int(_) | BongoLiteral::Decimal(_)) => {
+                let val = match val {
+                    BongoLiteral::Int(val) => Decimal::from(*val),
+                    BongoLiteral::Decimal(val) => {
+                        *kind = AvgKind::Decimal;
+                        *val
+                    }
+                    _ => unreachable!("only integers and decimals are summed up as decimals"),
+                };
+                *sum = sum.checked_add(&val).ok_or_else(|| BongoError::SqlRuntimeError("Overflow in AVG.".to_string()))?;
+                *count += 1;
+            }
+            (Accumulator::Avg { float_sum, count, kind, .. }, BongoLiteral::Float(val)) => {
+                *kind = AvgKind::Float;
+                *float_sum += val;
+                *count += 1;
+            }
+            (Accumulator::Min(min), val) => {
+                if *min == BongoLiteral::Null || compare_keys(val, min) == Ordering::Less {
+                    *min = val.clone();
+                }
+            }
+            (Accumulator::Max(max), val) => {
+                if *max == BongoLiteral::Null || compare_keys(val, max) == Ordering::Greater {
+                    *max = val.clone();
+                }
+            }
+            (_, val) => {
+                return Err(BongoError::SqlRuntimeError(format!(
+                    "SUM and AVG can only be applied to numbers, but found {:?}.", val
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the value of the aggregate function. Except for `COUNT` the value is NULL if no
+    /// value other than NULL has been added.
+    ///
+    fn finish(self) -> Result<BongoLiteral, BongoError> {
+        Ok(match self {
+            Accumulator::Count(count) => BongoLiteral::Int(count),
+            Accumulator::Sum(sum) => sum,
+            Accumulator::Avg { count: 0, .. } => BongoLiteral::Null,
+            // the average of integers always fits into an integer
+            Accumulator::Avg { sum, count, kind: AvgKind::Int, .. } => BongoLiteral::Int((sum.mantissa() / count as i128) as i64),
+            Accumulator::Avg { sum, count, kind: AvgKind::Decimal, .. } => match sum.checked_div(&Decimal::from(count)) {
+                Some(avg) => BongoLiteral::Decimal(avg),
+                None => return Err(BongoError::SqlRuntimeError("Overflow in AVG.".to_string())),
+            },
+            Accumulator::Avg { float_sum, count, kind: AvgKind::Float, .. } => BongoLiteral::Float(float_sum / count as f64),
+            Accumulator::Min(val) | Accumulator::Max(val) => val,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bongo_core::types::BongoLiteral::{Int, Null, Varchar};
+
+    use crate::aggregate::GroupQuery;
+    use crate::sql_parser::parser::SqlParser;
+    use crate::statement::{NullLogic, Select, Statement};
+
+    fn select(sql: &str) -> Select {
+        match SqlParser::parse(sql).unwrap() {
+            Statement::Select(select) => select,
+            _ => panic!("not a select statement"),
+        }
+    }
+
+    fn cols() -> Vec<String> {
+        vec!["t.a".to_string(), "t.b".to_string()]
+    }
+
+    #[test]
+    fn no_aggregation() {
+        assert!(GroupQuery::new(&select("SELECT a FROM t;"), &cols()).unwrap().is_none());
+    }
+
+    #[test]
+    fn aggregates_ignore_null() {
+        let query = GroupQuery::new(&select("SELECT COUNT(*), COUNT(b), SUM(b), AVG(b), MIN(b), MAX(b) FROM t;"), &cols())
+            .unwrap().unwrap();
+
+        let rows = query.group(vec![vec![Int(1), Int(4)], vec![Int(1), Null], vec![Int(2), Int(-1)]], NullLogic::default()).unwrap();
+        assert_eq!(vec![vec![Int(3), Int(2), Int(3), Int(1), Int(-1), Int(4)]], rows);
+
+        // without GROUP BY a single row is returned for no rows
+        let rows = query.group(vec![], NullLogic::default()).unwrap();
+        assert_eq!(vec![vec![Int(0), Int(0), Null, Null, Null, Null]], rows);
+    }
+
+    #[test]
+    fn group_by_and_having() {
+        let query = GroupQuery::new(&select("SELECT MAX(b), a FROM t GROUP BY a HAVING COUNT(*) > 1;"), &cols())
+            .unwrap().unwrap();
+
+        let rows = query.group(vec![
+            vec![Null, Varchar("x".to_string())],
+            vec![Int(1), Varchar("y".to_string())],
+            vec![Null, Varchar("z".to_string())],
+            vec![Int(2), Varchar("x".to_string())],
+        ], NullLogic::default()).unwrap();
+
+        // NULL values form a group of their own, the selected columns are projected by the executor
+        assert_eq!(vec![vec![Null, Varchar("z".to_string()), Int(2)]], rows);
+        assert_eq!(vec![1, 0], query.projection);
+    }
+
+    #[test]
+    fn ungrouped_columns() {
+        assert!(GroupQuery::new(&select("SELECT a, COUNT(*) FROM t;"), &cols()).is_err());
+        assert!(GroupQuery::new(&select("SELECT a FROM t GROUP BY a HAVING b > 1;"), &cols()).is_err());
+        assert!(GroupQuery::new(&select("SELECT * FROM t GROUP BY a;"), &cols()).is_err());
+        assert!(GroupQuery::new(&select("SELECT a FROM t GROUP BY a ORDER BY b;"), &cols()).is_err());
+        assert!(GroupQuery::new(&select("SELECT SUM(a) FROM t GROUP BY a ORDER BY a;"), &cols()).is_ok());
+    }
+}
@@ -0,0 +1,525 @@
+use std::cmp::Ordering;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use bongo_core::types::{BongoError, BongoLiteral};
+use serde::{Deserialize, Serialize};
+
+///
+/// Size of a page of a B-tree file. Every node of the tree is stored in exactly one page.
+///
+const PAGE_SIZE: usize = 4096;
+
+///
+/// Size of the length prefix of a serialized node inside of its page.
+///
+const NODE_LEN_SIZE: usize = 4;
+
+const MAGIC: &[u8; 4] = b"BGBT";
+
+///
+/// The first page of a B-tree file is the header. It consists of the magic bytes, a flag that tells
+/// whether the file has been closed cleanly, the page of the root node and the amount of pages.
+///
+const HEADER_PAGE: u64 = 0;
+const HEADER_SIZE: usize = 4 + 1 + 8 + 8;
+
+///
+/// Keys of a B-tree index must not be larger on disc than this, so that a node that has overflown
+/// its page can always be split into two nodes that each fit into a page.
+///
+pub const MAX_KEY_SIZE: usize = 512;
+
+///
+/// An `Entry` of a B-tree is a key together with the position of a row version on disc.
+///
+/// Entries are unique, so that inserting or removing the same entry twice has the same effect as
+/// doing it once. This makes replaying the write-ahead log on a B-tree idempotent.
+///
+pub type Entry = (BongoLiteral, u64);
+
+///
+/// Compares two keys of an index. Unlike `PartialOrd` this defines a total order on all literals:
+/// Literals of the same variant are compared by their values, literals of different variants by
+/// the order of the variants. `Null` is greater than all other literals.
+///
+pub fn compare_keys(left: &BongoLiteral, right: &BongoLiteral) -> Ordering {
+    fn rank(literal: &BongoLiteral) -> u8 {
+        match literal {
+            BongoLiteral::Int(_) => 0,
+            BongoLiteral::Float(_) => 1,
+            BongoLiteral::Decimal(_) => 2,
+            BongoLiteral::Bool(_) => 3,
+            BongoLiteral::Varchar(_) => 4,
+            BongoLiteral::Date(_) => 5,
+            BongoLiteral::Time(_) => 6,
+            BongoLiteral::Timestamp(_) => 7,
+            BongoLiteral::Interval(_) => 8,
+            BongoLiteral::Blob(_) => 9,
+            BongoLiteral::Null => 10,
+        }
+    }
+
+    left.partial_cmp(right).unwrap_or_else(|| rank(left).cmp(&rank(right)))
+}
+
+fn compare_entries(left: &Entry, right: &Entry) -> Ordering {
+    compare_keys(&left.0, &right.0).then(left.1.cmp(&right.1))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+enum Node {
+    ///
+    /// Leaves hold the entries in ascending order and are linked to the next leaf in that order.
+    ///
+    Leaf { entries: Vec<Entry>, next: Option<u64> },
+    ///
+    /// `children[i]` holds all entries that are less than `keys[i]` and greater or equal to `keys[i - 1]`.
+    ///
+    Internal { keys: Vec<Entry>, children: Vec<u64> },
+}
+
+#[derive(Debug)]
+struct Pager {
+    file: File,
+    root: u64,
+    page_count: u64,
+    ///
+    /// `clean` is true as long as the file on disc has not been modified since it has been synced.
+    ///
+    clean: bool,
+}
+
+///
+/// `BTree` is an ordered index which is stored in its own file. Each node of the tree is read from
+/// and written to its page in the file directly, so that the index does not have to fit into memory.
+///
+/// Nodes are never merged when entries are removed, as the tree is rebuilt when the table is compacted.
+///
+/// The file is marked as unclean before the first modification after the last `sync`. A file that
+/// has not been synced after its last modification might be inconsistent after a crash and is
+/// rebuilt from the table data.
+///
+#[derive(Debug)]
+pub struct BTree {
+    pager: Mutex<Pager>,
+}
+
+impl BTree {
+    ///
+    /// Creates a new empty B-tree at `path`. An existing file at `path` is overwritten.
+    ///
+    pub fn create(path: &Path) -> Result<Self, BongoError> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path);
+        if file.is_err() {
+            return Err(BongoError::WriteFileError("Could not create file of B-tree index.".to_string()));
+        }
+
+        let mut pager = Pager { file: file.unwrap(), root: 1, page_count: 2, clean: false };
+        pager.write_header()?;
+        pager.write_node(1, &Node::Leaf { entries: vec![], next: None })?;
+
+        Ok(Self { pager: Mutex::new(pager) })
+    }
+
+    ///
+    /// Opens the B-tree at `path`. Returns `None` if the file does not exist, is corrupted or has
+    /// not been synced after its last modification.
+    ///
+    pub fn open(path: &Path) -> Result<Option<Self>, BongoError> {
+        let file = OpenOptions::new().read(true).write(true).open(path);
+        if file.is_err() {
+            return Ok(None);
+        }
+        let mut file = file.unwrap();
+
+        let mut header = [0u8; HEADER_SIZE];
+        if file.read_exact(&mut header).is_err() || &header[0..4] != MAGIC || header[4] != 1 {
+            return Ok(None);
+        }
+
+        let root = u64::from_be_bytes(header[5..13].try_into().unwrap());
+        let page_count = u64::from_be_bytes(header[13..21].try_into().unwrap());
+
+        Ok(Some(Self { pager: Mutex::new(Pager { file, root, page_count, clean: true }) }))
+    }
+
+    ///
+    /// Forces all modifications to disc and marks the file as clean.
+    ///
+    pub fn sync(&self) -> Result<(), BongoError> {
+        let mut pager = self.pager()?;
+        if pager.clean {
+            return Ok(());
+        }
+
+        if pager.file.sync_all().is_err() {
+            return Err(BongoError::WriteFileError("Could not force B-tree index to disc.".to_string()));
+        }
+        pager.clean = true;
+        pager.write_header()?;
+        if pager.file.sync_all().is_err() {
+            return Err(BongoError::WriteFileError("Could not force B-tree index to disc.".to_string()));
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Inserts the entry (`key`, `pos`) unless it is already contained.
+    ///
+    pub fn insert(&self, key: BongoLiteral, pos: u64) -> Result<(), BongoError> {
+        let mut pager = self.pager()?;
+        pager.mark_dirty()?;
+
+        let root = pager.root;
+        if let Some((key, right)) = pager.insert_into(root, (key, pos))? {
+            // the root has been split, so the tree grows by one level
+            let new_root = pager.allocate();
+            pager.write_node(new_root, &Node::Internal { keys: vec![key], children: vec![root, right] })?;
+            pager.root = new_root;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Removes the entry (`key`, `pos`) if it is contained.
+    ///
+    pub fn remove(&self, key: BongoLiteral, pos: u64) -> Result<(), BongoError> {
+        let mut pager = self.pager()?;
+        pager.mark_dirty()?;
+
+        let entry = (key, pos);
+        let (page, node) = pager.find_leaf(Some(&entry))?;
+        if let Node::Leaf { mut entries, next } = node {
+            if let Ok(i) = entries.binary_search_by(|e| compare_entries(e, &entry)) {
+                entries.remove(i);
+                pager.write_node(page, &Node::Leaf { entries, next })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns all entries whose keys lie within `lower` and `upper` in ascending order.
+    ///
+    pub fn range(&self, lower: Bound<&BongoLiteral>, upper: Bound<&BongoLiteral>) -> Result<Vec<Entry>, BongoError> {
+        let mut pager = self.pager()?;
+
+        // the leaf that contains the first entry that might lie within the bounds
+        let start = match lower {
+            Bound::Included(key) => Some((key.clone(), 0)),
+            Bound::Excluded(key) => Some((key.clone(), u64::MAX)),
+            Bound::Unbounded => None,
+        };
+        let (_, mut node) = pager.find_leaf(start.as_ref())?;
+
+        let mut result = vec![];
+        loop {
+            let (entries, next) = match node {
+                Node::Leaf { entries, next } => (entries, next),
+                Node::Internal { .. } => return Err(BongoError::InternalError("B-tree leaf links to internal node.".to_string())),
+            };
+
+            for entry in entries {
+                let above_lower = match lower {
+                    Bound::Included(key) => compare_keys(&entry.0, key) != Ordering::Less,
+                    Bound::Excluded(key) => compare_keys(&entry.0, key) == Ordering::Greater,
+                    Bound::Unbounded => true,
+                };
+                let above_upper = match upper {
+                    Bound::Included(key) => compare_keys(&entry.0, key) == Ordering::Greater,
+                    Bound::Excluded(key) => compare_keys(&entry.0, key) != Ordering::Less,
+                    Bound::Unbounded => false,
+                };
+
+                if above_upper {
+                    return Ok(result);
+                }
+                if above_lower {
+                    result.push(entry);
+                }
+            }
+
+            match next {
+                Some(page) => node = pager.read_node(page)?,
+                None => return Ok(result),
+            }
+        }
+    }
+
+    fn pager(&self) -> Result<MutexGuard<'_, Pager>, BongoError> {
+        let pager = self.pager.lock();
+        if pager.is_err() {
+            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        }
+        Ok(pager.unwrap())
+    }
+}
+
+impl Pager {
+    fn write_header(&mut self) -> Result<(), BongoError> {
+        let mut header = MAGIC.to_vec();
+        header.push(self.clean as u8);
+        header.extend_from_slice(&self.root.to_be_bytes());
+        header.extend_from_slice(&self.page_count.to_be_bytes());
+
+        self.write_page(HEADER_PAGE, &header)
+    }
+
+    ///
+    /// Marks the file as unclean on disc before it is modified for the first time after a sync.
+    ///
+    fn mark_dirty(&mut self) -> Result<(), BongoError> {
+        if !self.clean {
+            return Ok(());
+        }
+
+        self.clean = false;
+        self.write_header()?;
+        if self.file.sync_data().is_err() {
+            return Err(BongoError::WriteFileError("Could not force B-tree index to disc.".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn allocate(&mut self) -> u64 {
+        self.page_count += 1;
+        self.page_count - 1
+    }
+
+    fn fits(node: &Node) -> Result<bool, BongoError> {
+        match bincode::serialized_size(node) {
+            Ok(size) => Ok(size as usize <= PAGE_SIZE - NODE_LEN_SIZE),
+            Err(_) => Err(BongoError::InternalError("Could not serialize node of B-tree index.".to_string())),
+        }
+    }
+
+    ///
+    /// Returns the index at which `entries` are split, so that both halves have about the same
+    /// size on disc. Splitting by count instead could leave all large keys in one half.
+    ///
+    fn split_point(entries: &[Entry]) -> Result<usize, BongoError> {
+        let mut sizes = vec![];
+        for entry in entries {
+            match bincode::serialized_size(entry) {
+                Ok(size) => sizes.push(size),
+                Err(_) => return Err(BongoError::InternalError("Could not serialize entry of B-tree index.".to_string())),
+            }
+        }
+
+        let half = sizes.iter().sum::<u64>() / 2;
+        let mut size = 0;
+        let split = sizes.iter().take_while(|s| {
+            size += *s;
+            size <= half
+        }).count();
+
+        // both halves must contain at least one entry
+        Ok(split.clamp(1, entries.len() - 1))
+    }
+
+    fn read_node(&mut self, page: u64) -> Result<Node, BongoError> {
+        let mut bytes = vec![0u8; PAGE_SIZE];
+        if self.file.seek(SeekFrom::Start(page * PAGE_SIZE as u64)).is_err() {
+            return Err(BongoError::ReadFileError("Could not jump to page of B-tree index.".to_string()));
+        }
+        match self.file.read_exact(&mut bytes) {
+            Ok(_) => {}
+            // the last page is not padded to the full page size
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => {}
+            Err(_) => return Err(BongoError::ReadFileError("Could not read page of B-tree index.".to_string())),
+        }
+
+        let len = u32::from_be_bytes(bytes[0..NODE_LEN_SIZE].try_into().unwrap()) as usize;
+        if NODE_LEN_SIZE + len > PAGE_SIZE {
+            return Err(BongoError::ReadFileError("B-tree index is corrupted.".to_string()));
+        }
+        match bincode::deserialize(&bytes[NODE_LEN_SIZE..NODE_LEN_SIZE + len]) {
+            Ok(node) => Ok(node),
+            Err(_) => Err(BongoError::ReadFileError("B-tree index is corrupted.".to_string())),
+        }
+    }
+
+    fn write_node(&mut self, page: u64, node: &Node) -> Result<(), BongoError> {
+        let encoded = bincode::serialize(node);
+        if encoded.is_err() {
+            return Err(BongoError::InternalError("Could not serialize node of B-tree index.".to_string()));
+        }
+        let encoded = encoded.unwrap();
+
+        let mut bytes = (encoded.len() as u32).to_be_bytes().to_vec();
+        bytes.extend(encoded);
+
+        self.write_page(page, &bytes)
+    }
+
+    fn write_page(&mut self, page: u64, bytes: &[u8]) -> Result<(), BongoError> {
+        if bytes.len() > PAGE_SIZE {
+            return Err(BongoError::InternalError("Node of B-tree index does not fit into a page.".to_string()));
+        }
+        if self.file.seek(SeekFrom::Start(page * PAGE_SIZE as u64)).is_err() || self.file.write_all(bytes).is_err() {
+            return Err(BongoError::WriteFileError("Could not write page of B-tree index.".to_string()));
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the leaf that `entry` belongs to together with its page. If `entry` is `None` the
+    /// first leaf is returned.
+    ///
+    fn find_leaf(&mut self, entry: Option<&Entry>) -> Result<(u64, Node), BongoError> {
+        let mut page = self.root;
+
+        loop {
+            match self.read_node(page)? {
+                Node::Internal { keys, children } => {
+                    let i = match entry {
+                        Some(entry) => keys.partition_point(|key| compare_entries(key, entry) != Ordering::Greater),
+                        None => 0,
+                    };
+                    page = children[i];
+                }
+                leaf => return Ok((page, leaf)),
+            }
+        }
+    }
+
+    ///
+    /// Inserts `entry` into the subtree at `page`. If the node at `page` had to be split, the
+    /// smallest entry of the new right node is returned together with the page of the new node.
+    ///
+    fn insert_into(&mut self, page: u64, entry: Entry) -> Result<Option<(Entry, u64)>, BongoError> {
+        match self.read_node(page)? {
+            Node::Leaf { mut entries, next } => {
+                match entries.binary_search_by(|e| compare_entries(e, &entry)) {
+                    Ok(_) => return Ok(None),
+                    Err(i) => entries.insert(i, entry),
+                }
+
+                let node = Node::Leaf { entries, next };
+                if Self::fits(&node)? {
+                    self.write_node(page, &node)?;
+                    return Ok(None);
+                }
+
+                let (mut entries, next) = match node {
+                    Node::Leaf { entries, next } => (entries, next),
+                    Node::Internal { .. } => unreachable!(),
+                };
+                let right_entries = entries.split_off(Self::split_point(&entries)?);
+                let separator = right_entries[0].clone();
+                let right = self.allocate();
+
+                self.write_node(right, &Node::Leaf { entries: right_entries, next })?;
+                self.write_node(page, &Node::Leaf { entries, next: Some(right) })?;
+
+                Ok(Some((separator, right)))
+            }
+            Node::Internal { mut keys, mut children } => {
+                let i = keys.partition_point(|key| compare_entries(key, &entry) != Ordering::Greater);
+
+                let (key, child) = match self.insert_into(children[i], entry)? {
+                    Some(split) => split,
+                    None => return Ok(None),
+                };
+                keys.insert(i, key);
+                children.insert(i + 1, child);
+
+                let node = Node::Internal { keys, children };
+                if Self::fits(&node)? {
+                    self.write_node(page, &node)?;
+                    return Ok(None);
+                }
+
+                let (mut keys, mut children) = match node {
+                    Node::Internal { keys, children } => (keys, children),
+                    Node::Leaf { .. } => unreachable!(),
+                };
+                let mid = Self::split_point(&keys)?;
+                let right_keys = keys.split_off(mid + 1);
+                let right_children = children.split_off(mid + 1);
+                // the middle key moves up to the parent
+                let separator = keys.pop().unwrap();
+                let right = self.allocate();
+
+                self.write_node(right, &Node::Internal { keys: right_keys, children: right_children })?;
+                self.write_node(page, &Node::Internal { keys, children })?;
+
+                Ok(Some((separator, right)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::ops::Bound;
+    use std::path::PathBuf;
+
+    use bongo_core::types::BongoLiteral;
+
+    use crate::btree::BTree;
+
+    #[test]
+    fn insert_remove_and_range() {
+        let dir = PathBuf::from("test_temp/btree_insert_remove_and_range");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("idx.btree");
+
+        let result_range;
+        let result_all;
+        let result_reopened;
+        let result_unclean;
+
+        {
+            let tree = BTree::create(&path).unwrap();
+            // enough entries with long keys to split leaves and internal nodes
+            for i in (0..2000).rev() {
+                tree.insert(BongoLiteral::Varchar(format!("{:0>200}", i)), i).unwrap();
+            }
+            tree.insert(BongoLiteral::Null, 5000).unwrap();
+            // inserting an existing entry again has no effect
+            tree.insert(BongoLiteral::Varchar(format!("{:0>200}", 7)), 7).unwrap();
+            for i in 100..1900 {
+                tree.remove(BongoLiteral::Varchar(format!("{:0>200}", i)), i).unwrap();
+            }
+
+            let lower = BongoLiteral::Varchar(format!("{:0>200}", 50));
+            let upper = BongoLiteral::Varchar(format!("{:0>200}", 1950));
+            result_range = tree.range(Bound::Excluded(&lower), Bound::Included(&upper)).unwrap()
+                .into_iter()
+                .map(|(_, pos)| pos)
+                .collect::<Vec<u64>>();
+            result_all = tree.range(Bound::Unbounded, Bound::Unbounded).unwrap().len();
+            tree.sync().unwrap();
+        }
+
+        {
+            let tree = BTree::open(&path).unwrap().unwrap();
+            result_reopened = tree.range(Bound::Included(&BongoLiteral::Null), Bound::Unbounded).unwrap();
+            tree.remove(BongoLiteral::Null, 5000).unwrap();
+        }
+
+        // the tree has been modified without syncing it afterwards
+        result_unclean = BTree::open(&path).unwrap().is_none();
+
+        // clean up before assertion in case it panics
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut expected = (51..100).collect::<Vec<u64>>();
+        expected.extend(1900..1951);
+        assert_eq!(expected, result_range);
+        assert_eq!(201, result_all);
+        assert_eq!(vec![(BongoLiteral::Null, 5000)], result_reopened);
+        assert!(result_unclean);
+    }
+}
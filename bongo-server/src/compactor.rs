@@ -0,0 +1,153 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::executor::Executor;
+
+///
+/// The time the background compactor waits between two checks of the ghost ratios of all tables.
+///
+pub const AUTO_VACUUM_INTERVAL: Duration = Duration::from_secs(10);
+
+///
+/// The longest time the background compactor waits before it tries again to compact a table whose
+/// compaction has failed.
+///
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+///
+/// `Compactor` is the state of the background compactor of `BongoServer`.
+///
+/// A table whose compaction has failed is not compacted again until its backoff has passed, which
+/// doubles with every further failure up to `MAX_BACKOFF`. The failure is only reported once per
+/// table until the compaction of the table succeeds again.
+///
+pub struct Compactor {
+    ghost_ratio: f64,
+    ///
+    /// Maps the names of the tables whose last compaction has failed to the time at which their
+    /// compaction is tried again and their current backoff.
+    ///
+    backoffs: HashMap<String, (Instant, Duration)>,
+    ///
+    /// Whether the last run failed before any table could be compacted.
+    ///
+    failed: bool,
+}
+
+impl Compactor {
+    pub fn new(ghost_ratio: f64) -> Self {
+        Self { ghost_ratio, backoffs: HashMap::new(), failed: false }
+    }
+
+    ///
+    /// Compacts all tables of `ex` whose ghost ratio is reached and whose backoff has passed.
+    ///
+    pub fn run(&mut self, ex: &Executor) {
+        let now = Instant::now();
+        let waiting = self.backoffs.iter()
+            .filter(|(_, (next_try, _))| *next_try > now)
+            .map(|(name, _)| name.clone())
+            .collect::<HashSet<String>>();
+
+        let results = match ex.auto_vacuum(self.ghost_ratio, &waiting) {
+            Ok(results) => results,
+            Err(err) => {
+                if !self.failed {
+                    println!("background compaction failed: '{:?}'", err);
+                }
+                self.failed = true;
+                return;
+            }
+        };
+        self.failed = false;
+
+        for (name, result) in results {
+            match result {
+                Ok(()) => {
+                    if self.backoffs.remove(&name).is_some() {
+                        println!("background compaction of table '{}' succeeded again", name);
+                    }
+                }
+                Err(err) => {
+                    let backoff = match self.backoffs.get(&name) {
+                        Some((_, backoff)) => (*backoff * 2).min(MAX_BACKOFF),
+                        None => {
+                            println!(
+                                "background compaction of table '{}' failed: '{:?}'. It is retried with increasing delays.",
+                                name, err
+                            );
+                            AUTO_VACUUM_INTERVAL
+                        }
+                    };
+                    self.backoffs.insert(name, (now + backoff, backoff));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    use bongo_core::bongo_request::BongoRequest;
+
+    use crate::compactor::{AUTO_VACUUM_INTERVAL, Compactor};
+    use crate::executor::Executor;
+
+    #[test]
+    fn failing_table_backs_off() {
+        let db_root = PathBuf::from("test_temp/compactor_failing_table_backs_off");
+        let table_name = "table_1";
+        let data_path = db_root.join(table_name).join("data.bongo");
+        let requests = [
+            format!("CREATE TABLE {table_name} (col_1 INT);"),
+            format!("INSERT INTO {table_name} (col_1) VALUES (1), (2), (3), (4);"),
+            format!("DELETE FROM {table_name} WHERE col_1 = 4;"),
+        ];
+
+        let mut compactor = Compactor::new(0.25);
+        let first_backoff;
+        let waiting_backoff;
+        let second_backoff;
+        let recovered;
+
+        {
+            let mut ex = Executor::new(&db_root, true, true).unwrap();
+            for sql in requests {
+                ex.execute(&BongoRequest { sql }).unwrap();
+            }
+
+            // let the compaction of the table fail
+            fs::rename(&data_path, db_root.join("data.bongo")).unwrap();
+            fs::create_dir(&data_path).unwrap();
+
+            compactor.run(&ex);
+            first_backoff = compactor.backoffs[table_name].1;
+
+            // the table is not compacted again before its backoff has passed
+            compactor.run(&ex);
+            waiting_backoff = compactor.backoffs[table_name].1;
+
+            compactor.backoffs.get_mut(table_name).unwrap().0 = Instant::now();
+            compactor.run(&ex);
+            second_backoff = compactor.backoffs[table_name].1;
+
+            fs::remove_dir(&data_path).unwrap();
+            fs::rename(db_root.join("data.bongo"), &data_path).unwrap();
+            compactor.backoffs.get_mut(table_name).unwrap().0 = Instant::now();
+            compactor.run(&ex);
+            recovered = compactor.backoffs.is_empty();
+        } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+        // clean up before assertion in case it panics
+        fs::remove_dir_all(&db_root).unwrap();
+
+        assert_eq!(AUTO_VACUUM_INTERVAL, first_backoff);
+        assert_eq!(AUTO_VACUUM_INTERVAL, waiting_backoff);
+        assert_eq!(AUTO_VACUUM_INTERVAL * 2, second_backoff);
+        assert!(recovered);
+    }
+}
@@ -1,1745 +1,7426 @@
-use std::cell::RefCell;
-use std::cmp::Ordering::Equal;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
 use std::fs::{File, OpenOptions};
-use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::ops::Deref;
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
-use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use bongo_core::bongo_request::BongoRequest;
 use bongo_core::bongo_result::BongoResult;
 use bongo_core::bytes_on_disc::{AsDiscBytes, FromDiscBytes};
 use bongo_core::conversions::TryConvertAllExt;
-use bongo_core::types::{BongoError, BongoLiteral, ColumnDef, GetColNamesExt as GetColNamesExtCore, GetDTypesExt, Row};
+use bongo_core::types::{BongoDataType, BongoError, BongoLiteral, ColumnDef, GetColNamesExt as GetColNamesExtCore, GetDTypesExt, Row};
 use bongo_core::types::BongoError::{InternalError, ReadFileError};
 use serde::{Deserialize, Serialize};
-
+use webserver::ConnectionId;
+
+use crate::aggregate::GroupQuery;
+use crate::btree::MAX_KEY_SIZE;
+use crate::format::{DatabaseInfo, FORMAT_VERSION, LegacyTable};
+use crate::heap::{copy_values, Heap, HEAP_FILE_NAME};
+use crate::index::{Index, IndexDef, IndexKind};
+use crate::join::{Candidates, join_key, join_rows, JoinPlan, JoinQuery, JoinStrategy, unqualify_col};
+use crate::planner::QueryPlan;
+use crate::sequence::{Sequence, Sequences};
+use crate::sort::{compare_rows, sort_rows};
 use crate::sql_parser::err_messages::generic_write_error;
 use crate::sql_parser::parser::SqlParser;
-use crate::statement::{ApplyAssignments, BinOp, CreateTable, Delete, DropTable, Expr, GetColNamesExt as GetColNamesExtServer, Insert, Order, Select, SelectItem, Statement, Update};
+use crate::statement::{AlterTable, AlterTableOperation, ApplyAssignments, Assignment, CreateIndex, CreateSequence, CreateTable, Delete, DropIndex, DropSequence, DropTable, Explain, Expr, fmt_literal, ForeignKey, GetColNamesExt as GetColNamesExtServer, Insert, Join, NullLogic, ReferentialAction, Select, Statement, UniqueConstraint, Update, Vacuum};
+use crate::transaction::{NO_TXN, Snapshot, Transaction, TransactionId};
+use crate::wal::{WalOp, WalRecord, WriteAheadLog};
+
+///
+/// Every row in data.bongo is prefixed with a slot header of this size. The first byte tells whether
+/// the slot holds a version of a row (`SLOT_LIVE`) or is unused (`SLOT_FREE`). It is followed by the
+/// id of the transaction that created the version and the id of the transaction that deleted it,
+/// each as 8 byte big endian number. A deleting transaction id of `NO_TXN` means that the version has
+/// not been deleted. This allows to rebuild the meta data of a table from data.bongo alone.
+///
+const SLOT_HEADER_SIZE: usize = 17;
+const SLOT_LIVE: u8 = 1;
+const SLOT_FREE: u8 = 0;
+///
+/// Offset of the deleting transaction id inside of the slot header.
+///
+const SLOT_DELETED_OFFSET: u64 = 9;
+///
+/// The id of the transaction that has created the rows of tables migrated from format version 0,
+/// see `FORMAT_VERSION`.
+///
+const MIGRATED_TXN: TransactionId = 1;
+
+///
+/// The connection that `Executor::execute` executes statements for.
+///
+const DEFAULT_CONNECTION: ConnectionId = ConnectionId::MAX;
+
+///
+/// The maximum time a statement waits for a table that is locked by a transaction of another connection.
+///
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+///
+/// `Schema` is the definition of a table and of its indexes. It is stored in schema.bongo, so that
+/// the meta data of a table can be rebuilt from data.bongo if meta.bongo is lost.
+///
+#[derive(Serialize, Deserialize, Debug)]
+struct Schema {
+    pub cols: Vec<ColumnDef>,
+    pub indexes: Vec<IndexDef>,
+    pub checks: Vec<Expr>,
+    pub uniques: Vec<UniqueConstraint>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+impl Schema {
+    ///
+    /// Returns the definition of a new table, which has the indexes of its `PRIMARY KEY` and
+    /// `UNIQUE` constraints only.
+    ///
+    fn create(cols: Vec<ColumnDef>, checks: Vec<Expr>, uniques: Vec<UniqueConstraint>, foreign_keys: Vec<ForeignKey>) -> Self {
+        Schema { cols, indexes: uniques.iter().map(UniqueConstraint::index_def).collect(), checks, uniques, foreign_keys }
+    }
+}
 
 ///
 /// `TableMetaData` stores all information about a table in the current state except the actual user data
 /// i.e. the rows of the tables. The user data is stored in a file on disk.
 ///
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct TableMetaData {
     ///
     /// `cols` is the definition of columns in the table given by the user in the CREATE TABLE statement.
     ///
     pub cols: Vec<ColumnDef>,
     ///
-    /// `idx` is a tuple of `String` and `HashMap<BongoLiteral, Vec<u64>>`.
-    /// The `idx.0` specifies the name of the indexed column.
-    /// the `idx.1` maps a BongoLiteral, which is the content of an entry for a column, to indices of
-    /// the start of rows on disc on disc. That means if we read exactly as many bytes as a row in
-    /// that table is long at one of those indices, we will get back exactly one row.
-    /// If the the Vec has length > 1 it means that there was a hash collision which requires a linear search.
-    /// The Vec always has length >= 1 because the HashMap entry will be removed if it has length == 0.
+    /// `indexes` are the hash and B-tree indexes that have been created on columns of the table
+    /// with CREATE INDEX statements. The entries of B-tree indexes are not part of the meta data.
     ///
-    // TODO: LOW_PRIO: allow multiple indexes and set them with CREATE INDEX statement
-    pub idx: (String, HashMap<BongoLiteral, Vec<u64>>),
+    pub indexes: Vec<Index>,
     ///
-    /// `ghosts` is a list of row indices marked as unused. Unused rows result from DELETE operations.
+    /// `checks` are the conditions of the `CHECK` constraints of the table, which every row has to
+    /// satisfy. The `NOT NULL` constraints are part of `cols`.
     ///
-    pub ghosts: Vec<u64>,
+    pub checks: Vec<Expr>,
     ///
-    /// The size in bytes which one row occupies.
+    /// `uniques` are the `PRIMARY KEY` and `UNIQUE` constraints of the table. Each of them is
+    /// backed by one of `indexes`.
     ///
-    pub row_size: usize,
+    pub uniques: Vec<UniqueConstraint>,
     ///
-    /// The amount of rows in the table
+    /// `foreign_keys` are the `FOREIGN KEY` constraints of the table. The constraints that reference
+    /// this table are stored in the meta data of the referencing tables.
     ///
-    pub row_count: usize,
-}
-
-impl TableMetaData {
+    pub foreign_keys: Vec<ForeignKey>,
     ///
-    /// returns whether a specified `Row` can be stored in this table.
-    /// To be able to store the `Row` the datatypes and order must match.
+    /// `serials` maps the name of each `SERIAL` column to the greatest value that has been stored
+    /// in it so far. A row inserted without a value for the column gets the value after it. Values
+    /// are never given out twice, even if the rows that have used them are deleted. Columns
+    /// without an entry have not stored any positive value yet.
     ///
-    pub fn can_store(&self, row: &Row) -> bool {
-        if self.cols.len() != row.len() {
-            return false;
-        }
-        for (i, item) in row.iter().enumerate() {
-            if !self.cols[i].data_type.can_store(item) {
-                return false;
-            }
-        }
-
-        true
-    }
-}
-
-///
-/// `Table` is used to store the name of a table together with its meta data.
-/// This structure is used to easily be inserted into a HashMap
-///
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
-struct Table((String, TableMetaData));
-
-///
-/// An `Executor` can execute a `BongoRequest`
-///
-pub struct Executor {
+    pub serials: HashMap<String, i64>,
     ///
-    /// `db_dir` is the root directory of the database storage.
+    /// `ghosts` is a list of row indices marked as unused. Unused rows result from rolled back insertions.
     ///
-    db_root: PathBuf,
+    pub ghosts: Vec<u64>,
     ///
-    /// `tables` maps table names to their meta data.
+    /// `expired` is a list of row indices of row versions that have been deleted by DELETE or UPDATE
+    /// statements. They stay in the indexes, because readers with an older snapshot might still see
+    /// them, until they are reclaimed by new row versions or by compacting the table.
     ///
-    /// We use a 2-level-RwLock-system allowing for the maximum possible degree of parallelism while
-    /// still keeping thread-safety.
+    pub expired: Vec<u64>,
     ///
-    /// 1st level RwLock (outer lock):
-    /// - A read lock on this means that we have immutable access to the HashMap which means we
-    ///     cannot remove or add any tables. However, we still have mutable access to the tables themselves
-    ///     as those are wrapped inside a RefCell.
-    ///     The execution of the following statements requires acquiring a read lock on the first level:
-    ///         SELECT, INSERT, UPDATE, DELETE
-    /// - A write lock on this means that we have mutable access on the entire HashMap allowing us to
-    ///     add and remove tables. This also means we guarantee that no other statement is executed at
-    ///     this time.
-    ///     The execution of the following statements requires acquiring a write lock on the first level:
-    ///         CREATE TABLE, DROP TABLE, FLUSH
+    /// The size in bytes which one row occupies including its slot header.
     ///
-    /// 2nd level RwLock (inner lock):
-    /// - A read lock on this means that we have immutable access to the tables which means we cannot
-    ///     modify anything at all.
-    ///     The execution of the following statements requires acquiring a read lock on the second level:
-    ///         SELECT
-    /// - A write lock on this means that we have mutable access to exactly ONE table and that no other thread
-    ///     currently has access to this table in any way.
-    ///     The execut  ion of the following statements requires acquiring a write lock on the second level:
-    ///         INSERT, UPDATE, DELETE
+    pub row_size: usize,
     ///
-    /// Lets compare this to using a single RwLock system:
-    /// Single-level RwLock:
-    /// - Enables multiple parallel SELECTs OR
-    ///     - block any other statement except SELECT no matter the table
-    /// - exactly one  CREATE TABLE, DROP TABLE, FLUSH, INSERT, UPDATE, DELETE
-    ///     - block any other statement
+    /// The amount of row versions on disc including expired ones.
     ///
-    /// 2-level RwLock (used here):
-    /// - Enables multiple parallel SELECTs
-    ///     - blocks only INSERTs, UPDATEs, DELETEs on this specific and CREATE TABLE, DROP TABLE, FLUSH
-    /// - Enables multiple parallel INSERTs, UPDATEs, DELETEs as long as they are on different tables
-    ///     - blocks only any other statement on this specific table
-    /// - exclusive access required for CREATE TABLE, DROP TABLE, FLUSH
-    ///     - blocks any other statements
+    pub row_count: usize,
     ///
-    /// So over all we get more parallelism while keeping thread safety, becasue we only lock what really
-    /// has to be locked.
-    tables: RwLock<HashMap<String, RefCell<RwLock<TableMetaData>>>>,
+    /// The greatest transaction id that has been written to data.bongo. Transaction ids given out
+    /// after a restart must be greater than all ids on disc.
     ///
-    /// `auto_flush` == true means that a flush shall be triggered after every other command except flush itself.
+    pub max_txn: TransactionId,
     ///
-    auto_flush: bool,
+    /// The log sequence number of the last `WalRecord` whose operations are reflected in this meta data.
+    /// When replaying the write-ahead log only records with a greater number are applied to the table.
+    ///
+    pub lsn: u64,
+    ///
+    /// `owner` is the connection whose transaction has modified this table. No other connection
+    /// can access the table until that transaction is finished.
+    ///
+    #[serde(skip)]
+    pub owner: Option<ConnectionId>,
+    ///
+    /// `latch` is held while slots of data.bongo are read or written, so that readers which do not
+    /// hold the lock on the meta data never see partially written slots.
+    ///
+    #[serde(skip)]
+    pub latch: Arc<RwLock<()>>,
 }
 
-// Executor internally ensures by its logic and by using RwLock that it is safe to use from different threads.
-unsafe impl Send for Executor {}
+///
+/// `SlotHeader` is the decoded header of a slot in data.bongo.
+///
+struct SlotHeader {
+    pub live: bool,
+    pub created: TransactionId,
+    pub deleted: TransactionId,
+}
 
-// Executor internally ensures by its logic and by using RwLock that it is safe to use from different threads.
-unsafe impl Sync for Executor {}
+impl SlotHeader {
+    fn from_disc_bytes(bytes: &[u8]) -> Self {
+        Self {
+            live: bytes[0] == SLOT_LIVE,
+            created: TransactionId::from_be_bytes(bytes[1..9].try_into().unwrap()),
+            deleted: TransactionId::from_be_bytes(bytes[9..17].try_into().unwrap()),
+        }
+    }
 
-impl Drop for Executor {
-    fn drop(&mut self) {
-        // there is nothing we can do for the user if this final flush does not work but panic.
-        self.flush().unwrap();
+    fn as_disc_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![if self.live { SLOT_LIVE } else { SLOT_FREE }];
+        bytes.extend_from_slice(&self.created.to_be_bytes());
+        bytes.extend_from_slice(&self.deleted.to_be_bytes());
+        bytes
     }
 }
 
-impl Executor {
-    pub fn new<P>(db_root: &P, create_db: bool, auto_flush: bool) -> Result<Self, BongoError>
-        where P: AsRef<Path> + std::convert::AsRef<std::ffi::OsStr> + ?Sized {
-        let path_buf = Self::get_db_root_dir(db_root, create_db)?;
+///
+/// `SlotReader` reads the row versions of a table from its data.bongo file.
+///
+/// It does not require any lock on the meta data of the table, so that long running reads do not
+/// block statements that modify the table.
+///
+struct SlotReader {
+    file: File,
+    heap: Heap,
+    buffer: Vec<u8>,
+    latch: Arc<RwLock<()>>,
+}
+
+impl SlotReader {
+    fn open(data_path: &Path, table: &TableMetaData) -> Result<Self, BongoError> {
+        let file = File::open(data_path);
+        if file.is_err() {
+            return Err(BongoError::ReadFileError("Could not get read access to file on disc".to_string()));
+        }
+
         Ok(Self {
-            tables: RwLock::new(Self::load_tables_from_disc(&path_buf)?),
-            db_root: path_buf,
-            auto_flush,
+            file: file.unwrap(),
+            heap: Heap::new(&data_path.with_file_name(HEAP_FILE_NAME)),
+            buffer: vec![0; table.row_size],
+            latch: table.latch.clone(),
         })
     }
 
-    fn get_db_root_dir<P>(root_dir: &P, create_db: bool) -> Result<PathBuf, BongoError>
-        where P: AsRef<Path> + std::convert::AsRef<std::ffi::OsStr> + ?Sized {
-        let path = AsRef::<Path>::as_ref(root_dir);
-        if !path.exists() {
-            if create_db {
-                if fs::create_dir_all(path).is_err() {
-                    return Err(BongoError::WriteFileError("could not create root directory for DB".to_string()));
-                }
-            } else {
-                // unwrap is safe, because BongoServer makes sure only utf8 directories are passed in
-                return Err(BongoError::DatabaseNotFoundError(format!(
-                    "location: '{}'",
-                    location = path.to_str().unwrap()
-                )));
-            }
+    ///
+    /// Returns the row version at `pos` if it is visible to `snapshot`.
+    ///
+    /// A slot that does not exist anymore, because the insertion of the version has been rolled
+    /// back in the meantime, is treated like a free slot.
+    ///
+    fn read(&mut self, pos: u64, d_types: &[&BongoDataType], snapshot: &Snapshot) -> Result<Option<Row>, BongoError> {
+        if !self.read_slot(pos)? {
+            return Ok(None);
         }
 
-        // safe because canonicalize will be valid if root_dir exists is valid, which we assure
-        Ok(PathBuf::from(path).canonicalize().unwrap())
-    }
-
-    fn load_tables_from_disc(root_dir: &Path) -> Result<HashMap<String, RefCell<RwLock<TableMetaData>>>, BongoError> {
-        //
-        // 1. if root_dir it does not exists and create_db is true create the directory
-        // 2. load files from disc for each directory entry into a HashMap
-        //
-        match root_dir.read_dir() {
-            Ok(entries) => {
-                Ok(entries
-                    .map(|entry_res| {
-                        match entry_res {
-                            Ok(entry) => Self::load_table_from_disc(entry.path()),
-                            Err(_) => Err(BongoError::ReadFileError(
-                                "Cannot read file inside DB root".to_string(),
-                            )),
-                        }
-                    })
-                    .collect::<Vec<Result<(String, RefCell<RwLock<TableMetaData>>), BongoError>>>()
-                    // fail fast unwrap of all contained elements and in error case bubble up error
-                    .try_convert_all(|t| t)?
-                    // back to iterator in order to convert vec of tuples to map
-                    .into_iter()
-                    .collect())
-            }
-            Err(_) => Err(BongoError::ReadFileError(
-                "Cannot read files inside DB root".to_string(),
-            )),
+        let header = SlotHeader::from_disc_bytes(&self.buffer);
+        if !header.live || !snapshot.sees_version(header.created, header.deleted) {
+            return Ok(None);
         }
+
+        // values are only appended to the heap, so they can be read without holding the latch
+        Ok(Some(self.heap.row_from_disc_bytes(&self.buffer[SLOT_HEADER_SIZE..], d_types)?))
     }
 
     ///
-    /// Loads a  key value pair of table name and `TableMetaData` from a file in a given directory.
+    /// Returns the row version at `pos` if it has been deleted by a transaction before `horizon`,
+    /// so that its slot can be reused, see `Executor::reclaim_horizon`.
     ///
-    fn load_table_from_disc(mut table_dir: PathBuf) -> Result<(String, RefCell<RwLock<TableMetaData>>), BongoError> {
-        //
-        // 1. read meta.bongo file content to end
-        // 2. deserialize content into a tuple `String, RwLock<TableMetaData)`
-        //
-        let mut data = Vec::new();
-        table_dir.push("meta.bongo");
-
-        match File::open(&table_dir) {
-            Ok(mut file) => match file.read_to_end(&mut data) {
-                Ok(_) => match bincode::deserialize(&data[..]) {
-                    Ok(table) => Ok(table),
-                    Err(_) => Err(BongoError::InternalError(format!(
-                        "Meta table file corrupted and cannot be deserialized at '{}'",
-                        table_dir.to_str().unwrap()
-                    ))),
-                },
-                Err(_) => Err(BongoError::ReadFileError(format!(
-                    "Could not read meta table information from file at '{}'",
-                    table_dir.to_str().unwrap()
-                ))),
-            },
-            Err(_) => Err(BongoError::ReadFileError(format!(
-                "Could not open meta table file for reading at '{}'",
-                table_dir.to_str().unwrap()
-            ))),
+    fn read_reclaimable(&mut self, pos: u64, d_types: &[&BongoDataType], horizon: TransactionId) -> Result<Option<Row>, BongoError> {
+        if !self.read_slot(pos)? {
+            return Ok(None);
+        }
+
+        let header = SlotHeader::from_disc_bytes(&self.buffer);
+        if !header.live || header.deleted == NO_TXN || header.deleted >= horizon {
+            return Ok(None);
         }
+
+        Ok(Some(self.heap.row_from_disc_bytes(&self.buffer[SLOT_HEADER_SIZE..], d_types)?))
     }
 
     ///
-    /// Executes a `BongoRequest` by first parsing it and then executing its contents.
-    /// returns a `BongoResult` representing the result of execution.
+    /// Reads the slot at `pos` into the buffer. Returns false if the slot does not exist.
     ///
-    pub fn execute(&mut self, request: &BongoRequest) -> BongoResult {
-        let statement = SqlParser::parse(&request.sql)?;
+    fn read_slot(&mut self, pos: u64) -> Result<bool, BongoError> {
+        let latch = self.latch.read();
+        if latch.is_err() {
+            return Err(InternalError("Concurrency Error.".to_string()));
+        }
 
-        println!(
-            "sql has been parsed with the following resulting statement:\n{:?}",
-            statement
-        );
+        if self.file.seek(SeekFrom::Start(pos)).is_err() {
+            return Err(ReadFileError("Could not jump to correct position in file".to_string()));
+        }
+        match self.file.read_exact(&mut self.buffer) {
+            Ok(_) => Ok(true),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => Ok(false),
+            Err(_) => Err(BongoError::ReadFileError("Could not read row from disc".to_string())),
+        }
+    }
+}
 
-        let must_flush = self.auto_flush && !matches!(&statement, Statement::Select(_) | Statement::Flush);
+///
+/// Returns an error if `row` violates a `NOT NULL` constraint of `cols` or one of the `CHECK`
+/// constraints `checks`, see `TableMetaData::can_store`.
+///
+fn check_constraints(table_name: &str, cols: &[ColumnDef], checks: &[Expr], row: &Row) -> Result<(), BongoError> {
+    for (val, col_def) in row.iter().zip(cols) {
+        if col_def.not_null && *val == BongoLiteral::Null {
+            return Err(BongoError::ConstraintViolationError(format!(
+                "The column '{}' of the table '{}' is NOT NULL, but the row has NULL in it.", col_def.name, table_name
+            )));
+        }
+    }
 
-        let result = match statement {
-            Statement::Select(select) => self.select(select),
-            Statement::Insert(insert) => self.insert(insert),
-            Statement::Update(update) => self.update(update),
-            Statement::Delete(delete) => self.delete(delete),
-            Statement::CreateTable(create_table) => self.create_table(create_table),
-            Statement::DropTable(drop_table) => self.drop_table(drop_table),
-            Statement::Flush => self.flush(),
-            Statement::CreateDB { .. } | Statement::DropDB { .. } => Self::create_drop_db(),
-        };
+    let col_names = cols.get_col_names();
+    for check in checks {
+        // constraints are checked with the logic of standard SQL, whatever logic queries use
+        if check.value(row, &col_names, NullLogic::ThreeValued)? == BongoLiteral::Bool(false) {
+            return Err(BongoError::ConstraintViolationError(format!(
+                "The row violates the CHECK constraint '{}' of the table '{}'.", check, table_name
+            )));
+        }
+    }
 
-        if must_flush { self.flush()?; }
+    Ok(())
+}
 
-        result
+///
+/// Returns the values of `row` in the columns at `positions`, which are the columns of a unique
+/// constraint, or `None` if one of the values is NULL and the row is therefore not compared.
+///
+fn unique_key(positions: &[usize], row: &Row) -> Option<Row> {
+    let key = positions.iter().map(|pos| row[*pos].clone()).collect::<Row>();
+    match key.contains(&BongoLiteral::Null) {
+        true => None,
+        false => Some(key),
     }
+}
 
-    // TODO: flush has a concurrency related bug that shows up in the tests of ../examples-and-tests-cli
-    //  The issue comes only up if all tests are ran in parallel and the server is running on auto_flush.
-    ///
-    /// A `Flush` statement is executed as follows.
-    ///
-    /// for each table:
-    ///  1. serialize table
-    ///  2. check that directory for table exists
-    ///  3. check if meta data file already exists and delete if so (effectively overwrite it)
-    ///  4. (re-)create meta data file
-    ///  5. write table to file
-    ///
-    fn flush(&mut self) -> BongoResult {
-        // this function requires write access on the whole hash map which means there can not be
-        // any other concurrent statement running. This is because this function will modify the disc
-        // directly which must be synchronized.
+fn unique_violation(table_name: &str, unique: &UniqueConstraint, key: &Row) -> BongoError {
+    BongoError::UniqueViolationError(format!(
+        "The row violates the constraint '{}' of the table '{}', because ({}) = ({}) already exists.",
+        unique.name,
+        table_name,
+        unique.cols.join(", "),
+        key.iter().map(fmt_literal).collect::<Vec<String>>().join(", ")
+    ))
+}
 
-        let db_root = self.db_root.clone(); // access here before mut ref access to self is required
+fn missing_reference(table_name: &str, foreign_key: &ForeignKey, key: &Row) -> BongoError {
+    BongoError::ForeignKeyViolationError(format!(
+        "The row violates the foreign key '{}' of the table '{}', because ({}) = ({}) does not exist in the table '{}'.",
+        foreign_key.name,
+        table_name,
+        foreign_key.cols.join(", "),
+        key.iter().map(fmt_literal).collect::<Vec<String>>().join(", "),
+        foreign_key.table
+    ))
+}
 
-        for table in self.tables_write_access()?.iter() {
-            let encoded = bincode::serialize(&table);
-            if encoded.is_err() { return Err(BongoError::InternalError("Could not write cashed state to disc.".to_string())); }
-            let encoded = encoded.unwrap();
+fn remaining_reference(table_name: &str, foreign_key: &ForeignKey, key: &Row) -> BongoError {
+    BongoError::ForeignKeyViolationError(format!(
+        "The row of the table '{}' with ({}) = ({}) is still referenced by the foreign key '{}' of the table '{}'.",
+        foreign_key.table,
+        foreign_key.ref_cols.join(", "),
+        key.iter().map(fmt_literal).collect::<Vec<String>>().join(", "),
+        foreign_key.name,
+        table_name
+    ))
+}
 
-            let mut location = db_root.clone();
-            location.push(table.0);
+///
+/// The tables a statement has locked for writing by their names.
+///
+type LockedTables<'a> = BTreeMap<String, RwLockWriteGuard<'a, TableMetaData>>;
 
-            if !location.is_dir() {
-                return Err(BongoError::InternalError("Table to flush has no directory on disc".to_string()));
-            }
+///
+/// The positions on disc for new row versions together with the expired versions whose slots are
+/// reclaimed for them, see `Executor::new_positions`.
+///
+type NewPositions = (Vec<(u64, bool)>, Vec<(u64, Row)>);
 
-            location.push("meta.bongo");
+///
+/// The name of each table that has been compacted by `Executor::auto_vacuum` together with the
+/// result of its compaction.
+///
+pub type Compactions = Vec<(String, Result<(), BongoError>)>;
 
-            if location.exists() {
-                if location.is_file() {
-                    // File exists from last flush
-                    if fs::remove_file(&location).is_err() {
-                        return Err(BongoError::WriteFileError("Could not delete outdated meta data file on disc.".to_string()));
-                    }
-                } else {
-                    return Err(BongoError::InternalError("Directory of table to flush contains invalid files.".to_string()));
-                }
-            }
+///
+/// `RowChanges` are the row versions that a statement deletes and inserts, grouped by table.
+///
+/// Foreign keys are checked against the state of the tables after all changes of the statement,
+/// so that e.g. a row can reference a row that is inserted by the same statement.
+///
+#[derive(Default)]
+struct RowChanges {
+    ///
+    /// `deleted` maps tables to the positions and values of the row versions that are deleted.
+    ///
+    deleted: HashMap<String, BTreeMap<u64, Row>>,
+    ///
+    /// `inserted` maps tables to the new row versions.
+    ///
+    inserted: HashMap<String, Vec<Row>>,
+}
 
-            let file = File::create(&location);
-            if file.is_err() { return Err(BongoError::WriteFileError("Could not create meta table file on disc.".to_string())); }
-            let mut file = file.unwrap();
+impl RowChanges {
+    fn is_deleted(&self, table_name: &str, pos: u64) -> bool {
+        self.deleted.get(table_name).is_some_and(|deleted| deleted.contains_key(&pos))
+    }
+}
 
-            if file.write_all(&encoded).is_err() {
-                return Err(BongoError::WriteFileError("Could not save current of meta data to file".to_string()));
-            }
+impl TableMetaData {
+    ///
+    /// Converts the values of a `Row` to the types of the columns of this table, so that the row
+    /// can be stored in the table. Returns an error if the row has not as many values as the table
+    /// has columns or a value cannot be converted to the type of its column.
+    ///
+    pub fn convert_row(&self, row: Row) -> Result<Row, BongoError> {
+        if self.cols.len() != row.len() {
+            return Err(BongoError::SqlRuntimeError(format!(
+                "The row '{:?}' has {} values, but the table has {} columns.", row, row.len(), self.cols.len()
+            )));
         }
 
-        Ok(None)
+        row.into_iter()
+            .zip(&self.cols)
+            .map(|(val, col_def)| col_def.data_type.convert(val))
+            .collect()
     }
 
     ///
-    /// A `Select` statement is executed as follows.
+    /// Completes a row of an `INSERT` statement, which has values for the columns `cols` only, in
+    /// their order. The values are arranged in the order of the columns of the table. `SERIAL`
+    /// columns that are not part of `cols` get the values after `serials`, which are advanced
+    /// accordingly. All other columns that are not part of `cols` get their default value or `NULL`.
     ///
-    /// 1. check if table exists in cache
-    /// 2. check if table exists on disc
-    /// 3. check if all columns exist in table
-    /// 4. get all indices that must be loaded from disc as a `DiscIdx`.
-    /// 5. Load rows from disc and if applicably check if condition is true for them.
-    /// 6. remove the not selected rows.
-    /// 7. check if order is given, if so sort accordingly.
-    /// 8. return result.
-    ///
-    fn select(&self, select: Select) -> BongoResult {
-        let mut path = self.get_table_dir_if_exists(&select.table)?;
-        let tables = self.tables_read_access()?;
-        // unwrap safe, because we have checked the entry exists before
-        let cell = tables.get(&select.table).unwrap();
-        let table_lock = cell.borrow();
-        let table = table_lock.read();
+    pub fn complete_row(&self, cols: &[String], row: Row, serials: &mut HashMap<String, i64>) -> Result<Row, BongoError> {
+        if cols.len() != row.len() {
+            return Err(BongoError::SqlRuntimeError(format!(
+                "The row '{:?}' has {} values, but {} columns are given.", row, row.len(), cols.len()
+            )));
+        }
 
-        if table.is_err() {
-            return Err(BongoError::InternalError("Could not acquire read access to cached meta data.".to_string()));
-        }
-        let table = table.unwrap();
-
-        // get indices of the selected columns in the structure of the table
-        let mut selected_col_indices = vec![];
-        for item in select.cols {
-            match item {
-                SelectItem::ColumnName(name) => {
-                    let loc = table.cols.iter().position(|col_def| { col_def.name == name });
-                    if loc.is_none() {
-                        return Err(BongoError::SqlRuntimeError(format!("The column '{name}' does not exist.")));
+        let mut values = row.into_iter().map(Some).collect::<Vec<Option<BongoLiteral>>>();
+        self.cols.iter()
+            .map(|col_def| match cols.iter().position(|col| col == &col_def.name) {
+                // unwrap safe, because every column is given at most once
+                Some(i) => Ok(values[i].take().unwrap()),
+                None if !col_def.serial => Ok(col_def.default.clone().unwrap_or(BongoLiteral::Null)),
+                None => {
+                    let last = serials.entry(col_def.name.clone()).or_insert(0);
+                    match last.checked_add(1) {
+                        Some(next) => {
+                            *last = next;
+                            Ok(BongoLiteral::Int(next))
+                        }
+                        None => Err(BongoError::SqlRuntimeError(format!("The SERIAL column '{}' has no values left.", col_def.name))),
                     }
-                    selected_col_indices.push(loc.unwrap());
                 }
-                SelectItem::Wildcard => {
-                    // in this case the for loop will only run once. This is ensured by the value we get from the parser
-                    selected_col_indices = (0..table.cols.len()).into_iter().collect();
-                }
-            }
-        }
+            })
+            .collect()
+    }
 
-        let selected_d_types = table.cols.get_d_types();
+    ///
+    /// Returns an error if `row` violates a constraint of this table, i.e. it has `NULL` in a
+    /// column that is `NOT NULL` or the condition of a `CHECK` constraint is false for it. As in
+    /// standard SQL, a `CHECK` constraint whose condition is unknown is satisfied.
+    ///
+    /// The values of `row` have to be converted to the types of the columns before, see `convert_row`.
+    ///
+    pub fn can_store(&self, table_name: &str, row: &Row) -> Result<(), BongoError> {
+        check_constraints(table_name, &self.cols, &self.checks, row)
+    }
 
-        path.push("data.bongo");
-        let file = File::open(&path);
-        if file.is_err() {
-            return Err(BongoError::ReadFileError("Could not get read access to file on disc".to_string()));
+    ///
+    /// Returns an error if one of `rows`, which are about to be stored in the table, has the same
+    /// values in the columns of a `PRIMARY KEY` or `UNIQUE` constraint as another one of `rows` or
+    /// as a current row of the table in `data_path`. The row versions at the positions `replaced`
+    /// are ignored, because they are deleted by the same statement.
+    ///
+    /// The rows of the table that might have the same values are looked up in the index of the
+    /// constraint and read from disc.
+    ///
+    pub fn check_unique(&self, table_name: &str, data_path: &Path, rows: &[Row], replaced: &HashSet<u64>) -> Result<(), BongoError> {
+        if self.uniques.is_empty() {
+            return Ok(());
         }
-        let mut file = file.unwrap();
-
-        let mut row_buffer = Vec::with_capacity(table.row_size);
-        unsafe { row_buffer.set_len(table.row_size) } // expand buffer to avoid useless initialization
 
-        let indexer = DiscIndexer::from_opt_expr(&table.idx, select.condition);
-        let mut rows = match &indexer.expr {
-            None => {
-                // when all indices are used, better already allocate
-                Vec::with_capacity(indexer.indices.len() * table.row_size)
-            }
-            Some(_) => {
-                // if there is an expression we do not know yet how much to allocate
-                vec![]
-            }
-        };
-
-        for i in indexer.indices {
-            if file.seek(SeekFrom::Start(i)).is_err() {
-                return Err(ReadFileError("Could not jump to correct position in file".to_string()));
-            }
-            if file.read_exact(&mut row_buffer).is_err() {
-                return Err(BongoError::ReadFileError("Could not read row from disc".to_string()));
-            }
-            let row = Row::from_disc_bytes(&row_buffer, &selected_d_types)?;
+        let mut reader = SlotReader::open(data_path, self)?;
+        // the statement has exclusive access to the table and sees the latest version of each row
+        let snapshot = Snapshot::latest();
+        let d_types = self.cols.get_d_types();
+
+        for unique in &self.uniques {
+            let positions = unique.cols.iter()
+                .map(|col| self.col_position(col))
+                .collect::<Result<Vec<usize>, BongoError>>()?;
+            let index = match self.indexes.iter().find(|index| index.def.name == unique.name) {
+                Some(index) => index,
+                None => return Err(InternalError(format!("The index of the constraint '{}' is missing.", unique.name))),
+            };
 
-            // if no condition exists or the existing condition evaluates to true this row shall be returned
-            if indexer.expr.is_none() ||
-                indexer.expr.as_ref().unwrap().eval(&row, &table.cols.get_col_names())? {
-                rows.push(row);
-            }
-        }
+            let mut keys = HashSet::new();
+            for key in rows.iter().filter_map(|row| unique_key(&positions, row)) {
+                if !keys.insert(key.clone()) {
+                    return Err(unique_violation(table_name, unique, &key));
+                }
 
-        // apply order before removing unselected indices, because we allow ordering by non-selected columns
-        if let Some(order) = select.order {
-            let col_idx = match &order {
-                Order::Asc(col) | Order::Desc(col) => {
-                    let idx = table.cols.iter().position(|c| { &c.name == col });
-                    if idx.is_none() {
-                        return Err(BongoError::SqlRuntimeError(format!("Cannot order, because column '{}' does not exist.",
-                                                                       col)));
+                for pos in index.get(&key[0])? {
+                    if replaced.contains(&pos) {
+                        continue;
+                    }
+                    if let Some(row) = reader.read(pos, &d_types, &snapshot)? {
+                        if unique_key(&positions, &row).as_ref() == Some(&key) {
+                            return Err(unique_violation(table_name, unique, &key));
+                        }
                     }
-                    idx.unwrap()
                 }
-            };
-
-            let mut asc = true;
-
-            if let Order::Desc(_) = order {
-                asc = false;
             }
-
-            rows.sort_by(|r1, r2| {
-                if asc {
-                    r1[col_idx].partial_cmp(&r2[col_idx]).unwrap_or(Equal)
-                } else {
-                    r2[col_idx].partial_cmp(&r1[col_idx]).unwrap_or(Equal)
-                }
-            });
         }
 
-        // remove non-selected indices
-        rows = rows.into_iter().map(|row| {
-            row.into_iter()
-                .enumerate()
-                .filter(|(i, _)| { selected_col_indices.contains(i) })
-                .map(|(_, item)| { item }) // remove indices again
-                .collect()
-        }).collect();
-
-        Ok(Some(rows))
+        Ok(())
     }
 
     ///
-    /// An `Insert` statement is executed as follows:
-    ///
-    /// 1. check if table exists in cache
-    /// 2. check if the table exists on disc
-    /// 3. check if specified columns are correct for the specified table
-    /// 4. check if specifies Rows have the correct datatypes for each element
-    /// 5. write rows to disc
-    ///      4.1 if ghosts exists use ghosts first
-    ///      4.2 if no ghosts exist write to end of file
-    /// 6. update index
-    /// 7. update row_count
+    /// Returns the values of `row` in the columns `cols` or `None` if one of them is NULL.
     ///
-    fn insert(&mut self, mut insert: Insert) -> BongoResult {
-        let mut location = self.get_table_dir_if_exists(&insert.table)?;
+    pub fn key(&self, cols: &[String], row: &Row) -> Result<Option<Row>, BongoError> {
+        let positions = cols.iter()
+            .map(|col| self.col_position(col))
+            .collect::<Result<Vec<usize>, BongoError>>()?;
 
-        location.push("data.bongo");
+        Ok(unique_key(&positions, row))
+    }
 
-        let tables = self.tables_read_access()?;
-        let cell = tables.get(&insert.table).unwrap();
-        let table_lock = cell.borrow_mut();
-        let table = table_lock.write();
+    ///
+    /// Returns the positions and values of the current rows of the table in `data_path` that have
+    /// the values `key` in the columns `cols`. The rows are looked up in an index on one of the
+    /// columns if there is one, otherwise all rows of the table are read.
+    ///
+    pub fn rows_with_key(&self, data_path: &Path, cols: &[String], key: &Row) -> Result<Vec<(u64, Row)>, BongoError> {
+        let candidates = match self.indexes.iter()
+            .find_map(|index| cols.iter().position(|col| *col == index.def.col).map(|i| (index, i))) {
+            Some((index, i)) => index.get(&key[i])?,
+            None => self.positions(),
+        };
 
-        if table.is_err() {
-            return Err(InternalError("Concurrency Error.".to_string()));
+        let mut reader = SlotReader::open(data_path, self)?;
+        // the statement has exclusive access to the table and sees the latest version of each row
+        let snapshot = Snapshot::latest();
+        let d_types = self.cols.get_d_types();
+
+        let mut rows = vec![];
+        for pos in candidates {
+            if let Some(row) = reader.read(pos, &d_types, &snapshot)? {
+                if self.key(cols, &row)?.as_ref() == Some(key) {
+                    rows.push((pos, row));
+                }
+            }
         }
-        let mut table = table.unwrap();
 
-        // check if all columns are correct
-        if table.cols.get_col_names() !=
-            insert.cols {
-            let col_names = table.cols
-                .iter()
-                .map(|col_def| { format!("{:?}", col_def) })
-                .intersperse_with(|| { " ".to_string() })
-                .collect::<String>();
+        Ok(rows)
+    }
 
-            return Err(BongoError::SqlRuntimeError(format!(
-                "Specified columns do not match columns of the table.\
-                table '{}' has the following columns: '{}'",
-                &insert.table,
-                col_names
-            )));
-        }
-        for row in &insert.rows {
-            if !table.can_store(row) {
-                return Err(BongoError::SqlRuntimeError(format!(
-                    "The row '{:?}' cannot be inserted into the table '{}', because not all elements have the correct type",
-                    row,
-                    insert.table
-                )));
-            }
+    ///
+    /// Returns an error if the table is locked by a transaction of another connection than `connection`.
+    ///
+    pub fn check_owner(&self, table_name: &str, connection: ConnectionId) -> Result<(), BongoError> {
+        match self.owner {
+            Some(owner) if owner != connection => Err(BongoError::TableLockedError(format!(
+                "Table '{}' is locked by a transaction of another connection.",
+                table_name
+            ))),
+            _ => Ok(()),
         }
+    }
 
+    ///
+    /// Applies row level operations of a `WalRecord` to the table.
+    ///
+    /// The rows are written to the `data.bongo` file at `data_path` and the cached indexes, ghosts,
+    /// expired versions and row_count are updated accordingly. Afterwards the table reflects the
+    /// record with the log sequence number `lsn`.
+    ///
+    pub fn apply_row_ops<'a>(&mut self, data_path: &Path, ops: impl Iterator<Item=&'a WalOp>, lsn: u64) -> Result<(), BongoError> {
         let file = OpenOptions::new()
             .read(true)
             .write(true)
-            .open(location);
+            .open(data_path);
         if file.is_err() {
             return Err(BongoError::WriteFileError("Could not open file on disc for writing.".to_string()));
         }
+        let mut file = file.unwrap();
+        let mut heap = Heap::new(&data_path.with_file_name(HEAP_FILE_NAME));
 
-        let mut writer = BufWriter::new(file.unwrap());
-
-        for row in &mut insert.rows {
-            let mut pos = SeekFrom::End(0);
-
-            if let Some(loc) = table.ghosts.pop() {
-                pos = SeekFrom::Start(loc as u64);
-            }
-
-            if writer.seek(pos).is_err() {
-                return generic_write_error();
-            }
-
-            // safe because we checked that seeking to this position did return an error
-            let item_pos = writer.stream_position().unwrap();
+        let latch = self.latch.clone();
+        let _latch = latch.write();
 
-            // write to specified position
-            if writer.write_all(&row.as_disc_bytes(&table.cols)?).is_err() {
-                return generic_write_error();
-            }
+        for op in ops {
+            self.write_slot(&mut file, &mut heap, op)?;
 
-            // update index
-            match table.idx.1.get_mut(&row[0]) {
-                None => {
-                    // index does not exist for this key yet.
-                    // row.remove(0) allows to avoid copying as this element is not needed anymore anyways.
-                    table.idx.1.insert(row.remove(0), vec![item_pos]);
+            match op {
+                WalOp::Insert { pos, row, txn, .. } => {
+                    // the position is taken from the ghosts if the row reuses an unused row
+                    if let Some(ghost) = self.ghosts.iter().rposition(|g| g == pos) {
+                        self.ghosts.remove(ghost);
+                    }
+                    self.index_insert(row, *pos)?;
+                    self.advance_serials(row);
+                    self.row_count += 1;
+                    self.max_txn = self.max_txn.max(*txn);
+                }
+                WalOp::Delete { pos, txn, .. } => {
+                    // the version stays in the indexes until it is reclaimed by a compaction
+                    self.expired.push(*pos);
+                    self.max_txn = self.max_txn.max(*txn);
                 }
-                Some(indices) => {
-                    // hash collision -> append new row index to list
-                    indices.push(item_pos);
+                WalOp::Restore { pos, .. } => {
+                    if let Some(expired) = self.expired.iter().rposition(|e| e == pos) {
+                        self.expired.remove(expired);
+                    }
+                }
+                WalOp::Remove { pos, row, .. } => {
+                    self.index_remove(row, *pos)?;
+                    self.row_count -= 1;
+                    // the removed version is either an undone insertion or a reclaimed expired version
+                    if let Some(expired) = self.expired.iter().rposition(|e| e == pos) {
+                        self.expired.remove(expired);
+                    }
+                    // mark removed row as ghost
+                    self.ghosts.push(*pos);
+                }
+                WalOp::Truncate { pos, row, .. } => {
+                    self.index_remove(row, *pos)?;
+                    self.row_count -= 1;
+                }
+                WalOp::CreateTable { .. } | WalOp::DropTable { .. } | WalOp::CreateIndex { .. } | WalOp::DropIndex { .. } => {
+                    return Err(BongoError::InternalError("Only row operations can be applied to a table.".to_string()));
                 }
             }
         }
 
-        if writer.flush().is_err() {
-            return generic_write_error();
-        }
-
-        table.row_count += insert.rows.len();
+        self.lsn = lsn;
 
-        Ok(None)
+        Ok(())
     }
 
     ///
-    /// An `Update` statement is executed as follows:
-    ///
-    /// 1. check if table exists in cache
-    /// 2. check if the table exists on disc
-    /// 3. check if specified columns in set expression exist in the specified table
-    /// 4. get indices of the relevant columns via DicsIndexer
-    /// 5. iterate over row-indices and for each row-index:
-    ///     - load row data from disc
-    ///     - check if condition applies (if there is a condition) and if so:
-    ///         + apply the assignments to the row+
-    ///         + write row back to disc
-    ///         + if the assignments also included the indexed column, update the index key for that row
-    /// 6. return Ok(None)
+    /// Writes the effect of the row level operation `op` into the slot it refers to in `file`
+    /// without touching the cached meta data. Values of out of line columns are appended to `heap`.
     ///
-    fn update(&mut self, update: Update) -> BongoResult {
-        let mut path = self.get_table_dir_if_exists(&update.table)?;
-        let tables = self.tables_read_access()?;
-        // unwrap safe, because we have checked the entry exists before
-        let cell = tables.get(&update.table).unwrap();
-        let table_lock = cell.borrow_mut();
-        let table = table_lock.write();
+    fn write_slot(&self, file: &mut File, heap: &mut Heap, op: &WalOp) -> Result<(), BongoError> {
+        let (pos, bytes) = match op {
+            WalOp::Insert { pos, row, txn, .. } => {
+                let mut bytes = SlotHeader { live: true, created: *txn, deleted: NO_TXN }.as_disc_bytes();
+                bytes.append(&mut heap.row_as_disc_bytes(row, &self.cols)?);
+                (*pos, bytes)
+            }
+            // only the deleting transaction id in the slot header is overwritten
+            WalOp::Delete { pos, txn, .. } => (*pos + SLOT_DELETED_OFFSET, txn.to_be_bytes().to_vec()),
+            WalOp::Restore { pos, .. } => (*pos + SLOT_DELETED_OFFSET, NO_TXN.to_be_bytes().to_vec()),
+            // only the first byte of the slot header is overwritten, the content of a free slot is irrelevant
+            WalOp::Remove { pos, .. } => (*pos, vec![SLOT_FREE]),
+            WalOp::Truncate { pos, .. } => {
+                if file.set_len(*pos).is_err() {
+                    return generic_write_error();
+                }
+                return Ok(());
+            }
+            WalOp::CreateTable { .. } | WalOp::DropTable { .. } | WalOp::CreateIndex { .. } | WalOp::DropIndex { .. } => {
+                return Err(BongoError::InternalError("Only row operations can be applied to a table.".to_string()));
+            }
+        };
 
-        if table.is_err() {
-            return Err(BongoError::InternalError("Could not acquire read access to cached meta data.".to_string()));
+        if file.seek(SeekFrom::Start(pos)).is_err() {
+            return generic_write_error();
         }
-        let mut table = table.unwrap();
-
-        // error if there is at least one column that does not exists
-        if update.assignments.get_col_names().iter().any(|name| {
-            !table.cols.get_col_names().contains(name)
-        }) {
-            return Err(BongoError::SqlRuntimeError(format!(
-                "There were columns in the SET expressions that are not columns of the table '{}'",
-                &update.table
-            )));
+        if file.write_all(&bytes).is_err() {
+            return Err(BongoError::WriteFileError("Could not write row to file on disc.".to_string()));
         }
 
-        let indexer = DiscIndexer::from_opt_expr(&table.idx, update.condition);
-        let col_names = table.cols.get_col_names();
+        Ok(())
+    }
 
-        path.push("data.bongo");
+    ///
+    /// Writes the effects of row level operations to the `data.bongo` file at `data_path` without
+    /// updating any meta data. This is used to replay the write-ahead log on tables whose meta data
+    /// is rebuilt from `data.bongo` afterwards anyway.
+    ///
+    pub fn redo_row_ops<'a>(&self, data_path: &Path, ops: impl Iterator<Item=&'a WalOp>) -> Result<(), BongoError> {
         let file = OpenOptions::new()
-            .read(true)
             .write(true)
-            .open(&path);
+            .open(data_path);
         if file.is_err() {
-            return Err(BongoError::ReadFileError("Could not get read access to file on disc".to_string()));
+            return Err(BongoError::WriteFileError("Could not open file on disc for writing.".to_string()));
         }
         let mut file = file.unwrap();
+        let mut heap = Heap::new(&data_path.with_file_name(HEAP_FILE_NAME));
 
-        let mut row_buffer = Vec::with_capacity(table.row_size);
-        unsafe { row_buffer.set_len(table.row_size) } // expand buffer to avoid useless initialization
-
-        for i in indexer.indices {
-            if file.seek(SeekFrom::Start(i)).is_err() {
-                return Err(ReadFileError("Could not jump to correct position in file".to_string()));
-            }
-            if file.read_exact(&mut row_buffer).is_err() {
-                return Err(BongoError::ReadFileError("Could not read row from disc".to_string()));
-            }
-            let row = Row::from_disc_bytes(&row_buffer, &table.cols.get_d_types())?;
-            // TODO: LOW_PRIO: look for index dynamically in the future, as for now the first column is the indexed column
-            let old_idx_val = row[0].clone();
+        for op in ops {
+            self.write_slot(&mut file, &mut heap, op)?;
+        }
 
-            // if no condition exists or the existing condition evaluates to true this row shall be returned
-            if indexer.expr.is_none() ||
-                indexer.expr.as_ref().unwrap().eval(&row, &col_names)? {
-                // modify row according to SET expressions
-                let mut row = row.apply_assignments(&update.assignments, &col_names)?;
+        Ok(())
+    }
 
-                // write modified row to disc
-                if file.seek(SeekFrom::Start(i)).is_err() { // reset position
-                    return Err(ReadFileError("Could not jump to correct position in file".to_string()));
-                }
-                if file.write_all(&row.as_disc_bytes(&table.cols.get_d_types())?).is_err() {
-                    return Err(BongoError::WriteFileError("Could not update row in file on disc.".to_string()));
-                }
+    ///
+    /// Rebuilds the meta data of a table with the definition `schema` by scanning the slots of the
+    /// `data.bongo` file inside of `table_dir`.
+    ///
+    /// Live slots are added to the indexes and free slots become ghosts. Live slots of deleted
+    /// versions are expired. An incomplete slot at the end of the file, which is the result of a
+    /// crash while appending a row, is cut off. The files of B-tree indexes are created again.
+    ///
+    pub fn rebuild(schema: Schema, table_dir: &Path) -> Result<Self, BongoError> {
+        let data_path = table_dir.join("data.bongo");
+        let cols = schema.cols;
+        let mut table = TableMetaData {
+            indexes: schema.indexes.into_iter()
+                .map(|def| Index::create(def, table_dir))
+                .collect::<Result<Vec<Index>, BongoError>>()?,
+            row_size: SLOT_HEADER_SIZE + cols.iter().map(|col_def| { col_def.as_ref().disc_size() }).sum::<usize>(),
+            cols,
+            checks: schema.checks,
+            uniques: schema.uniques,
+            foreign_keys: schema.foreign_keys,
+            serials: HashMap::new(),
+            ghosts: vec![],
+            expired: vec![],
+            row_count: 0,
+            max_txn: NO_TXN,
+            lsn: 0,
+            owner: None,
+            latch: Arc::default(),
+        };
 
-                // update index
-                if update.assignments.get_col_names().contains(&table.idx.0) {
-                    // remove old row index
-                    let indices = table.idx.1.get_mut(&old_idx_val);
-                    if indices.is_none() {
-                        return Err(BongoError::InternalError("Index not correct.".to_string()));
-                    }
-                    let indices = indices.unwrap();
-                    if indices.len() == 1 {
-                        // remove whole item if the thing we want to remove is the only index belonging to this value
-                        table.idx.1.remove(&old_idx_val).unwrap(); // unwrap safe, because we know for sure that the value existed
-                    } else {
-                        // if there are more than this element, only remove the element
-                        indices.remove(i as usize); // remove from index
-                    }
+        let mut bytes = vec![];
+        let file = OpenOptions::new().read(true).write(true).open(&data_path);
+        if file.is_err() || file.as_ref().unwrap().read_to_end(&mut bytes).is_err() {
+            return Err(BongoError::ReadFileError(format!(
+                "Could not read table data to rebuild meta data at '{}'",
+                data_path.to_str().unwrap()
+            )));
+        }
+        let file = file.unwrap();
 
-                    // insert new row index
-                    // use remove to avoid copying. row is not needed anymore, therefore mutation is ok
-                    let new_val = row.remove(0);
+        let mut heap = Heap::new(&table_dir.join(HEAP_FILE_NAME));
+        let slot_count = bytes.len() / table.row_size;
+        if file.set_len((slot_count * table.row_size) as u64).is_err() {
+            return Err(BongoError::WriteFileError("Could not cut off incomplete row on disc.".to_string()));
+        }
 
-                    match table.idx.1.get_mut(&new_val) {
-                        None => { // insert pair if this value does not exist yet
-                            table.idx.1.insert(new_val, vec![i]);
-                        }
-                        Some(indices) => { // append index if already another index is mapped to this value
-                            indices.push(i);
-                        }
-                    }
+        for (i, slot) in bytes.chunks_exact(table.row_size).enumerate() {
+            let pos = (i * table.row_size) as u64;
+            let header = SlotHeader::from_disc_bytes(slot);
+            if header.live {
+                let row = heap.row_from_disc_bytes(&slot[SLOT_HEADER_SIZE..], &table.cols.get_d_types())?;
+                table.index_insert(&row, pos)?;
+                table.advance_serials(&row);
+                table.row_count += 1;
+                table.max_txn = table.max_txn.max(header.created).max(header.deleted);
+                if header.deleted != NO_TXN {
+                    table.expired.push(pos);
                 }
+            } else {
+                table.ghosts.push(pos);
             }
         }
 
-        Ok(None)
+        Ok(table)
     }
 
     ///
-    /// A `Delete` statement is executed as follows.
+    /// Returns the definition of the table and of its indexes.
     ///
-    /// 1. Check if table exists in cache and on disc.
-    /// 2. Obtain relevant indices that might have to be deleted
-    /// 3. Iterate over all those indices and remove indices that do not fulfill condition
-    ///    (in case a condition exists) from list of relevant indices
-    /// 4. remove the indices from the cached index `self.tables`
-    /// 4. Mark all removed indices as ghosts
-    /// 5. Update row_count
-    /// 6. Return Ok
-    ///
-    fn delete(&mut self, delete: Delete) -> BongoResult {
-        let table_dir = self.get_table_dir_if_exists(&delete.table)?;
-        let mut data_loc = table_dir.clone();
-        data_loc.push("data.bongo");
-
-        let tables = self.tables_read_access()?;
-        let cell = tables.get(&delete.table).unwrap();
-        let table_lock = cell.borrow_mut();
-        let table = table_lock.write();
-
-        if table.is_err() {
-            return Err(InternalError("Concurrency Error.".to_string()));
+    pub fn schema(&self) -> Schema {
+        Schema {
+            cols: self.cols.clone(),
+            indexes: self.indexes.iter().map(|index| index.def.clone()).collect(),
+            checks: self.checks.clone(),
+            uniques: self.uniques.clone(),
+            foreign_keys: self.foreign_keys.clone(),
         }
-        let mut table = table.unwrap();
-
-        let indexer = DiscIndexer::from_opt_expr(&table.idx, delete.condition);
-
-        let mut indices_to_delete = match indexer.expr {
-            None => {
-                // in this case we do not need to load anything from disc, because no expression means all
-                // remaining indices shall be deleted.
-                // -> return all indices in indexer
-                indexer.indices
-            }
-            Some(expr) => {
-                // in case there is an expression we have to assemble a new list of indices
-                let mut indices = vec![];
-
-                let file = File::open(&data_loc);
-                if file.is_err() {
-                    return Err(BongoError::ReadFileError("Could not get read access to file on disc".to_string()));
-                }
-                let mut file = file.unwrap();
+    }
 
-                let mut row_buffer = Vec::with_capacity(table.row_size);
-                unsafe { row_buffer.set_len(table.row_size) } // expand buffer to avoid useless initialization
+    ///
+    /// Returns the positions of all slots in data.bongo including free slots.
+    ///
+    pub fn positions(&self) -> Vec<u64> {
+        // every slot either holds a version of a row or is a ghost
+        (0..self.row_count + self.ghosts.len())
+            .map(|i| (i * self.row_size) as u64)
+            .collect()
+    }
 
-                // additional for loop to fail early. Slower but safer
-                for i in indexer.indices {
-                    if file.seek(SeekFrom::Start(i)).is_err() {
-                        return Err(BongoError::ReadFileError("Could not jump to correct position in file.".to_string()));
-                    }
-                    if file.read_exact(&mut row_buffer).is_err() {
-                        return Err(BongoError::ReadFileError("Cannot read indexed row from file.".to_string()));
-                    }
-                    let row = Row::from_disc_bytes(&row_buffer, &table.cols.get_d_types())?;
+    ///
+    /// Adds a new index with the definition `def` to the table and fills it with all row versions
+    /// of the `data.bongo` file inside of `table_dir`.
+    ///
+    pub fn add_index(&mut self, table_dir: &Path, def: IndexDef) -> Result<(), BongoError> {
+        let mut index = Index::create(def, table_dir)?;
+        self.fill_index(&mut index, table_dir)?;
+        self.indexes.push(index);
 
-                    if expr.eval(&row, &table.cols.get_col_names())? {
-                        indices.push(i);
-                    }
-                }
+        Ok(())
+    }
 
-                indices
+    ///
+    /// Opens the files of all B-tree indexes inside of `table_dir` after the meta data has been
+    /// loaded from disc. B-tree indexes that might not match the table data anymore are filled again.
+    ///
+    pub fn open_indexes(&mut self, table_dir: &Path) -> Result<(), BongoError> {
+        let mut indexes = mem::take(&mut self.indexes);
+        for index in indexes.iter_mut() {
+            if !index.open(table_dir)? {
+                println!("Index '{}' is outdated. Rebuilding it from table data.", index.def.name);
+                self.fill_index(index, table_dir)?;
             }
-        };
-
-        // remove indices from cached index
-        table.idx.1 = table.idx.1.clone().into_iter()
-            .map(|(lit, ids)| -> (BongoLiteral, Vec<u64>){
-                let new_ids = ids.into_iter().filter(|i| {
-                    !indices_to_delete.contains(i) // remove i if it shall be deleted
-                }).collect();
-                (lit, new_ids)
-            }).filter(|(_lit, ids)| {
-            !ids.is_empty() // remove entries that have no associated indices anymore
-        }).collect();
-
-        table.row_count -= indices_to_delete.len();
-
-        // mark removed rows as ghosts
-        table.ghosts.append(&mut indices_to_delete);
+        }
+        self.indexes = indexes;
 
-        Ok(None)
+        Ok(())
     }
 
     ///
-    /// A `CreateTable` statement is executed as follows.
-    ///
-    /// 1. check that table does not exist
-    /// 2. check that folder does not exist
-    /// 3. create folder
-    /// 4. create empty data.bongo file
-    /// 5. update self.tables
+    /// Adds all row versions of the `data.bongo` file inside of `table_dir` to `index`.
     ///
-    fn create_table(&mut self, create_table: CreateTable) -> BongoResult {
-        let mut location = self.get_table_dir_on_disc(&create_table.table);
-
-        let mut tables = self.tables_write_access()?;
-        if tables.contains_key(&create_table.table) {
-            return Err(BongoError::SqlRuntimeError(format!("Table '{}' already exists", &create_table.table)));
-        }
-
-        if location.exists() {
-            return Err(BongoError::InternalError("DB root directory contains invalid elements.".to_string()));
-        }
+    fn fill_index(&self, index: &mut Index, table_dir: &Path) -> Result<(), BongoError> {
+        let col = self.col_position(&index.def.col)?;
 
-        if fs::create_dir(&location).is_err() {
-            return Err(BongoError::WriteFileError("Could directory for new table.".to_string()));
+        let mut bytes = vec![];
+        let file = File::open(table_dir.join("data.bongo"));
+        if file.is_err() || file.unwrap().read_to_end(&mut bytes).is_err() {
+            return Err(BongoError::ReadFileError("Could not read table data to create index.".to_string()));
         }
 
-        location.push("data.bongo");
-        if File::create(location).is_err() {
-            return Err(BongoError::WriteFileError("Could not create data.bongo file for new table".to_string()));
+        let mut heap = Heap::new(&table_dir.join(HEAP_FILE_NAME));
+        let d_types = self.cols.get_d_types();
+        for (i, slot) in bytes.chunks_exact(self.row_size).enumerate() {
+            if SlotHeader::from_disc_bytes(slot).live {
+                let row = heap.row_from_disc_bytes(&slot[SLOT_HEADER_SIZE..], &d_types)?;
+                index.insert(row[col].clone(), (i * self.row_size) as u64)?;
+            }
         }
 
-        let row_size = create_table.cols.iter()
-            .map(|col_def| { col_def.as_ref().disc_size() })
-            .sum();
-
-        tables.insert(create_table.table.clone(),
-                      RefCell::new(RwLock::new(
-                          TableMetaData {
-                              idx: (create_table.cols[0].name.clone(), HashMap::new()),
-                              cols: create_table.cols,
-                              ghosts: vec![],
-                              row_size,
-                              row_count: 0,
-                          })));
-
-        Ok(None)
+        Ok(())
     }
 
     ///
-    /// A `DropTable` statement is executed as follows.
-    ///
-    /// for each table name:
-    ///  1. check if table exists in self.tables
-    ///  2. check if table correctly exists on disc
-    ///  3. delete directory for the table on disc and delete cache entry (self.tables)
+    /// Raises the counters of the `SERIAL` columns to the values of `row`, which is stored in the table.
     ///
-    fn drop_table(&mut self, drop_table: DropTable) -> BongoResult {
-        // extra for loop here to fail early before actual execution begins.
-        // slightly less performant but more secure.
-        let mut paths_to_delete = HashMap::new();
-        for table_name in drop_table.names {
-            let path = self.get_table_dir_if_exists(&table_name)?;
-            paths_to_delete.insert(table_name, path);
-        }
-
-        let mut tables = self.tables_write_access()?;
-
-        for (name, path) in paths_to_delete {
-            if fs::remove_dir_all(&path).is_err() {
-                return Err(BongoError::WriteFileError(
-                    format!("Could not delete directory '{}'",
-                            path.to_str().unwrap())));
+    fn advance_serials(&mut self, row: &Row) {
+        for (col_def, val) in self.cols.iter().zip(row) {
+            if let (true, BongoLiteral::Int(val)) = (col_def.serial, val) {
+                let last = self.serials.entry(col_def.name.clone()).or_insert(0);
+                *last = (*last).max(*val);
             }
-
-            // previous conditions in this function assure that this will be safe because the key exists
-            tables.remove(&name).unwrap();
         }
-
-        Ok(None)
-    }
-
-    fn create_drop_db() -> BongoResult {
-        BongoResult::Err(BongoError::UnsupportedFeatureError(
-            "'CREATE DATABASE and DROP DATABASE statements are not supported by BongoDB so far. \
-            BongoDB assumes there is only one database."
-                .to_string(),
-        ))
     }
 
-    fn table_exists_in_cache(&self, table_name: &str) -> Result<bool, BongoError> {
-        let tables = self.tables_read_access()?;
-        Ok(tables.get(table_name).is_some())
+    fn col_position(&self, col: &str) -> Result<usize, BongoError> {
+        match self.cols.iter().position(|col_def| col_def.name == col) {
+            Some(pos) => Ok(pos),
+            None => Err(BongoError::SqlRuntimeError(format!("The column '{col}' does not exist."))),
+        }
     }
 
-    fn get_table_dir_on_disc(&self, table_name: &str) -> PathBuf {
-        let mut table_dir = self.db_root.clone();
-        table_dir.push(table_name);
+    ///
+    /// Adds the row position `pos` of `row` to all indexes.
+    ///
+    fn index_insert(&mut self, row: &Row, pos: u64) -> Result<(), BongoError> {
+        for index in self.indexes.iter_mut() {
+            // unwrap safe, because only existing columns can be indexed
+            let col = self.cols.iter().position(|col_def| col_def.name == index.def.col).unwrap();
+            index.insert(row[col].clone(), pos)?;
+        }
 
-        table_dir
+        Ok(())
     }
 
     ///
-    /// Checks if a table exists in cache and on disc.
-    /// If not found returns an Error.
-    /// If found returns the path to the directory of the table.
+    /// Removes the row position `pos` of `row` from all indexes.
     ///
-    fn get_table_dir_if_exists(&self, table_name: &str) -> Result<PathBuf, BongoError> {
-        if !self.table_exists_in_cache(&table_name)? {
-            return Err(BongoError::SqlRuntimeError(format!(
-                "Execution of statement failed because table {} does not exist.",
-                &table_name)));
+    fn index_remove(&mut self, row: &Row, pos: u64) -> Result<(), BongoError> {
+        for index in self.indexes.iter_mut() {
+            // unwrap safe, because only existing columns can be indexed
+            let col = self.cols.iter().position(|col_def| col_def.name == index.def.col).unwrap();
+            index.remove(&row[col], pos)?;
         }
 
-        let path = self.get_table_dir_on_disc(&table_name);
+        Ok(())
+    }
+}
+
+///
+/// `Table` is used to store the name of a table together with its meta data.
+/// This structure is used to easily be inserted into a HashMap
+///
+#[derive(Serialize, Deserialize, Debug)]
+struct Table((String, TableMetaData));
+
+///
+/// An `Executor` can execute a `BongoRequest`
+///
+pub struct Executor {
+    ///
+    /// `db_dir` is the root directory of the database storage.
+    ///
+    db_root: PathBuf,
+    ///
+    /// `tables` maps table names to their meta data.
+    ///
+    /// We use a 2-level-RwLock-system allowing for the maximum possible degree of parallelism while
+    /// still keeping thread-safety.
+    ///
+    /// 1st level RwLock (outer lock):
+    /// - A read lock on this means that we have immutable access to the HashMap which means we
+    ///     cannot remove or add any tables. However, we still have mutable access to the tables themselves
+    ///     as those are wrapped inside their own RwLock.
+    ///     The execution of the following statements requires acquiring a read lock on the first level:
+    ///         SELECT, INSERT, UPDATE, DELETE, FLUSH
+    /// - A write lock on this means that we have mutable access on the entire HashMap allowing us to
+    ///     add and remove tables. This also means we guarantee that no other statement is executed at
+    ///     this time.
+    ///     The execution of the following statements requires acquiring a write lock on the first level:
+    ///         CREATE TABLE, DROP TABLE, VACUUM
+    ///
+    /// 2nd level RwLock (inner lock):
+    /// - A read lock on this means that we have immutable access to the tables which means we cannot
+    ///     modify anything at all.
+    ///     The execution of the following statements requires acquiring a read lock on the second level:
+    ///         SELECT (only until its snapshot is taken), FLUSH
+    /// - A write lock on this means that we have mutable access to exactly ONE table and that no other thread
+    ///     currently has access to this table in any way.
+    ///     The execut  ion of the following statements requires acquiring a write lock on the second level:
+    ///         INSERT, UPDATE, DELETE
+    ///
+    /// Lets compare this to using a single RwLock system:
+    /// Single-level RwLock:
+    /// - Enables multiple parallel SELECTs OR
+    ///     - block any other statement except SELECT no matter the table
+    /// - exactly one  CREATE TABLE, DROP TABLE, FLUSH, INSERT, UPDATE, DELETE
+    ///     - block any other statement
+    ///
+    /// 2-level RwLock (used here):
+    /// - Enables multiple parallel SELECTs
+    ///     - blocks only CREATE TABLE, DROP TABLE, VACUUM
+    /// - Enables multiple parallel INSERTs, UPDATEs, DELETEs as long as they are on different tables
+    ///     - blocks only other INSERTs, UPDATEs, DELETEs on this specific table
+    /// - exclusive access required for CREATE TABLE, DROP TABLE, VACUUM
+    ///     - blocks any other statements
+    ///
+    /// SELECTs do not block writers on the same table, because rows are versioned on disc. A SELECT
+    /// only reads the row versions that are visible in the snapshot it has taken, so it does not see
+    /// any changes of statements that run concurrently.
+    ///
+    /// So over all we get more parallelism while keeping thread safety, becasue we only lock what really
+    /// has to be locked.
+    tables: RwLock<HashMap<String, RwLock<TableMetaData>>>,
+    ///
+    /// `auto_flush` == true means that a flush shall be triggered after every other command except flush itself.
+    ///
+    auto_flush: bool,
+    ///
+    /// `wal` is the write-ahead log of the database. Every mutating statement is appended to it before
+    /// `data.bongo` is touched, so that the state of the last acknowledged statement can be recovered
+    /// after a crash even if the meta data has not been flushed yet.
+    ///
+    wal: Mutex<WriteAheadLog>,
+    ///
+    /// `transactions` maps each connection with a transaction in progress to the state of that transaction.
+    ///
+    transactions: Mutex<HashMap<ConnectionId, Transaction>>,
+    ///
+    /// `sequences` are the sequences of the database created with CREATE SEQUENCE.
+    ///
+    sequences: Mutex<Sequences>,
+    ///
+    /// `transaction_ended` is notified each time a transaction ends, which might release locked tables.
+    ///
+    transaction_ended: Condvar,
+    next_txn_id: AtomicU64,
+    ///
+    /// `lock_timeout` is the maximum time a statement waits for a table locked by another transaction.
+    ///
+    lock_timeout: Duration,
+    ///
+    /// `null_logic` determines how `NULL` values are treated when the expressions of statements are evaluated.
+    ///
+    null_logic: NullLogic,
+    ///
+    /// `examined` counts the rows that the statement of an `EXPLAIN ANALYZE` examines in the table
+    /// after FROM by the connection executing it. Connections without an entry are not counted.
+    ///
+    examined: Mutex<HashMap<ConnectionId, usize>>,
+    ///
+    /// `snapshots` counts the snapshots of the running SELECT statements by their horizon, see
+    /// `Snapshot::horizon`. Expired versions are only reclaimed by new row versions if all these
+    /// snapshots see their deletion.
+    ///
+    snapshots: Mutex<BTreeMap<TransactionId, usize>>,
+}
+
+///
+/// `Context` describes on whose behalf a statement is executed.
+///
+#[derive(Clone, Copy)]
+struct Context {
+    connection: ConnectionId,
+    ///
+    /// The transaction of the connection or `None` if the statement is executed outside of a transaction.
+    ///
+    txn: Option<TransactionId>,
+    ///
+    /// `auto_commit` is true if `txn` has been started implicitly for a single statement that is
+    /// executed outside of a transaction. Such a transaction is committed as soon as the statement is applied.
+    ///
+    auto_commit: bool,
+}
+
+///
+/// A `SnapshotGuard` is a snapshot that is registered in the `snapshots` of the executor until it
+/// is dropped, so that no row version it might see is reclaimed in the meantime.
+///
+struct SnapshotGuard<'a> {
+    snapshot: Snapshot,
+    snapshots: &'a Mutex<BTreeMap<TransactionId, usize>>,
+}
+
+impl Deref for SnapshotGuard<'_> {
+    type Target = Snapshot;
+
+    fn deref(&self) -> &Snapshot {
+        &self.snapshot
+    }
+}
+
+impl Drop for SnapshotGuard<'_> {
+    fn drop(&mut self) {
+        // a poisoned lock only keeps expired versions from being reclaimed, which is safe
+        if let Ok(mut snapshots) = self.snapshots.lock() {
+            let horizon = self.snapshot.horizon();
+            if let Some(count) = snapshots.get_mut(&horizon) {
+                *count -= 1;
+                if *count == 0 {
+                    snapshots.remove(&horizon);
+                }
+            }
+        }
+    }
+}
+
+// Executor internally ensures by its logic and by using RwLock that it is safe to use from different threads.
+unsafe impl Send for Executor {}
+
+// Executor internally ensures by its logic and by using RwLock that it is safe to use from different threads.
+unsafe impl Sync for Executor {}
+
+impl Drop for Executor {
+    fn drop(&mut self) {
+        // there is nothing we can do for the user if this final flush does not work but panic.
+        self.flush().unwrap();
+    }
+}
+
+impl Executor {
+    ///
+    /// Creates a new `Executor` on the database in `db_root`.
+    ///
+    /// On startup the meta data of the last flush is loaded and all statements that have been
+    /// logged in the write-ahead log since then are replayed. Afterwards the recovered state is
+    /// flushed, which leaves an empty write-ahead log.
+    ///
+    /// Tables whose meta data is missing or corrupted are rebuilt from their data.bongo file.
+    ///
+    /// Databases written by earlier versions of BongoDB are migrated to the current format first.
+    /// Databases in a format that is newer than `FORMAT_VERSION` are not opened. How `NULL` values
+    /// are treated is read from the database.bongo file, see `set_null_logic`.
+    ///
+    pub fn new<P>(db_root: &P, create_db: bool, auto_flush: bool) -> Result<Self, BongoError>
+        where P: AsRef<Path> + std::convert::AsRef<std::ffi::OsStr> + ?Sized {
+        let path_buf = Self::get_db_root_dir(db_root, create_db)?;
+        let info = Self::check_format(&path_buf)?;
+        let (mut tables, mut rebuilt) = Self::load_tables_from_disc(&path_buf)?;
+        let (mut wal, records) = WriteAheadLog::open(&path_buf)?;
+
+        // numbering must continue after all records that the tables on disc already reflect. This
+        // has to happen before the replay, which appends records to roll back unfinished transactions.
+        for cell in tables.values_mut() {
+            match cell.get_mut() {
+                Ok(table) => wal.advance_past(table.lsn),
+                Err(_) => return Err(BongoError::InternalError("Concurrency Error.".to_string())),
+            }
+        }
+
+        Self::replay(&path_buf, &mut tables, &mut rebuilt, &mut wal, &records)?;
+
+        // transaction ids must not collide with the ids of the row versions on disc
+        let mut max_txn = NO_TXN;
+        for cell in tables.values_mut() {
+            match cell.get_mut() {
+                Ok(table) => max_txn = max_txn.max(table.max_txn),
+                Err(_) => return Err(BongoError::InternalError("Concurrency Error.".to_string())),
+            }
+        }
+
+        let mut executor = Self {
+            tables: RwLock::new(tables),
+            sequences: Mutex::new(Sequences::open(&path_buf)?),
+            db_root: path_buf,
+            auto_flush,
+            wal: Mutex::new(wal),
+            transactions: Mutex::new(HashMap::new()),
+            transaction_ended: Condvar::new(),
+            next_txn_id: AtomicU64::new(max_txn + 1),
+            lock_timeout: LOCK_TIMEOUT,
+            null_logic: info.null_logic,
+            examined: Mutex::new(HashMap::new()),
+            snapshots: Mutex::new(BTreeMap::new()),
+        };
+
+        executor.flush()?;
+
+        Ok(executor)
+    }
+
+    ///
+    /// Sets how `NULL` values are treated when the expressions of statements are evaluated. The
+    /// setting is stored in the database.bongo file, so that it is kept when the database is opened again.
+    ///
+    pub fn set_null_logic(&mut self, logic: NullLogic) -> Result<(), BongoError> {
+        DatabaseInfo { format_version: FORMAT_VERSION, null_logic: logic }.write(&self.db_root)?;
+        self.null_logic = logic;
+        Ok(())
+    }
+
+    fn get_db_root_dir<P>(root_dir: &P, create_db: bool) -> Result<PathBuf, BongoError>
+        where P: AsRef<Path> + std::convert::AsRef<std::ffi::OsStr> + ?Sized {
+        let path = AsRef::<Path>::as_ref(root_dir);
+        if !path.exists() {
+            if create_db {
+                if fs::create_dir_all(path).is_err() {
+                    return Err(BongoError::WriteFileError("could not create root directory for DB".to_string()));
+                }
+            } else {
+                // unwrap is safe, because BongoServer makes sure only utf8 directories are passed in
+                return Err(BongoError::DatabaseNotFoundError(format!(
+                    "location: '{}'",
+                    location = path.to_str().unwrap()
+                )));
+            }
+        }
+
+        // safe because canonicalize will be valid if root_dir exists is valid, which we assure
+        Ok(PathBuf::from(path).canonicalize().unwrap())
+    }
+
+    ///
+    /// Makes sure that the database in `db_root` is written in the format of `FORMAT_VERSION` and
+    /// returns the content of its database.bongo file.
+    ///
+    /// A database without database.bongo file is either new or has been written by an earlier
+    /// version of BongoDB. All of its tables are migrated before the file is written, so a migration
+    /// that has been interrupted is continued on the next start. An existing database keeps
+    /// `NullLogic::Legacy`, which earlier versions of BongoDB have used, a new database uses the
+    /// default `NullLogic`.
+    ///
+    fn check_format(db_root: &Path) -> Result<DatabaseInfo, BongoError> {
+        match DatabaseInfo::read(db_root)? {
+            Some(info) if info.format_version == FORMAT_VERSION => return Ok(info),
+            Some(info) => return Err(BongoError::UnsupportedFormatError(format!(
+                "The database at '{}' has been written in format version {}, but this version of BongoDB \
+                only supports format version {}.",
+                db_root.to_str().unwrap(), info.format_version, FORMAT_VERSION
+            ))),
+            None => {}
+        }
+
+        let entries = match db_root.read_dir() {
+            Ok(entries) => entries.collect::<Result<Vec<fs::DirEntry>, std::io::Error>>(),
+            Err(err) => Err(err),
+        };
+        if entries.is_err() {
+            return Err(BongoError::ReadFileError("Cannot read files inside DB root".to_string()));
+        }
+        let mut null_logic = NullLogic::default();
+        for entry in entries.unwrap() {
+            if entry.path().is_dir() {
+                Self::migrate_legacy_table(&entry.path())?;
+                null_logic = NullLogic::Legacy;
+            }
+        }
+
+        let info = DatabaseInfo { format_version: FORMAT_VERSION, null_logic };
+        info.write(db_root)?;
+        Ok(info)
+    }
+
+    ///
+    /// Migrates the table in `table_dir` from format version 0 to the current format as follows:
+    ///
+    /// 1. read the meta data of format version 0 from meta.bongo
+    /// 2. write the rows of data.bongo with slot headers to data.bongo.migrated. The rows are
+    ///    created by `MIGRATED_TXN` and deleted rows become free slots.
+    /// 3. write schema.bongo, which completes the migration. The column that has been indexed
+    ///    implicitly gets a hash index named `<table>_<col>_idx`.
+    /// 4. remove meta.bongo and replace data.bongo with data.bongo.migrated
+    ///
+    /// Afterwards the meta data is rebuilt from schema.bongo and data.bongo when the table is loaded.
+    /// If the migration has been interrupted before schema.bongo has been written, it is repeated,
+    /// otherwise the last step is completed. Tables that have a schema.bongo file already are left as they are.
+    ///
+    fn migrate_legacy_table(table_dir: &Path) -> Result<(), BongoError> {
+        let migrated_path = table_dir.join("data.bongo.migrated");
+
+        if !table_dir.join("schema.bongo").exists() {
+            if !LegacyTable::meta_path(table_dir).exists() {
+                // the table has been created right before a crash and is restored from the write-ahead log
+                return Ok(());
+            }
+
+            let table = LegacyTable::read(table_dir)?;
+            println!("Migrating table '{}' written by an earlier version of BongoDB.", table.name);
+
+            let data_path = table_dir.join("data.bongo");
+            let data = match fs::read(&data_path) {
+                Ok(data) => data,
+                Err(_) => return Err(BongoError::ReadFileError(format!(
+                    "Could not read table data to migrate it at '{}'",
+                    data_path.to_str().unwrap()
+                ))),
+            };
+
+            let d_types = table.cols.get_d_types();
+            let ghosts = table.ghosts.iter().collect::<HashSet<&u64>>();
+            let mut bytes = Vec::with_capacity(data.len() / table.row_size * (SLOT_HEADER_SIZE + table.row_size));
+            for (i, row_bytes) in data.chunks_exact(table.row_size).enumerate() {
+                match ghosts.contains(&((i * table.row_size) as u64)) {
+                    true => {
+                        bytes.extend(SlotHeader { live: false, created: NO_TXN, deleted: NO_TXN }.as_disc_bytes());
+                        bytes.extend(vec![0; table.row_size]);
+                    }
+                    false => {
+                        let row = Row::from_disc_bytes(row_bytes, &d_types)?;
+                        bytes.extend(SlotHeader { live: true, created: MIGRATED_TXN, deleted: NO_TXN }.as_disc_bytes());
+                        bytes.extend(row.as_disc_bytes(&d_types)?);
+                    }
+                }
+            }
+            Self::write_file_atomically(&migrated_path, &bytes)?;
+
+            let index = IndexDef {
+                name: format!("{}_{}_idx", table.name, table.index_col),
+                col: table.index_col,
+                kind: IndexKind::Hash,
+            };
+            let schema = Schema { indexes: vec![index], ..Schema::create(table.cols, vec![], vec![], vec![]) };
+            Self::write_schema(table_dir, &schema)?;
+        }
+
+        if migrated_path.exists() {
+            let meta_path = LegacyTable::meta_path(table_dir);
+            if meta_path.exists() && fs::remove_file(&meta_path).is_err() {
+                return Err(BongoError::WriteFileError(format!(
+                    "Could not remove meta data of format version 0 at '{}'",
+                    meta_path.to_str().unwrap()
+                )));
+            }
+            if fs::rename(&migrated_path, table_dir.join("data.bongo")).is_err() {
+                return Err(BongoError::WriteFileError(format!(
+                    "Could not complete migration at '{}'",
+                    migrated_path.to_str().unwrap()
+                )));
+            }
+            Self::sync_dir(table_dir)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Loads all tables inside of `root_dir` and returns them together with the names of all tables
+    /// whose meta data had to be rebuilt from data.bongo.
+    ///
+    fn load_tables_from_disc(root_dir: &Path) -> Result<(HashMap<String, RwLock<TableMetaData>>, HashSet<String>), BongoError> {
+        //
+        // 1. if root_dir it does not exists and create_db is true create the directory
+        // 2. load files from disc for each directory entry into a HashMap
+        //
+        match root_dir.read_dir() {
+            Ok(entries) => {
+                let loaded = entries
+                    // only directories are tables. The write-ahead log and the other files of the
+                    // database are also stored in the root directory. A table directory without any
+                    // meta data has been created right before a crash and is restored from the
+                    // write-ahead log.
+                    .filter(|entry_res| {
+                        entry_res.as_ref().map_or(true, |entry| {
+                            let path = entry.path();
+                            path.is_dir() && (path.join("schema.bongo").exists() || path.join("meta.bongo").exists())
+                        })
+                    })
+                    .map(|entry_res| {
+                        match entry_res {
+                            Ok(entry) => Self::load_table_from_disc(entry.path()),
+                            Err(_) => Err(BongoError::ReadFileError(
+                                "Cannot read file inside DB root".to_string(),
+                            )),
+                        }
+                    })
+                    .collect::<Vec<Result<(String, RwLock<TableMetaData>, bool), BongoError>>>()
+                    // fail fast unwrap of all contained elements and in error case bubble up error
+                    .try_convert_all(|t| t)?;
+
+                let rebuilt = loaded.iter()
+                    .filter(|(_, _, rebuilt)| *rebuilt)
+                    .map(|(name, _, _)| name.clone())
+                    .collect();
+
+                // back to iterator in order to convert vec of tuples to map
+                let tables = loaded.into_iter()
+                    .map(|(name, table, _)| (name, table))
+                    .collect();
+
+                Ok((tables, rebuilt))
+            }
+            Err(_) => Err(BongoError::ReadFileError(
+                "Cannot read files inside DB root".to_string(),
+            )),
+        }
+    }
+
+    ///
+    /// Loads a  key value pair of table name and `TableMetaData` from a file in a given directory.
+    ///
+    /// If meta.bongo is missing or cannot be read, the meta data is rebuilt from schema.bongo and
+    /// data.bongo instead. The returned flag tells whether the meta data has been rebuilt.
+    ///
+    fn load_table_from_disc(table_dir: PathBuf) -> Result<(String, RwLock<TableMetaData>, bool), BongoError> {
+        //
+        // 1. complete or remove files that have not been completely written during the last flush or compaction
+        // 2. read meta.bongo file content to end
+        // 3. deserialize content into a tuple `String, RwLock<TableMetaData)`
+        // 4. if any of the above fails rebuild the meta data from the data of the table
+        //
+        // the compacted heap and the schema of a rewritten table belong to the compacted data. They
+        // are only kept if data.bongo has already been replaced by the compacted data.
+        if !table_dir.join("data.bongo.compact").exists() {
+            for (compacted, name) in [("heap.bongo.compact", HEAP_FILE_NAME), ("schema.bongo.compact", "schema.bongo")] {
+                let compacted_path = table_dir.join(compacted);
+                if compacted_path.exists() && fs::rename(&compacted_path, table_dir.join(name)).is_err() {
+                    return Err(BongoError::WriteFileError(format!(
+                        "Could not complete compaction at '{}'",
+                        compacted_path.to_str().unwrap()
+                    )));
+                }
+            }
+            Self::sync_dir(&table_dir)?;
+        }
+
+        for incomplete in ["meta.bongo.tmp", "data.bongo.compact", "heap.bongo.compact", "schema.bongo.compact",
+            "schema.bongo.compact.tmp"] {
+            let incomplete_path = table_dir.join(incomplete);
+            if incomplete_path.exists() && fs::remove_file(&incomplete_path).is_err() {
+                return Err(BongoError::WriteFileError(format!(
+                    "Could not remove incomplete file at '{}'",
+                    incomplete_path.to_str().unwrap()
+                )));
+            }
+        }
+
+        let mut data = Vec::new();
+        let meta_path = table_dir.join("meta.bongo");
+
+        if let Ok(mut file) = File::open(&meta_path) {
+            if file.read_to_end(&mut data).is_ok() {
+                if let Ok((name, mut table)) = bincode::deserialize::<(String, TableMetaData)>(&data[..]) {
+                    table.open_indexes(&table_dir)?;
+                    return Ok((name, RwLock::new(table), false));
+                }
+            }
+        }
+
+        println!(
+            "Meta table file at '{}' is missing or corrupted. Rebuilding it from table data.",
+            meta_path.to_str().unwrap()
+        );
+
+        let schema_path = table_dir.join("schema.bongo");
+        let mut schema = Vec::new();
+        let schema: Schema = match File::open(&schema_path) {
+            Ok(mut file) => match file.read_to_end(&mut schema) {
+                Ok(_) => match bincode::deserialize(&schema[..]) {
+                    Ok(schema) => schema,
+                    Err(_) => return Err(BongoError::InternalError(format!(
+                        "Schema file corrupted and cannot be deserialized at '{}'",
+                        schema_path.to_str().unwrap()
+                    ))),
+                },
+                Err(_) => return Err(BongoError::ReadFileError(format!(
+                    "Could not read schema from file at '{}'",
+                    schema_path.to_str().unwrap()
+                ))),
+            },
+            Err(_) => return Err(BongoError::ReadFileError(format!(
+                "Could not open schema file for reading at '{}'",
+                schema_path.to_str().unwrap()
+            ))),
+        };
+
+        // unwrap safe, because the directory of a table always has a valid unicode name
+        let name = table_dir.file_name().unwrap().to_str().unwrap().to_string();
+        let table = TableMetaData::rebuild(schema, &table_dir)?;
+
+        Ok((name, RwLock::new(table), true))
+    }
+
+    ///
+    /// Replays all `records` of the write-ahead log on the `tables` loaded from disc.
+    ///
+    /// Operations are only applied to tables whose meta data does not reflect them yet. This makes
+    /// replaying safe even if the server crashed while flushing, where some tables might have
+    /// already been flushed and some not.
+    ///
+    /// For tables contained in `rebuilt` it is unknown which records are reflected in their meta data.
+    /// Therefore only data.bongo is updated for them and their meta data is rebuilt again afterwards.
+    ///
+    /// Transactions that have not been finished before the crash are rolled back afterwards. The
+    /// rollback is logged in `wal` like a regular `ROLLBACK`.
+    ///
+    fn replay(db_root: &Path, tables: &mut HashMap<String, RwLock<TableMetaData>>,
+              rebuilt: &mut HashSet<String>, wal: &mut WriteAheadLog, records: &[WalRecord]) -> Result<(), BongoError> {
+        let mut last_lsn = records.last().map_or(0, |record| record.lsn);
+
+        for record in records {
+            Self::replay_record(db_root, tables, rebuilt, record)?;
+        }
+
+        for txn in Transaction::unfinished(records) {
+            println!("Rolling back transaction {} which has not been finished before shutdown.", txn.id);
+            let record = wal.append(Some(txn.id), txn.undo_ops(), true)?;
+            last_lsn = record.lsn;
+            Self::replay_record(db_root, tables, rebuilt, &record)?;
+        }
+
+        for name in rebuilt.iter() {
+            if let Some(cell) = tables.get_mut(name) {
+                match cell.get_mut() {
+                    Ok(table) => {
+                        let schema = table.schema();
+                        *table = TableMetaData::rebuild(schema, &db_root.join(name))?;
+                        table.lsn = last_lsn;
+                    }
+                    Err(_) => return Err(BongoError::InternalError("Concurrency Error.".to_string())),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn replay_record(db_root: &Path, tables: &mut HashMap<String, RwLock<TableMetaData>>,
+                     rebuilt: &mut HashSet<String>, record: &WalRecord) -> Result<(), BongoError> {
+        for op in &record.ops {
+            match op {
+                WalOp::CreateTable { table, cols, checks, uniques, foreign_keys } => {
+                    if !tables.contains_key(table) {
+                        let schema = Schema::create(cols.clone(), checks.clone(), uniques.clone(), foreign_keys.clone());
+                        Self::apply_create_table(db_root, tables, table, schema, record.lsn)?;
+                    }
+                }
+                WalOp::DropTable { table } => {
+                    let reflected = match tables.get_mut(table) {
+                        None => false,
+                        Some(cell) => !rebuilt.contains(table) && Self::table_lsn(cell)? >= record.lsn,
+                    };
+                    if !reflected {
+                        Self::apply_drop_table(db_root, tables, table)?;
+                        rebuilt.remove(table);
+                    }
+                }
+                WalOp::CreateIndex { table: table_name, index } => {
+                    if let Some(cell) = tables.get_mut(table_name) {
+                        let is_rebuilt = rebuilt.contains(table_name);
+                        match cell.get_mut() {
+                            Ok(table) => {
+                                // the meta data of a rebuilt table contains the indexes of its schema
+                                let reflected = match is_rebuilt {
+                                    true => table.indexes.iter().any(|i| i.def.name == index.name),
+                                    false => table.lsn >= record.lsn,
+                                };
+                                if !reflected {
+                                    Self::apply_create_index(db_root, table_name, table, index.clone(), record.lsn)?;
+                                }
+                            }
+                            Err(_) => return Err(BongoError::InternalError("Concurrency Error.".to_string())),
+                        }
+                    }
+                }
+                WalOp::DropIndex { table: table_name, name } => {
+                    if let Some(cell) = tables.get_mut(table_name) {
+                        let is_rebuilt = rebuilt.contains(table_name);
+                        match cell.get_mut() {
+                            Ok(table) => {
+                                let reflected = match is_rebuilt {
+                                    true => table.indexes.iter().all(|i| &i.def.name != name),
+                                    false => table.lsn >= record.lsn,
+                                };
+                                if !reflected {
+                                    Self::apply_drop_index(db_root, table_name, table, name, record.lsn)?;
+                                }
+                            }
+                            Err(_) => return Err(BongoError::InternalError("Concurrency Error.".to_string())),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // row level operations of a record can belong to multiple tables if the record undoes a
+        // transaction. The operations on different tables are independent of each other.
+        let mut table_names = record.ops.iter()
+            .filter(|op| op.is_row_op())
+            .map(|op| op.table())
+            .collect::<Vec<&str>>();
+        table_names.sort_unstable();
+        table_names.dedup();
+
+        for table_name in table_names {
+            let row_ops = record.ops.iter().filter(|op| op.table() == table_name);
+
+            // a table that does not exist anymore has been dropped by a later record
+            if let Some(cell) = tables.get_mut(table_name) {
+                let data_path = db_root.join(table_name).join("data.bongo");
+                let is_rebuilt = rebuilt.contains(table_name);
+                if is_rebuilt || Self::table_lsn(cell)? < record.lsn {
+                    match cell.get_mut() {
+                        Ok(table) if is_rebuilt => table.redo_row_ops(&data_path, row_ops)?,
+                        Ok(table) => table.apply_row_ops(&data_path, row_ops, record.lsn)?,
+                        Err(_) => return Err(BongoError::InternalError("Concurrency Error.".to_string())),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn table_lsn(cell: &mut RwLock<TableMetaData>) -> Result<u64, BongoError> {
+        match cell.get_mut() {
+            Ok(table) => Ok(table.lsn),
+            Err(_) => Err(BongoError::InternalError("Concurrency Error.".to_string())),
+        }
+    }
+
+    ///
+    /// Executes a `BongoRequest` by first parsing it and then executing its contents.
+    /// returns a `BongoResult` representing the result of execution.
+    ///
+    /// The request is executed on behalf of a default connection. Use `execute_for` to execute
+    /// requests of different connections, which can have their own transactions.
+    ///
+    #[allow(dead_code)] // the server executes all requests on behalf of their connection
+    pub fn execute(&mut self, request: &BongoRequest) -> BongoResult {
+        self.execute_for(DEFAULT_CONNECTION, request)
+    }
+
+    ///
+    /// Executes a `BongoRequest` that has been received on `connection`.
+    ///
+    /// If a table the statement accesses is locked by a transaction of another connection, the
+    /// execution is retried until the table is released or `lock_timeout` has passed.
+    ///
+    pub fn execute_for(&mut self, connection: ConnectionId, request: &BongoRequest) -> BongoResult {
+        let statement = SqlParser::parse(&request.sql)?;
+
+        println!(
+            "sql has been parsed with the following resulting statement:\n{:?}",
+            statement
+        );
+
+        let must_flush = self.auto_flush && !matches!(&statement, Statement::Select(_) | Statement::Flush |
+            Statement::Vacuum(_) | Statement::Begin | Statement::Explain(Explain { analyze: false, .. }));
+
+        let deadline = Instant::now() + self.lock_timeout;
+        let mut statement = Some(statement);
+
+        let result = loop {
+            // parse again for each retry, because executing a statement consumes it
+            let statement = match statement.take() {
+                Some(statement) => statement,
+                None => SqlParser::parse(&request.sql)?,
+            };
+
+            match self.execute_statement(connection, statement) {
+                Err(BongoError::TableLockedError(msg)) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break Err(BongoError::TableLockedError(msg));
+                    }
+                    self.wait_for_transaction_end(deadline - now)?;
+                }
+                result => break result,
+            }
+        };
+
+        if must_flush { self.flush()?; }
+
+        result
+    }
+
+    fn execute_statement(&mut self, connection: ConnectionId, statement: Statement) -> BongoResult {
+        let ctx = Context { connection, txn: self.transaction_of(connection)?, auto_commit: false };
+
+        if ctx.txn.is_some() && matches!(&statement, Statement::CreateTable(_) | Statement::DropTable(_) |
+            Statement::AlterTable(_) | Statement::CreateIndex(_) | Statement::DropIndex(_) | Statement::CreateSequence(_) |
+            Statement::DropSequence(_) | Statement::Vacuum(_)) {
+            return Err(BongoError::UnsupportedFeatureError(
+                "CREATE TABLE, DROP TABLE, ALTER TABLE, CREATE INDEX, DROP INDEX, CREATE SEQUENCE, DROP SEQUENCE and VACUUM \
+                cannot be executed inside of a transaction.".to_string()
+            ));
+        }
+
+        match statement {
+            Statement::Select(select) => self.select(select, ctx),
+            Statement::Insert(insert) => self.in_transaction(ctx, |ex, ctx| ex.insert(insert, ctx)),
+            Statement::Update(update) => self.in_transaction(ctx, |ex, ctx| ex.update(update, ctx)),
+            Statement::Delete(delete) => self.in_transaction(ctx, |ex, ctx| ex.delete(delete, ctx)),
+            Statement::CreateTable(create_table) => self.create_table(create_table),
+            Statement::DropTable(drop_table) => self.drop_table(drop_table, ctx),
+            Statement::AlterTable(alter_table) => self.alter_table(alter_table, ctx),
+            Statement::CreateIndex(create_index) => self.create_index(create_index),
+            Statement::DropIndex(drop_index) => self.drop_index(drop_index),
+            Statement::CreateSequence(create_sequence) => self.create_sequence(create_sequence),
+            Statement::DropSequence(drop_sequence) => self.drop_sequence(drop_sequence),
+            Statement::Flush => self.flush(),
+            Statement::Vacuum(vacuum) => self.vacuum(vacuum),
+            Statement::Begin => self.begin(connection),
+            Statement::Commit => self.commit(connection),
+            Statement::Rollback => self.rollback(connection),
+            Statement::Explain(explain) => self.explain(explain, connection),
+            Statement::CreateDB { .. } | Statement::DropDB { .. } => Self::create_drop_db(),
+        }
+    }
+
+    ///
+    /// Executes a statement that modifies tables in the transaction of the connection or, outside
+    /// of a transaction, in a new transaction that is committed as soon as the statement is applied.
+    ///
+    fn in_transaction<F>(&mut self, ctx: Context, execute: F) -> BongoResult
+        where F: FnOnce(&mut Self, Context) -> BongoResult {
+        if ctx.txn.is_some() {
+            return execute(self, ctx);
+        }
+
+        let txn = {
+            let mut transactions = self.transactions_access()?;
+            let txn = self.next_txn_id.fetch_add(1, Ordering::Relaxed);
+            // registering the implicit transaction makes it invisible to snapshots until it is committed
+            transactions.insert(ctx.connection, Transaction::new(txn));
+            txn
+        };
+
+        let result = execute(self, Context { connection: ctx.connection, txn: Some(txn), auto_commit: true });
+
+        self.transactions_access()?.remove(&ctx.connection);
+
+        result
+    }
+
+    ///
+    /// Returns a snapshot of the transactions in progress for a statement that is executed in `ctx`.
+    /// The snapshot is registered until the returned guard is dropped, see `reclaim_horizon`.
+    ///
+    fn snapshot(&self, ctx: Context) -> Result<SnapshotGuard<'_>, BongoError> {
+        let transactions = self.transactions_access()?;
+
+        let snapshot = Snapshot {
+            // transaction ids are only given out while holding the lock on the transactions
+            bound: self.next_txn_id.load(Ordering::Relaxed),
+            active: transactions.values()
+                .map(|txn| txn.id)
+                .filter(|id| ctx.txn != Some(*id))
+                .collect(),
+            own: ctx.txn,
+        };
+        // the snapshot is registered before the lock is released, so that no writer misses it
+        *self.snapshots_access()?.entry(snapshot.horizon()).or_insert(0) += 1;
+
+        Ok(SnapshotGuard { snapshot, snapshots: &self.snapshots })
+    }
+
+    ///
+    /// Returns the transaction id before which all transactions have ended and are seen by the
+    /// snapshots of all running and future statements. Expired versions that have been deleted by
+    /// such a transaction are not visible to any statement anymore, so their slots can be reused.
+    ///
+    fn reclaim_horizon(&self) -> Result<TransactionId, BongoError> {
+        let transactions = self.transactions_access()?;
+        let snapshots = self.snapshots_access()?;
+
+        Ok(transactions.values()
+            .map(|txn| txn.id)
+            .chain(snapshots.keys().next().copied())
+            .fold(self.next_txn_id.load(Ordering::Relaxed), TransactionId::min))
+    }
+
+    ///
+    /// Rolls back the transaction of `connection` if there is one.
+    ///
+    /// This must be called when a connection is closed, as its transaction can never be finished
+    /// otherwise and the tables it has modified would stay locked.
+    ///
+    pub fn disconnect(&mut self, connection: ConnectionId) -> Result<(), BongoError> {
+        if self.transaction_of(connection)?.is_some() {
+            self.rollback(connection)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// A `Begin` statement is executed as follows.
+    ///
+    /// 1. check that there is no transaction in progress on this connection
+    /// 2. register a new transaction for this connection
+    ///
+    /// Nothing is written to the write-ahead log until the transaction modifies a table.
+    ///
+    fn begin(&mut self, connection: ConnectionId) -> BongoResult {
+        let mut transactions = self.transactions_access()?;
+
+        if transactions.contains_key(&connection) {
+            return Err(BongoError::SqlRuntimeError("There is already a transaction in progress.".to_string()));
+        }
+
+        let id = self.next_txn_id.fetch_add(1, Ordering::Relaxed);
+        transactions.insert(connection, Transaction::new(id));
+
+        Ok(None)
+    }
+
+    ///
+    /// A `Commit` statement is executed as follows.
+    ///
+    /// 1. lock all tables the transaction has modified
+    /// 2. append the end of the transaction to the write-ahead log
+    /// 3. remove the transaction of this connection, which makes its changes visible to new snapshots
+    /// 4. release all tables the transaction has locked
+    ///
+    fn commit(&mut self, connection: ConnectionId) -> BongoResult {
+        self.end_transaction(connection, false)
+    }
+
+    ///
+    /// A `Rollback` statement is executed as follows.
+    ///
+    /// 1. lock all tables the transaction has modified
+    /// 2. append the inverse of all operations of the transaction in reverse order together with
+    ///    the end of the transaction to the write-ahead log
+    /// 3. remove the transaction of this connection
+    /// 4. apply the inverse operations to each table the transaction has modified, which restores
+    ///    rows, index entries, expired versions and ghosts, and release the table
+    ///
+    fn rollback(&mut self, connection: ConnectionId) -> BongoResult {
+        self.end_transaction(connection, true)
+    }
+
+    fn end_transaction(&mut self, connection: ConnectionId, rollback: bool) -> BongoResult {
+        let tables = self.tables_read_access()?;
+
+        let mut names = match self.transactions_access()?.get(&connection) {
+            None => return Err(BongoError::SqlRuntimeError("There is no transaction in progress.".to_string())),
+            Some(txn) => txn.tables.iter().cloned().collect::<Vec<String>>(),
+        };
+        // tables are locked in the order of their names like in a checkpoint to avoid dead locks
+        names.sort_unstable();
+
+        let mut locked = vec![];
+        for name in &names {
+            // tables locked by a transaction cannot be dropped
+            let table = tables.get(name).unwrap().write();
+            if table.is_err() {
+                return Err(InternalError("Concurrency Error.".to_string()));
+            }
+            locked.push((name, table.unwrap()));
+        }
+
+        let undo = {
+            let mut transactions = self.transactions_access()?;
+            // only the connection itself can end its transaction
+            let txn = transactions.get(&connection).unwrap();
+
+            // a transaction without records has never been written to the write-ahead log
+            let undo = match (txn.records.is_empty(), rollback) {
+                (true, _) => None,
+                (false, true) => Some(self.wal_access()?.append(Some(txn.id), txn.undo_ops(), true)?),
+                (false, false) => {
+                    self.wal_access()?.append(Some(txn.id), vec![], true)?;
+                    None
+                }
+            };
+
+            transactions.remove(&connection);
+            undo
+        };
+
+        for (name, table) in locked.iter_mut() {
+            if let Some(record) = &undo {
+                let data_path = self.db_root.join(name.as_str()).join("data.bongo");
+                table.apply_row_ops(&data_path, record.ops.iter().filter(|op| op.table() == name.as_str()), record.lsn)?;
+            }
+
+            table.owner = None;
+        }
+
+        self.transaction_ended.notify_all();
+
+        Ok(None)
+    }
+
+    fn transaction_of(&self, connection: ConnectionId) -> Result<Option<TransactionId>, BongoError> {
+        Ok(self.transactions_access()?.get(&connection).map(|txn| txn.id))
+    }
+
+    ///
+    /// Blocks until any transaction ends or `timeout` has passed.
+    ///
+    fn wait_for_transaction_end(&self, timeout: Duration) -> Result<(), BongoError> {
+        let transactions = self.transactions_access()?;
+        // transactions might have ended between the failed attempt and acquiring the lock, which
+        // is why the waiting time is limited to a short interval before trying again.
+        let timeout = timeout.min(Duration::from_millis(100));
+        if self.transaction_ended.wait_timeout(transactions, timeout).is_err() {
+            return Err(InternalError("Concurrency Error.".to_string()));
+        }
+
+        Ok(())
+    }
+
+    // TODO: flush has a concurrency related bug that shows up in the tests of ../examples-and-tests-cli
+    //  The issue comes only up if all tests are ran in parallel and the server is running on auto_flush.
+    ///
+    /// A `Flush` statement is executed as follows.
+    ///
+    /// for each table:
+    ///  1. serialize table
+    ///  2. check that directory for table exists
+    ///  3. force the contents of data.bongo to disc
+    ///  4. atomically replace the meta data file with the serialized table
+    ///
+    /// afterwards:
+    ///  5. truncate the write-ahead log, because all logged statements are now reflected on disc
+    ///  6. write the exact state of the sequences, so that their reserved values are not skipped
+    ///
+    fn flush(&mut self) -> BongoResult {
+        // no tables can be added or removed during the flush. Concurrent selects can continue,
+        // because the flush only requires read access on the meta data of each table.
+        let tables = self.tables_read_access()?;
+
+        self.checkpoint(&tables)?;
+        self.sequences_access()?.sync()?;
+
+        Ok(None)
+    }
+
+    ///
+    /// Writes the meta data of all `tables` to disc and truncates the write-ahead log afterwards.
+    /// Only the records of transactions in progress are kept in the write-ahead log.
+    ///
+    /// The meta data of all tables is locked for reading during the whole checkpoint, so that no
+    /// statement can append records to the write-ahead log that are truncated without being
+    /// reflected in the flushed meta data.
+    ///
+    fn checkpoint(&self, tables: &HashMap<String, RwLock<TableMetaData>>) -> Result<(), BongoError> {
+        // tables are locked in the order of their names like when a transaction ends to avoid dead locks
+        let mut names = tables.keys().collect::<Vec<&String>>();
+        names.sort_unstable();
+
+        let mut locked = vec![];
+        for name in names {
+            let table = tables[name].read();
+            if table.is_err() {
+                return Err(InternalError("Concurrency Error.".to_string()));
+            }
+            locked.push((name, table.unwrap()));
+        }
+
+        for (name, table) in &locked {
+            Self::flush_table(&self.db_root, name, table)?;
+        }
+
+        // transactions in progress must still be undone if the server crashes before they end
+        let transactions = self.transactions_access()?;
+        let mut keep = transactions.values()
+            .flat_map(|txn| txn.records.iter())
+            .collect::<Vec<&WalRecord>>();
+        keep.sort_by_key(|record| record.lsn);
+
+        self.wal_access()?.truncate(&keep)
+    }
+
+    fn flush_table(db_root: &Path, name: &str, table: &TableMetaData) -> Result<(), BongoError> {
+        let encoded = bincode::serialize(&(name, table));
+        if encoded.is_err() { return Err(BongoError::InternalError("Could not write cashed state to disc.".to_string())); }
+        let encoded = encoded.unwrap();
+
+        let mut location = db_root.to_path_buf();
+        location.push(name);
+
+        if !location.is_dir() {
+            return Err(BongoError::InternalError("Table to flush has no directory on disc".to_string()));
+        }
+
+        // rows written since the last flush might still be in the cache of the operating system
+        let data = File::open(location.join("data.bongo"));
+        if data.is_err() || data.unwrap().sync_all().is_err() {
+            return Err(BongoError::WriteFileError("Could not force table data to disc.".to_string()));
+        }
+        Heap::new(&location.join(HEAP_FILE_NAME)).sync()?;
+        for index in &table.indexes {
+            index.sync()?;
+        }
+
+        location.push("meta.bongo");
+
+        Self::write_file_atomically(&location, &encoded)
+    }
+
+    ///
+    /// A `Vacuum` statement is executed as follows.
+    ///
+    /// 1. check that the table exists if a table is specified
+    /// 2. flush all tables, which empties the write-ahead log
+    /// 3. compact the specified table or all tables if no table is specified
+    ///
+    fn vacuum(&mut self, vacuum: Vacuum) -> BongoResult {
+        let names = match vacuum.table {
+            Some(table) => {
+                self.get_table_dir_if_exists(&table)?;
+                vec![table]
+            }
+            None => self.tables_read_access()?.keys().cloned().collect(),
+        };
+
+        self.vacuum_tables(&names, false)?;
+
+        Ok(None)
+    }
+
+    ///
+    /// Compacts all tables except `skipped` in which the share of ghosts and expired versions among
+    /// all rows on disc is at least `ghost_ratio`. Returns the name of each table that has been
+    /// compacted together with the result of its compaction, so that the failure of one table
+    /// does not prevent the compaction of the others.
+    ///
+    /// This is used by the background compactor of `BongoServer`. Tables that are locked by a
+    /// transaction are skipped.
+    ///
+    pub fn auto_vacuum(&self, ghost_ratio: f64, skipped: &HashSet<String>) -> Result<Compactions, BongoError> {
+        let names = self.tables_read_access()?.iter()
+            .filter(|(name, cell)| {
+                if skipped.contains(*name) {
+                    return false;
+                }
+                let table = cell.read();
+                match table {
+                    Ok(table) => {
+                        let reclaimable = table.ghosts.len() + table.expired.len();
+                        reclaimable > 0 &&
+                            reclaimable as f64 / (table.ghosts.len() + table.row_count) as f64 >= ghost_ratio
+                    }
+                    Err(_) => false,
+                }
+            })
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<String>>();
+
+        Ok(names.into_iter()
+            .map(|name| {
+                let result = self.vacuum_tables(std::slice::from_ref(&name), true);
+                (name, result)
+            })
+            .collect())
+    }
+
+    ///
+    /// Compacts the tables `names`. Tables that are locked by a transaction are skipped if
+    /// `skip_locked` is true and cause an error otherwise, because compacting them would relocate
+    /// rows that the transaction might have to restore.
+    ///
+    /// Expired versions of all other tables can be reclaimed, because the exclusive access to all
+    /// tables guarantees that no statement with an older snapshot is running anymore.
+    ///
+    fn vacuum_tables(&self, names: &[String], skip_locked: bool) -> Result<(), BongoError> {
+        // exclusive access is required as rows change their position on disc
+        let tables = self.tables_write_access()?;
+
+        // the compaction invalidates the positions of rows that operations in the write-ahead log
+        // refer to. Therefore the log must be empty before the compaction starts.
+        self.checkpoint(&tables)?;
+
+        for name in names {
+            // the table might have been dropped between determining the names and acquiring the lock
+            if let Some(cell) = tables.get(name) {
+                let table = cell.write();
+                if table.is_err() {
+                    return Err(InternalError("Concurrency Error.".to_string()));
+                }
+                let mut table = table.unwrap();
+
+                if table.owner.is_some() {
+                    if skip_locked {
+                        continue;
+                    }
+                    return Err(BongoError::TableLockedError(format!(
+                        "Table '{}' is locked by a transaction and cannot be compacted.", name
+                    )));
+                }
+
+                Self::compact_table(&self.db_root.join(name), &mut table)?;
+                Self::flush_table(&self.db_root, name, &table)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Rewrites the data.bongo file of a table without its ghosts and expired versions and relocates
+    /// all remaining rows in the indexes. The heap of a table with out of line columns is rewritten
+    /// with the values of the remaining rows only.
+    ///
+    /// The compaction is crash-safe:
+    /// 1. The compacted data is written to data.bongo.compact and the compacted heap to
+    ///    heap.bongo.compact. Both are removed on startup if the server crashes at this point.
+    /// 2. meta.bongo is removed, because it does not match the compacted data.
+    /// 3. data.bongo.compact is atomically renamed to data.bongo. If the server crashes before the
+    ///    new meta data is flushed by the caller, the meta data is rebuilt from data.bongo on startup
+    ///    no matter whether the old or the new data.bongo is present.
+    /// 4. heap.bongo.compact is atomically renamed to heap.bongo. If the server crashes before,
+    ///    the renaming is completed on startup, because the new data.bongo refers to the new heap.
+    ///
+    fn compact_table(table_dir: &Path, table: &mut TableMetaData) -> Result<(), BongoError> {
+        if table.ghosts.is_empty() && table.expired.is_empty() {
+            return Ok(());
+        }
+
+        let data_path = table_dir.join("data.bongo");
+        let compact_path = table_dir.join("data.bongo.compact");
+        let heap_path = table_dir.join(HEAP_FILE_NAME);
+        let compact_heap_path = table_dir.join("heap.bongo.compact");
+
+        let mut bytes = vec![];
+        let file = File::open(&data_path);
+        if file.is_err() || file.unwrap().read_to_end(&mut bytes).is_err() {
+            return Err(BongoError::ReadFileError("Could not read table data for compaction.".to_string()));
+        }
+
+        // maps the old position of each current row version to its new position
+        let mut relocations = HashMap::new();
+        let mut compacted = Vec::with_capacity((table.row_count - table.expired.len()) * table.row_size);
+        let has_heap = table.cols.iter().any(|col_def| col_def.data_type.is_out_of_line());
+        let mut heap = Heap::new(&heap_path);
+        let mut compacted_heap = vec![];
+        let d_types = table.cols.get_d_types();
+
+        for (i, slot) in bytes.chunks_exact_mut(table.row_size).enumerate() {
+            let header = SlotHeader::from_disc_bytes(slot);
+            if header.live && header.deleted == NO_TXN {
+                if has_heap {
+                    copy_values(&mut slot[SLOT_HEADER_SIZE..], &d_types, &mut heap, &mut compacted_heap)?;
+                }
+                relocations.insert((i * table.row_size) as u64, compacted.len() as u64);
+                compacted.extend_from_slice(slot);
+            }
+        }
+
+        let file = File::create(&compact_path);
+        if file.is_err() {
+            return Err(BongoError::WriteFileError("Could not create file for compacted table data.".to_string()));
+        }
+        let mut file = file.unwrap();
+        if file.write_all(&compacted).is_err() || file.sync_all().is_err() {
+            return Err(BongoError::WriteFileError("Could not write compacted table data.".to_string()));
+        }
+        if has_heap {
+            let file = File::create(&compact_heap_path);
+            if file.is_err() || file.as_ref().unwrap().write_all(&compacted_heap).is_err() || file.unwrap().sync_all().is_err() {
+                return Err(BongoError::WriteFileError("Could not write compacted heap of table.".to_string()));
+            }
+        }
+
+        let meta_path = table_dir.join("meta.bongo");
+        if meta_path.exists() && fs::remove_file(&meta_path).is_err() {
+            return Err(BongoError::WriteFileError("Could not delete outdated meta data file on disc.".to_string()));
+        }
+        Self::sync_dir(table_dir)?;
+
+        if fs::rename(&compact_path, &data_path).is_err() {
+            return Err(BongoError::WriteFileError("Could not replace table data with compacted table data.".to_string()));
+        }
+        Self::sync_dir(table_dir)?;
+
+        if has_heap {
+            if fs::rename(&compact_heap_path, &heap_path).is_err() {
+                return Err(BongoError::WriteFileError("Could not replace heap of table with compacted heap.".to_string()));
+            }
+            Self::sync_dir(table_dir)?;
+        }
+
+        // expired versions have no new position and are removed from the indexes
+        for index in table.indexes.iter_mut() {
+            index.relocate(&relocations, table_dir)?;
+        }
+        table.row_count -= table.expired.len();
+        table.ghosts.clear();
+        table.expired.clear();
+
+        Ok(())
+    }
+
+    ///
+    /// A `Select` statement is executed as follows.
+    ///
+    /// 1. check if all tables exist in cache and on disc
+    /// 2. resolve the column names against the columns of all tables as `JoinQuery` and take a
+    ///    snapshot of the transactions in progress
+    /// 3. read the rows of the first table that are visible in the snapshot and fulfill the parts of
+    ///    the condition that only refer to the first table, see `scan_table`
+    /// 4. join the other tables to the rows in the order in which they are joined, see `join_table`
+    /// 5. check if the remaining condition is true for the joined rows
+    /// 6. if the statement uses `GROUP BY` or aggregate functions, combine the rows of each group
+    ///    and check if the `HAVING` condition is true for the combined rows, see `GroupQuery`
+    /// 7. check if order is given, if so sort accordingly unless the rows have been read in order
+    ///    from a B-tree index. If `LIMIT` is given only the rows up to the limit are kept while sorting.
+    /// 8. skip the rows before `OFFSET` and the rows after `LIMIT`
+    /// 9. remove the not selected columns and return the result.
+    ///
+    /// Without order, joins and aggregation the reading in step 3 stops as soon as enough rows have
+    /// been found for `OFFSET` and `LIMIT`.
+    ///
+    fn select(&self, select: Select, ctx: Context) -> BongoResult {
+        let table_names = select.table_names();
+        for table_name in &table_names {
+            self.get_table_dir_if_exists(table_name)?;
+        }
+        // the read lock on the tables is held until the select is finished, because compacting a
+        // table while reading it would relocate its rows
+        let tables = self.tables_read_access()?;
+        let query = JoinQuery::new(&select, &Self::table_cols(&tables, &table_names)?)?;
+        let group = GroupQuery::new(&select, &query.cols.concat())?;
+        let snapshot = self.snapshot(ctx)?;
+
+        let offset = select.offset.unwrap_or(0);
+        let keep = select.limit.map(|limit| limit.saturating_add(offset));
+        let stop_after = match Self::stops_early(&select, &query, &group) {
+            true => keep,
+            false => None,
+        };
+
+        let (mut rows, ordered, examined) = self.scan_table(&tables, &select.table, query.condition.clone(),
+                                                            query.order_col.as_deref(), stop_after, &snapshot)?;
+        self.count_examined(ctx.connection, examined)?;
+
+        let mut cols = query.cols[0].clone();
+        for (join, join_cols) in query.joins.iter().zip(&query.cols[1..]) {
+            rows = self.join_table(&tables, rows, &cols, join, &snapshot)?;
+            cols.extend(join_cols.iter().cloned());
+        }
+
+        if let Some(filter) = &query.filter {
+            let mut filtered = vec![];
+            for row in rows {
+                if filter.eval(&row, &cols, self.null_logic)? {
+                    filtered.push(row);
+                }
+            }
+            rows = filtered;
+        }
+
+        let (order, projection) = match &group {
+            Some(group) => {
+                rows = group.group(rows, self.null_logic)?;
+                (&group.order, &group.projection)
+            }
+            None => {
+                rows = query.compute(rows, &cols, self.null_logic)?;
+                (&query.order, &query.projection)
+            }
+        };
+
+        // apply order before removing unselected indices, because we allow ordering by non-selected columns
+        if query.order_col.is_some() && ordered {
+            // the rows have been read in ascending order from a B-tree index
+            if !order[0].asc {
+                rows.reverse();
+            }
+        } else if !order.is_empty() {
+            rows = sort_rows(rows, keep, |r1, r2| compare_rows(r1, r2, order));
+        }
+
+        // remove skipped rows and non-selected indices
+        let rows = rows.into_iter()
+            .skip(offset)
+            .take(select.limit.unwrap_or(usize::MAX))
+            .map(|row| projection.iter().map(|i| row[*i].clone()).collect())
+            .collect();
+
+        Ok(Some(rows))
+    }
+
+    ///
+    /// Returns true if reading the first table of `select` can stop as soon as the rows for `LIMIT`
+    /// and `OFFSET` have been found, which is the case if each row read is returned in the order
+    /// in which it has been read.
+    ///
+    fn stops_early(select: &Select, query: &JoinQuery, group: &Option<GroupQuery>) -> bool {
+        select.limit.is_some() && select.order.is_empty() && query.joins.is_empty() && group.is_none()
+    }
+
+    ///
+    /// Returns the definitions of the columns of the tables `names`, which must exist.
+    ///
+    fn table_cols(tables: &HashMap<String, RwLock<TableMetaData>>, names: &[String]) -> Result<Vec<(String, Vec<ColumnDef>)>, BongoError> {
+        names.iter()
+            .map(|name| {
+                let table = tables.get(name).unwrap().read();
+                if table.is_err() {
+                    return Err(BongoError::InternalError("Could not acquire read access to cached meta data.".to_string()));
+                }
+                Ok((name.clone(), table.unwrap().cols.clone()))
+            })
+            .collect()
+    }
+
+    ///
+    /// Replaces the column names in `condition` that are qualified with `table` by their plain names,
+    /// so that conditions of statements on a single table may use either form.
+    ///
+    fn unqualify_condition(table: &str, condition: Option<Expr>) -> Result<Option<Expr>, BongoError> {
+        condition.map(|condition| condition.rename_identifiers(&|name| unqualify_col(table, name))).transpose()
+    }
+
+    ///
+    /// Reads the rows of the table `table_name` that are visible in `snapshot` and fulfill `condition`.
+    ///
+    /// The positions to read are planned as `QueryPlan`. Afterwards the meta data of the table is
+    /// released, so that other statements can modify the table while the rows are read. The returned
+    /// flag tells whether the rows have been read in ascending order of `order_col` from a B-tree index.
+    /// Reading stops as soon as `stop_after` rows have been found. The returned amount of examined
+    /// rows are the rows read that are visible in `snapshot`.
+    ///
+    fn scan_table(&self, tables: &HashMap<String, RwLock<TableMetaData>>, table_name: &str, condition: Option<Expr>,
+                  order_col: Option<&str>, stop_after: Option<usize>, snapshot: &Snapshot)
+                  -> Result<(Vec<Row>, bool, usize), BongoError> {
+        let path = self.get_table_dir_on_disc(table_name).join("data.bongo");
+        // unwrap safe, because we have checked the entry exists before
+        let cell = tables.get(table_name).unwrap();
+
+        let (cols, plan, positions, mut reader) = {
+            let table = cell.read();
+
+            if table.is_err() {
+                return Err(BongoError::InternalError("Could not acquire read access to cached meta data.".to_string()));
+            }
+            let table = table.unwrap();
+
+            let plan = QueryPlan::new(&table.indexes, &table.cols, condition, order_col, self.null_logic);
+            let positions = plan.positions(&table.indexes, || table.positions())?;
+
+            (table.cols.clone(), plan, positions, SlotReader::open(&path, &table)?)
+        }; // release the meta data, so that other statements can modify the table concurrently
+
+        let d_types = cols.get_d_types();
+        let col_names = cols.get_col_names();
+
+        let mut rows = match &plan.residual {
+            None => {
+                // when all indices are used, better already allocate
+                Vec::with_capacity(positions.len())
+            }
+            Some(_) => {
+                // if there is an expression we do not know yet how much to allocate
+                vec![]
+            }
+        };
+
+        let mut examined = 0;
+        for i in positions {
+            if stop_after.is_some_and(|stop_after| rows.len() >= stop_after) {
+                break;
+            }
+            let row = match reader.read(i, &d_types, snapshot)? {
+                Some(row) => row,
+                None => continue,
+            };
+            examined += 1;
+
+            // if no condition exists or the existing condition evaluates to true this row shall be returned
+            if plan.residual.is_none() ||
+                plan.residual.as_ref().unwrap().eval(&row, &col_names, self.null_logic)? {
+                rows.push(row);
+            }
+        }
+
+        Ok((rows, plan.ordered, examined))
+    }
+
+    ///
+    /// Joins the rows of the table `join.table` that are visible in `snapshot` to the `rows` with
+    /// the columns `cols` as planned by a `JoinPlan`.
+    ///
+    /// For an index lookup the values of the left side are looked up in the index while the meta
+    /// data of the joined table is locked, for a hash join or a nested loop all slots of the joined
+    /// table are read. In all cases the rows are read after the meta data has been released.
+    ///
+    fn join_table(&self, tables: &HashMap<String, RwLock<TableMetaData>>, rows: Vec<Row>, cols: &[String],
+                  join: &Join, snapshot: &Snapshot) -> Result<Vec<Row>, BongoError> {
+        let path = self.get_table_dir_on_disc(&join.table).join("data.bongo");
+        // unwrap safe, because we have checked the entry exists before
+        let cell = tables.get(&join.table).unwrap();
+
+        let (join_cols, plan, lookups, mut reader) = {
+            let table = cell.read();
+
+            if table.is_err() {
+                return Err(BongoError::InternalError("Could not acquire read access to cached meta data.".to_string()));
+            }
+            let table = table.unwrap();
+
+            let plan = JoinPlan::new(join, cols, &table.cols, &table.indexes, rows.len());
+
+            let mut lookups = HashMap::new();
+            match (&plan.strategy, plan.key) {
+                (JoinStrategy::IndexLookup { index }, Some((left, right))) => {
+                    // unwrap safe, because the plan only uses existing indexes
+                    let index = table.indexes.iter().find(|i| &i.def.name == index).unwrap();
+                    let mut looked_up = HashSet::new();
+                    for row in &rows {
+                        if !looked_up.insert(&row[left]) {
+                            continue;
+                        }
+                        // values are looked up with the type of the indexed column, values that
+                        // cannot be converted to it are not equal to any value of the column
+                        if let Ok(key) = table.cols[right].data_type.convert(row[left].clone()) {
+                            lookups.entry(join_key(&row[left])).or_insert_with(Vec::new).extend(index.get(&key)?);
+                        }
+                    }
+                }
+                _ => { lookups.insert(BongoLiteral::Null, table.positions()); }
+            }
+
+            (table.cols.clone(), plan, lookups, SlotReader::open(&path, &table)?)
+        }; // release the meta data, so that other statements can modify the table concurrently
+
+        let d_types = join_cols.get_d_types();
+        let mut read = |positions: Vec<u64>| -> Result<Vec<Row>, BongoError> {
+            let mut rows = vec![];
+            for pos in positions {
+                if let Some(row) = reader.read(pos, &d_types, snapshot)? {
+                    rows.push(row);
+                }
+            }
+            Ok(rows)
+        };
+
+        let candidates = match (plan.strategy, plan.key) {
+            (JoinStrategy::IndexLookup { .. }, Some((left, _))) => {
+                let mut by_key = HashMap::new();
+                for (key, mut positions) in lookups {
+                    // different values of the left side can have the same key
+                    positions.sort_unstable();
+                    positions.dedup();
+                    by_key.insert(key, read(positions)?);
+                }
+                Candidates::ByKey { col: left, rows: by_key }
+            }
+            (JoinStrategy::Hash, Some((left, right))) => {
+                let mut by_key: HashMap<BongoLiteral, Vec<Row>> = HashMap::new();
+                for row in read(lookups.into_values().flatten().collect())? {
+                    by_key.entry(join_key(&row[right])).or_default().push(row);
+                }
+                Candidates::ByKey { col: left, rows: by_key }
+            }
+            _ => Candidates::All(read(lookups.into_values().flatten().collect())?),
+        };
+
+        let mut joined_cols = cols.to_vec();
+        joined_cols.extend(join_cols.iter().map(|col_def| format!("{}.{}", join.table, col_def.name)));
+
+        join_rows(rows, &joined_cols, join, join_cols.len(), &candidates, self.null_logic)
+    }
+
+    ///
+    /// Returns a description of `LIMIT` and `OFFSET` of `select`, e.g. `Limit: 10 OFFSET 5, reading
+    /// stops after 15 rows`.
+    ///
+    fn describe_limit(select: &Select, query: &JoinQuery, group: &Option<GroupQuery>, ordered: bool) -> String {
+        let mut line = match select.limit {
+            Some(limit) => format!("Limit: {}", limit),
+            None => "Limit: none".to_string(),
+        };
+        if let Some(offset) = select.offset {
+            line.push_str(&format!(" OFFSET {}", offset));
+        }
+
+        if let Some(limit) = select.limit {
+            let keep = limit.saturating_add(select.offset.unwrap_or(0));
+            if Self::stops_early(select, query, group) {
+                line.push_str(&format!(", reading stops after {} rows", keep));
+            } else if !select.order.is_empty() && !ordered {
+                line.push_str(&format!(", the first {} rows are kept while sorting", keep));
+            }
+        }
+        line
+    }
+
+    ///
+    /// An `Explain` statement is executed as follows:
+    ///
+    /// 1. check if the tables of the explained statement exist
+    /// 2. plan the explained statement the same way it is planned when it is executed
+    /// 3. estimate the amount of rows to examine in the first table from the statistics of the
+    ///    indexes and the amount of live rows
+    /// 4. plan each join for the estimated amount of rows of the first table
+    /// 5. if ANALYZE is specified, execute the explained statement and measure the time it takes
+    ///    and the amount of rows it actually examines in the first table
+    /// 6. return one row with a single VARCHAR per line of the description
+    ///
+    fn explain(&mut self, explain: Explain, connection: ConnectionId) -> BongoResult {
+        let (name, table_names, order) = match explain.statement.as_ref() {
+            Statement::Select(select) => ("SELECT", select.table_names(), select.order.as_slice()),
+            Statement::Update(update) => ("UPDATE", vec![update.table.clone()], &[][..]),
+            Statement::Delete(delete) => ("DELETE", vec![delete.table.clone()], &[][..]),
+            _ => return Err(BongoError::UnsupportedFeatureError(
+                "EXPLAIN is only supported for SELECT, UPDATE and DELETE statements.".to_string()
+            )),
+        };
+        for table_name in &table_names {
+            self.get_table_dir_if_exists(table_name)?;
+        }
+        let table_name = &table_names[0];
+
+        let planning_start = Instant::now();
+        let mut lines = vec![format!("{} on table {}", name, table_name)];
+        {
+            let tables = self.tables_read_access()?;
+
+            let (condition, order_col, query, group) = match explain.statement.as_ref() {
+                Statement::Select(select) => {
+                    let query = JoinQuery::new(select, &Self::table_cols(&tables, &table_names)?)?;
+                    let group = GroupQuery::new(select, &query.cols.concat())?;
+                    (query.condition.clone(), query.order_col.clone(), Some(query), group)
+                }
+                Statement::Update(Update { condition, .. }) | Statement::Delete(Delete { condition, .. }) => {
+                    (Self::unqualify_condition(table_name, condition.clone())?, None, None, None)
+                }
+                _ => (None, None, None, None),
+            };
+
+            // unwrap safe, because we have checked the entry exists before
+            let table = tables.get(table_name).unwrap().read();
+
+            if table.is_err() {
+                return Err(BongoError::InternalError("Could not acquire read access to cached meta data.".to_string()));
+            }
+            let table = table.unwrap();
+
+            let plan = QueryPlan::new(&table.indexes, &table.cols, condition, order_col.as_deref(), self.null_logic);
+            // expired versions are not visible to the statement anymore
+            let estimated = plan.estimate(&table.indexes, table.row_count - table.expired.len())?;
+            let ordered = plan.ordered && order_col.is_some();
+
+            lines.extend(plan.describe(&table.indexes)?);
+            drop(table);
+
+            if let Some(query) = &query {
+                let mut cols = query.cols[0].clone();
+                for (join, join_cols) in query.joins.iter().zip(&query.cols[1..]) {
+                    let joined = tables.get(&join.table).unwrap().read();
+                    if joined.is_err() {
+                        return Err(BongoError::InternalError("Could not acquire read access to cached meta data.".to_string()));
+                    }
+                    let joined = joined.unwrap();
+
+                    lines.push(JoinPlan::new(join, &cols, &joined.cols, &joined.indexes, estimated).describe(join, &joined.cols));
+                    cols.extend(join_cols.iter().cloned());
+                }
+                if let Some(filter) = &query.filter {
+                    lines.push(format!("Join filter: {}", filter));
+                }
+            }
+            if let Some(group) = &group {
+                lines.extend(group.describe());
+            }
+
+            lines.push(match order {
+                [] => "Sort: none".to_string(),
+                [order] if ordered && order.asc => format!("Sort: none, rows are read in ascending order of {}", order.col),
+                [order] if ordered => format!("Sort: none, rows are read in descending order of {}", order.col),
+                _ => format!("Sort: {}", order.iter().map(|order| order.to_string()).collect::<Vec<String>>().join(", ")),
+            });
+            if let (Statement::Select(select), Some(query)) = (explain.statement.as_ref(), &query) {
+                if select.limit.is_some() || select.offset.is_some() {
+                    lines.push(Self::describe_limit(select, query, &group, ordered));
+                }
+            }
+            lines.push(format!("Rows examined: estimated {}", estimated));
+        } // release the meta data before the explained statement is executed
+        let planning_time = planning_start.elapsed();
+
+        if explain.analyze {
+            self.examined_access()?.insert(connection, 0);
+            let execution_start = Instant::now();
+            let result = self.execute_statement(connection, *explain.statement);
+            let execution_time = execution_start.elapsed();
+            let examined = self.examined_access()?.remove(&connection);
+            let result = result?;
+
+            // unwraps safe, because the lines end with the rows examined and the entry has been inserted before
+            let last = lines.last_mut().unwrap();
+            last.push_str(&format!(", actual {}", examined.unwrap()));
+
+            if let Some(rows) = result {
+                lines.push(format!("Rows returned: {}", rows.len()));
+            }
+            lines.push(format!("Planning time: {:.3} ms", planning_time.as_secs_f64() * 1000.0));
+            lines.push(format!("Execution time: {:.3} ms", execution_time.as_secs_f64() * 1000.0));
+        }
+
+        Ok(Some(lines.into_iter().map(|line| vec![BongoLiteral::Varchar(line)]).collect()))
+    }
+
+    ///
+    /// An `Insert` statement is executed as follows:
+    ///
+    /// 1. check if table exists in cache
+    /// 2. check if the table exists on disc
+    /// 3. lock the table and the tables its foreign keys reference
+    /// 4. check if specified columns exist in the specified table and are given only once
+    /// 5. fill in the values given by `nextval` and the values of the omitted columns, which are
+    ///    the next values of `SERIAL` columns and the default values or `NULL` otherwise
+    /// 6. check if specifies Rows have the correct datatypes for each element and satisfy the constraints of the table
+    /// 7. check that the rows reference existing rows with their foreign keys
+    /// 8. determine the position of each row on disc, see `apply_changes`
+    ///      8.1 if ghosts exists use ghosts first
+    ///      8.2 if no ghosts exist write to end of file
+    /// 9. append the insertions to the write-ahead log
+    /// 10. write rows as new versions created by the transaction to disc, update index, row_count
+    ///     and the counters of the `SERIAL` columns
+    ///
+    fn insert(&mut self, insert: Insert, ctx: Context) -> BongoResult {
+        self.get_table_dir_if_exists(&insert.table)?;
+
+        let tables = self.tables_read_access()?;
+        // the referenced tables are locked as well, so that the referenced rows cannot be deleted meanwhile
+        let foreign_keys = Self::foreign_keys(&tables)?;
+        let mut names = Self::referenced_tables(&foreign_keys, &insert.table);
+        names.insert(insert.table.clone());
+        let mut locked = Self::lock_tables(&tables, names, ctx)?;
+        let table = &locked[&insert.table];
+
+        // check if all columns are correct
+        for (i, col) in insert.cols.iter().enumerate() {
+            if insert.cols[..i].contains(col) {
+                return Err(BongoError::SqlRuntimeError(format!(
+                    "The column '{}' is specified more than once.", col
+                )));
+            }
+            if !table.cols.iter().any(|col_def| &col_def.name == col) {
+                let col_names = table.cols
+                    .iter()
+                    .map(|col_def| { format!("{:?}", col_def) })
+                    .intersperse_with(|| { " ".to_string() })
+                    .collect::<String>();
+
+                return Err(BongoError::SqlRuntimeError(format!(
+                    "Specified column '{}' does not exist in the table.\
+                    table '{}' has the following columns: '{}'",
+                    col,
+                    &insert.table,
+                    col_names
+                )));
+            }
+        }
+        let mut rows = insert.rows;
+        if !insert.nextvals.is_empty() {
+            let mut sequences = self.sequences_access()?;
+            for (row, i, sequence) in &insert.nextvals {
+                rows[*row][*i] = BongoLiteral::Int(sequences.next_value(sequence)?);
+            }
+        }
+        let mut serials = table.serials.clone();
+        let rows = rows.into_iter()
+            .map(|row| {
+                let row = table.complete_row(&insert.cols, row, &mut serials)?;
+                let row = table.convert_row(row)?;
+                table.can_store(&insert.table, &row)?;
+                Ok(row)
+            })
+            .collect::<Result<Vec<Row>, BongoError>>()
+            .map_err(|err| match err {
+                BongoError::SqlRuntimeError(msg) => BongoError::SqlRuntimeError(format!(
+                    "The row cannot be inserted into the table '{}': {}", insert.table, msg
+                )),
+                err => err,
+            })?;
+
+        let mut changes = RowChanges::default();
+        changes.inserted.insert(insert.table.clone(), rows);
+        self.check_references(&locked, &changes, &insert.table)?;
+
+        self.apply_changes(&mut locked, changes, ctx)?;
+
+        Ok(None)
+    }
+
+    ///
+    /// Returns the positions on disc for `count` new row versions of `table` together with a flag
+    /// that tells whether the position is behind the end of the `data.bongo` file at `data_path`.
+    /// Additionally the expired versions whose slots are reclaimed for the new versions are returned
+    /// with their rows. They have to be removed before the new versions are inserted.
+    ///
+    /// Ghosts are reused first. Afterwards the slots of expired versions that have been deleted
+    /// before `horizon` are reclaimed, see `reclaim_horizon`. The remaining versions are appended
+    /// to the end of the file.
+    ///
+    fn new_positions(table: &TableMetaData, data_path: &Path, count: usize, horizon: TransactionId) -> Result<NewPositions, BongoError> {
+        let end_of_file = fs::metadata(data_path);
+        if end_of_file.is_err() {
+            return Err(BongoError::ReadFileError("Could not read size of file on disc.".to_string()));
+        }
+        let mut end_of_file = end_of_file.unwrap().len();
+
+        // ghosts are reused from the back just like they are removed when the insertions are applied
+        let mut ghosts = table.ghosts.clone();
+        let mut reclaimed = vec![];
+        if count > ghosts.len() && !table.expired.is_empty() {
+            let mut reader = SlotReader::open(data_path, table)?;
+            let d_types = table.cols.get_d_types();
+            for pos in table.expired.iter().rev() {
+                if ghosts.len() + reclaimed.len() >= count {
+                    break;
+                }
+                if let Some(row) = reader.read_reclaimable(*pos, &d_types, horizon)? {
+                    reclaimed.push((*pos, row));
+                }
+            }
+        }
+        // removing the reclaimed versions turns them into ghosts, which are reused after the others
+        ghosts.splice(0..0, reclaimed.iter().rev().map(|(pos, _)| *pos));
+        let row_size = table.row_size as u64;
+
+        let positions = (0..count)
+            .map(|_| match ghosts.pop() {
+                Some(pos) => (pos, false),
+                None => {
+                    end_of_file += row_size;
+                    (end_of_file - row_size, true)
+                }
+            })
+            .collect();
+
+        Ok((positions, reclaimed))
+    }
+
+    ///
+    /// An `Update` statement is executed as follows:
+    ///
+    /// 1. check if table exists in cache
+    /// 2. check if the table exists on disc
+    /// 3. lock the table, the tables its foreign keys reference and the tables that reference it
+    /// 4. check if specified columns in set expression exist in the specified table
+    /// 5. get positions of the relevant rows via a `QueryPlan`
+    /// 6. iterate over row-indices and for each row-index:
+    ///     - load row data from disc
+    ///     - check if condition applies (if there is a condition) and if so:
+    ///         + apply the assignments to the row
+    ///         + check that the modified row can be stored in the table
+    /// 7. check that no row references a value of a modified row that does not exist anymore and
+    ///    that the modified rows reference existing rows
+    /// 8. check that the modified rows do not violate a `PRIMARY KEY` or `UNIQUE` constraint
+    /// 9. append the modifications to the write-ahead log. Each modified row is deleted and
+    ///    inserted again as new version, so that readers with an older snapshot still see the old version.
+    /// 10. mark the old versions as deleted, write the new versions to disc and add them to the index
+    /// 11. return Ok(None)
+    ///
+    fn update(&mut self, update: Update, ctx: Context) -> BongoResult {
+        let mut path = self.get_table_dir_if_exists(&update.table)?;
+        let tables = self.tables_read_access()?;
+        let foreign_keys = Self::foreign_keys(&tables)?;
+        let mut names = Self::referenced_tables(&foreign_keys, &update.table);
+        names.extend(Self::referencing_tables(&foreign_keys, &update.table, false));
+        names.insert(update.table.clone());
+        let mut locked = Self::lock_tables(&tables, names, ctx)?;
+        let table = &locked[&update.table];
+
+        // error if there is at least one column that does not exists
+        if update.assignments.get_col_names().iter().any(|name| {
+            !table.cols.get_col_names().contains(name)
+        }) {
+            return Err(BongoError::SqlRuntimeError(format!(
+                "There were columns in the SET expressions that are not columns of the table '{}'",
+                &update.table
+            )));
+        }
+
+        // the values of the assignments may refer to the columns of the row they are assigned to
+        let assignments = update.assignments.into_iter()
+            .map(|a| {
+                let val = a.val.rename_identifiers(&|name| unqualify_col(&update.table, name))?;
+                let mut unknown = None;
+                val.visit_identifiers(&mut |name| if !table.cols.iter().any(|col| col.name == name) {
+                    unknown = Some(name.to_string());
+                });
+                match unknown {
+                    Some(name) => Err(BongoError::SqlRuntimeError(format!(
+                        "Column '{}' in the SET expressions is not a column of the table '{}'", name, update.table
+                    ))),
+                    None => Ok(Assignment { col_name: a.col_name, val }),
+                }
+            })
+            .collect::<Result<Vec<Assignment>, BongoError>>()?;
+
+        let plan = QueryPlan::new(&table.indexes, &table.cols, Self::unqualify_condition(&update.table, update.condition)?, None, self.null_logic);
+        let positions = plan.positions(&table.indexes, || table.positions())?;
+        let col_names = table.cols.get_col_names();
+        let d_types = table.cols.get_d_types();
+
+        path.push("data.bongo");
+        let mut reader = SlotReader::open(&path, table)?;
+        // the statement has exclusive access to the table and sees the latest version of each row
+        let snapshot = Snapshot::latest();
+
+        let mut deletions = vec![];
+        let mut new_rows = vec![];
+        let mut examined = 0;
+
+        for i in positions {
+            let row = match reader.read(i, &d_types, &snapshot)? {
+                Some(row) => row,
+                None => continue,
+            };
+            examined += 1;
+
+            // if no condition exists or the existing condition evaluates to true this row shall be returned
+            if plan.residual.is_none() ||
+                plan.residual.as_ref().unwrap().eval(&row, &col_names, self.null_logic)? {
+                // modify row according to SET expressions
+                let new_row = row.clone().apply_assignments(&assignments, &col_names, self.null_logic)?;
+                let new_row = table.convert_row(new_row).map_err(|err| match err {
+                    BongoError::SqlRuntimeError(msg) => BongoError::SqlRuntimeError(format!(
+                        "The row cannot be stored in the table '{}': {}", update.table, msg
+                    )),
+                    err => err,
+                })?;
+                table.can_store(&update.table, &new_row)?;
+
+                deletions.push((i, row));
+                new_rows.push(new_row);
+            }
+        }
+        self.count_examined(ctx.connection, examined)?;
+
+        let mut changes = RowChanges::default();
+        changes.inserted.insert(update.table.clone(), new_rows);
+        // referenced values can only be changed if no row references them
+        self.remove_references(&locked, &foreign_keys, &mut changes, &update.table, deletions, true)?;
+        self.check_references(&locked, &changes, &update.table)?;
+
+        self.apply_changes(&mut locked, changes, ctx)?;
+
+        Ok(None)
+    }
+
+    ///
+    /// A `Delete` statement is executed as follows.
+    ///
+    /// 1. Check if table exists in cache and on disc.
+    /// 2. Lock the table and all tables that reference it directly or through other tables
+    /// 3. Obtain relevant indices that might have to be deleted
+    /// 4. Iterate over all those indices and load the rows from disc. Remove indices that do not
+    ///    fulfill condition (in case a condition exists) from list of relevant indices
+    /// 5. Apply the `ON DELETE` actions of the foreign keys that reference the deleted rows
+    /// 6. Append the deletions to the write-ahead log
+    /// 7. Mark the rows as deleted by the transaction on disc
+    /// 8. Mark the deleted rows as expired. They stay in the index until the table is compacted,
+    ///    because readers with an older snapshot might still see them.
+    /// 9. Return Ok
+    ///
+    fn delete(&mut self, delete: Delete, ctx: Context) -> BongoResult {
+        let table_dir = self.get_table_dir_if_exists(&delete.table)?;
+        let mut data_loc = table_dir.clone();
+        data_loc.push("data.bongo");
+
+        let tables = self.tables_read_access()?;
+        // deletions can cascade to the referencing tables and from there to the tables referencing them
+        let foreign_keys = Self::foreign_keys(&tables)?;
+        let mut names = Self::referencing_tables(&foreign_keys, &delete.table, true);
+        names.insert(delete.table.clone());
+        let mut locked = Self::lock_tables(&tables, names, ctx)?;
+        let table = &locked[&delete.table];
+
+        let plan = QueryPlan::new(&table.indexes, &table.cols, Self::unqualify_condition(&delete.table, delete.condition)?, None, self.null_logic);
+        let positions = plan.positions(&table.indexes, || table.positions())?;
+        let d_types = table.cols.get_d_types();
+
+        let mut reader = SlotReader::open(&data_loc, table)?;
+        // the statement has exclusive access to the table and sees the latest version of each row
+        let snapshot = Snapshot::latest();
+
+        // the deleted rows are needed even if there is no expression, because their values are
+        // required to remove them from the index if the deletion is rolled back
+        let mut deletions = vec![];
+        let mut examined = 0;
+
+        // additional for loop to fail early. Slower but safer
+        for i in positions {
+            let row = match reader.read(i, &d_types, &snapshot)? {
+                Some(row) => row,
+                None => continue,
+            };
+            examined += 1;
+
+            if plan.residual.is_none() ||
+                plan.residual.as_ref().unwrap().eval(&row, &table.cols.get_col_names(), self.null_logic)? {
+                deletions.push((i, row));
+            }
+        }
+        self.count_examined(ctx.connection, examined)?;
+
+        let mut changes = RowChanges::default();
+        self.remove_references(&locked, &foreign_keys, &mut changes, &delete.table, deletions, false)?;
+
+        self.apply_changes(&mut locked, changes, ctx)?;
+
+        Ok(None)
+    }
+
+    ///
+    /// A `CreateTable` statement is executed as follows.
+    ///
+    /// 1. check that table does not exist
+    /// 2. check that folder does not exist
+    /// 3. check that the defaults and constraints fit the columns and the referenced tables
+    /// 4. append the creation to the write-ahead log
+    /// 5. create folder
+    /// 6. create empty data.bongo file
+    /// 7. update self.tables
+    ///
+    fn create_table(&mut self, create_table: CreateTable) -> BongoResult {
+        let location = self.get_table_dir_on_disc(&create_table.table);
+
+        let mut tables = self.tables_write_access()?;
+        if tables.contains_key(&create_table.table) {
+            return Err(BongoError::SqlRuntimeError(format!("Table '{}' already exists", &create_table.table)));
+        }
+
+        if location.exists() {
+            return Err(BongoError::InternalError("DB root directory contains invalid elements.".to_string()));
+        }
+
+        let mut cols = create_table.cols.into_iter()
+            .map(Self::convert_default)
+            .collect::<Result<Vec<ColumnDef>, BongoError>>()?;
+        Self::check_constraint_cols(&cols, &create_table.checks)?;
+        Self::check_unique_constraints(&Self::index_names(&tables)?, &mut cols, &[], &create_table.uniques)?;
+        let mut foreign_keys = create_table.foreign_keys;
+        let schema = Schema::create(cols, create_table.checks, create_table.uniques, vec![]);
+        Self::check_foreign_keys(&tables, &create_table.table, &schema, &mut foreign_keys)?;
+        let schema = Schema { foreign_keys, ..schema };
+
+        let record = self.wal_access()?.append(None, vec![WalOp::CreateTable {
+            table: create_table.table.clone(),
+            cols: schema.cols.clone(),
+            checks: schema.checks.clone(),
+            uniques: schema.uniques.clone(),
+            foreign_keys: schema.foreign_keys.clone(),
+        }], false)?;
+
+        Self::apply_create_table(&self.db_root, &mut tables, &create_table.table, schema, record.lsn)?;
+
+        Ok(None)
+    }
+
+    ///
+    /// Creates the directory and the empty data.bongo file of a new table with the definition
+    /// `schema` if they do not exist yet and adds the new table to `tables`. The indexes of the
+    /// schema are created with the table.
+    ///
+    fn apply_create_table(db_root: &Path, tables: &mut HashMap<String, RwLock<TableMetaData>>, table_name: &str,
+                          schema: Schema, lsn: u64) -> Result<(), BongoError> {
+        let mut location = db_root.join(table_name);
+
+        if !location.is_dir() && fs::create_dir(&location).is_err() {
+            return Err(BongoError::WriteFileError("Could directory for new table.".to_string()));
+        }
+
+        location.push("data.bongo");
+        // the file might already exist if the creation is replayed from the write-ahead log
+        if OpenOptions::new().write(true).create(true).truncate(false).open(location).is_err() {
+            return Err(BongoError::WriteFileError("Could not create data.bongo file for new table".to_string()));
+        }
+
+        let table = TableMetaData {
+            indexes: schema.indexes.into_iter()
+                .map(|def| Index::create(def, &db_root.join(table_name)))
+                .collect::<Result<Vec<Index>, BongoError>>()?,
+            ghosts: vec![],
+            expired: vec![],
+            row_size: SLOT_HEADER_SIZE + schema.cols.iter()
+                .map(|col_def| { col_def.as_ref().disc_size() })
+                .sum::<usize>(),
+            cols: schema.cols,
+            checks: schema.checks,
+            uniques: schema.uniques,
+            foreign_keys: schema.foreign_keys,
+            serials: HashMap::new(),
+            row_count: 0,
+            max_txn: NO_TXN,
+            lsn,
+            owner: None,
+            latch: Arc::default(),
+        };
+
+        Self::write_schema(&db_root.join(table_name), &table.schema())?;
+
+        tables.insert(table_name.to_string(), RwLock::new(table));
+
+        Ok(())
+    }
+
+    ///
+    /// Converts the default value of a column to the data type of the column, so that it can be
+    /// stored in the column without further conversions.
+    ///
+    fn convert_default(mut col_def: ColumnDef) -> Result<ColumnDef, BongoError> {
+        if let Some(default) = col_def.default.take() {
+            col_def.default = Some(col_def.data_type.convert(default).map_err(|err| match err {
+                BongoError::SqlRuntimeError(msg) => BongoError::SqlRuntimeError(format!(
+                    "The default value of the column '{}' does not fit its data type: {}", col_def.name, msg
+                )),
+                err => err,
+            })?);
+        }
+
+        Ok(col_def)
+    }
+
+    ///
+    /// Returns an error if the condition of a `CHECK` constraint refers to a column that is not
+    /// one of `cols`.
+    ///
+    fn check_constraint_cols(cols: &[ColumnDef], checks: &[Expr]) -> Result<(), BongoError> {
+        let mut unknown = None;
+        for check in checks {
+            check.visit_identifiers(&mut |name| if !cols.iter().any(|col_def| col_def.name == name) {
+                unknown = Some(name.to_string());
+            });
+        }
+
+        match unknown {
+            Some(name) => Err(BongoError::SqlRuntimeError(format!(
+                "Column '{}' in the CHECK constraint is not a column of the table.", name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    ///
+    /// Returns an error if the unique constraints `uniques` of a table with the columns `cols`
+    /// and the unique constraints `existing` refer to columns that do not exist, if the table would
+    /// have more than one primary key or if the name of a constraint is one of `index_names`, which
+    /// are the names of all indexes of the database. The columns of a primary key become `NOT NULL`.
+    ///
+    fn check_unique_constraints(index_names: &[String], cols: &mut [ColumnDef],
+                                existing: &[UniqueConstraint], uniques: &[UniqueConstraint]) -> Result<(), BongoError> {
+        for (i, unique) in uniques.iter().enumerate() {
+            if unique.primary && existing.iter().chain(&uniques[..i]).any(|other| other.primary) {
+                return Err(BongoError::SqlRuntimeError("A table can only have one primary key.".to_string()));
+            }
+            if index_names.contains(&unique.name) || uniques[..i].iter().any(|other| other.name == unique.name) {
+                return Err(BongoError::SqlRuntimeError(format!("Index '{}' already exists", unique.name)));
+            }
+
+            for name in &unique.cols {
+                match cols.iter_mut().find(|col_def| col_def.name == *name) {
+                    Some(col_def) => col_def.not_null |= unique.primary,
+                    None => return Err(BongoError::SqlRuntimeError(format!(
+                        "Column '{}' of the constraint '{}' is not a column of the table.", name, unique.name
+                    ))),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns an error if the new foreign keys `foreign_keys` of the table `table_name` with the
+    /// definition `schema` refer to columns that do not exist, if the name of one of them is used
+    /// by another foreign key of the table or if the referenced columns are not the columns of a
+    /// `PRIMARY KEY` or `UNIQUE` constraint of the referenced table with the same data types.
+    /// Foreign keys without referenced columns get the columns of the primary key.
+    ///
+    /// Only the referenced tables are read from `tables`, so the table `table_name` itself may
+    /// be locked while the foreign keys are checked.
+    ///
+    fn check_foreign_keys(tables: &HashMap<String, RwLock<TableMetaData>>, table_name: &str, schema: &Schema,
+                          foreign_keys: &mut [ForeignKey]) -> Result<(), BongoError> {
+        for i in 0..foreign_keys.len() {
+            let name = &foreign_keys[i].name;
+            if schema.foreign_keys.iter().chain(&foreign_keys[..i]).any(|other| other.name == *name) {
+                return Err(BongoError::SqlRuntimeError(format!("The constraint '{}' already exists.", name)));
+            }
+
+            let foreign_key = &mut foreign_keys[i];
+            let referenced = match foreign_key.table == table_name {
+                true => None,
+                false => match tables.get(&foreign_key.table).map(|cell| cell.read()) {
+                    Some(Ok(table)) => Some(table.schema()),
+                    Some(Err(_)) => return Err(InternalError("Concurrency Error.".to_string())),
+                    None => return Err(BongoError::SqlRuntimeError(format!(
+                        "The table '{}' referenced by the foreign key '{}' does not exist.", foreign_key.table, foreign_key.name
+                    ))),
+                },
+            };
+            let referenced = referenced.as_ref().unwrap_or(schema);
+
+            if foreign_key.ref_cols.is_empty() {
+                match referenced.uniques.iter().find(|unique| unique.primary) {
+                    Some(primary) => foreign_key.ref_cols = primary.cols.clone(),
+                    None => return Err(BongoError::SqlRuntimeError(format!(
+                        "The table '{}' referenced by the foreign key '{}' has no primary key.", foreign_key.table, foreign_key.name
+                    ))),
+                }
+            }
+            if foreign_key.cols.len() != foreign_key.ref_cols.len() {
+                return Err(BongoError::SqlRuntimeError(format!(
+                    "The foreign key '{}' has {} columns, but references {} columns.",
+                    foreign_key.name, foreign_key.cols.len(), foreign_key.ref_cols.len()
+                )));
+            }
+            // the referenced rows are looked up like the rows of a unique constraint
+            let ref_cols = foreign_key.ref_cols.iter().collect::<HashSet<&String>>();
+            if ref_cols.len() != foreign_key.ref_cols.len() || !referenced.uniques.iter()
+                .any(|unique| unique.cols.len() == ref_cols.len() && unique.cols.iter().all(|col| ref_cols.contains(col))) {
+                return Err(BongoError::SqlRuntimeError(format!(
+                    "The columns ({}) referenced by the foreign key '{}' are not the columns of a PRIMARY KEY or UNIQUE constraint of the table '{}'.",
+                    foreign_key.ref_cols.join(", "), foreign_key.name, foreign_key.table
+                )));
+            }
+
+            for (col, ref_col) in foreign_key.cols.iter().zip(&foreign_key.ref_cols) {
+                let col_def = match schema.cols.iter().find(|col_def| col_def.name == *col) {
+                    Some(col_def) => col_def,
+                    None => return Err(BongoError::SqlRuntimeError(format!(
+                        "Column '{}' of the constraint '{}' is not a column of the table.", col, foreign_key.name
+                    ))),
+                };
+                // unwrap safe, because the columns of unique constraints exist
+                let ref_col_def = referenced.cols.iter().find(|col_def| col_def.name == *ref_col).unwrap();
+                if col_def.data_type != ref_col_def.data_type {
+                    return Err(BongoError::SqlRuntimeError(format!(
+                        "The column '{}' of the foreign key '{}' has the data type {}, but the referenced column '{}' has the data type {}.",
+                        col, foreign_key.name, col_def.data_type, ref_col, ref_col_def.data_type
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Writes the definition of a table and of its indexes to the schema.bongo file in `table_dir`.
+    /// The schema allows to rebuild the meta data from data.bongo if meta.bongo is lost.
+    ///
+    fn write_schema(table_dir: &Path, schema: &Schema) -> Result<(), BongoError> {
+        Self::write_schema_to(&table_dir.join("schema.bongo"), schema)
+    }
+
+    fn write_schema_to(path: &Path, schema: &Schema) -> Result<(), BongoError> {
+        let encoded = bincode::serialize(schema);
+        if encoded.is_err() { return Err(BongoError::InternalError("Could not serialize schema of table.".to_string())); }
+
+        Self::write_file_atomically(path, &encoded.unwrap())
+    }
+
+    ///
+    /// A `DropTable` statement is executed as follows.
+    ///
+    /// for each table name:
+    ///  1. check if table exists in self.tables
+    ///  2. check if table correctly exists on disc
+    ///  3. check that the table is not locked by a transaction
+    ///  4. check that no foreign key of a table that is not dropped references the table
+    ///
+    /// afterwards:
+    ///  5. append the deletion of all tables to the write-ahead log
+    ///  6. delete directory for each table on disc and delete cache entry (self.tables)
+    ///
+    fn drop_table(&mut self, drop_table: DropTable, ctx: Context) -> BongoResult {
+        // extra for loop here to fail early before actual execution begins.
+        // slightly less performant but more secure.
+        for table_name in &drop_table.names {
+            self.get_table_dir_if_exists(table_name)?;
+        }
+
+        let mut tables = self.tables_write_access()?;
+
+        for table_name in &drop_table.names {
+            if let Some(cell) = tables.get(table_name) {
+                let table = cell.read();
+                if table.is_err() {
+                    return Err(InternalError("Concurrency Error.".to_string()));
+                }
+                table.unwrap().check_owner(table_name, ctx.connection)?;
+            }
+        }
+
+        for (name, foreign_key) in Self::foreign_keys(&tables)? {
+            if drop_table.names.contains(&foreign_key.table) && !drop_table.names.contains(&name) {
+                return Err(BongoError::SqlRuntimeError(format!(
+                    "The table '{}' cannot be dropped, because the foreign key '{}' of the table '{}' references it.",
+                    foreign_key.table, foreign_key.name, name
+                )));
+            }
+        }
+
+        self.wal_access()?.append(None, drop_table.names.iter()
+            .map(|name| WalOp::DropTable { table: name.clone() })
+            .collect(), false)?;
+
+        for name in drop_table.names {
+            Self::apply_drop_table(&self.db_root, &mut tables, &name)?;
+        }
+
+        Ok(None)
+    }
+
+    ///
+    /// Deletes the directory of a table on disc if it exists and removes the table from `tables`.
+    ///
+    fn apply_drop_table(db_root: &Path, tables: &mut HashMap<String, RwLock<TableMetaData>>,
+                        table_name: &str) -> Result<(), BongoError> {
+        let path = db_root.join(table_name);
+
+        if path.exists() && fs::remove_dir_all(&path).is_err() {
+            return Err(BongoError::WriteFileError(
+                format!("Could not delete directory '{}'",
+                        path.to_str().unwrap())));
+        }
+
+        tables.remove(table_name);
+
+        Ok(())
+    }
+
+    ///
+    /// An `AlterTable` statement is executed as follows.
+    ///
+    /// 1. check that the table exists
+    /// 2. flush all tables, which empties the write-ahead log
+    /// 3. check that the table is not locked by a transaction
+    /// 4. determine the new columns and indexes of the table and check that the operation is valid
+    /// 5. rewrite the rows of the table to the new layout, see `rewrite_table`
+    ///
+    /// Like a compaction the statement requires exclusive access to the entire DB. Only the current
+    /// versions of the rows are kept, so the table has no ghosts and expired versions afterwards.
+    ///
+    fn alter_table(&mut self, alter_table: AlterTable, ctx: Context) -> BongoResult {
+        let table_dir = self.get_table_dir_if_exists(&alter_table.table)?;
+
+        let tables = self.tables_write_access()?;
+        // the rows in the write-ahead log have the old layout of the table
+        self.checkpoint(&tables)?;
+        // the names of the indexes and the foreign keys are collected before the table is locked
+        let index_names = Self::index_names(&tables)?;
+        let foreign_keys = Self::foreign_keys(&tables)?;
+        // the foreign keys of other tables that reference columns of the table
+        let references = |col: &str| foreign_keys.iter()
+            .find(|(name, foreign_key)| *name != alter_table.table && foreign_key.table == alter_table.table &&
+                foreign_key.ref_cols.iter().any(|ref_col| ref_col == col));
+
+        let table = match tables.get(&alter_table.table) {
+            Some(cell) => cell.write(),
+            None => return Err(BongoError::SqlRuntimeError(format!(
+                "Execution of statement failed because table {} does not exist.", alter_table.table
+            ))),
+        };
+        if table.is_err() {
+            return Err(InternalError("Concurrency Error.".to_string()));
+        }
+        let mut table = table.unwrap();
+        table.check_owner(&alter_table.table, ctx.connection)?;
+
+        let mut schema = table.schema();
+        // the counters of the SERIAL columns are rebuilt from the rewritten rows, which might not
+        // contain the greatest values given out so far
+        let mut serials = table.serials.clone();
+        let col_exists = |schema: &Schema, name: &str| schema.cols.iter().any(|col_def| col_def.name == name);
+        let convert: Box<dyn Fn(Row) -> Result<Row, BongoError>> = match alter_table.operation {
+            AlterTableOperation::AddColumn { col, checks, uniques, foreign_keys: mut new_foreign_keys } => {
+                if col_exists(&schema, &col.name) {
+                    return Err(BongoError::SqlRuntimeError(format!("The column '{}' already exists.", col.name)));
+                }
+                let col = Self::convert_default(col)?;
+                let default = col.default.clone().unwrap_or(BongoLiteral::Null);
+                let serial = col.serial;
+                if serial && !new_foreign_keys.is_empty() {
+                    return Err(BongoError::SqlRuntimeError(format!(
+                        "The SERIAL column '{}' cannot be added together with a foreign key.", col.name
+                    )));
+                }
+                schema.cols.push(col);
+                Self::check_constraint_cols(&schema.cols, &checks)?;
+                Self::check_unique_constraints(&index_names, &mut schema.cols, &schema.uniques, &uniques)?;
+                schema.checks.extend(checks);
+                schema.indexes.extend(uniques.iter().map(UniqueConstraint::index_def));
+                schema.uniques.extend(uniques);
+                Self::check_foreign_keys(&tables, &alter_table.table, &schema, &mut new_foreign_keys)?;
+                // all current rows reference the default value of the new column
+                if default != BongoLiteral::Null && table.row_count > table.expired.len() {
+                    for foreign_key in &new_foreign_keys {
+                        let key = vec![default.clone()];
+                        let data_path = self.db_root.join(&foreign_key.table).join("data.bongo");
+                        let exists = match foreign_key.table == alter_table.table {
+                            true => !table.rows_with_key(&data_path, &foreign_key.ref_cols, &key)?.is_empty(),
+                            false => match tables[&foreign_key.table].read() {
+                                Ok(referenced) => !referenced.rows_with_key(&data_path, &foreign_key.ref_cols, &key)?.is_empty(),
+                                Err(_) => return Err(InternalError("Concurrency Error.".to_string())),
+                            },
+                        };
+                        if !exists {
+                            return Err(missing_reference(&alter_table.table, foreign_key, &key));
+                        }
+                    }
+                }
+                schema.foreign_keys.extend(new_foreign_keys);
+                // the current rows of a SERIAL column are numbered in the order in which they are stored
+                let last = Cell::new(0);
+                Box::new(move |mut row| {
+                    row.push(match serial {
+                        true => {
+                            last.set(last.get() + 1);
+                            BongoLiteral::Int(last.get())
+                        }
+                        false => default.clone(),
+                    });
+                    Ok(row)
+                })
+            }
+            AlterTableOperation::DropColumn { col, if_exists } => {
+                if if_exists && !col_exists(&schema, &col) {
+                    return Ok(None);
+                }
+                let pos = table.col_position(&col)?;
+                if schema.cols.len() == 1 {
+                    return Err(BongoError::SqlRuntimeError("The last column of a table cannot be dropped.".to_string()));
+                }
+                if let Some((name, foreign_key)) = references(&col) {
+                    return Err(BongoError::SqlRuntimeError(format!(
+                        "The column '{}' cannot be dropped, because the foreign key '{}' of the table '{}' references it.",
+                        col, foreign_key.name, name
+                    )));
+                }
+                schema.cols.remove(pos);
+                // like indexes, the constraints on the column are dropped with it
+                let dropped = schema.uniques.iter()
+                    .filter(|unique| unique.cols.contains(&col))
+                    .map(|unique| unique.name.clone())
+                    .collect::<Vec<String>>();
+                schema.uniques.retain(|unique| !dropped.contains(&unique.name));
+                schema.indexes.retain(|def| def.col != col && !dropped.contains(&def.name));
+                schema.checks.retain(|check| {
+                    let mut uses_col = false;
+                    check.visit_identifiers(&mut |name| uses_col |= name == col);
+                    !uses_col
+                });
+                schema.foreign_keys.retain(|foreign_key| {
+                    !foreign_key.cols.contains(&col) && !(foreign_key.table == alter_table.table && foreign_key.ref_cols.contains(&col))
+                });
+                Box::new(move |mut row| {
+                    row.remove(pos);
+                    Ok(row)
+                })
+            }
+            AlterTableOperation::RenameColumn { col, new_name } => {
+                let pos = table.col_position(&col)?;
+                if col_exists(&schema, &new_name) {
+                    return Err(BongoError::SqlRuntimeError(format!("The column '{}' already exists.", new_name)));
+                }
+                if let Some((name, foreign_key)) = references(&col) {
+                    return Err(BongoError::SqlRuntimeError(format!(
+                        "The column '{}' cannot be renamed, because the foreign key '{}' of the table '{}' references it.",
+                        col, foreign_key.name, name
+                    )));
+                }
+                schema.cols[pos].name = new_name.clone();
+                if let Some(last) = serials.remove(&col) {
+                    serials.insert(new_name.clone(), last);
+                }
+                for def in schema.indexes.iter_mut().filter(|def| def.col == col) {
+                    def.col = new_name.clone();
+                }
+                for unique_col in schema.uniques.iter_mut().flat_map(|unique| unique.cols.iter_mut()).filter(|name| **name == col) {
+                    *unique_col = new_name.clone();
+                }
+                for foreign_key in schema.foreign_keys.iter_mut() {
+                    let references_itself = foreign_key.table == alter_table.table;
+                    let ref_cols = foreign_key.ref_cols.iter_mut().filter(|_| references_itself);
+                    for foreign_key_col in foreign_key.cols.iter_mut().chain(ref_cols).filter(|name| **name == col) {
+                        *foreign_key_col = new_name.clone();
+                    }
+                }
+                schema.checks = mem::take(&mut schema.checks).into_iter()
+                    .map(|check| check.rename_identifiers(&|name| Ok(match name == col {
+                        true => new_name.clone(),
+                        false => name.to_string(),
+                    })))
+                    .collect::<Result<Vec<Expr>, BongoError>>()?;
+                Box::new(Ok)
+            }
+            AlterTableOperation::AlterColumnType { col, data_type } => {
+                let pos = table.col_position(&col)?;
+                // the referencing and the referenced columns must have the same data types
+                let foreign_key = foreign_keys.iter().find(|(name, foreign_key)| {
+                    (*name == alter_table.table && foreign_key.cols.contains(&col)) ||
+                        (foreign_key.table == alter_table.table && foreign_key.ref_cols.contains(&col))
+                });
+                if let Some((_, foreign_key)) = foreign_key {
+                    return Err(BongoError::SqlRuntimeError(format!(
+                        "The data type of the column '{}' cannot be changed, because it is part of the foreign key '{}'.",
+                        col, foreign_key.name
+                    )));
+                }
+                if schema.cols[pos].serial && !data_type.is_integer() {
+                    return Err(BongoError::SqlRuntimeError(format!(
+                        "The SERIAL column '{}' can only be changed to another integer type.", col
+                    )));
+                }
+                let has_btree = schema.indexes.iter().any(|def| def.col == col && def.kind == IndexKind::BTree);
+                if has_btree && (data_type.is_out_of_line() || data_type.disc_size() > MAX_KEY_SIZE) {
+                    return Err(BongoError::SqlRuntimeError(format!(
+                        "The column '{}' has a B-tree index and its values would be larger than {} bytes.",
+                        col, MAX_KEY_SIZE
+                    )));
+                }
+                schema.cols[pos].data_type = data_type.clone();
+                schema.cols[pos] = Self::convert_default(schema.cols[pos].clone())?;
+                Box::new(move |mut row| {
+                    row[pos] = data_type.convert(mem::replace(&mut row[pos], BongoLiteral::Null))?;
+                    Ok(row)
+                })
+            }
+        };
+
+        // the rewritten rows have to satisfy the constraints of the new schema
+        let (cols, checks, uniques) = (schema.cols.clone(), schema.checks.clone(), schema.uniques.clone());
+        let unique_positions = uniques.iter()
+            // unwrap safe, because the columns of the constraints have been checked before
+            .map(|unique| unique.cols.iter()
+                .map(|name| cols.iter().position(|col_def| col_def.name == *name).unwrap())
+                .collect::<Vec<usize>>())
+            .collect::<Vec<Vec<usize>>>();
+        let keys = RefCell::new(vec![HashSet::new(); uniques.len()]);
+        Self::rewrite_table(&table_dir, &mut table, schema, |row| {
+            let row = convert(row)?;
+            check_constraints(&alter_table.table, &cols, &checks, &row)?;
+            for ((unique, positions), keys) in uniques.iter().zip(&unique_positions).zip(keys.borrow_mut().iter_mut()) {
+                if let Some(key) = unique_key(positions, &row) {
+                    if !keys.insert(key.clone()) {
+                        return Err(unique_violation(&alter_table.table, unique, &key));
+                    }
+                }
+            }
+            Ok(row)
+        })?;
+        for (col, last) in serials {
+            if table.cols.iter().any(|col_def| col_def.serial && col_def.name == col) {
+                let rebuilt = table.serials.entry(col).or_insert(0);
+                *rebuilt = (*rebuilt).max(last);
+            }
+        }
+        Self::flush_table(&self.db_root, &alter_table.table, &table)?;
+
+        Ok(None)
+    }
+
+    ///
+    /// Rewrites the current versions of all rows of a table converted by `convert` to the layout of
+    /// the columns of `schema` and replaces the meta data of the table with meta data rebuilt from
+    /// the new rows and `schema`.
+    ///
+    /// The rewrite is crash-safe like a compaction, see `compact_table`. The new schema is written
+    /// to schema.bongo.compact and renamed to schema.bongo after data.bongo has been replaced. If
+    /// `convert` fails for any row, the table is left unchanged.
+    ///
+    fn rewrite_table<F>(table_dir: &Path, table: &mut TableMetaData, schema: Schema, convert: F) -> Result<(), BongoError>
+        where F: Fn(Row) -> Result<Row, BongoError> {
+        let data_path = table_dir.join("data.bongo");
+        let compact_path = table_dir.join("data.bongo.compact");
+        let heap_path = table_dir.join(HEAP_FILE_NAME);
+        let compact_heap_path = table_dir.join("heap.bongo.compact");
+        let compact_schema_path = table_dir.join("schema.bongo.compact");
+
+        let written = Self::write_rewritten_table(table_dir, table, &schema, convert);
+        if written.is_err() {
+            for path in [&compact_path, &compact_heap_path, &compact_schema_path] {
+                let _ = fs::remove_file(path);
+            }
+            return written;
+        }
+
+        let meta_path = table_dir.join("meta.bongo");
+        if meta_path.exists() && fs::remove_file(&meta_path).is_err() {
+            return Err(BongoError::WriteFileError("Could not delete outdated meta data file on disc.".to_string()));
+        }
+        Self::sync_dir(table_dir)?;
+
+        if fs::rename(&compact_path, &data_path).is_err() {
+            return Err(BongoError::WriteFileError("Could not replace table data with rewritten table data.".to_string()));
+        }
+        Self::sync_dir(table_dir)?;
+
+        // the old heap is not needed anymore if the table has no out of line columns left
+        let heap_replaced = match compact_heap_path.exists() {
+            true => fs::rename(&compact_heap_path, &heap_path).is_ok(),
+            false => !heap_path.exists() || fs::remove_file(&heap_path).is_ok(),
+        };
+        if !heap_replaced || fs::rename(&compact_schema_path, table_dir.join("schema.bongo")).is_err() {
+            return Err(BongoError::WriteFileError("Could not replace heap and schema of table.".to_string()));
+        }
+        Self::sync_dir(table_dir)?;
+
+        for index in &table.indexes {
+            if !schema.indexes.contains(&index.def) {
+                index.remove_file(table_dir)?;
+            }
+        }
+
+        let (lsn, max_txn) = (table.lsn, table.max_txn);
+        *table = TableMetaData::rebuild(schema, table_dir)?;
+        table.lsn = lsn;
+        table.max_txn = table.max_txn.max(max_txn);
+
+        Ok(())
+    }
+
+    ///
+    /// Writes the files data.bongo.compact, heap.bongo.compact and schema.bongo.compact for
+    /// `rewrite_table`.
+    ///
+    fn write_rewritten_table<F>(table_dir: &Path, table: &TableMetaData, schema: &Schema, convert: F) -> Result<(), BongoError>
+        where F: Fn(Row) -> Result<Row, BongoError> {
+        let mut bytes = vec![];
+        let file = File::open(table_dir.join("data.bongo"));
+        if file.is_err() || file.unwrap().read_to_end(&mut bytes).is_err() {
+            return Err(BongoError::ReadFileError("Could not read table data to rewrite it.".to_string()));
+        }
+
+        let compact_heap_path = table_dir.join("heap.bongo.compact");
+        if compact_heap_path.exists() && fs::remove_file(&compact_heap_path).is_err() {
+            return Err(BongoError::WriteFileError("Could not remove outdated heap of table.".to_string()));
+        }
+        let mut heap = Heap::new(&table_dir.join(HEAP_FILE_NAME));
+        let mut rewritten_heap = Heap::new(&compact_heap_path);
+        let mut rewritten = vec![];
+        let d_types = table.cols.get_d_types();
+
+        for slot in bytes.chunks_exact(table.row_size) {
+            let header = SlotHeader::from_disc_bytes(slot);
+            if header.live && header.deleted == NO_TXN {
+                let row = convert(heap.row_from_disc_bytes(&slot[SLOT_HEADER_SIZE..], &d_types)?)?;
+                rewritten.extend_from_slice(&slot[..SLOT_HEADER_SIZE]);
+                rewritten.append(&mut rewritten_heap.row_as_disc_bytes(&row, &schema.cols)?);
+            }
+        }
+
+        let file = File::create(table_dir.join("data.bongo.compact"));
+        if file.is_err() || file.as_ref().unwrap().write_all(&rewritten).is_err() || file.unwrap().sync_all().is_err() {
+            return Err(BongoError::WriteFileError("Could not write rewritten table data.".to_string()));
+        }
+        rewritten_heap.sync()?;
+
+        Self::write_schema_to(&table_dir.join("schema.bongo.compact"), schema)
+    }
+
+    ///
+    /// A `CreateIndex` statement is executed as follows.
+    ///
+    /// 1. check that the table exists
+    /// 2. check that no index with the same name exists on any table
+    /// 3. check that the indexed column exists and that its values are small enough for a B-tree index
+    /// 4. append the creation to the write-ahead log
+    /// 5. fill the new index with all row versions of the table and add it to the meta data
+    /// 6. add the index to schema.bongo
+    ///
+    /// Transactions that have modified the table do not prevent the creation of an index, because
+    /// the index contains all row versions and undoing a transaction maintains all indexes of a table.
+    ///
+    fn create_index(&mut self, create_index: CreateIndex) -> BongoResult {
+        self.get_table_dir_if_exists(&create_index.table)?;
+
+        // exclusive access is required, because index names are unique among all tables
+        let tables = self.tables_write_access()?;
+        if Self::find_index(&tables, &create_index.name)?.is_some() {
+            return Err(BongoError::SqlRuntimeError(format!("Index '{}' already exists", create_index.name)));
+        }
+
+        let table = tables.get(&create_index.table).unwrap().write();
+        if table.is_err() {
+            return Err(InternalError("Concurrency Error.".to_string()));
+        }
+        let mut table = table.unwrap();
+        let col = table.col_position(&create_index.col)?;
+        // the values of out of line columns are not limited in size
+        let d_type = &table.cols[col].data_type;
+        if create_index.kind == IndexKind::BTree && (d_type.is_out_of_line() || d_type.disc_size() > MAX_KEY_SIZE) {
+            return Err(BongoError::SqlRuntimeError(format!(
+                "B-tree indexes can only be created on columns with a size of at most {} bytes.",
+                MAX_KEY_SIZE
+            )));
+        }
+
+        let index = IndexDef { name: create_index.name, col: create_index.col, kind: create_index.kind };
+        let record = self.wal_access()?.append(None, vec![WalOp::CreateIndex {
+            table: create_index.table.clone(),
+            index: index.clone(),
+        }], false)?;
+
+        Self::apply_create_index(&self.db_root, &create_index.table, &mut table, index, record.lsn)?;
+
+        Ok(None)
+    }
+
+    fn apply_create_index(db_root: &Path, table_name: &str, table: &mut TableMetaData,
+                          index: IndexDef, lsn: u64) -> Result<(), BongoError> {
+        let table_dir = db_root.join(table_name);
+
+        table.add_index(&table_dir, index)?;
+        table.lsn = lsn;
+
+        Self::write_schema(&table_dir, &table.schema())
+    }
+
+    ///
+    /// A `DropIndex` statement is executed as follows.
+    ///
+    /// for each index name:
+    ///  1. check that the index exists and find the table it belongs to
+    ///  2. check that the index does not back a `PRIMARY KEY` or `UNIQUE` constraint
+    ///
+    /// afterwards:
+    ///  3. append the deletion of all indexes to the write-ahead log
+    ///  4. remove each index from the meta data and from schema.bongo of its table
+    ///
+    fn drop_index(&mut self, drop_index: DropIndex) -> BongoResult {
+        let tables = self.tables_write_access()?;
+
+        let mut ops = vec![];
+        for name in drop_index.names {
+            match Self::find_index(&tables, &name)? {
+                None => return Err(BongoError::SqlRuntimeError(format!("Index '{}' does not exist", name))),
+                Some(table) => {
+                    // unwrap safe, because the index has been found in this table
+                    let backs_constraint = tables[&table].read().unwrap().uniques.iter().any(|unique| unique.name == name);
+                    if backs_constraint {
+                        return Err(BongoError::SqlRuntimeError(format!(
+                            "Index '{}' cannot be dropped, because the constraint of the same name uses it.", name
+                        )));
+                    }
+                    ops.push(WalOp::DropIndex { table, name })
+                }
+            }
+        }
+
+        let record = self.wal_access()?.append(None, ops, false)?;
+
+        for op in &record.ops {
+            if let WalOp::DropIndex { table: table_name, name } = op {
+                let table = tables.get(table_name).unwrap().write();
+                if table.is_err() {
+                    return Err(InternalError("Concurrency Error.".to_string()));
+                }
+                Self::apply_drop_index(&self.db_root, table_name, &mut table.unwrap(), name, record.lsn)?;
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn apply_drop_index(db_root: &Path, table_name: &str, table: &mut TableMetaData,
+                        name: &str, lsn: u64) -> Result<(), BongoError> {
+        let table_dir = db_root.join(table_name);
+        for index in table.indexes.iter().filter(|index| index.def.name == name) {
+            index.remove_file(&table_dir)?;
+        }
+        table.indexes.retain(|index| index.def.name != name);
+        table.lsn = lsn;
+
+        Self::write_schema(&table_dir, &table.schema())
+    }
+
+    ///
+    /// A `CreateSequence` statement adds the sequence and writes all sequences to sequences.bongo.
+    /// Sequences are not part of the write-ahead log, because they are written to disc right away.
+    ///
+    fn create_sequence(&mut self, create_sequence: CreateSequence) -> BongoResult {
+        let sequence = Sequence { next: create_sequence.start, increment: create_sequence.increment };
+        self.sequences_access()?.create(&create_sequence.name, sequence)?;
+
+        Ok(None)
+    }
+
+    ///
+    /// A `DropSequence` statement removes the sequence and writes the remaining sequences to
+    /// sequences.bongo.
+    ///
+    fn drop_sequence(&mut self, drop_sequence: DropSequence) -> BongoResult {
+        let dropped = self.sequences_access()?.remove(&drop_sequence.name)?;
+        if !dropped && !drop_sequence.if_exists {
+            return Err(BongoError::SqlRuntimeError(format!("Sequence '{}' does not exist", drop_sequence.name)));
+        }
+
+        Ok(None)
+    }
+
+    ///
+    /// Returns the names of the indexes of all tables.
+    ///
+    fn index_names(tables: &HashMap<String, RwLock<TableMetaData>>) -> Result<Vec<String>, BongoError> {
+        let mut names = vec![];
+        for cell in tables.values() {
+            let table = cell.read();
+            if table.is_err() {
+                return Err(InternalError("Concurrency Error.".to_string()));
+            }
+            names.extend(table.unwrap().indexes.iter().map(|index| index.def.name.clone()));
+        }
+
+        Ok(names)
+    }
+
+    ///
+    /// Returns the name of the table that has an index with the name `index_name` if there is one.
+    ///
+    fn find_index(tables: &HashMap<String, RwLock<TableMetaData>>, index_name: &str) -> Result<Option<String>, BongoError> {
+        for (name, cell) in tables {
+            let table = cell.read();
+            if table.is_err() {
+                return Err(InternalError("Concurrency Error.".to_string()));
+            }
+            if table.unwrap().indexes.iter().any(|index| index.def.name == index_name) {
+                return Ok(Some(name.clone()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    ///
+    /// Replaces the file at `path` with `bytes` so that after a crash the file either has its old
+    /// or its new content, but is never partially written.
+    ///
+    /// The content is written to a temporary file next to `path` first, forced to disc and then
+    /// atomically renamed to `path`.
+    ///
+    pub(crate) fn write_file_atomically(path: &Path, bytes: &[u8]) -> Result<(), BongoError> {
+        let mut tmp_name = path.file_name().unwrap().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let file = File::create(&tmp_path);
+        if file.is_err() {
+            return Err(BongoError::WriteFileError(format!("Could not create file '{}'", tmp_path.to_str().unwrap())));
+        }
+        let mut file = file.unwrap();
+
+        if file.write_all(bytes).is_err() || file.sync_all().is_err() {
+            return Err(BongoError::WriteFileError(format!("Could not write file '{}'", tmp_path.to_str().unwrap())));
+        }
+
+        if fs::rename(&tmp_path, path).is_err() {
+            return Err(BongoError::WriteFileError(format!("Could not replace file '{}'", path.to_str().unwrap())));
+        }
+
+        // the rename itself is only durable once the directory is forced to disc
+        Self::sync_dir(path.parent().unwrap())
+    }
+
+    ///
+    /// Forces changes to the entries of the directory `dir` like renaming or deleting files to disc.
+    ///
+    fn sync_dir(dir: &Path) -> Result<(), BongoError> {
+        let file = File::open(dir);
+        if file.is_err() || file.unwrap().sync_all().is_err() {
+            return Err(BongoError::WriteFileError(format!("Could not force directory '{}' to disc", dir.to_str().unwrap())));
+        }
+
+        Ok(())
+    }
+
+    fn create_drop_db() -> BongoResult {
+        BongoResult::Err(BongoError::UnsupportedFeatureError(
+            "'CREATE DATABASE and DROP DATABASE statements are not supported by BongoDB so far. \
+            BongoDB assumes there is only one database."
+                .to_string(),
+        ))
+    }
+
+    fn table_exists_in_cache(&self, table_name: &str) -> Result<bool, BongoError> {
+        let tables = self.tables_read_access()?;
+        Ok(tables.get(table_name).is_some())
+    }
+
+    fn get_table_dir_on_disc(&self, table_name: &str) -> PathBuf {
+        let mut table_dir = self.db_root.clone();
+        table_dir.push(table_name);
+
+        table_dir
+    }
+
+    ///
+    /// Checks if a table exists in cache and on disc.
+    /// If not found returns an Error.
+    /// If found returns the path to the directory of the table.
+    ///
+    fn get_table_dir_if_exists(&self, table_name: &str) -> Result<PathBuf, BongoError> {
+        if !self.table_exists_in_cache(&table_name)? {
+            return Err(BongoError::SqlRuntimeError(format!(
+                "Execution of statement failed because table {} does not exist.",
+                &table_name)));
+        }
+
+        let path = self.get_table_dir_on_disc(&table_name);
 
         if !path.is_dir() {
             return Err(BongoError::InternalError(
                 "Cache inconsistency detected.".to_string()));
         }
 
-        Ok(path)
-    }
+        Ok(path)
+    }
+
+    fn tables_read_access(&self) -> Result<RwLockReadGuard<HashMap<String, RwLock<TableMetaData>>>, BongoError> {
+        let tables = self.tables.read();
+        if tables.is_err() {
+            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        }
+        Ok(tables.unwrap())
+    }
+
+    fn tables_write_access(&self) -> Result<RwLockWriteGuard<HashMap<String, RwLock<TableMetaData>>>, BongoError> {
+        let tables = self.tables.write();
+        if tables.is_err() {
+            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        }
+        Ok(tables.unwrap())
+    }
+
+    fn wal_access(&self) -> Result<MutexGuard<'_, WriteAheadLog>, BongoError> {
+        let wal = self.wal.lock();
+        if wal.is_err() {
+            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        }
+        Ok(wal.unwrap())
+    }
+
+    fn sequences_access(&self) -> Result<MutexGuard<'_, Sequences>, BongoError> {
+        let sequences = self.sequences.lock();
+        if sequences.is_err() {
+            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        }
+        Ok(sequences.unwrap())
+    }
+
+    ///
+    /// Returns the foreign keys of all tables together with the name of the table they belong to.
+    ///
+    /// Foreign keys are only created and removed while all tables are locked exclusively, so they
+    /// can be collected before the tables that a statement modifies are locked.
+    ///
+    fn foreign_keys(tables: &HashMap<String, RwLock<TableMetaData>>) -> Result<Vec<(String, ForeignKey)>, BongoError> {
+        let mut foreign_keys = vec![];
+        for (name, cell) in tables {
+            let table = cell.read();
+            if table.is_err() {
+                return Err(InternalError("Concurrency Error.".to_string()));
+            }
+            foreign_keys.extend(table.unwrap().foreign_keys.iter().map(|foreign_key| (name.clone(), foreign_key.clone())));
+        }
+
+        Ok(foreign_keys)
+    }
+
+    ///
+    /// Returns the names of the tables that the foreign keys of the table `table_name` reference.
+    ///
+    fn referenced_tables(foreign_keys: &[(String, ForeignKey)], table_name: &str) -> BTreeSet<String> {
+        foreign_keys.iter()
+            .filter(|(name, _)| name == table_name)
+            .map(|(_, foreign_key)| foreign_key.table.clone())
+            .collect()
+    }
+
+    ///
+    /// Returns the names of the tables whose foreign keys reference the table `table_name`. If
+    /// `transitive` is true, the tables that reference those tables are included and so on.
+    ///
+    fn referencing_tables(foreign_keys: &[(String, ForeignKey)], table_name: &str, transitive: bool) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        let mut queue = vec![table_name.to_string()];
+        while let Some(referenced) = queue.pop() {
+            for (name, _) in foreign_keys.iter().filter(|(_, foreign_key)| foreign_key.table == referenced) {
+                if names.insert(name.clone()) && transitive {
+                    queue.push(name.clone());
+                }
+            }
+        }
+
+        names
+    }
+
+    ///
+    /// Locks the tables `names` for writing and returns an error if one of them is locked by a
+    /// transaction of another connection. The tables are locked in the order of their names like
+    /// when a transaction ends to avoid dead locks.
+    ///
+    fn lock_tables<'a>(tables: &'a HashMap<String, RwLock<TableMetaData>>, names: BTreeSet<String>,
+                       ctx: Context) -> Result<LockedTables<'a>, BongoError> {
+        let mut locked = BTreeMap::new();
+        for name in names {
+            let table = match tables.get(&name) {
+                Some(cell) => cell.write(),
+                None => return Err(BongoError::SqlRuntimeError(format!(
+                    "Execution of statement failed because table {} does not exist.", name
+                ))),
+            };
+            if table.is_err() {
+                return Err(InternalError("Concurrency Error.".to_string()));
+            }
+            let table = table.unwrap();
+            table.check_owner(&name, ctx.connection)?;
+            locked.insert(name, table);
+        }
+
+        Ok(locked)
+    }
+
+    ///
+    /// Returns whether the table `table_name` has a row with the values `key` in the columns `cols`
+    /// once `changes` have been applied.
+    ///
+    fn key_exists(&self, locked: &LockedTables, changes: &RowChanges, table_name: &str,
+                  cols: &[String], key: &Row) -> Result<bool, BongoError> {
+        let table = &locked[table_name];
+        for row in changes.inserted.get(table_name).into_iter().flatten() {
+            if table.key(cols, row)?.as_ref() == Some(key) {
+                return Ok(true);
+            }
+        }
+
+        let data_path = self.db_root.join(table_name).join("data.bongo");
+        Ok(table.rows_with_key(&data_path, cols, key)?.iter().any(|(pos, _)| !changes.is_deleted(table_name, *pos)))
+    }
+
+    ///
+    /// Returns an error if one of the rows that `changes` insert into the table `table_name`
+    /// references a row with a foreign key of the table that does not exist once the changes have
+    /// been applied.
+    ///
+    fn check_references(&self, locked: &LockedTables, changes: &RowChanges, table_name: &str) -> Result<(), BongoError> {
+        let table = &locked[table_name];
+        for foreign_key in &table.foreign_keys {
+            for row in changes.inserted.get(table_name).into_iter().flatten() {
+                if let Some(key) = table.key(&foreign_key.cols, row)? {
+                    if !self.key_exists(locked, changes, &foreign_key.table, &foreign_key.ref_cols, &key)? {
+                        return Err(missing_reference(table_name, foreign_key, &key));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Adds the row versions `rows` of the table `table_name` to the deleted rows of `changes` and
+    /// applies the actions of the foreign keys that reference them to the referencing rows.
+    ///
+    /// `ON DELETE CASCADE` deletes the referencing rows as well, which is applied to the rows
+    /// referencing them in turn. `ON DELETE SET NULL` replaces the referencing rows with new versions
+    /// that have NULL in the referencing columns. `ON DELETE RESTRICT` returns an error. If `update`
+    /// is true, the rows are replaced by the new versions in `changes` and an error is returned
+    /// for all foreign keys, because referenced values cannot be changed. Values that another row
+    /// version of the statement still has are not considered deleted.
+    ///
+    fn remove_references(&self, locked: &LockedTables, foreign_keys: &[(String, ForeignKey)], changes: &mut RowChanges,
+                         table_name: &str, rows: Vec<(u64, Row)>, update: bool) -> Result<(), BongoError> {
+        let mut queue = VecDeque::new();
+        for (pos, row) in rows {
+            changes.deleted.entry(table_name.to_string()).or_default().insert(pos, row.clone());
+            queue.push_back((table_name.to_string(), row));
+        }
+
+        while let Some((referenced, row)) = queue.pop_front() {
+            for (name, foreign_key) in foreign_keys.iter().filter(|(_, foreign_key)| foreign_key.table == referenced) {
+                let key = match locked[&referenced].key(&foreign_key.ref_cols, &row)? {
+                    Some(key) => key,
+                    None => continue,
+                };
+                if self.key_exists(locked, changes, &referenced, &foreign_key.ref_cols, &key)? {
+                    continue;
+                }
+
+                let action = match update {
+                    true => ReferentialAction::Restrict,
+                    false => foreign_key.on_delete,
+                };
+                let table = &locked[name];
+                let set_null = |row: &mut Row| -> Result<(), BongoError> {
+                    for col in &foreign_key.cols {
+                        row[table.col_position(col)?] = BongoLiteral::Null;
+                    }
+                    table.can_store(name, row)
+                };
+
+                let data_path = self.db_root.join(name).join("data.bongo");
+                for (pos, row) in table.rows_with_key(&data_path, &foreign_key.cols, &key)? {
+                    if changes.is_deleted(name, pos) {
+                        continue;
+                    }
+                    match action {
+                        ReferentialAction::Restrict => return Err(remaining_reference(name, foreign_key, &key)),
+                        ReferentialAction::Cascade => {}
+                        ReferentialAction::SetNull => {
+                            let mut new_row = row.clone();
+                            set_null(&mut new_row)?;
+                            changes.inserted.entry(name.clone()).or_default().push(new_row);
+                        }
+                    }
+                    changes.deleted.entry(name.clone()).or_default().insert(pos, row.clone());
+                    // the rows that reference the deleted version might have to be changed as well
+                    queue.push_back((name.clone(), row));
+                }
+
+                // new versions of the statement cannot reference the deleted values either
+                let inserted = changes.inserted.entry(name.clone()).or_default();
+                for mut row in mem::take(inserted) {
+                    if table.key(&foreign_key.cols, &row)?.as_ref() != Some(&key) {
+                        inserted.push(row);
+                        continue;
+                    }
+                    match action {
+                        ReferentialAction::Restrict => return Err(remaining_reference(name, foreign_key, &key)),
+                        ReferentialAction::Cascade => queue.push_back((name.clone(), row)),
+                        ReferentialAction::SetNull => {
+                            queue.push_back((name.clone(), row.clone()));
+                            set_null(&mut row)?;
+                            inserted.push(row);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Checks that the row versions that `changes` insert do not violate a `PRIMARY KEY` or
+    /// `UNIQUE` constraint and logs and applies the changes to the tables in `locked`, see
+    /// `log_and_apply`. The new versions are written to ghosts and to the slots of expired versions
+    /// that no statement can see anymore first and appended to the end of data.bongo afterwards.
+    ///
+    fn apply_changes(&self, locked: &mut LockedTables, mut changes: RowChanges, ctx: Context) -> Result<(), BongoError> {
+        // statements that modify a table are always executed in a transaction
+        let txn = ctx.txn.unwrap();
+        let horizon = self.reclaim_horizon()?;
+
+        let mut ops = vec![];
+        for (name, table) in locked.iter() {
+            let deleted = changes.deleted.remove(name).unwrap_or_default();
+            let inserted = changes.inserted.remove(name).unwrap_or_default();
+            let data_path = self.db_root.join(name).join("data.bongo");
+
+            if !inserted.is_empty() {
+                // the new versions must not collide with each other or with the rows that are not deleted
+                table.check_unique(name, &data_path, &inserted, &deleted.keys().copied().collect())?;
+            }
+
+            let (positions, reclaimed) = Self::new_positions(table, &data_path, inserted.len(), horizon)?;
+            ops.extend(deleted.into_iter().map(|(pos, row)| WalOp::Delete { table: name.clone(), pos, row, txn }));
+            ops.extend(reclaimed.into_iter().map(|(pos, row)| WalOp::Remove { table: name.clone(), pos, row }));
+            ops.extend(inserted.into_iter()
+                .zip(positions)
+                .map(|(row, (pos, append))| WalOp::Insert { table: name.clone(), pos, row, txn, append }));
+        }
+
+        self.log_and_apply(locked, ops, ctx)
+    }
+
+    ///
+    /// Appends the row level operations `ops` of a statement to the write-ahead log and applies
+    /// them to the tables in `locked` afterwards. The operations on all tables are appended as one
+    /// record, so that the statement is either recovered completely or not at all.
+    ///
+    /// If the statement is part of an explicit transaction, the record is added to the transaction
+    /// and the modified tables are locked for all other connections that modify tables until the
+    /// transaction ends.
+    ///
+    fn log_and_apply(&self, locked: &mut LockedTables, ops: Vec<WalOp>, ctx: Context) -> Result<(), BongoError> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+
+        // the record of an implicit transaction also ends it
+        let record = self.wal_access()?.append(ctx.txn, ops, ctx.auto_commit)?;
+
+        // tables that are only read by the statement are not modified
+        let mut modified = locked.iter_mut()
+            .filter(|(name, _)| record.ops.iter().any(|op| op.table() == name.as_str()))
+            .collect::<Vec<(&String, &mut RwLockWriteGuard<TableMetaData>)>>();
+
+        if !ctx.auto_commit {
+            let mut transactions = self.transactions_access()?;
+            // the transaction cannot end meanwhile, because only its own connection can end it
+            let txn = transactions.get_mut(&ctx.connection).unwrap();
+            txn.records.push(record.clone());
+            for (name, table) in modified.iter_mut() {
+                txn.tables.insert(name.to_string());
+                table.owner = Some(ctx.connection);
+            }
+        }
+
+        for (name, table) in modified {
+            let data_path = self.db_root.join(name).join("data.bongo");
+            table.apply_row_ops(&data_path, record.ops.iter().filter(|op| op.table() == name.as_str()), record.lsn)?;
+        }
+
+        Ok(())
+    }
+
+    fn transactions_access(&self) -> Result<MutexGuard<'_, HashMap<ConnectionId, Transaction>>, BongoError> {
+        let transactions = self.transactions.lock();
+        if transactions.is_err() {
+            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        }
+        Ok(transactions.unwrap())
+    }
+
+    fn snapshots_access(&self) -> Result<MutexGuard<'_, BTreeMap<TransactionId, usize>>, BongoError> {
+        let snapshots = self.snapshots.lock();
+        if snapshots.is_err() {
+            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        }
+        Ok(snapshots.unwrap())
+    }
+
+    fn examined_access(&self) -> Result<MutexGuard<'_, HashMap<ConnectionId, usize>>, BongoError> {
+        let examined = self.examined.lock();
+        if examined.is_err() {
+            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        }
+        Ok(examined.unwrap())
+    }
+
+    ///
+    /// Adds `count` to the rows examined by the connection if it executes an `EXPLAIN ANALYZE`.
+    ///
+    fn count_examined(&self, connection: ConnectionId, count: usize) -> Result<(), BongoError> {
+        if let Some(examined) = self.examined_access()?.get_mut(&connection) {
+            *examined += count;
+        }
+        Ok(())
+    }
+}
+
+// TODO: LOW_PRIO: write concurrency tests with by using many threads that access the same executor
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bongo_core::bongo_request::BongoRequest;
+    use bongo_core::bongo_result::BongoResult;
+    use bongo_core::decimal::Decimal;
+    use bongo_core::types::{BongoError, BongoLiteral, Row};
+    use webserver::ConnectionId;
+
+    use crate::executor::{DEFAULT_CONNECTION, Executor};
+
+    mod constructor {
+        use std::fs;
+
+        use bongo_core::types::BongoError;
+
+        use crate::executor::Executor;
+
+        #[test]
+        fn non_existing_path_create_db_true() {
+            let path = "test_temp/non_existing_path_create_db_true";
+            non_existing_path(path, true);
+            // clen up, because this call should create a new DB
+            fs::remove_dir_all(path).unwrap();
+        }
+
+        #[test]
+        fn non_existing_path_create_db_false() {
+            non_existing_path("test_temp/non_existing_path_create_db_false", false);
+        }
+
+        fn non_existing_path(path: &str, create_db: bool) {
+            let ex = Executor::new(path, create_db, false);
+            match ex {
+                Ok(_) => {
+                    // if we create the db this should return Ok(_)
+                    assert!(create_db);
+                }
+                Err(err) => {
+                    // an error should be thrown if we do not create the db
+                    assert!(!create_db);
+                    assert_eq!(
+                        BongoError::DatabaseNotFoundError(format!("location: '{}'", path)),
+                        err
+                    )
+                }
+            }
+        }
+
+        #[test]
+        fn existing_path() {
+            let path = "test_temp/test_existing_path";
+            fs::create_dir_all(path).unwrap();
+
+            let ex = Executor::new(path, false, false);
+
+            fs::remove_dir_all(path).unwrap();
+
+            match ex {
+                Ok(ex) => {
+                    assert!(ex.db_root.is_absolute());
+                }
+                Err(_) => {
+                    panic!("This should not return an error on unix systems")
+                }
+            }
+        }
+    }
+
+    mod create_drop_tables {
+        use std::fs;
+        use std::path::PathBuf;
+        use std::str::FromStr;
+
+        use bongo_core::bongo_request::BongoRequest;
+        use crate::executor::Executor;
+        use crate::executor::tests::create_example_table;
+
+        ///
+        /// 1. Creates an `Executor` on en empty DB and creates a table
+        /// 2. Destroys `Executor`
+        /// 3. Creates new `Executor` and checks if the table can be loaded from disc
+        /// 4. Drops Table and ensures that table got successfully dropped from cache and disc worked
+        ///
+        /// NOTE: uses private functions for test
+        ///
+        #[test]
+        fn create_table_and_load_in_next_session_and_drop() {
+            let db_root = PathBuf::from_str("test_temp/create_table_and_load_in_next_session_and_drop").unwrap();
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                // create table
+                create_example_table(&mut ex, "table_1");
+            }
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+
+                match ex.tables.read().unwrap().get("table_1") {
+                    None => { panic!("The entry table_1 does not exist in the meta data table") }
+                    Some(_data) => {
+                        // nice, some data is in there
+                        // unfortunately it is a bit hard checking the data is correct as it is behind a RwLock.
+                        // But we have loads of other tests that check the data is loaded correctly using the
+                        // public select interface of the Executor
+                    }
+                }
+
+                // drop table again
+                let drop_table_req = BongoRequest { sql: "DROP TABLE table_1;".to_string() };
+                ex.execute(&drop_table_req).unwrap();
+
+                // table has been removed from cache
+                let tables = ex.tables_read_access().unwrap();
+                assert!(tables.is_empty());
+
+                // table has been removed from disk. Only the write-ahead log remains in the DB root.
+                assert!(db_root.read_dir().unwrap().all(|entry| !entry.unwrap().path().is_dir()))
+
+                // leaving this scope drops the executor before cleaning up the directory.
+                // This way the executors drop method can work like expected.
+            }
+
+            // clean up afterwards (CAREFULLY!)
+            fs::remove_dir_all(db_root).unwrap();
+        }
+    }
+
+    mod insert {
+        use std::fs;
+        use std::fs::File;
+        use std::io::Read;
+        use std::path::PathBuf;
+
+        use bongo_core::bytes_on_disc::FromDiscBytes;
+        use bongo_core::types::{BongoDataType, BongoError, BongoLiteral, Row};
+
+        use crate::executor::{Executor, SLOT_HEADER_SIZE, SLOT_LIVE};
+        use crate::executor::tests::{create_example_table, execute, get_example_rows, insert_example_rows, try_execute};
+
+        #[test]
+        fn insert_in_empty_db() {
+            let db_root = PathBuf::from("test_temp/insert_in_empty_db");
+            let mut loc = db_root.clone();
+            let table_name = "table_1";
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            // read contents from DB directly to buffer
+            loc.push(table_name);
+            loc.push("data.bongo");
+            let mut file = File::open(&loc).unwrap();
+            let mut buffer = vec![];
+            file.read_to_end(&mut buffer).unwrap();
+
+            let table_def = vec![BongoDataType::Int,
+                                 BongoDataType::Varchar(256),
+                                 BongoDataType::Bool];
+
+            // convert bytes back to BongoLiterals
+            let slots = buffer.chunks(SLOT_HEADER_SIZE + BongoDataType::Int.disc_size() +
+                BongoDataType::Varchar(256).disc_size()
+                + BongoDataType::Bool.disc_size())
+                .collect::<Vec<&[u8]>>();
+            let rows = slots.iter()
+                .map(|slot| {
+                    Row::from_disc_bytes(&slot[SLOT_HEADER_SIZE..], &table_def).unwrap()
+                })
+                .collect::<Vec<Row>>();
+
+            let expected_rows = get_example_rows();
+
+            // clean up before assertion
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(slots.iter().all(|slot| slot[0] == SLOT_LIVE));
+            assert_eq!(rows, expected_rows);
+        }
+
+        #[test]
+        fn omitted_and_reordered_columns() {
+            let db_root = PathBuf::from("test_temp/insert_omitted_and_reordered_columns");
+            let result_duplicate;
+            let result_unknown;
+            let result_not_null;
+            let result_rows;
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE table_1 (col_1 INT, col_2 VARCHAR(10) DEFAULT 'none', col_3 BOOLEAN NOT NULL DEFAULT false);");
+                execute(&mut ex, "INSERT INTO table_1 (col_3, col_1) VALUES (true, 1);");
+                execute(&mut ex, "INSERT INTO table_1 (col_2) VALUES ('b');");
+                execute(&mut ex, "INSERT INTO table_1 DEFAULT VALUES;");
+                result_duplicate = try_execute(&mut ex, "INSERT INTO table_1 (col_1, col_1) VALUES (1, 2);");
+                result_unknown = try_execute(&mut ex, "INSERT INTO table_1 (col_4) VALUES (1);");
+                result_not_null = try_execute(&mut ex, "INSERT INTO table_1 (col_3) VALUES (NULL);");
+                result_rows = execute(&mut ex, "SELECT * FROM table_1 ORDER BY col_2;");
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(matches!(result_duplicate, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_unknown, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_not_null, Err(BongoError::ConstraintViolationError(_))));
+            assert_eq!(vec![
+                vec![BongoLiteral::Null, BongoLiteral::Varchar("b".to_string()), BongoLiteral::Bool(false)],
+                vec![BongoLiteral::Int(1), BongoLiteral::Varchar("none".to_string()), BongoLiteral::Bool(true)],
+                vec![BongoLiteral::Null, BongoLiteral::Varchar("none".to_string()), BongoLiteral::Bool(false)],
+            ], result_rows);
+        }
+    }
+
+    // NOTE: this also involves create table and insert statement
+    mod select {
+        use std::fs;
+        use std::path::PathBuf;
+
+        use bongo_core::bongo_request::BongoRequest;
+        use bongo_core::types::{BongoLiteral, Row};
+
+        use crate::executor::Executor;
+        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows};
+        use crate::statement::NullLogic;
+
+        #[test]
+        ///
+        /// - specify a subset of columns
+        /// - nested where condition
+        /// - descending order
+        /// - test on two executors to see if persisting cache works correctly
+        ///
+        fn all_features_together() {
+            let db_root = PathBuf::from("test_temp/all_features_together");
+            let table_name = "table_1";
+
+            let sql = format!("SELECT col_1, col_3 \
+               FROM {table_name} \
+               WHERE col_2 = 'c❤' OR col_3 \
+               ORDER BY col_1 DESC");
+            let request = BongoRequest { sql: sql.to_string() };
+            // should return the first two columns in the opposite order and only col_1 and col_3
+            let expected = get_example_rows().into_iter()
+                .take(2)
+                .rev() // example rows are initially sorted ascending by row 1
+                .map(|row| {
+                    vec![row[0].clone(), row[2].clone()]
+                })
+                .collect::<Vec<Row>>();
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&request).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn where_clause_false() {
+            let db_root = PathBuf::from("test_temp/where_clause_false");
+            let table_name = "table_1";
+
+            let sql = format!("SELECT col_1, col_3 \
+               FROM {table_name} \
+               WHERE false \
+               ORDER BY col_1 DESC");
+            let request = BongoRequest { sql: sql.to_string() };
+            // should return the first two columns in the opposite order and only col_1 and col_3
+            let expected: Vec<Row> = vec![];
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&request).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn wildcard_no_where() {
+            let db_root = PathBuf::from("test_temp/wildcard_no_where");
+            let table_name = "table_1";
+
+            let sql = format!("SELECT * \
+               FROM {table_name} \
+               ORDER BY col_1 DESC");
+            let request = BongoRequest { sql: sql.to_string() };
+            // should return all columns in reverse order
+            let expected: Vec<Row> = get_example_rows().into_iter().rev().collect();
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&request).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn order_by_non_selected_col() {
+            let db_root = PathBuf::from("test_temp/order_by_non_selected_col");
+            let table_name = "table_1";
+
+            let sql = format!("SELECT col_2, col_3 \
+               FROM {table_name} \
+               ORDER BY col_1 DESC");
+            let request = BongoRequest { sql: sql.to_string() };
+            // should return columns 2 and 3 in reverse order
+            let expected: Vec<Row> = get_example_rows().into_iter()
+                .rev() // example rows are initially sorted ascending
+                .map(|row| {
+                    vec![row[1].clone(), row[2].clone()]
+                })
+                .collect::<Vec<Row>>();
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&request).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn order_asc() {
+            let db_root = PathBuf::from("test_temp/order_asc");
+            let table_name = "table_1";
+
+            let sql = format!("SELECT * \
+               FROM {table_name} \
+               ORDER BY col_2 ASC"); // col_2 is ordered desc on insert
+            let request = BongoRequest { sql: sql.to_string() };
+            // should return all columns in reverse order
+            let expected: Vec<Row> = get_example_rows().into_iter().rev().collect();
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&request).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        ///
+        /// Tests how the system responds if the where condition is indexable
+        ///
+        #[test]
+        fn eq_indexable_where() {
+            let db_root = PathBuf::from("test_temp/eq_indexable_where");
+            let table_name = "table_1";
+
+            let sql = format!("SELECT * \
+               FROM {table_name} \
+               WHERE col_1 = 3");
+            let request = BongoRequest { sql: sql.to_string() };
+            // should return only 3rd row
+            let expected: Vec<Row> = vec![get_example_rows().remove(2)];
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&request).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn comparison_with_null() {
+            let db_root = PathBuf::from("test_temp/comparison_with_null");
+            let table_name = "table_1";
+
+            let sql = format!("SELECT * \
+                                     FROM {table_name} \
+                                     WHERE col_3 = Null");
+            let request = BongoRequest { sql: sql.to_string() };
+            // should return only 3rd row with the legacy logic
+            let expected: Vec<Row> = get_example_rows().into_iter()
+                .filter(|r| {
+                    match r[2] {
+                        BongoLiteral::Null => true,
+                        _ => false
+                    }
+                }).collect();
+            let result;
+            let result_three_valued;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result_three_valued = ex.execute(&request).unwrap().unwrap();
+                ex.set_null_logic(NullLogic::Legacy).unwrap();
+                result = ex.execute(&request).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+            // the comparison with NULL is unknown for all rows
+            assert!(result_three_valued.is_empty());
+        }
+    }
+
+    mod delete {
+        use std::{fs, iter};
+        use std::path::PathBuf;
+
+        use bongo_core::bongo_request::BongoRequest;
+        use bongo_core::types::{BongoLiteral, Row};
+
+        use crate::Executor;
+        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows};
+
+        #[test]
+        fn simple_delete() {
+            let db_root = PathBuf::from("test_temp/simple_delete");
+            let table_name = "table_1";
+
+            create_table_and_delete_nth_row(table_name, &db_root, 3);
+
+            let select = format!("SELECT * \
+                               FROM {table_name} \
+                               ORDER BY col_1 ASC;");
+
+            let request = BongoRequest { sql: select.to_string() };
+
+            // shall return all but the 3rd row
+            let expected: Vec<Row> = get_example_rows().into_iter()
+                .enumerate()
+                .filter(|&(i, _)| i != 2)
+                .map(|(_, r)| r).collect();
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&request).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn delete_and_insert_in_ghosts() {
+            let db_root = PathBuf::from("test_temp/delete_and_insert_in_ghosts");
+            let table_name = "table_1";
+            let data_path = db_root.join(table_name).join("data.bongo");
+
+            create_table_and_delete_nth_row(table_name, &db_root, 3);
+
+            let insert = format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES \
+                          (42, 'x', true);");
+            let insert_req = BongoRequest { sql: insert.to_string() };
+            let file_len_before;
+            let file_len_after;
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                { // new scope to drop the ReadGuard and avoid deadlock
+                    // assert we have one expired version after deleting one row
+                    assert_eq!(ex.tables.read().unwrap().get("table_1").unwrap().read().unwrap().expired.len(), 1)
+                }
+                file_len_before = fs::metadata(&data_path).unwrap().len();
+                ex.execute(&insert_req).unwrap();
+                file_len_after = fs::metadata(&data_path).unwrap().len();
+                {
+                    // assert the slot of the expired version is now reused for the inserted row
+                    let table = ex.tables.read().unwrap();
+                    let table = table.get("table_1").unwrap().read().unwrap();
+                    assert!(table.expired.is_empty() && table.ghosts.is_empty())
+                }
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            let select = format!("SELECT * \
+                               FROM {table_name} \
+                               ORDER BY col_1 ASC;");
+
+            let select_req = BongoRequest { sql: select.to_string() };
+
+            // 3rd row shall be removed and instead the inserted row shall be contained
+            let expected: Vec<Row> = get_example_rows().into_iter()
+                .enumerate()
+                .filter(|&(i, _)| i != 2)
+                .map(|(_, r)| r)
+                .chain(iter::once(vec![BongoLiteral::Int(42), BongoLiteral::Varchar("x".to_string()), BongoLiteral::Bool(true)]))
+                .collect();
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&select_req).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(file_len_before, file_len_after);
+            assert_eq!(expected, result);
+        }
+
+        fn create_table_and_delete_nth_row(table_name: &str, db_root: &PathBuf, n: i32) {
+            let db_root = PathBuf::from(db_root);
+
+            let delete = format!("DELETE FROM {table_name} \
+                               WHERE col_1 = {n};");
+
+            let request = BongoRequest { sql: delete };
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                ex.execute(&request).unwrap();
+            } // leaving scope triggers drop and flush on the executor
+        }
+    }
+
+    mod update {
+        use std::fs;
+        use std::path::PathBuf;
+        use bongo_core::bongo_request::BongoRequest;
+        use bongo_core::types::{BongoLiteral, Row};
+        use crate::Executor;
+        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows};
+
+        #[test]
+        fn update_including_index() {
+            let db_root = PathBuf::from("test_temp/update_including_index");
+            let table_name = "table_1";
+
+            let update = format!("UPDATE {table_name} \
+                                SET col_1 = 42, col_3 = NULL;");
+            let select = "SELECT * FROM table_1 \
+                               ORDER BY col_2 DESC ;";
+            let update_req = BongoRequest { sql: update.to_string() };
+            let select_req = BongoRequest { sql: select.to_string() };
+
+            let expected = get_example_rows().into_iter()
+                .map(|mut row| {
+                    row[0] = BongoLiteral::Int(42);
+                    row[2] = BongoLiteral::Null;
+                    row
+                })
+                .collect::<Vec<Row>>();
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, true).unwrap();
+                ex.execute(&update_req).unwrap();
+                result = ex.execute(&select_req).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn update_with_wrong_d_type() {
+            let db_root = PathBuf::from("test_temp/update_with_wrong_d_type");
+            let table_name = "table_1";
+
+            let update = format!("UPDATE {table_name} \
+                                SET col_1 = 'col_1 is datatype int...';");
+            let request = BongoRequest { sql: update.to_string() };
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, true).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                result = ex.execute(&request);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn update_only_one() {
+            let db_root = PathBuf::from("test_temp/update_only_one");
+            let table_name = "table_1";
+
+            let update = format!("UPDATE {table_name} \
+                                SET col_2 = 'updated' \
+                                WHERE col_1 = 3;");
+            let select = format!("SELECT * FROM {table_name} \
+                               ORDER BY col_1 ASC ;");
+            let update_req = BongoRequest { sql: update };
+            let select_req = BongoRequest { sql: select };
+
+            let expected = get_example_rows().into_iter()
+                .enumerate()
+                .map(|(i, mut row)| {
+                    if i == 2 {
+                        row[1] = BongoLiteral::Varchar("updated".to_string());
+                    }
+                    row
+                })
+                .collect::<Vec<Row>>();
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, true).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                ex.execute(&update_req).unwrap();
+                result = ex.execute(&select_req).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+    }
+
+    mod write_ahead_log {
+        use std::fs;
+        use std::mem;
+        use std::path::PathBuf;
+
+        use bongo_core::bongo_request::BongoRequest;
+        use bongo_core::types::{BongoLiteral, Row};
+
+        use crate::executor::Executor;
+        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows};
+
+        ///
+        /// Simulates a crash by leaking the `Executor`, which prevents the final flush in its drop method.
+        ///
+        fn crash(ex: Executor) {
+            mem::forget(ex);
+        }
+
+        #[test]
+        fn recover_unflushed_create_table_and_insert() {
+            let db_root = PathBuf::from("test_temp/recover_unflushed_create_table_and_insert");
+            let table_name = "table_1";
+            let select_req = BongoRequest { sql: format!("SELECT * FROM {table_name} ORDER BY col_1 ASC;") };
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                crash(ex);
+            }
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&select_req).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(get_example_rows(), result);
+        }
+
+        #[test]
+        fn recover_unflushed_update_and_delete() {
+            let db_root = PathBuf::from("test_temp/recover_unflushed_update_and_delete");
+            let table_name = "table_1";
+            let update_req = BongoRequest { sql: format!("UPDATE {table_name} SET col_1 = 42 WHERE col_1 = 3;") };
+            let delete_req = BongoRequest { sql: format!("DELETE FROM {table_name} WHERE col_1 = 1;") };
+            let insert_req = BongoRequest { sql: format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (5, 'e', true);") };
+            let select_req = BongoRequest { sql: format!("SELECT * FROM {table_name} WHERE col_1 = 42 OR col_1 != 42 ORDER BY col_1 ASC;") };
+
+            let mut expected = get_example_rows();
+            expected.remove(0);
+            expected[1][0] = BongoLiteral::Int(42);
+            expected.insert(2, vec![BongoLiteral::Int(5), BongoLiteral::Varchar("e".to_string()), BongoLiteral::Bool(true)]);
+            expected.sort_by_key(|row: &Row| match row[0] {
+                BongoLiteral::Int(i) => i,
+                _ => unreachable!(),
+            });
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                ex.execute(&update_req).unwrap();
+                ex.execute(&delete_req).unwrap();
+                // reuses the ghost of the deleted row
+                ex.execute(&insert_req).unwrap();
+                crash(ex);
+            }
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = ex.execute(&select_req).unwrap().unwrap();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn recover_unflushed_drop_table() {
+            let db_root = PathBuf::from("test_temp/recover_unflushed_drop_table");
+            let table_name = "table_1";
+            let drop_req = BongoRequest { sql: format!("DROP TABLE {table_name};") };
+            let tables_empty;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                ex.execute(&drop_req).unwrap();
+                crash(ex);
+            }
+
+            {
+                let ex = Executor::new(&db_root, false, false).unwrap();
+                tables_empty = ex.tables_read_access().unwrap().is_empty();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(tables_empty);
+        }
+    }
+
+    mod meta_data_recovery {
+        use std::fs;
+        use std::mem;
+        use std::path::{Path, PathBuf};
+
+        use bongo_core::bongo_request::BongoRequest;
+        use bongo_core::types::{BongoError, BongoLiteral};
+
+        use crate::executor::Executor;
+        use crate::executor::tests::{create_example_table, execute, get_example_rows, insert_example_rows, select_all, try_execute};
+        use crate::format::{DatabaseInfo, FORMAT_VERSION};
+        use crate::statement::NullLogic;
+
+        #[test]
+        fn flush_leaves_no_temporary_file() {
+            let db_root = PathBuf::from("test_temp/flush_leaves_no_temporary_file");
+            let table_dir = db_root.join("table_1");
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, "table_1");
+                insert_example_rows(&mut ex, "table_1");
+            } // leaving scope triggers drop and flush on the executor
+
+            let meta_exists = table_dir.join("meta.bongo").is_file();
+            let tmp_exists = table_dir.join("meta.bongo.tmp").exists();
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(meta_exists);
+            assert!(!tmp_exists);
+        }
+
+        #[test]
+        fn rebuild_missing_meta_data() {
+            let db_root = PathBuf::from("test_temp/rebuild_missing_meta_data");
+            let table_name = "table_1";
+            let delete_req = BongoRequest { sql: format!("DELETE FROM {table_name} WHERE col_1 = 2;") };
+            let insert_req = BongoRequest { sql: format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (5, 'e', true);") };
+
+            let mut expected = get_example_rows();
+            expected.remove(1);
+            expected.push(vec![BongoLiteral::Int(5), BongoLiteral::Varchar("e".to_string()), BongoLiteral::Bool(true)]);
+            let result;
+            let expired;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                ex.execute(&delete_req).unwrap();
+            } // leaving scope triggers drop and flush on the executor
+
+            fs::remove_file(db_root.join(table_name).join("meta.bongo")).unwrap();
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                // the deleted row is rebuilt as expired version
+                expired = ex.tables.read().unwrap().get(table_name).unwrap().read().unwrap().expired.clone();
+                ex.execute(&insert_req).unwrap();
+                result = select_all(&mut ex, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+            assert_eq!(1, expired.len());
+        }
+
+        #[test]
+        fn rebuild_corrupted_meta_data_and_replay_log() {
+            let db_root = PathBuf::from("test_temp/rebuild_corrupted_meta_data_and_replay_log");
+            let table_name = "table_1";
+            let update_req = BongoRequest { sql: format!("UPDATE {table_name} SET col_1 = 42 WHERE col_1 = 4;") };
+
+            let mut expected = get_example_rows();
+            expected[3][0] = BongoLiteral::Int(42);
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                ex.execute(&update_req).unwrap();
+                // simulate a crash, which prevents the final flush
+                mem::forget(ex);
+            }
+
+            let table_dir = db_root.join(table_name);
+            fs::write(table_dir.join("meta.bongo"), [1, 2, 3]).unwrap();
+            fs::write(table_dir.join("meta.bongo.tmp"), [4, 5, 6]).unwrap();
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = select_all(&mut ex, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        ///
+        /// Copies the database in test_fixtures/`fixture`, which has been written by an earlier
+        /// version of BongoDB, to `db_root`.
+        ///
+        fn copy_fixture(fixture: &str, db_root: &Path) {
+            let _ = fs::remove_dir_all(db_root);
+            for table_dir in fs::read_dir(PathBuf::from("test_fixtures").join(fixture)).unwrap() {
+                let table_dir = table_dir.unwrap().path();
+                let target = db_root.join(table_dir.file_name().unwrap());
+                fs::create_dir_all(&target).unwrap();
+                for file in fs::read_dir(&table_dir).unwrap() {
+                    let file = file.unwrap().path();
+                    fs::copy(&file, target.join(file.file_name().unwrap())).unwrap();
+                }
+            }
+        }
+
+        #[test]
+        fn migrate_legacy_database() {
+            let db_root = PathBuf::from("test_temp/migrate_legacy_database");
+            // written by the version of BongoDB before the write-ahead log: the table users with the
+            // ids 1 to 4, of which 2 has been deleted, and the table empty without rows
+            copy_fixture("legacy_db", &db_root);
+            let result_rows;
+            let result_explain;
+            let result_create;
+            let result_empty;
+            let result_restarted;
+            let meta_exists;
+            let null_logic;
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                null_logic = ex.null_logic;
+                result_rows = execute(&mut ex, "SELECT * FROM users ORDER BY id;");
+                result_explain = execute(&mut ex, "EXPLAIN SELECT * FROM users WHERE id = 4;");
+                result_create = try_execute(&mut ex, "CREATE TABLE users (id INT);");
+                result_empty = execute(&mut ex, "SELECT * FROM empty;");
+                execute(&mut ex, "INSERT INTO users (id, name, active) VALUES (5, 'eve', false);");
+                execute(&mut ex, "DELETE FROM users WHERE id = 1;");
+            }
+            meta_exists = db_root.join("users").join("meta.bongo").exists();
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result_restarted = execute(&mut ex, "SELECT id FROM users ORDER BY id;");
+            }
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(1), BongoLiteral::Varchar("alice".to_string()), BongoLiteral::Bool(true)],
+                vec![BongoLiteral::Int(4), BongoLiteral::Varchar("dave".to_string()), BongoLiteral::Null],
+                vec![BongoLiteral::Int(3000000000), BongoLiteral::Null, BongoLiteral::Bool(true)],
+            ], result_rows);
+            // the implicit index of the first column is kept
+            assert_eq!(
+                BongoLiteral::Varchar("Index lookup using users_id_idx (hash) where id = 4".to_string()),
+                result_explain[1][0]
+            );
+            assert!(matches!(result_create, Err(BongoError::SqlRuntimeError(_))));
+            assert!(result_empty.is_empty());
+            assert!(meta_exists);
+            // existing databases keep the treatment of NULL values of earlier versions
+            assert_eq!(NullLogic::Legacy, null_logic);
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(4)],
+                vec![BongoLiteral::Int(5)],
+                vec![BongoLiteral::Int(3000000000)],
+            ], result_restarted);
+        }
+
+        #[test]
+        fn null_logic_is_stored_with_database() {
+            let db_root = PathBuf::from("test_temp/null_logic_is_stored_with_database");
+            let null_logic_new;
+            let null_logic_reopened;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                null_logic_new = ex.null_logic;
+                ex.set_null_logic(NullLogic::Legacy).unwrap();
+            }
+            {
+                let ex = Executor::new(&db_root, false, false).unwrap();
+                null_logic_reopened = ex.null_logic;
+            }
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(NullLogic::ThreeValued, null_logic_new);
+            assert_eq!(NullLogic::Legacy, null_logic_reopened);
+        }
+
+        #[test]
+        fn refuse_newer_format() {
+            let db_root = PathBuf::from("test_temp/refuse_newer_format");
+            {
+                Executor::new(&db_root, true, false).unwrap();
+            }
+            DatabaseInfo { format_version: FORMAT_VERSION + 1, null_logic: NullLogic::default() }.write(&fs::canonicalize(&db_root).unwrap()).unwrap();
+
+            let result = Executor::new(&db_root, false, false);
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(matches!(result, Err(BongoError::UnsupportedFormatError(_))));
+        }
+    }
+
+    mod vacuum {
+        use std::collections::HashSet;
+        use std::fs;
+        use std::path::PathBuf;
+
+        use bongo_core::bongo_request::BongoRequest;
+        use bongo_core::types::BongoLiteral;
+
+        use crate::executor::Executor;
+        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows, select_all};
+
+        #[test]
+        fn vacuum_removes_ghosts() {
+            let db_root = PathBuf::from("test_temp/vacuum_removes_ghosts");
+            let table_name = "table_1";
+            let data_path = db_root.join(table_name).join("data.bongo");
+            let delete_req = BongoRequest { sql: format!("DELETE FROM {table_name} WHERE col_1 = 1 OR col_1 = 3;") };
+            let insert_req = BongoRequest { sql: format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (5, 'e', true);") };
+            let vacuum_req = BongoRequest { sql: format!("VACUUM {table_name};") };
+
+            let mut expected = get_example_rows();
+            expected.remove(2);
+            expected.remove(0);
+            let mut expected_after_insert = expected.clone();
+            expected_after_insert.push(vec![BongoLiteral::Int(5), BongoLiteral::Varchar("e".to_string()), BongoLiteral::Bool(true)]);
+
+            let file_len_before;
+            let file_len_after;
+            let expired_before;
+            let expired_after;
+            let result;
+            let result_after_insert;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                ex.execute(&delete_req).unwrap();
+                file_len_before = fs::metadata(&data_path).unwrap().len();
+                expired_before = ex.tables.read().unwrap().get(table_name).unwrap().read().unwrap().expired.len();
+                ex.execute(&vacuum_req).unwrap();
+                file_len_after = fs::metadata(&data_path).unwrap().len();
+                expired_after = ex.tables.read().unwrap().get(table_name).unwrap().read().unwrap().expired.len();
+                result = select_all(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                ex.execute(&insert_req).unwrap();
+                result_after_insert = select_all(&mut ex, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(file_len_before / 2, file_len_after);
+            // the expired versions have been reclaimed
+            assert_eq!((2, 0), (expired_before, expired_after));
+            assert_eq!(expected, result);
+            assert_eq!(expected_after_insert, result_after_insert);
+        }
+
+        #[test]
+        fn vacuum_non_existing_table() {
+            let db_root = PathBuf::from("test_temp/vacuum_non_existing_table");
+            let vacuum_req = BongoRequest { sql: "VACUUM table_1;".to_string() };
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                result = ex.execute(&vacuum_req);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn auto_vacuum_respects_ghost_ratio() {
+            let db_root = PathBuf::from("test_temp/auto_vacuum_respects_ghost_ratio");
+            let table_name = "table_1";
+            let data_path = db_root.join(table_name).join("data.bongo");
+            let delete_req = BongoRequest { sql: format!("DELETE FROM {table_name} WHERE col_1 = 4;") };
+
+            let file_len_before;
+            let file_len_below_ratio;
+            let file_len_above_ratio;
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                ex.execute(&delete_req).unwrap();
+                file_len_before = fs::metadata(&data_path).unwrap().len();
+
+                // one ghost in four rows
+                assert!(ex.auto_vacuum(0.5, &HashSet::new()).unwrap().is_empty());
+                file_len_below_ratio = fs::metadata(&data_path).unwrap().len();
+                // a skipped table is not compacted
+                assert!(ex.auto_vacuum(0.25, &HashSet::from([table_name.to_string()])).unwrap().is_empty());
+                assert_eq!(file_len_before, fs::metadata(&data_path).unwrap().len());
+                let results = ex.auto_vacuum(0.25, &HashSet::new()).unwrap();
+                assert_eq!(vec![(table_name.to_string(), Ok(()))], results);
+                file_len_above_ratio = fs::metadata(&data_path).unwrap().len();
+
+                result = select_all(&mut ex, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(file_len_before, file_len_below_ratio);
+            assert_eq!(file_len_before / 4 * 3, file_len_above_ratio);
+            assert_eq!(get_example_rows()[0..3].to_vec(), result);
+        }
+
+        #[test]
+        fn incomplete_compaction_is_discarded() {
+            let db_root = PathBuf::from("test_temp/incomplete_compaction_is_discarded");
+            let table_name = "table_1";
+            let table_dir = db_root.join(table_name);
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+            } // leaving scope triggers drop and flush on the executor
+
+            // simulate a crash while the compacted data is written
+            fs::write(table_dir.join("data.bongo.compact"), [1, 2, 3]).unwrap();
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result = select_all(&mut ex, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            let compact_exists = table_dir.join("data.bongo.compact").exists();
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(!compact_exists);
+            assert_eq!(get_example_rows(), result);
+        }
+    }
+
+
+    mod index {
+        use std::fs;
+        use std::mem;
+        use std::path::PathBuf;
+
+        use bongo_core::types::{BongoError, BongoLiteral};
+
+        use crate::executor::Executor;
+        use crate::planner::QueryPlan;
+        use crate::executor::tests::{create_example_table, execute, get_example_rows, insert_example_rows, try_execute};
+        use crate::statement::{BinOp, Expr, NullLogic};
+
+        fn index_names(ex: &Executor, table_name: &str) -> Vec<String> {
+            let tables = ex.tables_read_access().unwrap();
+            let table = tables.get(table_name).unwrap().read().unwrap();
+            table.indexes.iter().map(|index| index.def.name.clone()).collect()
+        }
+
+        #[test]
+        fn index_is_used_and_maintained() {
+            let db_root = PathBuf::from("test_temp/index_is_used_and_maintained");
+            let table_name = "table_1";
+            let condition = Expr::BinaryExpr {
+                left: Box::new(Expr::Identifier("col_2".to_string())),
+                op: BinOp::Eq,
+                right: Box::new(Expr::Value(BongoLiteral::Varchar("b❤".to_string()))),
+            };
+            let mut updated = get_example_rows()[2].clone();
+            updated[1] = BongoLiteral::Varchar("x".to_string());
+            let indexed_positions;
+            let result_before_update;
+            let result_old_value;
+            let result_new_value;
+            let result_after_delete;
+            let result_after_drop;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                // the index is filled with the rows that already exist
+                execute(&mut ex, &format!("CREATE INDEX idx_2 ON {table_name} (col_2);"));
+                {
+                    let tables = ex.tables_read_access().unwrap();
+                    let table = tables.get(table_name).unwrap().read().unwrap();
+                    let plan = QueryPlan::new(&table.indexes, &table.cols, Some(condition), None, NullLogic::default());
+                    assert!(plan.residual.is_none());
+                    indexed_positions = plan.positions(&table.indexes, || table.positions()).unwrap().len();
+                }
+
+                result_before_update = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_2 = 'b❤';"));
+                execute(&mut ex, &format!("UPDATE {table_name} SET col_2 = 'x' WHERE col_2 = 'b❤';"));
+                result_old_value = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_2 = 'b❤';"));
+                result_new_value = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_2 = 'x';"));
+                execute(&mut ex, &format!("DELETE FROM {table_name} WHERE col_2 = 'x';"));
+                result_after_delete = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_2 != 'c❤' ORDER BY col_1;"));
+                execute(&mut ex, "DROP INDEX idx_2;");
+                result_after_drop = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_2 = 'a❤';"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            let rows = get_example_rows();
+            assert_eq!(1, indexed_positions);
+            assert_eq!(vec![rows[2].clone()], result_before_update);
+            assert!(result_old_value.is_empty());
+            assert_eq!(vec![updated], result_new_value);
+            assert_eq!(vec![rows[0].clone(), rows[3].clone()], result_after_delete);
+            assert_eq!(vec![rows[3].clone()], result_after_drop);
+        }
+
+        #[test]
+        fn invalid_index_statements() {
+            let db_root = PathBuf::from("test_temp/invalid_index_statements");
+            let table_name = "table_1";
+            let result_duplicate;
+            let result_no_column;
+            let result_no_table;
+            let result_drop_missing;
+            let result_key_too_large;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                create_example_table(&mut ex, "table_2");
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} (col_1);"));
+                execute(&mut ex, "CREATE TABLE table_4 (col_1 VARCHAR(1024));");
+                result_key_too_large = try_execute(&mut ex, "CREATE INDEX idx_3 ON table_4 USING BTREE (col_1);");
+
+                // index names are unique among all tables
+                result_duplicate = try_execute(&mut ex, "CREATE INDEX idx_1 ON table_2 (col_1);");
+                result_no_column = try_execute(&mut ex, &format!("CREATE INDEX idx_2 ON {table_name} (col_4);"));
+                result_no_table = try_execute(&mut ex, "CREATE INDEX idx_2 ON table_3 (col_1);");
+                result_drop_missing = try_execute(&mut ex, "DROP INDEX idx_1, idx_2;");
+                // a failing DROP INDEX does not drop any index
+                assert_eq!(vec!["idx_1".to_string()], index_names(&ex, table_name));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(matches!(result_duplicate, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_no_column, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_no_table, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_drop_missing, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_key_too_large, Err(BongoError::SqlRuntimeError(_))));
+        }
+
+        #[test]
+        fn btree_index_is_used_for_ranges_and_order() {
+            let db_root = PathBuf::from("test_temp/btree_index_is_used_for_ranges_and_order");
+            let table_name = "table_1";
+            let condition = Expr::Between {
+                expr: Box::new(Expr::Identifier("col_1".to_string())),
+                negated: false,
+                low: Box::new(Expr::Value(BongoLiteral::Int(2))),
+                high: Box::new(Expr::Value(BongoLiteral::Int(3))),
+            };
+            let range_indexer;
+            let order_indexer;
+            let result_between;
+            let result_not_between;
+            let result_lt_desc;
+            let result_gt_eq;
+            let result_varchar;
+            let result_order_by_index;
+            let result_after_update;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} USING BTREE (col_1);"));
+                execute(&mut ex, &format!("CREATE INDEX idx_2 ON {table_name} USING BTREE (col_2);"));
+                {
+                    let tables = ex.tables_read_access().unwrap();
+                    let table = tables.get(table_name).unwrap().read().unwrap();
+                    let plan = QueryPlan::new(&table.indexes, &table.cols, Some(condition), Some("col_2"), NullLogic::default());
+                    let positions = plan.positions(&table.indexes, || table.positions()).unwrap();
+                    range_indexer = (positions.len(), plan.residual.is_none(), plan.ordered);
+                    let plan = QueryPlan::new(&table.indexes, &table.cols, None, Some("col_2"), NullLogic::default());
+                    let positions = plan.positions(&table.indexes, || table.positions()).unwrap();
+                    order_indexer = (positions.len(), plan.ordered);
+                }
+
+                result_between = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_1 BETWEEN 2 AND 3 ORDER BY col_1;"));
+                result_not_between = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_1 NOT BETWEEN 2 AND 3 ORDER BY col_1;"));
+                result_lt_desc = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE 3 > col_1 ORDER BY col_1 DESC;"));
+                result_gt_eq = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_1 >= 2 ORDER BY col_1;"));
+                result_varchar = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_2 < 'c' ORDER BY col_1;"));
+                result_order_by_index = execute(&mut ex, &format!("SELECT * FROM {table_name} ORDER BY col_2;"));
+                execute(&mut ex, &format!("UPDATE {table_name} SET col_1 = 0 WHERE col_1 = 4;"));
+                execute(&mut ex, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (Null, 'e', true);"));
+                execute(&mut ex, &format!("DELETE FROM {table_name} WHERE col_1 <= 1;"));
+                result_after_update = execute(&mut ex, &format!("SELECT * FROM {table_name} ORDER BY col_1 DESC;"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            let rows = get_example_rows();
+            let null_row = vec![BongoLiteral::Null, BongoLiteral::Varchar("e".to_string()), BongoLiteral::Bool(true)];
+            assert_eq!((2, true, false), range_indexer);
+            assert_eq!((4, true), order_indexer);
+            assert_eq!(vec![rows[1].clone(), rows[2].clone()], result_between);
+            assert_eq!(vec![rows[0].clone(), rows[3].clone()], result_not_between);
+            assert_eq!(vec![rows[1].clone(), rows[0].clone()], result_lt_desc);
+            assert_eq!(vec![rows[1].clone(), rows[2].clone(), rows[3].clone()], result_gt_eq);
+            assert_eq!(vec![rows[2].clone(), rows[3].clone()], result_varchar);
+            assert_eq!(vec![rows[3].clone(), rows[2].clone(), rows[1].clone(), rows[0].clone()], result_order_by_index);
+            // NULL values are ordered last and are never within a range
+            assert_eq!(vec![null_row, rows[2].clone(), rows[1].clone()], result_after_update);
+        }
+
+        #[test]
+        fn indexes_are_used_inside_and_or() {
+            let db_root = PathBuf::from("test_temp/indexes_are_used_inside_and_or");
+            let table_name = "table_1";
+            let result_and;
+            let result_or;
+            let result_or_unindexed;
+            let result_ordered_and;
+            let result_after_delete;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} (col_1);"));
+                execute(&mut ex, &format!("CREATE INDEX idx_2 ON {table_name} USING BTREE (col_2);"));
+
+                result_and = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_1 = 2 AND col_2 = 'c❤';"));
+                result_or = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_1 = 1 OR col_2 = 'a❤' ORDER BY col_1;"));
+                result_or_unindexed = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_1 = 1 OR col_3 = false ORDER BY col_1;"));
+                result_ordered_and = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_2 > 'a' AND col_3 = false ORDER BY col_2;"));
+                execute(&mut ex, &format!("DELETE FROM {table_name} WHERE col_1 = 4 AND col_3 = false;"));
+                result_after_delete = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_1 = 4 OR col_2 = 'd❤';"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            let rows = get_example_rows();
+            assert_eq!(vec![rows[1].clone()], result_and);
+            assert_eq!(vec![rows[0].clone(), rows[3].clone()], result_or);
+            assert_eq!(vec![rows[0].clone(), rows[1].clone(), rows[3].clone()], result_or_unindexed);
+            assert_eq!(vec![rows[3].clone(), rows[1].clone()], result_ordered_and);
+            assert_eq!(vec![rows[0].clone()], result_after_delete);
+        }
+
+        #[test]
+        fn btree_index_is_rebuilt_after_crash() {
+            let db_root = PathBuf::from("test_temp/btree_index_is_rebuilt_after_crash");
+            let table_name = "table_1";
+            let select_sql = format!("SELECT col_1 FROM {table_name} WHERE col_1 > 2 ORDER BY col_1;");
+            let result_recovered;
+            let result_after_vacuum;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} USING BTREE (col_1);"));
+                execute(&mut ex, "FLUSH;");
+                // the index is modified after it has been synced
+                insert_example_rows(&mut ex, table_name);
+                execute(&mut ex, &format!("DELETE FROM {table_name} WHERE col_1 = 3;"));
+                // simulate a crash by preventing the final flush
+                mem::forget(ex);
+            }
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result_recovered = execute(&mut ex, &select_sql);
+                execute(&mut ex, "VACUUM;");
+                execute(&mut ex, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (5, 'e', true);"));
+                result_after_vacuum = execute(&mut ex, &select_sql);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(vec![vec![BongoLiteral::Int(4)]], result_recovered);
+            assert_eq!(vec![vec![BongoLiteral::Int(4)], vec![BongoLiteral::Int(5)]], result_after_vacuum);
+        }
+
+        #[test]
+        fn indexes_are_recovered() {
+            let db_root = PathBuf::from("test_temp/indexes_are_recovered");
+            let table_name = "table_1";
+            let select_sql = format!("SELECT * FROM {table_name} WHERE col_3 = false ORDER BY col_1;");
+            let names_replayed;
+            let names_rebuilt;
+            let result_replayed;
+            let result_rebuilt;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} (col_1);"));
+                execute(&mut ex, &format!("CREATE INDEX idx_3 ON {table_name} (col_3);"));
+                execute(&mut ex, "DROP INDEX idx_1;");
+                // simulate a crash by preventing the final flush
+                mem::forget(ex);
+            }
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                names_replayed = index_names(&ex, table_name);
+                result_replayed = execute(&mut ex, &select_sql);
+            } // leaving scope triggers drop and flush on the executor
+
+            fs::remove_file(db_root.join(table_name).join("meta.bongo")).unwrap();
+
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                names_rebuilt = index_names(&ex, table_name);
+                result_rebuilt = execute(&mut ex, &select_sql);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            let rows = get_example_rows();
+            let expected = vec![rows[1].clone(), rows[3].clone()];
+            assert_eq!(vec!["idx_3".to_string()], names_replayed);
+            assert_eq!(vec!["idx_3".to_string()], names_rebuilt);
+            assert_eq!(expected, result_replayed);
+            assert_eq!(expected, result_rebuilt);
+        }
+    }
+
+    mod transaction {
+        use std::fs;
+        use std::mem;
+        use std::path::PathBuf;
+        use std::sync::atomic::Ordering;
+        use std::time::Duration;
+
+        use bongo_core::types::{BongoError, BongoLiteral};
+
+        use crate::executor::{Executor, TableMetaData};
+        use crate::executor::tests::{create_example_table, execute_for, get_example_rows, insert_example_rows, select_all_for, try_execute_for};
+
+        ///
+        /// Returns a copy of the indexes, the ghosts, the expired versions and the row count of a table
+        /// with sorted index entries.
+        ///
+        fn table_state(ex: &Executor, table_name: &str) -> (Vec<(String, Vec<u64>)>, Vec<u64>, Vec<u64>, usize) {
+            let tables = ex.tables_read_access().unwrap();
+            let table: &TableMetaData = &tables.get(table_name).unwrap().read().unwrap();
+            let mut idx = table.indexes.iter()
+                .flat_map(|index| index.entries.iter().map(|(key, positions)| {
+                    let mut positions = positions.clone();
+                    positions.sort_unstable();
+                    (format!("{}: {:?}", index.def.name, key), positions)
+                }))
+                .collect::<Vec<(String, Vec<u64>)>>();
+            idx.sort();
+
+            (idx, table.ghosts.clone(), table.expired.clone(), table.row_count)
+        }
+
+        #[test]
+        fn commit_persists_changes() {
+            let db_root = PathBuf::from("test_temp/transaction_commit_persists_changes");
+            let table_name = "table_1";
+            let mut expected = get_example_rows();
+            expected.remove(0);
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, true).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute_for(&mut ex, 1, "BEGIN;");
+                execute_for(&mut ex, 1, &format!("DELETE FROM {table_name} WHERE col_1 = 1;"));
+                execute_for(&mut ex, 1, "COMMIT;");
+            }
+
+            {
+                let mut ex = Executor::new(&db_root, false, true).unwrap();
+                result = select_all_for(&mut ex, 1, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected, result);
+        }
+
+        #[test]
+        fn rollback_restores_rows_index_and_ghosts() {
+            let db_root = PathBuf::from("test_temp/transaction_rollback_restores_rows_index_and_ghosts");
+            let table_name = "table_1";
+            let data_path = db_root.join(table_name).join("data.bongo");
+            let state_before;
+            let state_after;
+            let file_len_before;
+            let file_len_after;
+            let result_inside;
+            let result_after;
+
+            {
+                let mut ex = Executor::new(&db_root, true, true).unwrap();
+                create_example_table(&mut ex, table_name);
+                execute_for(&mut ex, 1, &format!("CREATE INDEX idx_1 ON {table_name} (col_1);"));
+                execute_for(&mut ex, 1, &format!("CREATE INDEX idx_3 ON {table_name} (col_3);"));
+                insert_example_rows(&mut ex, table_name);
+                execute_for(&mut ex, 1, &format!("DELETE FROM {table_name} WHERE col_1 = 2;"));
+                // the rolled back row reclaims the slot of the deleted row, which is a ghost afterwards
+                execute_for(&mut ex, 1, "BEGIN;");
+                execute_for(&mut ex, 1, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (9, 'x', true);"));
+                execute_for(&mut ex, 1, "ROLLBACK;");
+                state_before = table_state(&ex, table_name);
+                file_len_before = fs::metadata(&data_path).unwrap().len();
+
+                execute_for(&mut ex, 1, "BEGIN;");
+                // the first row reuses the ghost and the second one is appended to the file
+                execute_for(&mut ex, 1, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (5, 'e', true), (6, 'f', true);"));
+                execute_for(&mut ex, 1, &format!("UPDATE {table_name} SET col_1 = 42 WHERE col_1 = 3;"));
+                execute_for(&mut ex, 1, &format!("DELETE FROM {table_name} WHERE col_1 = 1;"));
+                result_inside = select_all_for(&mut ex, 1, table_name);
+                execute_for(&mut ex, 1, "ROLLBACK;");
+
+                state_after = table_state(&ex, table_name);
+                file_len_after = fs::metadata(&data_path).unwrap().len();
+                result_after = select_all_for(&mut ex, 1, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(4, result_inside.len());
+            assert_eq!(BongoLiteral::Int(42), result_inside[3][0]);
+            assert_eq!(state_before, state_after);
+            assert_eq!(file_len_before, file_len_after);
+            let mut expected = get_example_rows();
+            expected.remove(1);
+            assert_eq!(expected, result_after);
+        }
+
+        #[test]
+        fn table_is_locked_for_other_writers() {
+            let db_root = PathBuf::from("test_temp/transaction_table_is_locked_for_other_writers");
+            let table_name = "table_1";
+            let result_locked;
+            let result_released;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                ex.lock_timeout = Duration::from_millis(50);
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute_for(&mut ex, 1, "BEGIN;");
+                execute_for(&mut ex, 1, &format!("DELETE FROM {table_name} WHERE col_1 = 1;"));
+
+                result_locked = try_execute_for(&mut ex, 2, &format!("DELETE FROM {table_name} WHERE col_1 = 2;"));
+                execute_for(&mut ex, 1, "COMMIT;");
+                result_released = select_all_for(&mut ex, 2, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(matches!(result_locked, Err(BongoError::TableLockedError(_))));
+            assert_eq!(get_example_rows()[1..].to_vec(), result_released);
+        }
+
+        #[test]
+        fn unfinished_transaction_is_rolled_back_on_startup() {
+            let db_root = PathBuf::from("test_temp/transaction_unfinished_transaction_is_rolled_back_on_startup");
+            let table_name = "table_1";
+            let result_after_crash;
+            let result_after_shutdown;
+
+            {
+                let mut ex = Executor::new(&db_root, true, true).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute_for(&mut ex, 1, "BEGIN;");
+                execute_for(&mut ex, 1, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (5, 'e', true);"));
+                execute_for(&mut ex, 1, &format!("UPDATE {table_name} SET col_1 = 42 WHERE col_1 = 3;"));
+                // simulates a crash by preventing the final flush
+                mem::forget(ex);
+            }
+
+            {
+                let mut ex = Executor::new(&db_root, false, true).unwrap();
+                result_after_crash = select_all_for(&mut ex, 1, table_name);
+                execute_for(&mut ex, 1, "BEGIN;");
+                execute_for(&mut ex, 1, &format!("DELETE FROM {table_name};"));
+            } // the final flush keeps the records of the unfinished transaction
+
+            {
+                let mut ex = Executor::new(&db_root, false, true).unwrap();
+                result_after_shutdown = select_all_for(&mut ex, 1, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
 
-    fn tables_read_access(&self) -> Result<RwLockReadGuard<HashMap<String, RefCell<RwLock<TableMetaData>>>>, BongoError> {
-        let tables = self.tables.read();
-        if tables.is_err() {
-            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+            assert_eq!(get_example_rows(), result_after_crash);
+            assert_eq!(get_example_rows(), result_after_shutdown);
         }
-        Ok(tables.unwrap())
-    }
 
-    fn tables_write_access(&mut self) -> Result<RwLockWriteGuard<HashMap<String, RefCell<RwLock<TableMetaData>>>>, BongoError> {
-        let tables = self.tables.write();
-        if tables.is_err() {
-            return Err(BongoError::InternalError("Concurrency Error.".to_string()));
+        #[test]
+        fn disconnect_rolls_back() {
+            let db_root = PathBuf::from("test_temp/transaction_disconnect_rolls_back");
+            let table_name = "table_1";
+            let result;
+
+            {
+                let mut ex = Executor::new(&db_root, true, true).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute_for(&mut ex, 1, "BEGIN;");
+                execute_for(&mut ex, 1, &format!("DELETE FROM {table_name} WHERE col_1 = 4;"));
+                ex.disconnect(1).unwrap();
+                result = select_all_for(&mut ex, 2, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(get_example_rows(), result);
         }
-        Ok(tables.unwrap())
-    }
-}
 
-///
-/// An expression that can trivially indexed.
-///
-/// The definition of trivial is here that the expression must be a binary expression where one of the
-/// operands is the indexable, the other operand is a literal and the operator is Eq or NotEq.
-///
-struct TrivialIdxExpr {
-    ///
-    /// The column to be indexed.
-    /// Not used yet, might be useful later.
-    ///
-    pub _col: String,
-    ///
-    /// The comparison operator which must be `Eq` or `NotEq` because only those are supported.
-    ///
-    pub op: IndexBinOp,
-    ///
-    /// The value that the value of the column is compared to.
-    ///
-    pub val: BongoLiteral,
-}
+        #[test]
+        fn invalid_statements_in_transaction() {
+            let db_root = PathBuf::from("test_temp/transaction_invalid_statements_in_transaction");
+            let table_name = "table_1";
+            let create_result;
+            let begin_result;
+            let commit_result;
 
-enum IndexBinOp {
-    Eq,
-    NotEq,
-}
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute_for(&mut ex, 1, "BEGIN;");
+                create_result = try_execute_for(&mut ex, 1, &format!("CREATE TABLE {table_name} (col_1 INT);"));
+                begin_result = try_execute_for(&mut ex, 1, "BEGIN;");
+                execute_for(&mut ex, 1, "ROLLBACK;");
+                commit_result = try_execute_for(&mut ex, 1, "COMMIT;");
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
-impl TryFrom<&BinOp> for IndexBinOp {
-    type Error = ();
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
 
-    fn try_from(op: &BinOp) -> Result<Self, Self::Error> {
-        match op {
-            BinOp::Eq => Ok(IndexBinOp::Eq),
-            BinOp::NotEq => Ok(IndexBinOp::NotEq),
-            _ => Err(())
+            assert!(matches!(create_result, Err(BongoError::UnsupportedFeatureError(_))));
+            assert!(matches!(begin_result, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(commit_result, Err(BongoError::SqlRuntimeError(_))));
         }
-    }
-}
 
+        #[test]
+        fn readers_see_snapshot_of_committed_rows() {
+            let db_root = PathBuf::from("test_temp/transaction_readers_see_snapshot_of_committed_rows");
+            let table_name = "table_1";
+            let mut expected_inside = get_example_rows();
+            expected_inside.remove(0);
+            expected_inside.push(vec![BongoLiteral::Int(5), BongoLiteral::Varchar("e".to_string()), BongoLiteral::Bool(true)]);
+            let result_own;
+            let result_other;
+            let result_committed;
 
-impl TryFrom<(&str, &Expr)> for TrivialIdxExpr {
-    type Error = ();
-    ///
-    /// In BongoDB so far if an `Expr` can be converted to a `TrivialIdxExpr` all elements fulfilling the
-    /// expression can be calculated using the tables index. If not then the evaluation of the `Expr`
-    /// can not trivially be sped up by using an index.
-    /// For BongoDB to use indexes where ever possible it would need to be able to recursively
-    /// restructure `Expr`s which is unfortunately out of the scope of this project.
-    ///
-    fn try_from((idx_col, expr): (&str, &Expr)) -> Result<Self, Self::Error> {
-        match expr {
-            Expr::BinaryExpr { left, op, right } => {
-                match IndexBinOp::try_from(op) {
-                    Ok(idx_op) => {
-                        match &(**left) {
-                            Expr::Identifier(name) => {
-                                match &(**right) {
-                                    Expr::BinaryExpr { .. } => {
-                                        // not trivially indexable, requires recursive analysing
-                                        Err(())
-                                    }
-                                    Expr::Identifier(_) => {
-                                        // not  indexable, because index requires concrete value lookup
-                                        Err(())
-                                    }
-                                    Expr::Value(val) => {
-                                        return if name == idx_col {
-                                            Ok(TrivialIdxExpr {
-                                                _col: name.to_string(),
-                                                op: idx_op,
-                                                val: (*val).clone(),
-                                            })
-                                        } else {
-                                            // the identifier is not the indexed column
-                                            Err(())
-                                        };
-                                    }
-                                }
-                            }
-                            Expr::Value(val) => {
-                                match &(**right) {
-                                    Expr::BinaryExpr { .. } => {
-                                        // not trivially indexable, requires recursive analysing
-                                        Err(())
-                                    }
-                                    Expr::Identifier(name) => {
-                                        return if name == idx_col {
-                                            Ok(TrivialIdxExpr {
-                                                _col: name.to_string(),
-                                                op: idx_op,
-                                                val: (*val).clone(),
-                                            })
-                                        } else {
-                                            // the identifier is not the indexed column
-                                            Err(())
-                                        };
-                                    }
-                                    Expr::Value(_) => {
-                                        // not  indexable, because this expression does not contain any Identifier (column).
-                                        // It can in fact be immediately evaluated, but this does not matter here.
-                                        Err(())
-                                    }
-                                }
-                            }
-                            Expr::BinaryExpr { .. } => {
-                                // not trivially indexable, requires recursive analysing
-                                Err(())
-                            }
-                        }
-                    }
-                    Err(_) => { Err(()) }
-                }
-            }
-            // not trivially indexable, requires recursive analysing
-            _ => Err(())
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                ex.lock_timeout = Duration::from_millis(50);
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute_for(&mut ex, 1, "BEGIN;");
+                execute_for(&mut ex, 1, &format!("DELETE FROM {table_name} WHERE col_1 = 1;"));
+                execute_for(&mut ex, 1, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (5, 'e', true);"));
+
+                result_own = select_all_for(&mut ex, 1, table_name);
+                // the reader is not blocked by the transaction and does not see its changes
+                result_other = select_all_for(&mut ex, 2, table_name);
+                execute_for(&mut ex, 1, "COMMIT;");
+                result_committed = select_all_for(&mut ex, 2, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(expected_inside, result_own);
+            assert_eq!(get_example_rows(), result_other);
+            assert_eq!(expected_inside, result_committed);
         }
-    }
-}
 
-struct DiscIndexer {
-    pub indices: Vec<u64>,
-    ///
-    /// Concrete row indexes are already known due to use of index.
-    /// Because `TrivialIdxExpr` only supports non-nested expressions, we can calculate row indices
-    /// from the `TrivialIdxExpr` and know that all those row indices will match the condition.
-    /// Therefore, In the indexable cases there is no expression to be applied
-    ///
-    pub expr: Option<Expr>,
-}
+        #[test]
+        fn expired_versions_are_reclaimed_once_no_snapshot_sees_them() {
+            let db_root = PathBuf::from("test_temp/transaction_expired_versions_are_reclaimed_once_no_snapshot_sees_them");
+            let table_name = "table_1";
+            let data_path = db_root.join(table_name).join("data.bongo");
+            let mut expected = get_example_rows();
+            expected.remove(0);
+            expected.push(vec![BongoLiteral::Int(5), BongoLiteral::Varchar("e".to_string()), BongoLiteral::Bool(true)]);
+            expected.push(vec![BongoLiteral::Int(6), BongoLiteral::Varchar("f".to_string()), BongoLiteral::Bool(true)]);
+            let file_len_before;
+            let file_len_kept;
+            let file_len_reclaimed;
+            let expired_kept;
+            let expired_reclaimed;
+            let result;
 
-impl DiscIndexer {
-    ///
-    /// Returns all rows indices that are applicable after potentially having applied the index and
-    /// the expression left to apply to all those indices. In case the index could already be used the
-    /// expression is None, and no linear search is needed anymore. In case the index could not be used
-    /// all indices are returned as a linear search is needed along with the expression which must be
-    /// checked for each of the indices.
-    ///
-    pub fn from_opt_expr(idx: &(String, HashMap<BongoLiteral, Vec<u64>>), opt_expr: Option<Expr>) -> Self {
-        let (name, map) = idx;
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                file_len_before = fs::metadata(&data_path).unwrap().len();
+
+                // a SELECT that has taken its snapshot before the deletion is still running
+                let horizon = ex.next_txn_id.load(Ordering::Relaxed);
+                ex.snapshots.lock().unwrap().insert(horizon, 1);
+                execute_for(&mut ex, 1, &format!("DELETE FROM {table_name} WHERE col_1 = 1;"));
+                execute_for(&mut ex, 1, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (5, 'e', true);"));
+                file_len_kept = fs::metadata(&data_path).unwrap().len();
+                expired_kept = table_state(&ex, table_name).2;
+
+                // the SELECT has finished
+                ex.snapshots.lock().unwrap().clear();
+                execute_for(&mut ex, 1, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES (6, 'f', true);"));
+                file_len_reclaimed = fs::metadata(&data_path).unwrap().len();
+                expired_reclaimed = table_state(&ex, table_name).2;
+                result = select_all_for(&mut ex, 1, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
-        match opt_expr {
-            None => {
-                // not indexable and no condition -> all indices that are in map + None
-                Self {
-                    indices: Self::idx_to_indices(map.clone()),
-                    expr: None,
-                }
-            }
-            Some(expr) => {
-                match TrivialIdxExpr::try_from((name.as_str(), &expr)) {
-                    Ok(idx_expr) => {
-                        match idx_expr.op {
-                            IndexBinOp::Eq => {
-                                // indexable with Eq operator -> return the contents of the index at that position + None
-                                // because condition is always true for all values at the those indices
-                                match map.get(&idx_expr.val) {
-                                    None => Self { indices: vec![], expr: None },
-                                    Some(indices) => Self { indices: indices.clone(), expr: None }
-                                }
-                            }
-                            IndexBinOp::NotEq => {
-                                // indexable with NotEq operator -> return all indexes but the ones that match the index
-                                // and none, because expr is true for all values at those indices
-                                let mut idx = map.clone();
-                                idx.remove(&idx_expr.val);
-                                Self { indices: Self::idx_to_indices(idx), expr: None }
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        // not indexable -> all indices in map + Some(expression)
-                        // because the expression must still be evaluated for each value
-                        Self { indices: Self::idx_to_indices(map.clone()), expr: Some(expr) }
-                    }
-                }
-            }
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            let row_size = file_len_before / 4;
+            assert_eq!(file_len_before + row_size, file_len_kept);
+            assert_eq!(1, expired_kept.len());
+            assert_eq!(file_len_kept, file_len_reclaimed);
+            assert!(expired_reclaimed.is_empty());
+            assert_eq!(expected, result);
         }
-    }
 
-    fn idx_to_indices(idx: HashMap<BongoLiteral, Vec<u64>>) -> Vec<u64> {
-        idx.into_iter()
-            .map(|(_lit, indices)| { indices })
-            .collect::<Vec<Vec<u64>>>()
-            .concat()
-    }
-}
+        #[test]
+        fn vacuum_reclaims_old_versions() {
+            let db_root = PathBuf::from("test_temp/transaction_vacuum_reclaims_old_versions");
+            let table_name = "table_1";
+            let data_path = db_root.join(table_name).join("data.bongo");
+            let mut expected = get_example_rows();
+            expected[1][1] = BongoLiteral::Varchar("x".to_string());
+            let file_len_inserted;
+            let file_len_updated;
+            let file_len_vacuumed;
+            let state_vacuumed;
+            let result;
 
-// TODO: LOW_PRIO: write concurrency tests with by using many threads that access the same executor
-#[cfg(test)]
-mod tests {
-    use bongo_core::bongo_request::BongoRequest;
-    use bongo_core::bongo_result::BongoResult;
-    use bongo_core::types::{BongoLiteral, Row};
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                file_len_inserted = fs::metadata(&data_path).unwrap().len();
+                execute_for(&mut ex, 1, &format!("UPDATE {table_name} SET col_2 = 'x' WHERE col_1 = 2;"));
+                file_len_updated = fs::metadata(&data_path).unwrap().len();
+                execute_for(&mut ex, 1, &format!("VACUUM {table_name};"));
+                file_len_vacuumed = fs::metadata(&data_path).unwrap().len();
+                state_vacuumed = table_state(&ex, table_name);
+                result = select_all_for(&mut ex, 1, table_name);
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
-    use crate::executor::Executor;
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
 
-    mod constructor {
+            assert_eq!(expected, result);
+            // the old version is kept and the new version is appended
+            assert!(file_len_updated > file_len_inserted);
+            // the file shrinks back to the size of the four remaining rows
+            assert_eq!(file_len_inserted, file_len_vacuumed);
+            assert!(state_vacuumed.2.is_empty());
+            assert_eq!(4, state_vacuumed.3);
+        }
+    }
+
+    mod explain {
         use std::fs;
+        use std::path::PathBuf;
 
-        use bongo_core::types::BongoError;
+        use bongo_core::types::BongoLiteral;
 
         use crate::executor::Executor;
+        use crate::executor::tests::{create_example_table, execute, insert_example_rows};
 
-        #[test]
-        fn non_existing_path_create_db_true() {
-            let path = "test_temp/non_existing_path_create_db_true";
-            non_existing_path(path, true);
-            // clen up, because this call should create a new DB
-            fs::remove_dir(path).unwrap();
+        fn explain(ex: &mut Executor, sql: &str) -> Vec<String> {
+            execute(ex, sql).into_iter().map(|row| match &row[0] {
+                BongoLiteral::Varchar(line) => line.clone(),
+                _ => panic!("EXPLAIN must return strings"),
+            }).collect()
         }
 
         #[test]
-        fn non_existing_path_create_db_false() {
-            non_existing_path("test_temp/non_existing_path_create_db_false", false);
-        }
+        fn explain_select_update_delete() {
+            let db_root = PathBuf::from("test_temp/explain_select_update_delete");
+            let table_name = "table_1";
+            let result_lookup;
+            let result_index_scan;
+            let result_full_scan;
+            let result_after_explain;
 
-        fn non_existing_path(path: &str, create_db: bool) {
-            let ex = Executor::new(path, create_db, false);
-            match ex {
-                Ok(_) => {
-                    // if we create the db this should return Ok(_)
-                    assert!(create_db);
-                }
-                Err(err) => {
-                    // an error should be thrown if we do not create the db
-                    assert!(!create_db);
-                    assert_eq!(
-                        BongoError::DatabaseNotFoundError(format!("location: '{}'", path)),
-                        err
-                    )
-                }
-            }
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} (col_1);"));
+                execute(&mut ex, &format!("CREATE INDEX idx_2 ON {table_name} USING BTREE (col_2);"));
+
+                result_lookup = explain(&mut ex, &format!("EXPLAIN SELECT * FROM {table_name} \
+                    WHERE col_1 = 2 AND col_3 = false ORDER BY col_2 DESC;"));
+                result_index_scan = explain(&mut ex, &format!("EXPLAIN SELECT * FROM {table_name} ORDER BY col_2;"));
+                result_full_scan = explain(&mut ex, &format!("EXPLAIN DELETE FROM {table_name} WHERE col_3 = true OR col_1 = 1;"));
+                // the explained statement is not executed
+                result_after_explain = execute(&mut ex, &format!("SELECT * FROM {table_name};")).len();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(vec![
+                "SELECT on table table_1",
+                "Index lookup using idx_1 (hash) where col_1 = 2",
+                "Filter: col_3 = false",
+                "Sort: col_2 DESC",
+                "Rows examined: estimated 1",
+            ], result_lookup);
+            assert_eq!(vec![
+                "SELECT on table table_1",
+                "Index scan using idx_2",
+                "Filter: none",
+                "Sort: none, rows are read in ascending order of col_2",
+                "Rows examined: estimated 4",
+            ], result_index_scan);
+            assert_eq!(vec![
+                "DELETE on table table_1",
+                "Full scan",
+                "Filter: col_3 = true OR col_1 = 1",
+                "Sort: none",
+                "Rows examined: estimated 4",
+            ], result_full_scan);
+            assert_eq!(4, result_after_explain);
         }
 
         #[test]
-        fn existing_path() {
-            let path = "test_temp/test_existing_path";
-            fs::create_dir_all(path).unwrap();
+        fn explain_analyze_executes_statement() {
+            let db_root = PathBuf::from("test_temp/explain_analyze_executes_statement");
+            let table_name = "table_1";
+            let result_delete;
+            let result_select;
+            let result_limit;
+            let result_after_delete;
 
-            let ex = Executor::new(path, false, false);
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} USING BTREE (col_1);"));
 
-            fs::remove_dir(path).unwrap();
+                result_delete = explain(&mut ex, &format!("EXPLAIN ANALYZE DELETE FROM {table_name} WHERE col_1 > 2;"));
+                result_select = explain(&mut ex, &format!("EXPLAIN ANALYZE SELECT col_1 FROM {table_name} WHERE col_1 != 1;"));
+                result_limit = explain(&mut ex, &format!("EXPLAIN ANALYZE SELECT col_1 FROM {table_name} LIMIT 1;"));
+                result_after_delete = execute(&mut ex, &format!("SELECT * FROM {table_name};")).len();
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
-            match ex {
-                Ok(ex) => {
-                    assert!(ex.db_root.is_absolute());
-                }
-                Err(_) => {
-                    panic!("This should not return an error on unix systems")
-                }
-            }
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!("Index lookup using idx_1 (B-tree) where col_1 > 2", result_delete[1]);
+            // the B-tree index can only estimate the amount of rows in a range
+            assert_eq!("Rows examined: estimated 2, actual 2", result_delete[4]);
+            assert!(result_delete[5].starts_with("Planning time: "));
+            assert!(result_delete[6].starts_with("Execution time: "));
+            // the deleted versions are neither estimated nor examined
+            assert_eq!("Rows examined: estimated 1, actual 1", result_select[4]);
+            assert_eq!("Rows returned: 1", result_select[5]);
+            assert_eq!(8, result_select.len());
+            // reading stops as soon as the limit is reached
+            assert_eq!("Rows examined: estimated 2, actual 1", result_limit[5]);
+            assert_eq!(2, result_after_delete);
         }
     }
 
-    mod create_drop_tables {
+    mod join {
         use std::fs;
         use std::path::PathBuf;
-        use std::str::FromStr;
 
-        use bongo_core::bongo_request::BongoRequest;
+        use bongo_core::types::BongoLiteral;
+
         use crate::executor::Executor;
-        use crate::executor::tests::create_example_table;
+        use crate::executor::tests::{create_example_table, execute, insert_example_rows, int, try_execute, varchar};
 
         ///
-        /// 1. Creates an `Executor` on en empty DB and creates a table
-        /// 2. Destroys `Executor`
-        /// 3. Creates new `Executor` and checks if the table can be loaded from disc
-        /// 4. Drops Table and ensures that table got successfully dropped from cache and disc worked
-        ///
-        /// NOTE: uses private functions for test
+        /// Creates `table_1` with the example rows and `table_2` whose `col_1` references `table_1.col_1`.
         ///
+        fn create_joined_tables(ex: &mut Executor) {
+            create_example_table(ex, "table_1");
+            insert_example_rows(ex, "table_1");
+            execute(ex, "CREATE TABLE table_2 (col_1 INT, col_2 VARCHAR(16));");
+            execute(ex, "INSERT INTO table_2 (col_1, col_2) VALUES (1, 'x'), (2, 'y'), (2, 'z'), (5, 'w');");
+        }
+
         #[test]
-        fn create_table_and_load_in_next_session_and_drop() {
-            let db_root = PathBuf::from_str("test_temp/create_table_and_load_in_next_session_and_drop").unwrap();
+        fn inner_and_left_join() {
+            let db_root = PathBuf::from("test_temp/inner_and_left_join");
+            let result_inner;
+            let result_left;
+            let result_wildcards;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
-                // create table
-                create_example_table(&mut ex, "table_1");
-            }
+                create_joined_tables(&mut ex);
+
+                result_inner = execute(&mut ex, "SELECT table_1.col_1, table_2.col_2 FROM table_1 \
+                    JOIN table_2 ON table_1.col_1 = table_2.col_1 ORDER BY table_2.col_2;");
+                result_left = execute(&mut ex, "SELECT table_1.col_1, table_2.col_2 FROM table_1 \
+                    LEFT JOIN table_2 ON table_1.col_1 = table_2.col_1 AND table_2.col_2 != 'z' \
+                    WHERE table_1.col_2 != 'b❤' ORDER BY table_1.col_1 DESC;");
+                result_wildcards = execute(&mut ex, "SELECT table_2.*, col_3 FROM table_1 \
+                    INNER JOIN table_2 ON table_1.col_1 = table_2.col_1 WHERE table_2.col_2 = 'x';");
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(vec![
+                vec![int(1), varchar("x")],
+                vec![int(2), varchar("y")],
+                vec![int(2), varchar("z")],
+            ], result_inner);
+            assert_eq!(vec![
+                vec![int(4), BongoLiteral::Null],
+                vec![int(2), varchar("y")],
+                vec![int(1), varchar("x")],
+            ], result_left);
+            assert_eq!(vec![vec![int(1), varchar("x"), BongoLiteral::Bool(true)]], result_wildcards);
+        }
+
+        #[test]
+        fn join_strategies() {
+            let db_root = PathBuf::from("test_temp/join_strategies");
+            let result_nested_loop;
+            let result_index_lookup;
+            let result_hash;
+            let explain_index_lookup;
+            let explain_hash;
 
             {
-                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_joined_tables(&mut ex);
+                execute(&mut ex, "CREATE TABLE table_3 (col_1 INT);");
+                let values = (0..100).map(|i| format!("({})", i % 5)).collect::<Vec<String>>().join(", ");
+                execute(&mut ex, &format!("INSERT INTO table_3 (col_1) VALUES {values};"));
+
+                let sql = "SELECT table_1.col_1, table_2.col_2 FROM table_1 \
+                    JOIN table_2 ON table_2.col_1 = table_1.col_1;";
+                result_nested_loop = execute(&mut ex, sql);
+                execute(&mut ex, "CREATE INDEX idx_1 ON table_2 (col_1);");
+                result_index_lookup = execute(&mut ex, sql);
+                explain_index_lookup = execute(&mut ex, &format!("EXPLAIN {sql}"));
+
+                // the 100 rows of table_3 are enough to put table_2 into a hash table
+                let sql = "SELECT table_2.col_2 FROM table_3 \
+                    JOIN table_1 ON table_3.col_1 = table_1.col_1 \
+                    JOIN table_2 ON table_2.col_2 = table_1.col_2 WHERE table_1.col_1 = 2;";
+                result_hash = execute(&mut ex, sql).len();
+                explain_hash = execute(&mut ex, &format!("EXPLAIN {sql}"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
-                match ex.tables.read().unwrap().get("table_1") {
-                    None => { panic!("The entry table_1 does not exist in the meta data table") }
-                    Some(_data) => {
-                        // nice, some data is in there
-                        // unfortunately it is a bit hard checking the data is correct as it is behind a RwLock.
-                        // But we have loads of other tests that check the data is loaded correctly using the
-                        // public select interface of the Executor
-                    }
-                }
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
 
-                // drop table again
-                let drop_table_req = BongoRequest { sql: "DROP TABLE table_1;".to_string() };
-                ex.execute(&drop_table_req).unwrap();
+            assert_eq!(result_nested_loop, result_index_lookup);
+            assert_eq!(3, result_index_lookup.len());
+            assert_eq!(vec![
+                vec![varchar("SELECT on table table_1")],
+                vec![varchar("Full scan")],
+                vec![varchar("Filter: none")],
+                vec![varchar("Inner join table_2 using index lookup using idx_1 on table_2.col_1")],
+                vec![varchar("Sort: none")],
+                vec![varchar("Rows examined: estimated 4")],
+            ], explain_index_lookup);
+            assert_eq!(0, result_hash);
+            assert_eq!(vec![
+                vec![varchar("SELECT on table table_3")],
+                vec![varchar("Full scan")],
+                vec![varchar("Filter: none")],
+                vec![varchar("Inner join table_1 using hash join on table_1.col_1")],
+                vec![varchar("Inner join table_2 using hash join on table_2.col_2")],
+                vec![varchar("Join filter: table_1.col_1 = 2")],
+                vec![varchar("Sort: none")],
+                vec![varchar("Rows examined: estimated 100")],
+            ], explain_hash);
+        }
+
+        #[test]
+        fn ambiguous_or_unknown_columns() {
+            let db_root = PathBuf::from("test_temp/ambiguous_or_unknown_columns");
+            let result_ambiguous;
+            let result_unknown;
+            let result_unknown_table;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_joined_tables(&mut ex);
+
+                result_ambiguous = try_execute(&mut ex, "SELECT col_2 FROM table_1 JOIN table_2 ON table_1.col_1 = table_2.col_1;");
+                result_unknown = try_execute(&mut ex, "SELECT table_2.col_3 FROM table_1 JOIN table_2 ON table_1.col_1 = table_2.col_1;");
+                result_unknown_table = try_execute(&mut ex, "SELECT * FROM table_1 JOIN table_9 ON table_1.col_1 = table_9.col_1;");
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(result_ambiguous.is_err());
+            assert!(result_unknown.is_err());
+            assert!(result_unknown_table.is_err());
+        }
+    }
+
+    mod aggregate {
+        use std::fs;
+        use std::path::PathBuf;
+
+        use bongo_core::types::BongoLiteral;
+
+        use crate::executor::Executor;
+        use crate::executor::tests::{create_example_table, execute, insert_example_rows, try_execute};
+
+        #[test]
+        fn aggregates_without_group_by() {
+            let db_root = PathBuf::from("test_temp/aggregates_without_group_by");
+            let table_name = "table_1";
+            let result_all;
+            let result_none;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+
+                result_all = execute(&mut ex, &format!("SELECT COUNT(*), COUNT(col_3), SUM(col_1), AVG(col_1), \
+                    MIN(col_2), MAX(col_3) FROM {table_name};"));
+                result_none = execute(&mut ex, &format!("SELECT COUNT(*), SUM(col_1), MAX(col_2) FROM {table_name} \
+                    WHERE col_1 > 10;"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(vec![vec![
+                BongoLiteral::Int(4),
+                BongoLiteral::Int(3),
+                BongoLiteral::Int(10),
+                BongoLiteral::Int(2),
+                BongoLiteral::Varchar("a❤".to_string()),
+                BongoLiteral::Bool(true),
+            ]], result_all);
+            assert_eq!(vec![vec![BongoLiteral::Int(0), BongoLiteral::Null, BongoLiteral::Null]], result_none);
+        }
+
+        #[test]
+        fn group_by_and_having() {
+            let db_root = PathBuf::from("test_temp/group_by_and_having");
+            let table_name = "table_1";
+            let result_group_by;
+            let result_having;
+            let result_explain;
+            let result_ungrouped;
 
-                // table has been removed from cache
-                let tables = ex.tables_read_access().unwrap();
-                assert!(tables.is_empty());
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
 
-                // table has been removed from disk
-                assert!(db_root.read_dir().unwrap().next().is_none())
+                result_group_by = execute(&mut ex, &format!("SELECT col_3, COUNT(*), SUM(col_1) FROM {table_name} \
+                    GROUP BY col_3 ORDER BY col_3;"));
+                let sql = format!("SELECT col_3 FROM {table_name} WHERE col_1 > 1 GROUP BY col_3 HAVING COUNT(col_1) > 1;");
+                result_having = execute(&mut ex, &sql);
+                result_explain = execute(&mut ex, &format!("EXPLAIN {sql}"));
+                result_ungrouped = try_execute(&mut ex, &format!("SELECT col_1, COUNT(*) FROM {table_name} GROUP BY col_3;"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
-                // leaving this scope drops the executor before cleaning up the directory.
-                // This way the executors drop method can work like expected.
-            }
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
 
-            // clean up afterwards (CAREFULLY!)
-            fs::remove_dir_all(db_root).unwrap();
+            assert_eq!(vec![
+                vec![BongoLiteral::Bool(false), BongoLiteral::Int(2), BongoLiteral::Int(6)],
+                vec![BongoLiteral::Bool(true), BongoLiteral::Int(1), BongoLiteral::Int(1)],
+                vec![BongoLiteral::Null, BongoLiteral::Int(1), BongoLiteral::Int(3)],
+            ], result_group_by);
+            assert_eq!(vec![vec![BongoLiteral::Bool(false)]], result_having);
+            assert_eq!(vec![
+                vec![BongoLiteral::Varchar("SELECT on table table_1".to_string())],
+                vec![BongoLiteral::Varchar("Full scan".to_string())],
+                vec![BongoLiteral::Varchar("Filter: col_1 > 1".to_string())],
+                vec![BongoLiteral::Varchar("Aggregate: COUNT(table_1.col_1) grouped by table_1.col_3".to_string())],
+                vec![BongoLiteral::Varchar("Having: COUNT(table_1.col_1) > 1".to_string())],
+                vec![BongoLiteral::Varchar("Sort: none".to_string())],
+                vec![BongoLiteral::Varchar("Rows examined: estimated 4".to_string())],
+            ], result_explain);
+            assert!(result_ungrouped.is_err());
         }
     }
 
-    mod insert {
+    mod limit {
         use std::fs;
-        use std::fs::File;
-        use std::io::Read;
         use std::path::PathBuf;
 
-        use bongo_core::bytes_on_disc::FromDiscBytes;
-        use bongo_core::types::{BongoDataType, Row};
+        use bongo_core::types::BongoLiteral;
 
         use crate::executor::Executor;
-        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows};
+        use crate::executor::tests::{create_example_table, execute, get_example_rows, insert_example_rows};
 
         #[test]
-        fn insert_in_empty_db() {
-            let db_root = PathBuf::from("test_temp/insert_in_empty_db");
-            let mut loc = db_root.clone();
+        fn limit_and_offset() {
+            let db_root = PathBuf::from("test_temp/limit_and_offset");
             let table_name = "table_1";
+            let result_limit;
+            let result_offset;
+            let result_ordered;
+            let result_after_end;
+            let result_zero;
+
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
                 create_example_table(&mut ex, table_name);
                 insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
 
-            // read contents from DB directly to buffer
-            loc.push(table_name);
-            loc.push("data.bongo");
-            let mut file = File::open(&loc).unwrap();
-            let mut buffer = vec![];
-            file.read_to_end(&mut buffer).unwrap();
+                result_limit = execute(&mut ex, &format!("SELECT * FROM {table_name} WHERE col_1 > 1 LIMIT 2;"));
+                result_offset = execute(&mut ex, &format!("SELECT * FROM {table_name} OFFSET 3;"));
+                result_ordered = execute(&mut ex, &format!("SELECT col_1 FROM {table_name} ORDER BY col_2 LIMIT 2 OFFSET 1;"));
+                result_after_end = execute(&mut ex, &format!("SELECT * FROM {table_name} LIMIT 3 OFFSET 4;"));
+                result_zero = execute(&mut ex, &format!("SELECT COUNT(*) FROM {table_name} LIMIT 0;"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
-            let table_def = vec![BongoDataType::Int,
-                                 BongoDataType::Varchar(256),
-                                 BongoDataType::Bool];
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
 
-            // convert bytes back to BongoLiterals
-            let rows = buffer.chunks(BongoDataType::Int.disc_size() +
-                BongoDataType::Varchar(256).disc_size()
-                + BongoDataType::Bool.disc_size())
-                .map(|row| {
-                    Row::from_disc_bytes(row, &table_def).unwrap()
-                })
-                .collect::<Vec<Row>>();
+            // without order the rows are read in the order in which they have been inserted
+            assert_eq!(get_example_rows()[1..3].to_vec(), result_limit);
+            assert_eq!(get_example_rows()[3..].to_vec(), result_offset);
+            assert_eq!(vec![vec![BongoLiteral::Int(3)], vec![BongoLiteral::Int(2)]], result_ordered);
+            assert!(result_after_end.is_empty());
+            assert!(result_zero.is_empty());
+        }
 
-            let expected_rows = get_example_rows();
+        #[test]
+        fn explain_limit() {
+            let db_root = PathBuf::from("test_temp/explain_limit");
+            let table_name = "table_1";
+            let result_scan;
+            let result_sort;
+            let result_index_scan;
 
-            // clean up before assertion
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                insert_example_rows(&mut ex, table_name);
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} USING BTREE (col_1);"));
+
+                result_scan = execute(&mut ex, &format!("EXPLAIN SELECT * FROM {table_name} LIMIT 1 OFFSET 2;"));
+                result_sort = execute(&mut ex, &format!("EXPLAIN SELECT * FROM {table_name} ORDER BY col_2 DESC LIMIT 3;"));
+                result_index_scan = execute(&mut ex, &format!("EXPLAIN SELECT * FROM {table_name} ORDER BY col_1 OFFSET 2;"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(rows, expected_rows);
+            let line = |s: &str| vec![BongoLiteral::Varchar(s.to_string())];
+            assert_eq!(line("Limit: 1 OFFSET 2, reading stops after 3 rows"), result_scan[4]);
+            assert_eq!(line("Limit: 3, the first 3 rows are kept while sorting"), result_sort[4]);
+            assert_eq!(line("Limit: none OFFSET 2"), result_index_scan[4]);
         }
     }
 
-    // NOTE: this also involves create table and insert statement
-    mod select {
+    mod order {
         use std::fs;
         use std::path::PathBuf;
 
-        use bongo_core::bongo_request::BongoRequest;
         use bongo_core::types::{BongoLiteral, Row};
 
         use crate::executor::Executor;
-        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows};
+        use crate::executor::tests::{create_example_table, execute};
+
+        fn col_1(rows: Vec<Row>) -> Vec<i64> {
+            rows.into_iter().map(|row| match row[0] {
+                BongoLiteral::Int(val) => val,
+                _ => panic!("col_1 must be an INT"),
+            }).collect()
+        }
 
         #[test]
-        ///
-        /// - specify a subset of columns
-        /// - nested where condition
-        /// - descending order
-        /// - test on two executors to see if persisting cache works correctly
-        ///
-        fn all_features_together() {
-            let db_root = PathBuf::from("test_temp/all_features_together");
+        fn multiple_columns_and_nulls() {
+            let db_root = PathBuf::from("test_temp/multiple_columns_and_nulls");
             let table_name = "table_1";
+            let result_default;
+            let result_nulls_first;
+            let result_desc_nulls_last;
+            let result_index_nulls_first;
+            let explain_index;
+            let explain_sort;
 
-            let sql = format!("SELECT col_1, col_3 \
-               FROM {table_name} \
-               WHERE col_2 = 'c❤' OR col_3 \
-               ORDER BY col_1 DESC");
-            let request = BongoRequest { sql: sql.to_string() };
-            // should return the first two columns in the opposite order and only col_1 and col_3
-            let expected = get_example_rows().into_iter()
-                .take(2)
-                .rev() // example rows are initially sorted ascending by row 1
-                .map(|row| {
-                    vec![row[0].clone(), row[2].clone()]
-                })
-                .collect::<Vec<Row>>();
-            let result;
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                create_example_table(&mut ex, table_name);
+                execute(&mut ex, &format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES
+                              (1, 'b', true),
+                              (2, NULL, false),
+                              (3, 'a', NULL),
+                              (4, 'b', false),
+                              (5, NULL, true),
+                              (6, 'a', true);"));
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} USING BTREE (col_2);"));
+
+                result_default = col_1(execute(&mut ex, &format!("SELECT * FROM {table_name} ORDER BY col_2, col_3 DESC;")));
+                result_nulls_first = col_1(execute(&mut ex, &format!("SELECT * FROM {table_name} \
+                    ORDER BY col_2 NULLS FIRST, col_1 DESC;")));
+                result_desc_nulls_last = col_1(execute(&mut ex, &format!("SELECT * FROM {table_name} \
+                    ORDER BY col_3 DESC NULLS LAST, col_2 DESC, col_1;")));
+                result_index_nulls_first = col_1(execute(&mut ex, &format!("SELECT * FROM {table_name} \
+                    WHERE col_1 != 5 ORDER BY col_2 ASC NULLS FIRST;")));
+                explain_index = execute(&mut ex, &format!("EXPLAIN SELECT * FROM {table_name} ORDER BY col_2 DESC NULLS FIRST;"));
+                explain_sort = execute(&mut ex, &format!("EXPLAIN SELECT * FROM {table_name} ORDER BY col_2 DESC NULLS LAST, col_1;"));
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            // NULL values are last for ASC and first for DESC by default
+            assert_eq!(vec![3, 6, 1, 4, 5, 2], result_default);
+            assert_eq!(vec![5, 2, 6, 3, 4, 1], result_nulls_first);
+            assert_eq!(vec![5, 1, 6, 2, 4, 3], result_desc_nulls_last);
+            assert_eq!(vec![2, 3, 6, 1, 4], result_index_nulls_first);
+            assert_eq!(vec![BongoLiteral::Varchar("Sort: none, rows are read in descending order of col_2".to_string())],
+                       explain_index[3]);
+            assert_eq!(vec![BongoLiteral::Varchar("Sort: col_2 DESC NULLS LAST, col_1 ASC".to_string())],
+                       explain_sort[3]);
+        }
+    }
+
+    mod expressions {
+        use std::fs;
+        use std::path::PathBuf;
+
+        use bongo_core::types::{BongoLiteral, Row};
+
+        use crate::executor::Executor;
+        use crate::executor::tests::{create_example_table, execute, insert_example_rows, try_execute};
+        use crate::statement::NullLogic;
+
+        #[test]
+        fn arithmetic_in_projection_where_and_set() {
+            let db_root = PathBuf::from("test_temp/arithmetic_in_projection_where_and_set");
+            let table_name = "table_1";
+            let result_computed;
+            let result_where;
+            let result_grouped;
+            let result_after_update;
+            let division_by_zero;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
                 create_example_table(&mut ex, table_name);
                 insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
 
-            {
-                let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&request).unwrap().unwrap();
+                result_computed = execute(&mut ex, &format!("SELECT col_1, col_1 % 2 * -10 AS x, (col_1 + 1) * 2 \
+                    FROM {table_name} ORDER BY x, col_1 DESC;"));
+                result_where = execute(&mut ex, &format!("SELECT col_1 FROM {table_name} WHERE col_1 * 2 - 1 > 4;"));
+                result_grouped = execute(&mut ex, &format!("SELECT col_3, SUM(col_1) * 10 + COUNT(*) AS total \
+                    FROM {table_name} GROUP BY col_3 ORDER BY total;"));
+                execute(&mut ex, &format!("UPDATE {table_name} SET col_1 = col_1 - 10, col_3 = col_1 > 2 WHERE col_1 >= 2;"));
+                result_after_update = execute(&mut ex, &format!("SELECT col_1, col_3 FROM {table_name};"));
+                division_by_zero = try_execute(&mut ex, &format!("SELECT col_1 / (col_1 - 1) FROM {table_name};"));
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(3), BongoLiteral::Int(-10), BongoLiteral::Int(8)],
+                vec![BongoLiteral::Int(1), BongoLiteral::Int(-10), BongoLiteral::Int(4)],
+                vec![BongoLiteral::Int(4), BongoLiteral::Int(0), BongoLiteral::Int(10)],
+                vec![BongoLiteral::Int(2), BongoLiteral::Int(0), BongoLiteral::Int(6)],
+            ], result_computed);
+            assert_eq!(vec![vec![BongoLiteral::Int(3)], vec![BongoLiteral::Int(4)]], result_where);
+            assert_eq!(vec![
+                vec![BongoLiteral::Bool(true), BongoLiteral::Int(11)],
+                vec![BongoLiteral::Null, BongoLiteral::Int(31)],
+                vec![BongoLiteral::Bool(false), BongoLiteral::Int(62)],
+            ], result_grouped);
+            // all values are computed from the row before the update
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(1), BongoLiteral::Bool(true)],
+                vec![BongoLiteral::Int(-8), BongoLiteral::Bool(false)],
+                vec![BongoLiteral::Int(-7), BongoLiteral::Bool(true)],
+                vec![BongoLiteral::Int(-6), BongoLiteral::Bool(true)],
+            ], result_after_update);
+            assert!(division_by_zero.is_err());
         }
 
         #[test]
-        fn where_clause_false() {
-            let db_root = PathBuf::from("test_temp/where_clause_false");
+        fn in_like_and_is_null() {
+            let db_root = PathBuf::from("test_temp/in_like_and_is_null");
             let table_name = "table_1";
-
-            let sql = format!("SELECT col_1, col_3 \
-               FROM {table_name} \
-               WHERE false \
-               ORDER BY col_1 DESC");
-            let request = BongoRequest { sql: sql.to_string() };
-            // should return the first two columns in the opposite order and only col_1 and col_3
-            let expected: Vec<Row> = vec![];
-            let result;
+            let col_1 = |rows: Vec<Row>| rows.into_iter().map(|row| row[0].clone()).collect::<Vec<BongoLiteral>>();
+            let ints = |vals: &[i64]| vals.iter().map(|val| BongoLiteral::Int(*val)).collect::<Vec<BongoLiteral>>();
+            let result_in;
+            let result_not_in;
+            let result_like;
+            let result_is_null;
+            let result_not;
+            let explain_in;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
                 create_example_table(&mut ex, table_name);
                 insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
-
-            {
-                let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&request).unwrap().unwrap();
+                execute(&mut ex, &format!("CREATE INDEX idx_1 ON {table_name} USING BTREE (col_1);"));
+
+                result_in = col_1(execute(&mut ex, &format!("SELECT col_1 FROM {table_name} WHERE col_1 IN (4, 2, 7) ORDER BY col_1;")));
+                result_not_in = col_1(execute(&mut ex, &format!("SELECT col_1 FROM {table_name} WHERE col_1 NOT IN (1, 2);")));
+                result_like = col_1(execute(&mut ex, &format!("SELECT col_1 FROM {table_name} \
+                    WHERE col_2 LIKE '_❤' AND col_2 NOT LIKE '%d%';")));
+                result_is_null = col_1(execute(&mut ex, &format!("SELECT col_1 FROM {table_name} WHERE col_3 IS NULL;")));
+                result_not = col_1(execute(&mut ex, &format!("SELECT col_1 FROM {table_name} \
+                    WHERE NOT (col_3 IS NOT NULL AND col_1 BETWEEN 2 AND 4);")));
+                explain_in = execute(&mut ex, &format!("EXPLAIN SELECT * FROM {table_name} WHERE col_1 IN (4, 2, 7) ORDER BY col_1;"));
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert_eq!(ints(&[2, 4]), result_in);
+            assert_eq!(ints(&[3, 4]), result_not_in);
+            assert_eq!(ints(&[2, 3, 4]), result_like);
+            assert_eq!(ints(&[3]), result_is_null);
+            assert_eq!(ints(&[1, 3]), result_not);
+            assert_eq!(vec![
+                vec![BongoLiteral::Varchar("Index lookup using idx_1 (B-tree) where col_1 IN (2, 4, 7)".to_string())],
+                vec![BongoLiteral::Varchar("Filter: none".to_string())],
+            ], explain_in[1..3].to_vec());
+            assert_eq!(vec![BongoLiteral::Varchar("Sort: none, rows are read in ascending order of col_1".to_string())],
+                       explain_in[3]);
         }
 
         #[test]
-        fn wildcard_no_where() {
-            let db_root = PathBuf::from("test_temp/wildcard_no_where");
+        fn three_valued_and_legacy_null_logic() {
+            let db_root = PathBuf::from("test_temp/three_valued_and_legacy_null_logic");
             let table_name = "table_1";
-
-            let sql = format!("SELECT * \
-               FROM {table_name} \
-               ORDER BY col_1 DESC");
-            let request = BongoRequest { sql: sql.to_string() };
-            // should return all columns in reverse order
-            let expected: Vec<Row> = get_example_rows().into_iter().rev().collect();
-            let result;
+            let col_1 = |rows: Vec<Row>| rows.into_iter().map(|row| row[0].clone()).collect::<Vec<BongoLiteral>>();
+            let ints = |vals: &[i64]| vals.iter().map(|val| BongoLiteral::Int(*val)).collect::<Vec<BongoLiteral>>();
+            let queries = [
+                "WHERE NOT (col_3 = true)",
+                "WHERE col_3 != true",
+                "WHERE col_3 OR col_1 = 3",
+                "WHERE NOT (col_3 AND col_1 > 1)",
+                "WHERE col_1 NOT IN (1, NULL)",
+            ];
+            let mut results = vec![];
+            let mut legacy_results = vec![];
+            let result_indexed;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
                 create_example_table(&mut ex, table_name);
                 insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
+
+                for query in queries {
+                    results.push(col_1(execute(&mut ex, &format!("SELECT col_1 FROM {table_name} {query} ORDER BY col_1;"))));
+                }
+                execute(&mut ex, &format!("CREATE INDEX idx_3 ON {table_name} (col_3);"));
+                result_indexed = col_1(execute(&mut ex, &format!("SELECT col_1 FROM {table_name} WHERE col_3 != true;")));
+
+                ex.set_null_logic(NullLogic::Legacy).unwrap();
+                for query in queries {
+                    legacy_results.push(col_1(execute(&mut ex, &format!("SELECT col_1 FROM {table_name} {query} ORDER BY col_1;"))));
+                }
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            // col_3 of the row with col_1 = 3 is NULL
+            assert_eq!(vec![ints(&[2, 4]), ints(&[2, 4]), ints(&[1, 3]), ints(&[1, 2, 4]), vec![]], results);
+            // the hash index does not return the row whose value is unknown either
+            assert_eq!(ints(&[2, 4]), result_indexed);
+            assert_eq!(vec![ints(&[2, 3, 4]), ints(&[2, 3, 4]), ints(&[1, 3]), ints(&[1, 2, 3, 4]), ints(&[2, 3, 4])],
+                       legacy_results);
+        }
+    }
+
+    mod alter_table {
+        use std::fs;
+        use std::path::PathBuf;
+        use std::str::FromStr;
+
+        use bongo_core::decimal::Decimal;
+        use bongo_core::types::{BongoError, BongoLiteral};
+
+        use crate::executor::Executor;
+        use crate::executor::tests::{execute, int, try_execute, varchar};
+
+        #[test]
+        fn add_drop_and_rename_columns() {
+            let db_root = PathBuf::from("test_temp/alter_table_add_drop_and_rename_columns");
+            let table_dir = db_root.join("people");
+            let result_added;
+            let explain_renamed;
+            let result_renamed;
+            let index_names;
+            let btree_exists;
+            let data_size;
+            let result_existing;
+            let result_missing;
+            let result_missing_if_exists;
+            let result_in_transaction;
+            let result_reloaded;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE people (id INT, name VARCHAR(20), age INT);");
+                execute(&mut ex, "INSERT INTO people (id, name, age) VALUES (1, 'a', 30), (2, 'b', 40), (3, 'c', 50);");
+                execute(&mut ex, "DELETE FROM people WHERE id = 3;");
+                execute(&mut ex, "CREATE INDEX idx_age ON people USING BTREE (age);");
+                execute(&mut ex, "CREATE INDEX idx_name ON people (name);");
+
+                try_execute(&mut ex, "ALTER TABLE people ADD COLUMN city TEXT DEFAULT 'Ber' || 'lin';").unwrap_err();
+                execute(&mut ex, "ALTER TABLE people ADD COLUMN city TEXT DEFAULT 'Berlin';");
+                result_added = execute(&mut ex, "SELECT * FROM people WHERE age >= 30 ORDER BY id;");
+
+                execute(&mut ex, "ALTER TABLE people RENAME COLUMN name TO full_name;");
+                explain_renamed = execute(&mut ex, "EXPLAIN SELECT id FROM people WHERE full_name = 'b';");
+                result_renamed = execute(&mut ex, "SELECT id FROM people WHERE full_name = 'b';");
+
+                execute(&mut ex, "ALTER TABLE people DROP COLUMN age;");
+                index_names = ex.tables_read_access().unwrap()["people"].read().unwrap().indexes.iter()
+                    .map(|index| index.def.name.clone())
+                    .collect::<Vec<String>>();
+                btree_exists = table_dir.join("idx_age.btree").exists();
+                data_size = fs::metadata(table_dir.join("data.bongo")).unwrap().len();
+
+                result_existing = try_execute(&mut ex, "ALTER TABLE people ADD COLUMN id INT;");
+                result_missing = try_execute(&mut ex, "ALTER TABLE people DROP COLUMN age;");
+                result_missing_if_exists = try_execute(&mut ex, "ALTER TABLE people DROP COLUMN IF EXISTS age;");
+                execute(&mut ex, "BEGIN;");
+                result_in_transaction = try_execute(&mut ex, "ALTER TABLE people DROP COLUMN city;");
+                execute(&mut ex, "ROLLBACK;");
+            }
 
             {
                 let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&request).unwrap().unwrap();
+                execute(&mut ex, "INSERT INTO people (id, full_name, city) VALUES (4, 'd', 'Rome');");
+                result_reloaded = execute(&mut ex, "SELECT * FROM people ORDER BY id;");
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert_eq!(vec![
+                vec![int(1), varchar("a"), int(30), varchar("Berlin")],
+                vec![int(2), varchar("b"), int(40), varchar("Berlin")],
+            ], result_added);
+            assert_eq!(varchar("Index lookup using idx_name (hash) where full_name = 'b'"), explain_renamed[1][0]);
+            assert_eq!(vec![vec![int(2)]], result_renamed);
+
+            // the index on the dropped column is dropped as well
+            assert_eq!(vec!["idx_name".to_string()], index_names);
+            assert!(!btree_exists);
+            // the deleted row has been removed while rewriting the table
+            assert_eq!(2 * (17 + 5 + 22 + 17), data_size);
+
+            assert!(matches!(result_existing, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_missing, Err(BongoError::SqlRuntimeError(_))));
+            assert_eq!(Ok(vec![]), result_missing_if_exists);
+            assert!(matches!(result_in_transaction, Err(BongoError::UnsupportedFeatureError(_))));
+
+            assert_eq!(vec![
+                vec![int(1), varchar("a"), varchar("Berlin")],
+                vec![int(2), varchar("b"), varchar("Berlin")],
+                vec![int(4), varchar("d"), varchar("Rome")],
+            ], result_reloaded);
         }
 
         #[test]
-        fn order_by_non_selected_col() {
-            let db_root = PathBuf::from("test_temp/order_by_non_selected_col");
-            let table_name = "table_1";
+        fn alter_column_type() {
+            let db_root = PathBuf::from("test_temp/alter_table_alter_column_type");
+            let table_dir = db_root.join("items");
+            let result_decimal;
+            let result_too_long;
+            let result_unchanged;
+            let leftovers;
+            let heap_exists_text;
+            let heap_exists_varchar;
+            let result_varchar;
+            let result_btree;
 
-            let sql = format!("SELECT col_2, col_3 \
-               FROM {table_name} \
-               ORDER BY col_1 DESC");
-            let request = BongoRequest { sql: sql.to_string() };
-            // should return columns 2 and 3 in reverse order
-            let expected: Vec<Row> = get_example_rows().into_iter()
-                .rev() // example rows are initially sorted ascending
-                .map(|row| {
-                    vec![row[1].clone(), row[2].clone()]
-                })
-                .collect::<Vec<Row>>();
-            let result;
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE items (id INT, price INT, label VARCHAR(10));");
+                execute(&mut ex, "INSERT INTO items (id, price, label) VALUES (1, 5, 'abc'), (2, 7, 'abcdef');");
+
+                execute(&mut ex, "ALTER TABLE items ALTER COLUMN price TYPE DECIMAL(6, 2);");
+                result_decimal = execute(&mut ex, "SELECT price FROM items WHERE price > 6.5;");
+
+                // the conversion fails for the second row
+                result_too_long = try_execute(&mut ex, "ALTER TABLE items ALTER COLUMN label TYPE VARCHAR(3);");
+                result_unchanged = execute(&mut ex, "SELECT * FROM items ORDER BY id;");
+                leftovers = fs::read_dir(&table_dir).unwrap()
+                    .filter(|entry| entry.as_ref().unwrap().file_name().to_str().unwrap().ends_with(".compact"))
+                    .count();
+
+                execute(&mut ex, "ALTER TABLE items ALTER label TYPE TEXT;");
+                heap_exists_text = table_dir.join("heap.bongo").exists();
+                execute(&mut ex, "ALTER TABLE items ALTER COLUMN label SET DATA TYPE VARCHAR(20);");
+                heap_exists_varchar = table_dir.join("heap.bongo").exists();
+                result_varchar = execute(&mut ex, "SELECT label FROM items ORDER BY id;");
+
+                execute(&mut ex, "CREATE INDEX idx_id ON items USING BTREE (id);");
+                result_btree = try_execute(&mut ex, "ALTER TABLE items ALTER COLUMN id TYPE TEXT;");
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(vec![vec![BongoLiteral::Decimal(Decimal::from_str("7.00").unwrap())]], result_decimal);
+            assert!(matches!(result_too_long, Err(BongoError::SqlRuntimeError(_))));
+            assert_eq!(vec![
+                vec![int(1), BongoLiteral::Decimal(Decimal::from_str("5.00").unwrap()), varchar("abc")],
+                vec![int(2), BongoLiteral::Decimal(Decimal::from_str("7.00").unwrap()), varchar("abcdef")],
+            ], result_unchanged);
+            assert_eq!(0, leftovers);
+            assert!(heap_exists_text);
+            assert!(!heap_exists_varchar);
+            assert_eq!(vec![vec![varchar("abc")], vec![varchar("abcdef")]], result_varchar);
+            assert!(matches!(result_btree, Err(BongoError::SqlRuntimeError(_))));
+        }
+    }
+
+    mod constraints {
+        use std::fs;
+        use std::path::PathBuf;
+
+        use bongo_core::types::{BongoError, BongoLiteral};
+
+        use crate::executor::Executor;
+        use crate::executor::tests::{decimal, execute, try_execute};
+
+        fn violates<T>(result: Result<T, BongoError>) -> bool {
+            matches!(result, Err(BongoError::ConstraintViolationError(_)))
+        }
+
+        #[test]
+        fn not_null_default_and_check() {
+            let db_root = PathBuf::from("test_temp/constraints_not_null_default_and_check");
+            let result_null;
+            let result_negative;
+            let result_unknown;
+            let result_partly_valid;
+            let result_update;
+            let result_rows;
+            let result_bad_default;
+            let result_unknown_col;
+            let default;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
-                create_example_table(&mut ex, table_name);
-                insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
+                execute(&mut ex, "CREATE TABLE accounts (\
+                    id INT NOT NULL, \
+                    balance DECIMAL(8, 2) DEFAULT 0 CHECK (balance >= 0), \
+                    CHECK (id > 0));");
+                default = ex.tables_read_access().unwrap()["accounts"].read().unwrap().cols[1].default.clone();
+
+                execute(&mut ex, "INSERT INTO accounts (id, balance) VALUES (1, 10);");
+                result_null = try_execute(&mut ex, "INSERT INTO accounts (id, balance) VALUES (NULL, 10);");
+                result_negative = try_execute(&mut ex, "INSERT INTO accounts (id, balance) VALUES (2, -10);");
+                // the condition of the CHECK constraint is unknown for NULL
+                result_unknown = try_execute(&mut ex, "INSERT INTO accounts (id, balance) VALUES (3, NULL);");
+                result_partly_valid = try_execute(&mut ex, "INSERT INTO accounts (id, balance) VALUES (4, 5), (0, 5);");
+                result_update = try_execute(&mut ex, "UPDATE accounts SET balance = balance - 20 WHERE id = 1;");
+                result_rows = execute(&mut ex, "SELECT * FROM accounts ORDER BY id;");
+
+                result_bad_default = try_execute(&mut ex, "CREATE TABLE other (col_1 INT DEFAULT 'abc');");
+                result_unknown_col = try_execute(&mut ex, "CREATE TABLE other (col_1 INT CHECK (col_2 > 0));");
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            // the default is converted to the type of the column
+            assert_eq!(Some(decimal("0.00")), default);
+            assert!(violates(result_null));
+            assert!(violates(result_negative));
+            assert_eq!(Ok(vec![]), result_unknown);
+            assert!(violates(result_partly_valid));
+            assert!(violates(result_update));
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(1), decimal("10.00")],
+                vec![BongoLiteral::Int(3), BongoLiteral::Null],
+            ], result_rows);
+            assert!(matches!(result_bad_default, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_unknown_col, Err(BongoError::SqlRuntimeError(_))));
+        }
+
+        #[test]
+        fn alter_table_keeps_constraints() {
+            let db_root = PathBuf::from("test_temp/constraints_alter_table_keeps_constraints");
+            let result_not_null;
+            let result_added;
+            let result_too_large;
+            let result_renamed;
+            let checks_after_drop;
+            let result_dropped;
+            let default_reloaded;
+
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE items (id INT, price INT CHECK (price > 0));");
+                execute(&mut ex, "INSERT INTO items (id, price) VALUES (1, 5), (2, 7);");
+
+                // the existing rows would be NULL in the new column
+                result_not_null = try_execute(&mut ex, "ALTER TABLE items ADD COLUMN stock INT NOT NULL;");
+                execute(&mut ex, "ALTER TABLE items ADD COLUMN stock INT NOT NULL DEFAULT 3 CHECK (stock < 10);");
+                result_added = execute(&mut ex, "SELECT * FROM items ORDER BY id;");
+                result_too_large = try_execute(&mut ex, "UPDATE items SET stock = 20;");
+
+                execute(&mut ex, "ALTER TABLE items RENAME COLUMN price TO cost;");
+                result_renamed = try_execute(&mut ex, "UPDATE items SET cost = 0;");
+
+                execute(&mut ex, "ALTER TABLE items DROP COLUMN cost;");
+                checks_after_drop = ex.tables_read_access().unwrap()["items"].read().unwrap().checks.len();
+            }
 
             {
                 let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&request).unwrap().unwrap();
+                result_dropped = try_execute(&mut ex, "INSERT INTO items (id, stock) VALUES (3, 12);");
+                default_reloaded = ex.tables_read_access().unwrap()["items"].read().unwrap().cols[1].default.clone();
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert!(violates(result_not_null));
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(1), BongoLiteral::Int(5), BongoLiteral::Int(3)],
+                vec![BongoLiteral::Int(2), BongoLiteral::Int(7), BongoLiteral::Int(3)],
+            ], result_added);
+            assert!(violates(result_too_large));
+            assert!(violates(result_renamed));
+            // the CHECK constraint of the dropped column is dropped with it
+            assert_eq!(1, checks_after_drop);
+            assert!(violates(result_dropped));
+            assert_eq!(Some(BongoLiteral::Int(3)), default_reloaded);
         }
 
-        #[test]
-        fn order_asc() {
-            let db_root = PathBuf::from("test_temp/order_asc");
-            let table_name = "table_1";
+        fn not_unique<T>(result: Result<T, BongoError>, constraint: &str) -> bool {
+            matches!(result, Err(BongoError::UniqueViolationError(msg)) if msg.contains(&format!("'{}'", constraint)))
+        }
 
-            let sql = format!("SELECT * \
-               FROM {table_name} \
-               ORDER BY col_2 ASC"); // col_2 is ordered desc on insert
-            let request = BongoRequest { sql: sql.to_string() };
-            // should return all columns in reverse order
-            let expected: Vec<Row> = get_example_rows().into_iter().rev().collect();
-            let result;
+        #[test]
+        fn primary_key_and_unique() {
+            let db_root = PathBuf::from("test_temp/constraints_primary_key_and_unique");
+            let index_names;
+            let result_id;
+            let result_email;
+            let result_nulls;
+            let result_pair;
+            let result_null_id;
+            let result_batch;
+            let result_shift;
+            let result_update;
+            let result_reinsert;
+            let result_drop_index;
+            let result_rows;
+            let result_reloaded;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
-                create_example_table(&mut ex, table_name);
-                insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
+                execute(&mut ex, "CREATE TABLE users (\
+                    id INT PRIMARY KEY, \
+                    email VARCHAR(20) UNIQUE, \
+                    team INT, \
+                    nr INT, \
+                    UNIQUE (team, nr));");
+                index_names = ex.tables_read_access().unwrap()["users"].read().unwrap().indexes.iter()
+                    .map(|index| index.def.name.clone())
+                    .collect::<Vec<String>>();
+
+                execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (1, 'a', 1, 1), (2, 'b', 1, 2);");
+                result_id = try_execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (1, 'c', 2, 1);");
+                result_email = try_execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (3, 'a', 2, 1);");
+                // rows with NULL in a column of a constraint are not compared
+                result_nulls = try_execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (3, NULL, 1, NULL), (4, NULL, 1, NULL);");
+                result_pair = try_execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (5, 'e', 1, 2);");
+                result_null_id = try_execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (NULL, 'e', 2, 1);");
+                result_batch = try_execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (5, 'e', 2, 1), (5, 'f', 2, 2);");
+
+                // the constraint is checked for the result of the whole statement
+                result_shift = try_execute(&mut ex, "UPDATE users SET id = id + 1;");
+                result_update = try_execute(&mut ex, "UPDATE users SET email = 'a' WHERE id = 3;");
+
+                execute(&mut ex, "BEGIN;");
+                execute(&mut ex, "DELETE FROM users WHERE id = 2;");
+                result_reinsert = try_execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (2, 'a', 1, 1);");
+                execute(&mut ex, "ROLLBACK;");
+
+                result_drop_index = try_execute(&mut ex, "DROP INDEX users_pkey;");
+                result_rows = execute(&mut ex, "SELECT id, email FROM users ORDER BY id;");
+            }
 
             {
                 let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&request).unwrap().unwrap();
+                result_reloaded = try_execute(&mut ex, "INSERT INTO users (id, email, team, nr) VALUES (5, 'b', 3, 1);");
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert_eq!(vec!["users_pkey", "users_email_key", "users_team_nr_key"], index_names);
+            assert!(not_unique(result_id, "users_pkey"));
+            assert!(not_unique(result_email, "users_email_key"));
+            assert_eq!(Ok(vec![]), result_nulls);
+            assert!(not_unique(result_pair, "users_team_nr_key"));
+            // the columns of a primary key are NOT NULL
+            assert!(violates(result_null_id));
+            assert!(not_unique(result_batch, "users_pkey"));
+            assert_eq!(Ok(vec![]), result_shift);
+            assert!(not_unique(result_update, "users_email_key"));
+            assert_eq!(Ok(vec![]), result_reinsert);
+            assert!(matches!(result_drop_index, Err(BongoError::SqlRuntimeError(_))));
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(2), BongoLiteral::Varchar("a".to_string())],
+                vec![BongoLiteral::Int(3), BongoLiteral::Varchar("b".to_string())],
+                vec![BongoLiteral::Int(4), BongoLiteral::Null],
+                vec![BongoLiteral::Int(5), BongoLiteral::Null],
+            ], result_rows);
+            assert!(not_unique(result_reloaded, "users_pkey"));
         }
 
-        ///
-        /// Tests how the system responds if the where condition is indexable
-        ///
         #[test]
-        fn eq_indexable_where() {
-            let db_root = PathBuf::from("test_temp/eq_indexable_where");
-            let table_name = "table_1";
-
-            let sql = format!("SELECT * \
-               FROM {table_name} \
-               WHERE col_1 = 3");
-            let request = BongoRequest { sql: sql.to_string() };
-            // should return only 3rd row
-            let expected: Vec<Row> = vec![get_example_rows().remove(2)];
-            let result;
+        fn alter_table_keeps_unique_constraints() {
+            let db_root = PathBuf::from("test_temp/constraints_alter_table_keeps_unique_constraints");
+            let result_nullable;
+            let result_default;
+            let result_type;
+            let result_renamed;
+            let index_names;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
-                create_example_table(&mut ex, table_name);
-                insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
+                execute(&mut ex, "CREATE TABLE items (id INT, price DECIMAL(5, 1) UNIQUE, team INT, UNIQUE (team, id));");
+                execute(&mut ex, "INSERT INTO items (id, price, team) VALUES (1, 1.1, 1), (2, 1.2, 1);");
 
-            {
-                let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&request).unwrap().unwrap();
+                result_nullable = try_execute(&mut ex, "ALTER TABLE items ADD COLUMN code INT UNIQUE;");
+                result_default = try_execute(&mut ex, "ALTER TABLE items ADD COLUMN other INT CONSTRAINT other_key UNIQUE DEFAULT 1;");
+                // both prices are rounded to 1
+                result_type = try_execute(&mut ex, "ALTER TABLE items ALTER COLUMN price TYPE INT;");
+
+                execute(&mut ex, "ALTER TABLE items RENAME COLUMN price TO cost;");
+                result_renamed = try_execute(&mut ex, "INSERT INTO items (id, cost, team, code) VALUES (3, 1.1, 2, NULL);");
+
+                execute(&mut ex, "ALTER TABLE items DROP COLUMN team;");
+                index_names = ex.tables_read_access().unwrap()["items"].read().unwrap().indexes.iter()
+                    .map(|index| index.def.name.clone())
+                    .collect::<Vec<String>>();
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert_eq!(Ok(vec![]), result_nullable);
+            assert!(not_unique(result_default, "other_key"));
+            assert!(not_unique(result_type, "items_price_key"));
+            assert!(not_unique(result_renamed, "items_price_key"));
+            // the constraint on both columns is dropped with one of them
+            assert_eq!(vec!["items_price_key", "items_code_key"], index_names);
         }
 
-        #[test]
-        fn comparison_with_null() {
-            let db_root = PathBuf::from("test_temp/comparison_with_null");
-            let table_name = "table_1";
+        fn no_reference<T>(result: Result<T, BongoError>, constraint: &str) -> bool {
+            matches!(result, Err(BongoError::ForeignKeyViolationError(msg)) if msg.contains(&format!("'{}'", constraint)))
+        }
 
-            let sql = format!("SELECT * \
-                                     FROM {table_name} \
-                                     WHERE col_3 = Null");
-            let request = BongoRequest { sql: sql.to_string() };
-            // should return only 3rd row
-            let expected: Vec<Row> = get_example_rows().into_iter()
-                .filter(|r| {
-                    match r[2] {
-                        BongoLiteral::Null => true,
-                        _ => false
-                    }
-                }).collect();
-            let result;
+        #[test]
+        fn foreign_keys() {
+            let db_root = PathBuf::from("test_temp/constraints_foreign_keys");
+            let result_unknown_table;
+            let result_not_unique;
+            let result_other_type;
+            let result_missing;
+            let result_null;
+            let result_mentors;
+            let result_update_child;
+            let result_update_name;
+            let result_update_key;
+            let result_restricted;
+            let result_drop_parent;
+            let result_cascaded;
+            let result_rolled_back;
+            let result_reloaded;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
-                create_example_table(&mut ex, table_name);
-                insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
+                execute(&mut ex, "CREATE TABLE teams (id INT PRIMARY KEY, name VARCHAR(10) UNIQUE);");
+                result_unknown_table = try_execute(&mut ex, "CREATE TABLE other (team INT REFERENCES unknown);");
+                result_other_type = try_execute(&mut ex, "CREATE TABLE other (team BIGINT REFERENCES teams);");
+
+                execute(&mut ex, "CREATE TABLE players (\
+                    id INT PRIMARY KEY, \
+                    team INT REFERENCES teams ON DELETE CASCADE, \
+                    mentor INT REFERENCES players ON DELETE SET NULL);");
+                execute(&mut ex, "CREATE TABLE coaches (id INT, team VARCHAR(10), FOREIGN KEY (team) REFERENCES teams (name) ON DELETE SET NULL);");
+                execute(&mut ex, "CREATE TABLE contracts (player INT CONSTRAINT contracts_player REFERENCES players);");
+                result_not_unique = try_execute(&mut ex, "CREATE TABLE other (coach INT REFERENCES coaches (id));");
+
+                execute(&mut ex, "INSERT INTO teams (id, name) VALUES (1, 'a'), (2, 'b');");
+                execute(&mut ex, "INSERT INTO coaches (id, team) VALUES (1, 'a'), (2, 'b');");
+                result_missing = try_execute(&mut ex, "INSERT INTO players (id, team, mentor) VALUES (1, 3, NULL);");
+                // rows with NULL in a column of the foreign key do not reference any row
+                result_null = try_execute(&mut ex, "INSERT INTO players (id, team, mentor) VALUES (4, NULL, NULL);");
+                execute(&mut ex, "DELETE FROM players WHERE id = 4;");
+                // the rows of the statement can reference each other
+                result_mentors = try_execute(&mut ex, "INSERT INTO players (id, team, mentor) VALUES (1, 1, NULL), (2, 1, 3), (3, 2, 1);");
+                execute(&mut ex, "INSERT INTO contracts (player) VALUES (3);");
+
+                result_update_child = try_execute(&mut ex, "UPDATE players SET team = 3 WHERE id = 1;");
+                result_update_name = try_execute(&mut ex, "UPDATE teams SET name = 'c' WHERE id = 1;");
+                result_update_key = try_execute(&mut ex, "UPDATE teams SET id = 3 WHERE id = 1;");
+
+                // the player 3 of the team 2 is still referenced by a contract
+                result_restricted = try_execute(&mut ex, "DELETE FROM teams WHERE id = 2;");
+                result_drop_parent = try_execute(&mut ex, "DROP TABLE teams;");
+
+                execute(&mut ex, "BEGIN;");
+                execute(&mut ex, "DELETE FROM teams WHERE id = 1;");
+                result_rolled_back = execute(&mut ex, "SELECT * FROM players ORDER BY id;");
+                execute(&mut ex, "ROLLBACK;");
+
+                execute(&mut ex, "DELETE FROM contracts;");
+                execute(&mut ex, "DELETE FROM teams WHERE id = 2;");
+                result_cascaded = (
+                    execute(&mut ex, "SELECT * FROM players ORDER BY id;"),
+                    execute(&mut ex, "SELECT * FROM coaches ORDER BY id;"),
+                );
+            }
 
             {
                 let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&request).unwrap().unwrap();
+                result_reloaded = try_execute(&mut ex, "INSERT INTO contracts (player) VALUES (3);");
+                execute(&mut ex, "DROP TABLE contracts, players, coaches, teams;");
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert!(matches!(result_unknown_table, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_not_unique, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_other_type, Err(BongoError::SqlRuntimeError(_))));
+            assert!(no_reference(result_missing, "players_team_fkey"));
+            assert_eq!(Ok(vec![]), result_null);
+            assert_eq!(Ok(vec![]), result_mentors);
+            assert!(no_reference(result_update_child, "players_team_fkey"));
+            assert!(no_reference(result_update_name, "coaches_team_fkey"));
+            assert!(no_reference(result_update_key, "players_team_fkey"));
+            assert!(no_reference(result_restricted, "contracts_player"));
+            assert!(matches!(result_drop_parent, Err(BongoError::SqlRuntimeError(_))));
+            // the players of the team 1 are deleted and the mentor of the player 3 is set to NULL
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(3), BongoLiteral::Int(2), BongoLiteral::Null],
+            ], result_rolled_back);
+            assert_eq!((
+                vec![
+                    vec![BongoLiteral::Int(1), BongoLiteral::Int(1), BongoLiteral::Null],
+                    vec![BongoLiteral::Int(2), BongoLiteral::Int(1), BongoLiteral::Null],
+                ],
+                vec![
+                    vec![BongoLiteral::Int(1), BongoLiteral::Varchar("a".to_string())],
+                    vec![BongoLiteral::Int(2), BongoLiteral::Null],
+                ],
+            ), result_cascaded);
+            assert!(no_reference(result_reloaded, "contracts_player"));
         }
-    }
-
-    mod delete {
-        use std::{fs, iter};
-        use std::path::PathBuf;
-
-        use bongo_core::bongo_request::BongoRequest;
-        use bongo_core::types::{BongoLiteral, Row};
-
-        use crate::Executor;
-        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows};
 
         #[test]
-        fn simple_delete() {
-            let db_root = PathBuf::from("test_temp/simple_delete");
-            let table_name = "table_1";
-
-            create_table_and_delete_nth_row(table_name, &db_root, 3);
-
-            let select = format!("SELECT * \
-                               FROM {table_name} \
-                               ORDER BY col_1 ASC;");
-
-            let request = BongoRequest { sql: select.to_string() };
-
-            // shall return all but the 3rd row
-            let expected: Vec<Row> = get_example_rows().into_iter()
-                .enumerate()
-                .filter(|&(i, _)| i != 2)
-                .map(|(_, r)| r).collect();
-            let result;
+        fn alter_table_keeps_foreign_keys() {
+            let db_root = PathBuf::from("test_temp/constraints_alter_table_keeps_foreign_keys");
+            let result_drop_referenced;
+            let result_rename_referenced;
+            let result_type;
+            let result_missing_default;
+            let result_added;
+            let result_renamed;
+            let foreign_keys_after_drop;
 
             {
-                let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&request).unwrap().unwrap();
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE teams (id INT PRIMARY KEY, code INT UNIQUE);");
+                execute(&mut ex, "CREATE TABLE players (id INT, team INT REFERENCES teams);");
+                execute(&mut ex, "INSERT INTO teams (id, code) VALUES (1, 10);");
+                execute(&mut ex, "INSERT INTO players (id, team) VALUES (1, 1);");
+
+                result_drop_referenced = try_execute(&mut ex, "ALTER TABLE teams DROP COLUMN id;");
+                result_rename_referenced = try_execute(&mut ex, "ALTER TABLE teams RENAME COLUMN id TO nr;");
+                result_type = try_execute(&mut ex, "ALTER TABLE players ALTER COLUMN team TYPE BIGINT;");
+
+                // the existing row would reference the team with the code 20
+                result_missing_default = try_execute(&mut ex, "ALTER TABLE players ADD COLUMN code INT DEFAULT 20 REFERENCES teams (code);");
+                execute(&mut ex, "ALTER TABLE players ADD COLUMN code INT DEFAULT 10 REFERENCES teams (code);");
+                result_added = try_execute(&mut ex, "INSERT INTO players (id, team, code) VALUES (2, 1, 20);");
+
+                execute(&mut ex, "ALTER TABLE players RENAME COLUMN team TO team_id;");
+                result_renamed = try_execute(&mut ex, "INSERT INTO players (id, team_id, code) VALUES (2, 2, 10);");
+
+                execute(&mut ex, "ALTER TABLE players DROP COLUMN team_id;");
+                foreign_keys_after_drop = ex.tables_read_access().unwrap()["players"].read().unwrap().foreign_keys.iter()
+                    .map(|fk| fk.name.clone())
+                    .collect::<Vec<String>>();
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert!(matches!(result_drop_referenced, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_rename_referenced, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_type, Err(BongoError::SqlRuntimeError(_))));
+            assert!(no_reference(result_missing_default, "players_code_fkey"));
+            assert!(no_reference(result_added, "players_code_fkey"));
+            assert!(no_reference(result_renamed, "players_team_fkey"));
+            // the foreign key of the dropped column is dropped with it
+            assert_eq!(vec!["players_code_fkey"], foreign_keys_after_drop);
         }
+    }
 
-        #[test]
-        fn delete_and_insert_in_ghosts() {
-            let db_root = PathBuf::from("test_temp/delete_and_insert_in_ghosts");
-            let table_name = "table_1";
+    mod sequences {
+        use std::fs;
+        use std::path::PathBuf;
 
-            create_table_and_delete_nth_row(table_name, &db_root, 3);
+        use bongo_core::types::{BongoError, BongoLiteral, Row};
 
-            let insert = format!("INSERT INTO {table_name} (col_1, col_2, col_3) VALUES \
-                          (42, 'x', true);");
-            let insert_req = BongoRequest { sql: insert.to_string() };
+        use crate::executor::Executor;
+        use crate::executor::tests::{execute, try_execute};
 
-            {
-                let mut ex = Executor::new(&db_root, false, false).unwrap();
-                { // new scope to drop the ReadGuard and avoid deadlock
-                    // assert we have one ghost after deleting one row
-                    assert_eq!(ex.tables.read().unwrap().get("table_1").unwrap().borrow().read().unwrap().ghosts.len(), 1)
-                }
-                ex.execute(&insert_req).unwrap();
-                {
-                    // asser the one ghost is now replaced with a value
-                    assert!(ex.tables.read().unwrap().get("table_1").unwrap().borrow().read().unwrap().ghosts.is_empty())
-                }
-            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+        fn ids(rows: Vec<Row>) -> Vec<i64> {
+            rows.into_iter()
+                .map(|row| match row[0] {
+                    BongoLiteral::Int(id) => id,
+                    _ => panic!("unexpected row {:?}", row),
+                })
+                .collect()
+        }
 
-            let select = format!("SELECT * \
-                               FROM {table_name} \
-                               ORDER BY col_1 ASC;");
+        #[test]
+        fn serial_columns() {
+            let db_root = PathBuf::from("test_temp/sequences_serial_columns");
+            let result_null;
+            let result_unknown;
+            let result_ids;
+            let result_restarted;
+            let result_rebuilt;
+            let result_added;
 
-            let select_req = BongoRequest { sql: select.to_string() };
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE users (id SERIAL PRIMARY KEY, name VARCHAR(10));");
+                execute(&mut ex, "INSERT INTO users (name) VALUES ('a'), ('b');");
+                // explicit values advance the counter
+                execute(&mut ex, "INSERT INTO users (id, name) VALUES (10, 'c');");
+                execute(&mut ex, "INSERT INTO users (name) VALUES ('d');");
+                result_null = try_execute(&mut ex, "INSERT INTO users (id, name) VALUES (NULL, 'e');");
+                result_unknown = try_execute(&mut ex, "INSERT INTO users (name, nr) VALUES ('e', 20);");
+
+                // values are not given out again, even if their rows are gone
+                execute(&mut ex, "BEGIN;");
+                execute(&mut ex, "INSERT INTO users (name) VALUES ('e');");
+                execute(&mut ex, "ROLLBACK;");
+                execute(&mut ex, "DELETE FROM users WHERE id = 11;");
+                execute(&mut ex, "INSERT INTO users (name) VALUES ('f');");
+                result_ids = ids(execute(&mut ex, "SELECT id FROM users ORDER BY id;"));
+            }
 
-            // 3rd row shall be removed and instead the inserted row shall be contained
-            let expected: Vec<Row> = get_example_rows().into_iter()
-                .enumerate()
-                .filter(|&(i, _)| i != 2)
-                .map(|(_, r)| r)
-                .chain(iter::once(vec![BongoLiteral::Int(42), BongoLiteral::Varchar("x".to_string()), BongoLiteral::Bool(true)]))
-                .collect();
-            let result;
+            {
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                execute(&mut ex, "INSERT INTO users (name) VALUES ('g');");
+                result_restarted = ids(execute(&mut ex, "SELECT id FROM users WHERE name = 'g';"));
+            }
 
+            // the counter is rebuilt from all row versions on disc
+            fs::remove_file(db_root.join("users").join("meta.bongo")).unwrap();
             {
                 let mut ex = Executor::new(&db_root, false, false).unwrap();
-                result = ex.execute(&select_req).unwrap().unwrap();
+                execute(&mut ex, "INSERT INTO users (name) VALUES ('h');");
+                result_rebuilt = ids(execute(&mut ex, "SELECT id FROM users WHERE name = 'h';"));
+
+                // the current rows are numbered when a SERIAL column is added
+                execute(&mut ex, "ALTER TABLE users ADD COLUMN nr BIGSERIAL;");
+                execute(&mut ex, "INSERT INTO users (name) VALUES ('i');");
+                result_added = execute(&mut ex, "SELECT id, nr FROM users WHERE name = 'i';");
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert!(matches!(result_null, Err(BongoError::ConstraintViolationError(_))));
+            assert!(matches!(result_unknown, Err(BongoError::SqlRuntimeError(_))));
+            assert_eq!(vec![1, 2, 10, 13], result_ids);
+            assert_eq!(vec![14], result_restarted);
+            assert_eq!(vec![15], result_rebuilt);
+            assert_eq!(vec![vec![BongoLiteral::Int(16), BongoLiteral::Int(7)]], result_added);
         }
 
-        fn create_table_and_delete_nth_row(table_name: &str, db_root: &PathBuf, n: i32) {
-            let db_root = PathBuf::from(db_root);
-
-            let delete = format!("DELETE FROM {table_name} \
-                               WHERE col_1 = {n};");
-
-            let request = BongoRequest { sql: delete };
+        #[test]
+        fn create_and_drop_sequence() {
+            let db_root = PathBuf::from("test_temp/sequences_create_and_drop_sequence");
+            let result_too_long;
+            let result_exists;
+            let result_in_transaction;
+            let result_ids;
+            let result_dropped;
+            let result_drop_missing;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
-                create_example_table(&mut ex, table_name);
-                insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
+                execute(&mut ex, "CREATE SEQUENCE item_ids START WITH 100 INCREMENT BY 10;");
+                execute(&mut ex, "CREATE TABLE items (id INT, name VARCHAR(5));");
+                execute(&mut ex, "INSERT INTO items (id, name) VALUES (nextval('item_ids'), 'a'), (nextval('item_ids'), 'b');");
+                // the value is used up although the statement fails
+                result_too_long = try_execute(&mut ex, "INSERT INTO items (id, name) VALUES (nextval('item_ids'), 'too long');");
+                execute(&mut ex, "INSERT INTO items (id, name) VALUES (nextval('item_ids'), 'c');");
+                result_exists = try_execute(&mut ex, "CREATE SEQUENCE item_ids;");
+
+                execute(&mut ex, "BEGIN;");
+                result_in_transaction = try_execute(&mut ex, "CREATE SEQUENCE other;");
+                execute(&mut ex, "ROLLBACK;");
+            }
 
             {
                 let mut ex = Executor::new(&db_root, false, false).unwrap();
-                ex.execute(&request).unwrap();
-            } // leaving scope triggers drop and flush on the executor
+                execute(&mut ex, "INSERT INTO items (id, name) VALUES (nextval('item_ids'), 'd');");
+                result_ids = ids(execute(&mut ex, "SELECT id FROM items ORDER BY id;"));
+
+                execute(&mut ex, "DROP SEQUENCE item_ids;");
+                result_dropped = try_execute(&mut ex, "INSERT INTO items (id, name) VALUES (nextval('item_ids'), 'e');");
+                execute(&mut ex, "DROP SEQUENCE IF EXISTS item_ids;");
+                result_drop_missing = try_execute(&mut ex, "DROP SEQUENCE item_ids;");
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert!(matches!(result_too_long, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_exists, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_in_transaction, Err(BongoError::UnsupportedFeatureError(_))));
+            // the values reserved before the restart are not skipped after a regular shutdown
+            assert_eq!(vec![100, 110, 130, 140], result_ids);
+            assert!(matches!(result_dropped, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_drop_missing, Err(BongoError::SqlRuntimeError(_))));
         }
     }
 
-    mod update {
+    mod data_types {
         use std::fs;
         use std::path::PathBuf;
-        use bongo_core::bongo_request::BongoRequest;
-        use bongo_core::types::{BongoLiteral, Row};
-        use crate::Executor;
-        use crate::executor::tests::{create_example_table, get_example_rows, insert_example_rows};
+        use std::str::FromStr;
 
-        #[test]
-        fn update_including_index() {
-            let db_root = PathBuf::from("test_temp/update_including_index");
-            let table_name = "table_1";
+        use bongo_core::datetime::{Date, Interval, Time, Timestamp};
+        use bongo_core::types::{BongoError, BongoLiteral};
 
-            let update = format!("UPDATE {table_name} \
-                                SET col_1 = 42, col_3 = NULL;");
-            let select = "SELECT * FROM table_1 \
-                               ORDER BY col_2 DESC ;";
-            let update_req = BongoRequest { sql: update.to_string() };
-            let select_req = BongoRequest { sql: select.to_string() };
+        use crate::executor::Executor;
+        use crate::executor::tests::{decimal, execute, try_execute};
 
-            let expected = get_example_rows().into_iter()
-                .map(|mut row| {
-                    row[0] = BongoLiteral::Int(42);
-                    row[2] = BongoLiteral::Null;
-                    row
-                })
-                .collect::<Vec<Row>>();
-            let result;
+        #[test]
+        fn numeric_types() {
+            let db_root = PathBuf::from("test_temp/numeric_types");
+            let result_rows;
+            let result_small_out_of_range;
+            let result_decimal_out_of_range;
+            let result_computed;
+            let result_aggregates;
+            let result_lookup;
+            let explain_lookup;
+            let explain_no_lookup;
+            let result_int_out_of_range;
+            let result_join;
+            let result_updated;
 
             {
                 let mut ex = Executor::new(&db_root, true, false).unwrap();
-                create_example_table(&mut ex, table_name);
-                insert_example_rows(&mut ex, table_name);
-            } // leaving scope triggers drop and flush on the executor
+                execute(&mut ex, "CREATE TABLE numbers (id SMALLINT, big BIGINT, real_val REAL, \
+                    double_val DOUBLE PRECISION, price DECIMAL(6, 2));");
+                execute(&mut ex, "INSERT INTO numbers (id, big, real_val, double_val, price) VALUES \
+                    (1, 9000000000, 0.5, 0.1, 19.99), \
+                    (2, -9000000000, 1.25, 2.5, 5), \
+                    (3, NULL, NULL, NULL, 0.125);");
+                result_rows = execute(&mut ex, "SELECT * FROM numbers ORDER BY id;");
+                result_small_out_of_range = try_execute(&mut ex, "INSERT INTO numbers (id, big, real_val, double_val, price) VALUES (40000, 1, 1, 1, 1);");
+                result_decimal_out_of_range = try_execute(&mut ex, "INSERT INTO numbers (id, big, real_val, double_val, price) VALUES (4, 1, 1, 1, 10000);");
+
+                result_computed = execute(&mut ex, "SELECT id, price * 2, double_val + id FROM numbers WHERE real_val < 1;");
+                result_aggregates = execute(&mut ex, "SELECT SUM(price), AVG(price), AVG(real_val), SUM(big) FROM numbers;");
+
+                execute(&mut ex, "CREATE INDEX idx_price ON numbers USING BTREE (price);");
+                execute(&mut ex, "CREATE INDEX idx_id ON numbers (id);");
+                result_lookup = execute(&mut ex, "SELECT id FROM numbers WHERE price = 5 OR price > 19.5 ORDER BY id;");
+                explain_lookup = execute(&mut ex, "EXPLAIN SELECT id FROM numbers WHERE price = 5;");
+                // no value of a SMALLINT column is equal to 1.5
+                explain_no_lookup = execute(&mut ex, "EXPLAIN SELECT id FROM numbers WHERE id = 1.5;");
+
+                execute(&mut ex, "CREATE TABLE other (val INT);");
+                execute(&mut ex, "INSERT INTO other (val) VALUES (2), (5);");
+                result_int_out_of_range = try_execute(&mut ex, "INSERT INTO other (val) VALUES (3000000000);");
+                result_join = execute(&mut ex, "SELECT numbers.id, other.val FROM other JOIN numbers ON numbers.price = other.val;");
+
+                execute(&mut ex, "UPDATE numbers SET price = price / 3 WHERE id = 1;");
+                result_updated = execute(&mut ex, "SELECT price FROM numbers WHERE id = 1;");
+            } // drop executors before cleanup to avoid executor flushing on non existing dir.
+
+            // clean up before assertion in case it panics
+            fs::remove_dir_all(&db_root).unwrap();
+
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(1), BongoLiteral::Int(9000000000), BongoLiteral::Float(0.5), BongoLiteral::Float(0.1), decimal("19.99")],
+                vec![BongoLiteral::Int(2), BongoLiteral::Int(-9000000000), BongoLiteral::Float(1.25), BongoLiteral::Float(2.5), decimal("5.00")],
+                // values are rounded to the scale of the column
+                vec![BongoLiteral::Int(3), BongoLiteral::Null, BongoLiteral::Null, BongoLiteral::Null, decimal("0.13")],
+            ], result_rows);
+            assert!(matches!(result_small_out_of_range, Err(BongoError::SqlRuntimeError(_))));
+            assert!(matches!(result_decimal_out_of_range, Err(BongoError::SqlRuntimeError(_))));
+
+            assert_eq!(vec![vec![BongoLiteral::Int(1), decimal("39.98"), BongoLiteral::Float(0.1 + 1.0)]], result_computed);
+            assert_eq!(vec![vec![decimal("25.12"), decimal("8.37333333"), BongoLiteral::Float(0.875), BongoLiteral::Int(0)]],
+                       result_aggregates);
+
+            assert_eq!(vec![vec![BongoLiteral::Int(1)], vec![BongoLiteral::Int(2)]], result_lookup);
+            assert_eq!(BongoLiteral::Varchar("Index lookup using idx_price (B-tree) where price = 5.00".to_string()),
+                       explain_lookup[1][0]);
+            assert_eq!(BongoLiteral::Varchar("Full scan".to_string()), explain_no_lookup[1][0]);
+
+            assert!(matches!(result_int_out_of_range, Err(BongoError::SqlRuntimeError(_))));
+            assert_eq!(vec![vec![BongoLiteral::Int(2), BongoLiteral::Int(5)]], result_join);
+            assert_eq!(vec![vec![decimal("6.66")]], result_updated);
+        }
+
+        #[test]
+        fn dates_and_times() {
+            let db_root = PathBuf::from("test_temp/dates_and_times");
+            let result_rows;
+            let result_invalid;
+            let result_computed;
+            let result_compared;
+            let explain_lookup;
+            let result_lookup;
+            let result_now;
+            let result_updated;
 
             {
-                let mut ex = Executor::new(&db_root, false, true).unwrap();
-                ex.execute(&update_req).unwrap();
-                result = ex.execute(&select_req).unwrap().unwrap();
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE events (id INT, start_day DATE, start_time TIME, created TIMESTAMP WITH TIME ZONE);");
+                execute(&mut ex, "INSERT INTO events (id, start_day, start_time, created) VALUES \
+                    (1, '2024-01-31', '08:30', '2024-01-31 10:00:00+01:00'), \
+                    (2, DATE '2024-02-29', TIME '23:15:30.5', TIMESTAMP '2024-03-01 12:00'), \
+                    (3, NULL, NULL, NULL);");
+                result_rows = execute(&mut ex, "SELECT * FROM events ORDER BY id;");
+                result_invalid = try_execute(&mut ex, "INSERT INTO events (id, start_day, start_time, created) VALUES (4, '2023-02-29', NULL, NULL);");
+
+                result_computed = execute(&mut ex, "SELECT start_day + 1, start_day + INTERVAL '1' MONTH, created - TIMESTAMP '2024-02-28 11:30', \
+                    EXTRACT(YEAR FROM start_day), EXTRACT(SECOND FROM start_time) FROM events WHERE id = 2;");
+                result_compared = execute(&mut ex, "SELECT id FROM events WHERE created >= DATE '2024-02-01' OR start_time < TIME '09:00' ORDER BY id;");
+
+                execute(&mut ex, "CREATE INDEX idx_created ON events USING BTREE (created);");
+                explain_lookup = execute(&mut ex, "EXPLAIN SELECT id FROM events WHERE created = DATE '2024-03-01';");
+                result_lookup = execute(&mut ex, "SELECT id FROM events WHERE created BETWEEN TIMESTAMP '2024-01-01' AND DATE '2024-02-01';");
+
+                execute(&mut ex, "INSERT INTO events (id, start_day, start_time, created) VALUES (4, CURRENT_DATE, CURRENT_TIME, NOW());");
+                result_now = execute(&mut ex, "SELECT id FROM events WHERE created > NOW() - INTERVAL '1 hour' AND start_day = CURRENT_DATE;");
+
+                execute(&mut ex, "UPDATE events SET start_day = start_day - 31, start_time = start_time + INTERVAL '16' HOUR WHERE id = 1;");
+                result_updated = execute(&mut ex, "SELECT start_day, start_time FROM events WHERE id = 1;");
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            let date = |s: &str| BongoLiteral::Date(Date::from_str(s).unwrap());
+            let time = |s: &str| BongoLiteral::Time(Time::from_str(s).unwrap());
+            let timestamp = |s: &str| BongoLiteral::Timestamp(Timestamp::from_str(s).unwrap());
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(1), date("2024-01-31"), time("08:30"), timestamp("2024-01-31 09:00")],
+                vec![BongoLiteral::Int(2), date("2024-02-29"), time("23:15:30.5"), timestamp("2024-03-01 12:00")],
+                vec![BongoLiteral::Int(3), BongoLiteral::Null, BongoLiteral::Null, BongoLiteral::Null],
+            ], result_rows);
+            assert!(matches!(result_invalid, Err(BongoError::SqlRuntimeError(_))));
+
+            assert_eq!(vec![vec![
+                date("2024-03-01"),
+                timestamp("2024-03-29 00:00"),
+                BongoLiteral::Interval(Interval::from_str("2 days 00:30").unwrap()),
+                BongoLiteral::Int(2024),
+                decimal("30.500000"),
+            ]], result_computed);
+            assert_eq!(vec![vec![BongoLiteral::Int(1)], vec![BongoLiteral::Int(2)]], result_compared);
+
+            assert_eq!(BongoLiteral::Varchar(
+                "Index lookup using idx_created (B-tree) where created = TIMESTAMP '2024-03-01 00:00:00'".to_string()
+            ), explain_lookup[1][0]);
+            assert_eq!(vec![vec![BongoLiteral::Int(1)]], result_lookup);
+            assert_eq!(vec![vec![BongoLiteral::Int(4)]], result_now);
+            assert_eq!(vec![vec![date("2023-12-31"), time("00:30")]], result_updated);
         }
 
         #[test]
-        fn update_with_wrong_d_type() {
-            let db_root = PathBuf::from("test_temp/update_with_wrong_d_type");
-            let table_name = "table_1";
-
-            let update = format!("UPDATE {table_name} \
-                                SET col_1 = 'col_1 is datatype int...';");
-            let request = BongoRequest { sql: update.to_string() };
-            let result;
+        fn dates_and_times_from_strings() {
+            let db_root = PathBuf::from("test_temp/dates_and_times_from_strings");
+            let result_compared;
+            let result_lookup;
+            let result_updated;
+            let result_default;
 
             {
-                let mut ex = Executor::new(&db_root, true, true).unwrap();
-                create_example_table(&mut ex, table_name);
-                insert_example_rows(&mut ex, table_name);
-                result = ex.execute(&request);
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE events (id INT, start_day DATE DEFAULT '2024-01-01', \
+                    start_time TIME DEFAULT '12:00', created TIMESTAMP DEFAULT '2024-01-01 08:00');");
+                execute(&mut ex, "INSERT INTO events (id, start_day, start_time, created) VALUES \
+                    (1, '2023-12-31', '08:30', '2023-12-31 23:00'), (2, '2024-02-29', '23:15', '2024-03-01 12:00');");
+
+                result_compared = execute(&mut ex, "SELECT id FROM events WHERE start_day < '2024-01-01' \
+                    OR start_time = '23:15:00' ORDER BY id;");
+                execute(&mut ex, "CREATE INDEX idx_created ON events USING BTREE (created);");
+                result_lookup = execute(&mut ex, "SELECT id FROM events WHERE created >= '2024-01-01';");
+
+                execute(&mut ex, "UPDATE events SET start_day = '2024-03-01', start_time = '09:45', created = '2024-03-01' WHERE id = 1;");
+                result_updated = execute(&mut ex, "SELECT start_day, start_time, created FROM events WHERE id = 1;");
+
+                execute(&mut ex, "INSERT INTO events (id) VALUES (3);");
+                result_default = execute(&mut ex, "SELECT start_day, start_time, created FROM events WHERE id = 3;");
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert!(result.is_err());
+            let date = |s: &str| BongoLiteral::Date(Date::from_str(s).unwrap());
+            let time = |s: &str| BongoLiteral::Time(Time::from_str(s).unwrap());
+            let timestamp = |s: &str| BongoLiteral::Timestamp(Timestamp::from_str(s).unwrap());
+            assert_eq!(vec![vec![BongoLiteral::Int(1)], vec![BongoLiteral::Int(2)]], result_compared);
+            assert_eq!(vec![vec![BongoLiteral::Int(2)]], result_lookup);
+            assert_eq!(vec![vec![date("2024-03-01"), time("09:45"), timestamp("2024-03-01 00:00")]], result_updated);
+            assert_eq!(vec![vec![date("2024-01-01"), time("12:00"), timestamp("2024-01-01 08:00")]], result_default);
         }
 
         #[test]
-        fn update_only_one() {
-            let db_root = PathBuf::from("test_temp/update_only_one");
-            let table_name = "table_1";
-
-            let update = format!("UPDATE {table_name} \
-                                SET col_2 = 'updated' \
-                                WHERE col_1 = 3;");
-            let select = format!("SELECT * FROM {table_name} \
-                               ORDER BY col_1 ASC ;");
-            let update_req = BongoRequest { sql: update };
-            let select_req = BongoRequest { sql: select };
+        fn text_and_blob() {
+            let db_root = PathBuf::from("test_temp/text_and_blob");
+            let long_a = "a".repeat(5000);
+            let long_b = "b".repeat(3000);
+            let result_rows;
+            let data_size;
+            let result_like;
+            let result_lookup;
+            let result_btree;
+            let heap_size;
+            let result_reloaded;
 
-            let expected = get_example_rows().into_iter()
-                .enumerate()
-                .map(|(i, mut row)| {
-                    if i == 2 {
-                        row[1] = BongoLiteral::Varchar("updated".to_string());
-                    }
-                    row
-                })
-                .collect::<Vec<Row>>();
-            let result;
+            {
+                let mut ex = Executor::new(&db_root, true, false).unwrap();
+                execute(&mut ex, "CREATE TABLE docs (id INT, body TEXT, data BYTEA);");
+                execute(&mut ex, &format!("INSERT INTO docs (id, body, data) VALUES \
+                    (1, '{}', X'00FF'), (2, 'it''s short', '\\x0102'), (3, NULL, NULL);", long_a));
+                result_rows = execute(&mut ex, "SELECT * FROM docs ORDER BY id;");
+                data_size = fs::metadata(db_root.join("docs").join("data.bongo")).unwrap().len();
+                result_like = execute(&mut ex, "SELECT id FROM docs WHERE body LIKE 'a%' OR data = X'0102' ORDER BY id;");
+
+                execute(&mut ex, "CREATE INDEX idx_body ON docs (body);");
+                result_lookup = execute(&mut ex, "SELECT id FROM docs WHERE body = 'it''s short';");
+                result_btree = try_execute(&mut ex, "CREATE INDEX idx_data ON docs USING BTREE (data);");
+
+                execute(&mut ex, &format!("UPDATE docs SET body = '{}' WHERE id = 2;", long_b));
+                execute(&mut ex, "DELETE FROM docs WHERE id = 1;");
+                execute(&mut ex, "VACUUM docs;");
+                heap_size = fs::metadata(db_root.join("docs").join("heap.bongo")).unwrap().len();
+            }
 
             {
-                let mut ex = Executor::new(&db_root, true, true).unwrap();
-                create_example_table(&mut ex, table_name);
-                insert_example_rows(&mut ex, table_name);
-                ex.execute(&update_req).unwrap();
-                result = ex.execute(&select_req).unwrap().unwrap();
+                let mut ex = Executor::new(&db_root, false, false).unwrap();
+                result_reloaded = execute(&mut ex, "SELECT * FROM docs WHERE body = '' OR id > 0 ORDER BY id;");
             } // drop executors before cleanup to avoid executor flushing on non existing dir.
 
             // clean up before assertion in case it panics
             fs::remove_dir_all(&db_root).unwrap();
 
-            assert_eq!(expected, result);
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(1), BongoLiteral::Varchar(long_a), BongoLiteral::Blob(vec![0, 255])],
+                vec![BongoLiteral::Int(2), BongoLiteral::Varchar("it's short".to_string()), BongoLiteral::Blob(vec![1, 2])],
+                vec![BongoLiteral::Int(3), BongoLiteral::Null, BongoLiteral::Null],
+            ], result_rows);
+            // rows only hold references to their values
+            assert_eq!(3 * (17 + 5 + 17 + 17), data_size);
+            assert_eq!(vec![vec![BongoLiteral::Int(1)], vec![BongoLiteral::Int(2)]], result_like);
+            assert_eq!(vec![vec![BongoLiteral::Int(2)]], result_lookup);
+            assert!(matches!(result_btree, Err(BongoError::SqlRuntimeError(_))));
+
+            // only the values of the remaining rows are kept in the heap
+            assert_eq!(3000 + 2, heap_size);
+            assert_eq!(vec![
+                vec![BongoLiteral::Int(2), BongoLiteral::Varchar(long_b), BongoLiteral::Blob(vec![1, 2])],
+                vec![BongoLiteral::Int(3), BongoLiteral::Null, BongoLiteral::Null],
+            ], result_reloaded);
         }
     }
 
-
     ///
     /// creates an an example table with three columns
     ///
@@ -1760,6 +7441,48 @@ mod tests {
         // drop(Executor) gets triggered here and the executor writes its cache to disc.
     }
 
+    ///
+    /// Executes `sql` and returns the resulting rows, which are empty for statements without result.
+    ///
+    fn execute(ex: &mut Executor, sql: &str) -> Vec<Row> {
+        execute_for(ex, DEFAULT_CONNECTION, sql)
+    }
+
+    ///
+    /// Executes `sql` and returns the resulting rows or the error of the statement.
+    ///
+    fn try_execute(ex: &mut Executor, sql: &str) -> Result<Vec<Row>, BongoError> {
+        try_execute_for(ex, DEFAULT_CONNECTION, sql)
+    }
+
+    ///
+    /// Executes `sql` on behalf of `connection` like `execute`.
+    ///
+    fn execute_for(ex: &mut Executor, connection: ConnectionId, sql: &str) -> Vec<Row> {
+        try_execute_for(ex, connection, sql).unwrap()
+    }
+
+    ///
+    /// Executes `sql` on behalf of `connection` like `try_execute`.
+    ///
+    fn try_execute_for(ex: &mut Executor, connection: ConnectionId, sql: &str) -> Result<Vec<Row>, BongoError> {
+        ex.execute_for(connection, &BongoRequest { sql: sql.to_string() }).map(|rows| rows.unwrap_or_default())
+    }
+
+    ///
+    /// Returns all rows of the example table `table_name` in ascending order of `col_1`.
+    ///
+    fn select_all(ex: &mut Executor, table_name: &str) -> Vec<Row> {
+        select_all_for(ex, DEFAULT_CONNECTION, table_name)
+    }
+
+    ///
+    /// Returns all rows of the example table `table_name` that are visible to `connection` like `select_all`.
+    ///
+    fn select_all_for(ex: &mut Executor, connection: ConnectionId, table_name: &str) -> Vec<Row> {
+        execute_for(ex, connection, &format!("SELECT * FROM {table_name} ORDER BY col_1 ASC;"))
+    }
+
     ///
     /// Inserts some rows into the example table created with `create_example_table`.
     ///
@@ -1785,4 +7508,25 @@ mod tests {
             vec![BongoLiteral::Int(4), BongoLiteral::Varchar("a❤".to_string()), BongoLiteral::Bool(false)],
         ];
     }
+
+    ///
+    /// Returns `i` as literal of an integer column.
+    ///
+    fn int(i: i64) -> BongoLiteral {
+        BongoLiteral::Int(i)
+    }
+
+    ///
+    /// Returns `s` as literal of a VARCHAR column.
+    ///
+    fn varchar(s: &str) -> BongoLiteral {
+        BongoLiteral::Varchar(s.to_string())
+    }
+
+    ///
+    /// Returns the DECIMAL literal that `val` is the text of.
+    ///
+    fn decimal(val: &str) -> BongoLiteral {
+        BongoLiteral::Decimal(Decimal::from_str(val).unwrap())
+    }
 }
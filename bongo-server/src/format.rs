@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bongo_core::types::{BongoDataType, BongoError, ColumnDef};
+use serde::{Deserialize, Serialize};
+
+use crate::executor::Executor;
+use crate::statement::NullLogic;
+
+///
+/// Name of the file inside the DB root directory that tells in which format the files of the
+/// database are written.
+///
+pub const DATABASE_FILE_NAME: &str = "database.bongo";
+
+///
+/// The version of the format in which this version of BongoDB writes databases.
+///
+/// Databases of earlier versions of BongoDB have no database.bongo file and are of version 0:
+/// Their tables have no schema.bongo file and the rows in their data.bongo files have no slot
+/// header. They are migrated to the current format when they are opened.
+///
+pub const FORMAT_VERSION: u32 = 1;
+
+///
+/// `DatabaseInfo` is the content of the database.bongo file.
+///
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct DatabaseInfo {
+    pub format_version: u32,
+    ///
+    /// How `NULL` values are treated when the expressions of statements on the database are evaluated.
+    ///
+    pub null_logic: NullLogic,
+}
+
+impl DatabaseInfo {
+    ///
+    /// Reads the database.bongo file of the database in `db_root`. Returns `None` if the database
+    /// has no such file, i.e. it is new or has been written by an earlier version of BongoDB.
+    ///
+    pub fn read(db_root: &Path) -> Result<Option<Self>, BongoError> {
+        let path = db_root.join(DATABASE_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        match fs::read(&path).ok().and_then(|bytes| bincode::deserialize(&bytes).ok()) {
+            Some(info) => Ok(Some(info)),
+            None => Err(BongoError::ReadFileError(format!(
+                "Could not read the format of the database at '{}'", path.to_str().unwrap()
+            ))),
+        }
+    }
+
+    pub fn write(&self, db_root: &Path) -> Result<(), BongoError> {
+        match bincode::serialize(self) {
+            Ok(bytes) => Executor::write_file_atomically(&db_root.join(DATABASE_FILE_NAME), &bytes),
+            Err(_) => Err(BongoError::InternalError("Could not serialize the format of the database.".to_string())),
+        }
+    }
+}
+
+///
+/// The data types of format version 0. Only the position of the variants matters for reading them.
+///
+#[derive(Deserialize, Debug)]
+enum LegacyDataType {
+    Int,
+    Bool,
+    Varchar(usize),
+}
+
+///
+/// The literals of format version 0, which are the keys of the index in its meta.bongo files.
+///
+// the values are only read to get to the fields behind the index
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+enum LegacyLiteral {
+    Int(i64),
+    Bool(bool),
+    Varchar(String),
+    Null,
+}
+
+#[derive(Deserialize, Debug)]
+struct LegacyColumnDef {
+    name: String,
+    data_type: LegacyDataType,
+}
+
+///
+/// The content of a meta.bongo file of format version 0.
+///
+#[derive(Deserialize, Debug)]
+struct LegacyTableMetaData {
+    cols: Vec<LegacyColumnDef>,
+    ///
+    /// The name of the indexed column and the index. bincode writes a map just like a sequence of
+    /// key value pairs, so the index can be read without hashing the keys.
+    ///
+    idx: (String, Vec<(LegacyLiteral, Vec<u64>)>),
+    ///
+    /// Positions of the rows that have been deleted.
+    ///
+    ghosts: Vec<u64>,
+    row_size: usize,
+    #[allow(dead_code)]
+    row_count: usize,
+}
+
+///
+/// `LegacyTable` is a table of format version 0 as far as it is needed to migrate it.
+///
+#[derive(Debug)]
+pub struct LegacyTable {
+    pub name: String,
+    pub cols: Vec<ColumnDef>,
+    ///
+    /// The column that every table of format version 0 has implicitly indexed, which is its first one.
+    ///
+    pub index_col: String,
+    ///
+    /// Positions of the rows in data.bongo that have been deleted.
+    ///
+    pub ghosts: Vec<u64>,
+    ///
+    /// The size in bytes of one row in data.bongo, which has no slot header.
+    ///
+    pub row_size: usize,
+}
+
+impl LegacyTable {
+    ///
+    /// Returns the path of the meta.bongo file of format version 0 inside of `table_dir`.
+    ///
+    pub fn meta_path(table_dir: &Path) -> PathBuf {
+        table_dir.join("meta.bongo")
+    }
+
+    ///
+    /// Reads the meta.bongo file of format version 0 inside of `table_dir`.
+    ///
+    pub fn read(table_dir: &Path) -> Result<Self, BongoError> {
+        let path = Self::meta_path(table_dir);
+        let (name, meta) = match fs::read(&path).ok().and_then(|bytes| bincode::deserialize::<(String, LegacyTableMetaData)>(&bytes).ok()) {
+            Some(table) => table,
+            None => return Err(BongoError::UnsupportedFormatError(format!(
+                "The table at '{}' has been written by an earlier version of BongoDB, but its meta data \
+                cannot be read, so it cannot be migrated.",
+                table_dir.to_str().unwrap()
+            ))),
+        };
+
+        let cols = meta.cols.into_iter()
+            .map(|col| ColumnDef {
+                name: col.name,
+                data_type: match col.data_type {
+                    LegacyDataType::Int => BongoDataType::BigInt,
+                    LegacyDataType::Bool => BongoDataType::Bool,
+                    LegacyDataType::Varchar(size) => BongoDataType::Varchar(size),
+                },
+                not_null: false,
+                default: None,
+                serial: false,
+            })
+            .collect::<Vec<ColumnDef>>();
+        if meta.row_size != cols.iter().map(|col| col.data_type.disc_size()).sum::<usize>() {
+            return Err(BongoError::UnsupportedFormatError(format!(
+                "The size of the rows of the table at '{}' does not match its columns.",
+                table_dir.to_str().unwrap()
+            )));
+        }
+
+        Ok(Self { name, cols, index_col: meta.idx.0, ghosts: meta.ghosts, row_size: meta.row_size })
+    }
+}
@@ -0,0 +1,255 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use bongo_core::bytes_on_disc::{AsDiscBytes, FromDiscBytes};
+use bongo_core::types::{BongoDataType, BongoError, BongoLiteral, ColumnDef, Row};
+
+///
+/// Name of the file inside the directory of a table that holds the values of its out of line columns.
+///
+pub const HEAP_FILE_NAME: &str = "heap.bongo";
+
+///
+/// `Heap` is the file of a table that stores the values of `TEXT` and `BLOB` columns, so that the
+/// rows in data.bongo keep their fixed size no matter how large these values are.
+///
+/// The row only holds a reference to its value in the heap: One byte that tells whether the value
+/// is NULL followed by the position and the length of the value in the heap, each as 8 byte big
+/// endian number. Values are only ever appended to the heap. The values of row versions that have
+/// been removed or deleted stay in the heap until the table is compacted, see `copy_values`.
+///
+/// Replaying an insert of the write-ahead log appends its values again and lets the row refer to
+/// the new copies. The earlier copies are unused afterwards and are reclaimed by the next compaction.
+///
+/// The file is only created when the first value is written to it, so that tables without out of
+/// line columns have no heap.
+///
+pub struct Heap {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl Heap {
+    pub fn new(path: &Path) -> Self {
+        Self { path: path.to_path_buf(), file: None }
+    }
+
+    fn file(&mut self) -> Result<&mut File, BongoError> {
+        if self.file.is_none() {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&self.path);
+            if file.is_err() {
+                return Err(BongoError::ReadFileError("Could not open heap file of table on disc.".to_string()));
+            }
+            self.file = file.ok();
+        }
+
+        // unwrap safe, because the file has been opened before
+        Ok(self.file.as_mut().unwrap())
+    }
+
+    ///
+    /// Converts `row` to its byte representation in data.bongo. The values of out of line columns
+    /// are appended to the heap before.
+    ///
+    pub fn row_as_disc_bytes(&mut self, row: &Row, cols: &[ColumnDef]) -> Result<Vec<u8>, BongoError> {
+        if !cols.iter().any(|col_def| col_def.data_type.is_out_of_line()) {
+            return row.as_disc_bytes(cols);
+        }
+        if row.len() != cols.len() {
+            return Err(BongoError::InternalError("Row does not match the columns of the table.".to_string()));
+        }
+
+        let mut bytes = vec![];
+        for (lit, col_def) in row.iter().zip(cols) {
+            let value = match lit {
+                BongoLiteral::Varchar(val) if col_def.data_type.is_out_of_line() => val.as_bytes(),
+                BongoLiteral::Blob(val) if col_def.data_type.is_out_of_line() => val.as_slice(),
+                _ => {
+                    bytes.append(&mut lit.as_disc_bytes(col_def)?);
+                    continue;
+                }
+            };
+
+            let pos = self.append(value)?;
+            bytes.push(true as u8);
+            bytes.extend_from_slice(&pos.to_be_bytes());
+            bytes.extend_from_slice(&(value.len() as u64).to_be_bytes());
+        }
+
+        Ok(bytes)
+    }
+
+    ///
+    /// Creates a `Row` from its byte representation in data.bongo. The values of out of line
+    /// columns are read from the heap.
+    ///
+    pub fn row_from_disc_bytes(&mut self, bytes: &[u8], d_types: &[&BongoDataType]) -> Result<Row, BongoError> {
+        if !d_types.iter().any(|d_type| d_type.is_out_of_line()) {
+            return Row::from_disc_bytes(bytes, d_types);
+        }
+
+        let mut row = Vec::with_capacity(d_types.len());
+        let mut offset = 0;
+        for d_type in d_types {
+            let size = d_type.disc_size();
+            if offset + size > bytes.len() {
+                return Err(BongoError::InternalError("Reading row from bytes not successful.".to_string()));
+            }
+            let field = &bytes[offset..offset + size];
+            offset += size;
+
+            row.push(match reference(field, d_type) {
+                None => BongoLiteral::from_disc_bytes(field, *d_type)?,
+                Some((pos, len)) => {
+                    let value = self.read(pos, len)?;
+                    match d_type {
+                        BongoDataType::Blob => BongoLiteral::Blob(value),
+                        _ => match String::from_utf8(value) {
+                            Ok(val) => BongoLiteral::Varchar(val),
+                            Err(_) => return Err(BongoError::InternalError(
+                                "Cannot read literal from bytes due to corrupted format.".to_string(),
+                            )),
+                        },
+                    }
+                }
+            });
+        }
+
+        Ok(row)
+    }
+
+    ///
+    /// Forces all values written to the heap to disc.
+    ///
+    pub fn sync(&mut self) -> Result<(), BongoError> {
+        if self.file.is_none() && !self.path.exists() {
+            return Ok(());
+        }
+
+        if self.file()?.sync_all().is_err() {
+            return Err(BongoError::WriteFileError("Could not force heap of table to disc.".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn append(&mut self, value: &[u8]) -> Result<u64, BongoError> {
+        let file = self.file()?;
+        let pos = file.seek(SeekFrom::End(0));
+        if pos.is_err() || file.write_all(value).is_err() {
+            return Err(BongoError::WriteFileError("Could not write value to heap of table.".to_string()));
+        }
+
+        Ok(pos.unwrap())
+    }
+
+    fn read(&mut self, pos: u64, len: u64) -> Result<Vec<u8>, BongoError> {
+        let file = self.file()?;
+        let mut value = vec![0; len as usize];
+        if file.seek(SeekFrom::Start(pos)).is_err() || file.read_exact(&mut value).is_err() {
+            return Err(BongoError::ReadFileError("Could not read value from heap of table.".to_string()));
+        }
+
+        Ok(value)
+    }
+}
+
+///
+/// Returns the position and the length of the value in the heap that the bytes of a field of the
+/// type `d_type` refer to. `None` is returned for NULL values and types that are stored in the row.
+///
+fn reference(field: &[u8], d_type: &BongoDataType) -> Option<(u64, u64)> {
+    if !d_type.is_out_of_line() || field[0] == 0 {
+        return None;
+    }
+
+    // unwraps safe, because the size of a reference is defined by the data type
+    Some((
+        u64::from_be_bytes(field[1..9].try_into().unwrap()),
+        u64::from_be_bytes(field[9..17].try_into().unwrap()),
+    ))
+}
+
+///
+/// Copies the values that the row `bytes` of data.bongo refers to from `heap` to the end of
+/// `compacted` and lets the references in `bytes` point to the copies. This is used to build the
+/// heap of a compacted table from the values of its remaining rows only.
+///
+pub fn copy_values(bytes: &mut [u8], d_types: &[&BongoDataType], heap: &mut Heap, compacted: &mut Vec<u8>) -> Result<(), BongoError> {
+    let mut offset = 0;
+    for d_type in d_types {
+        let size = d_type.disc_size();
+        if let Some((pos, len)) = reference(&bytes[offset..offset + size], d_type) {
+            let value = heap.read(pos, len)?;
+            bytes[offset + 1..offset + 9].copy_from_slice(&(compacted.len() as u64).to_be_bytes());
+            compacted.extend_from_slice(&value);
+        }
+        offset += size;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use bongo_core::types::{BongoDataType, BongoLiteral, ColumnDef, GetDTypesExt};
+
+    use crate::heap::{copy_values, Heap};
+
+    #[test]
+    fn values_are_stored_out_of_line() {
+        let dir = PathBuf::from("test_temp/heap_values_are_stored_out_of_line");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("heap.bongo");
+
+        let cols = vec![
+            ColumnDef { name: "id".to_string(), data_type: BongoDataType::Int, not_null: false, default: None, serial: false },
+            ColumnDef { name: "body".to_string(), data_type: BongoDataType::Text, not_null: false, default: None, serial: false },
+            ColumnDef { name: "data".to_string(), data_type: BongoDataType::Blob, not_null: false, default: None, serial: false },
+        ];
+        let d_types = cols.get_d_types();
+        let rows = vec![
+            vec![BongoLiteral::Int(1), BongoLiteral::Varchar("a".repeat(10000)), BongoLiteral::Blob(vec![0, 1, 2])],
+            vec![BongoLiteral::Int(2), BongoLiteral::Null, BongoLiteral::Blob(vec![255; 50])],
+        ];
+
+        let mut heap = Heap::new(&path);
+        let mut bytes = vec![];
+        for row in &rows {
+            let row_bytes = heap.row_as_disc_bytes(row, &cols).unwrap();
+            // the size of a row does not depend on its values
+            assert_eq!(5 + 17 + 17, row_bytes.len());
+            bytes.push(row_bytes);
+        }
+        assert_eq!(10053, fs::metadata(&path).unwrap().len());
+        for (row, row_bytes) in rows.iter().zip(&bytes) {
+            assert_eq!(row, &heap.row_from_disc_bytes(row_bytes, &d_types).unwrap());
+        }
+
+        // only the values of the second row are kept
+        let mut compacted = vec![];
+        copy_values(&mut bytes[1], &d_types, &mut heap, &mut compacted).unwrap();
+        assert_eq!(vec![255; 50], compacted);
+        fs::write(&path, &compacted).unwrap();
+        assert_eq!(rows[1], Heap::new(&path).row_from_disc_bytes(&bytes[1], &d_types).unwrap());
+
+        // tables without out of line columns have no heap
+        let cols = vec![ColumnDef { name: "id".to_string(), data_type: BongoDataType::Int, not_null: false, default: None, serial: false }];
+        let path = dir.join("other.bongo");
+        Heap::new(&path).row_as_disc_bytes(&vec![BongoLiteral::Int(1)], &cols).unwrap();
+        Heap::new(&path).sync().unwrap();
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
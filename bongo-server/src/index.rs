@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+
+use bongo_core::types::{BongoError, BongoLiteral};
+use serde::{Deserialize, Serialize};
+
+use crate::btree::{BTree, Entry};
+
+///
+/// `IndexKind` is the data structure of an index, which is chosen with `USING` in a `CREATE INDEX`
+/// statement.
+///
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum IndexKind {
+    ///
+    /// A hash index is kept in the meta data of the table and supports lookups by `=` and `!=`.
+    ///
+    Hash,
+    ///
+    /// A B-tree index is stored in its own file and additionally supports range lookups and
+    /// returning rows in the order of the indexed column.
+    ///
+    BTree,
+}
+
+///
+/// `IndexDef` is the definition of an index given by the user in a `CREATE INDEX` statement.
+///
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct IndexDef {
+    ///
+    /// The name of the index, which is unique among the indexes of all tables.
+    ///
+    pub name: String,
+    ///
+    /// The name of the indexed column.
+    ///
+    pub col: String,
+    pub kind: IndexKind,
+}
+
+///
+/// An `Index` is a hash index or a B-tree index on one column of a table.
+///
+/// It maps a `BongoLiteral`, which is the content of an entry for the indexed column, to the
+/// positions of the start of row versions on disc. That means if we read exactly as many bytes as a
+/// row in that table is long at one of those positions, we will get back exactly one row version.
+///
+/// The entries of a hash index are stored in `entries`. If the Vec has length > 1 it means that
+/// there was a hash collision which requires a linear search. The Vec always has length >= 1
+/// because the HashMap entry will be removed if it has length == 0.
+///
+/// The entries of a B-tree index are stored in the file `<name>.btree` inside of the directory of
+/// the table and not in the meta data. `btree` is `None` until the file has been opened.
+///
+/// All versions of a row are contained in the index until they are reclaimed by compacting the table.
+///
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Index {
+    pub def: IndexDef,
+    pub entries: HashMap<BongoLiteral, Vec<u64>>,
+    #[serde(skip)]
+    pub btree: Option<BTree>,
+}
+
+impl Index {
+    ///
+    /// Creates a new empty index. The file of a B-tree index is created inside of `table_dir`.
+    ///
+    pub fn create(def: IndexDef, table_dir: &Path) -> Result<Self, BongoError> {
+        let btree = match def.kind {
+            IndexKind::Hash => None,
+            IndexKind::BTree => Some(BTree::create(&Self::btree_path(table_dir, &def.name))?),
+        };
+
+        Ok(Self { def, entries: HashMap::new(), btree })
+    }
+
+    ///
+    /// Opens the file of a B-tree index inside of `table_dir` after the index has been loaded
+    /// from the meta data. Returns false if the index has to be filled again, because its file is
+    /// missing or has not been synced after its last modification.
+    ///
+    pub fn open(&mut self, table_dir: &Path) -> Result<bool, BongoError> {
+        if self.def.kind == IndexKind::Hash {
+            return Ok(true);
+        }
+
+        match BTree::open(&Self::btree_path(table_dir, &self.def.name))? {
+            Some(btree) => {
+                self.btree = Some(btree);
+                Ok(true)
+            }
+            None => {
+                *self = Self::create(self.def.clone(), table_dir)?;
+                Ok(false)
+            }
+        }
+    }
+
+    ///
+    /// Removes the file of a B-tree index inside of `table_dir`.
+    ///
+    pub fn remove_file(&self, table_dir: &Path) -> Result<(), BongoError> {
+        let path = Self::btree_path(table_dir, &self.def.name);
+        if path.exists() && fs::remove_file(&path).is_err() {
+            return Err(BongoError::WriteFileError(format!(
+                "Could not delete file of index at '{}'",
+                path.to_str().unwrap()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn btree_path(table_dir: &Path, name: &str) -> PathBuf {
+        table_dir.join(format!("{}.btree", name))
+    }
+
+    ///
+    /// Forces the modifications of a B-tree index to disc. Hash indexes are written with the meta data.
+    ///
+    pub fn sync(&self) -> Result<(), BongoError> {
+        match &self.btree {
+            Some(btree) => btree.sync(),
+            None => Ok(()),
+        }
+    }
+
+    ///
+    /// Adds the row position `pos` to the index entry of `key`.
+    ///
+    pub fn insert(&mut self, key: BongoLiteral, pos: u64) -> Result<(), BongoError> {
+        if let Some(btree) = &self.btree {
+            return btree.insert(key, pos);
+        }
+
+        match self.entries.get_mut(&key) {
+            None => {
+                // index does not exist for this key yet.
+                self.entries.insert(key, vec![pos]);
+            }
+            Some(positions) => {
+                // hash collision -> append new row position to list
+                positions.push(pos);
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Removes the row position `pos` from the index entry of `key` and removes the whole entry if
+    /// `pos` was the only row position belonging to this key.
+    ///
+    pub fn remove(&mut self, key: &BongoLiteral, pos: u64) -> Result<(), BongoError> {
+        if let Some(btree) = &self.btree {
+            return btree.remove(key.clone(), pos);
+        }
+
+        if let Some(positions) = self.entries.get_mut(key) {
+            positions.retain(|p| *p != pos);
+            if positions.is_empty() {
+                self.entries.remove(key);
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the row positions of all entries with the key `key`.
+    ///
+    pub fn get(&self, key: &BongoLiteral) -> Result<Vec<u64>, BongoError> {
+        match &self.btree {
+            Some(btree) => Ok(Self::positions(btree.range(Bound::Included(key), Bound::Included(key))?)),
+            None => Ok(self.entries.get(key).cloned().unwrap_or_default()),
+        }
+    }
+
+    ///
+    /// Returns the row positions of all entries whose key is not `key`.
+    ///
+    pub fn get_except(&self, key: &BongoLiteral) -> Result<Vec<u64>, BongoError> {
+        match &self.btree {
+            Some(btree) => Ok(btree.range(Bound::Unbounded, Bound::Unbounded)?.into_iter()
+                .filter(|(k, _)| k != key)
+                .map(|(_, pos)| pos)
+                .collect()),
+            None => Ok(self.entries.iter()
+                .filter(|(k, _)| *k != key)
+                .flat_map(|(_, positions)| positions.iter().copied())
+                .collect()),
+        }
+    }
+
+    ///
+    /// Returns the entries whose keys lie within `lower` and `upper` ordered by their keys.
+    /// Returns `None` if the index is a hash index, which does not support range lookups.
+    ///
+    pub fn range(&self, lower: Bound<&BongoLiteral>, upper: Bound<&BongoLiteral>) -> Result<Option<Vec<Entry>>, BongoError> {
+        match &self.btree {
+            Some(btree) => Ok(Some(btree.range(lower, upper)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn positions(entries: Vec<Entry>) -> Vec<u64> {
+        entries.into_iter().map(|(_, pos)| pos).collect()
+    }
+
+    ///
+    /// Moves all row positions to the new positions in `relocations`. Positions without a new
+    /// position are removed from the index. The file of a B-tree index inside of `table_dir` is rewritten.
+    ///
+    pub fn relocate(&mut self, relocations: &HashMap<u64, u64>, table_dir: &Path) -> Result<(), BongoError> {
+        if let Some(btree) = &self.btree {
+            let entries = btree.range(Bound::Unbounded, Bound::Unbounded)?;
+            *self = Self::create(self.def.clone(), table_dir)?;
+            for (key, pos) in entries {
+                if let Some(new_pos) = relocations.get(&pos) {
+                    self.insert(key, *new_pos)?;
+                }
+            }
+
+            return Ok(());
+        }
+
+        for positions in self.entries.values_mut() {
+            *positions = positions.iter()
+                .filter_map(|pos| relocations.get(pos).copied())
+                .collect();
+        }
+        self.entries.retain(|_, positions| !positions.is_empty());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::ops::Bound;
+    use std::path::{Path, PathBuf};
+
+    use bongo_core::types::BongoLiteral;
+
+    use crate::index::{Index, IndexDef, IndexKind};
+
+    fn example_index(kind: IndexKind, table_dir: &Path) -> Index {
+        Index::create(IndexDef { name: "idx_1".to_string(), col: "col_1".to_string(), kind }, table_dir).unwrap()
+    }
+
+    #[test]
+    fn insert_remove_and_relocate() {
+        let mut index = example_index(IndexKind::Hash, Path::new("unused"));
+        index.insert(BongoLiteral::Int(1), 0).unwrap();
+        index.insert(BongoLiteral::Int(1), 10).unwrap();
+        index.insert(BongoLiteral::Int(2), 20).unwrap();
+
+        index.remove(&BongoLiteral::Int(2), 20).unwrap();
+        assert_eq!(index.entries.get(&BongoLiteral::Int(2)), None);
+
+        index.relocate(&HashMap::from([(10, 0)]), Path::new("unused")).unwrap();
+        assert_eq!(index.entries, HashMap::from([(BongoLiteral::Int(1), vec![0])]));
+        assert_eq!(index.range(Bound::Unbounded, Bound::Unbounded).unwrap(), None);
+    }
+
+    #[test]
+    fn btree_insert_remove_and_relocate() {
+        let table_dir = PathBuf::from("test_temp/index_btree_insert_remove_and_relocate");
+        fs::create_dir_all(&table_dir).unwrap();
+
+        let mut index = example_index(IndexKind::BTree, &table_dir);
+        index.insert(BongoLiteral::Int(3), 0).unwrap();
+        index.insert(BongoLiteral::Int(1), 10).unwrap();
+        index.insert(BongoLiteral::Int(2), 20).unwrap();
+        index.remove(&BongoLiteral::Int(3), 0).unwrap();
+        index.relocate(&HashMap::from([(10, 0), (20, 10)]), &table_dir).unwrap();
+
+        let result_get = index.get(&BongoLiteral::Int(2)).unwrap();
+        let result_except = index.get_except(&BongoLiteral::Int(2)).unwrap();
+        let result_range = index.range(Bound::Excluded(&BongoLiteral::Int(0)), Bound::Unbounded).unwrap();
+
+        index.remove_file(&table_dir).unwrap();
+        let file_removed = fs::read_dir(&table_dir).unwrap().count() == 0;
+        fs::remove_dir_all(&table_dir).unwrap();
+
+        assert_eq!(vec![10], result_get);
+        assert_eq!(vec![0], result_except);
+        assert_eq!(Some(vec![(BongoLiteral::Int(1), 0), (BongoLiteral::Int(2), 10)]), result_range);
+        assert!(file_removed);
+    }
+}
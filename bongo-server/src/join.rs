@@ -0,0 +1,515 @@
+use std::collections::HashMap;
+
+use bongo_core::datetime::Timestamp;
+use bongo_core::types::{BongoError, BongoLiteral, ColumnDef, Row};
+
+use crate::index::Index;
+use crate::sort::SortKey;
+use crate::statement::{BinOp, Expr, Join, JoinKind, NullLogic, Order, Select, SelectItem};
+
+///
+/// The minimum amount of rows on the left side of a join for which a hash table of the joined table
+/// is built if no index can be used. For fewer rows comparing each row to all rows of the joined
+/// table is cheaper than building the hash table.
+///
+const HASH_JOIN_MIN_ROWS: usize = 64;
+
+///
+/// Returns the column with the name `name` among the columns `cols`, which are qualified by the
+/// name of their table in the form `<table>.<column>`.
+///
+/// An unqualified name matches the column of that name in any table, but only if exactly one of
+/// the tables has such a column.
+///
+pub fn resolve_col(name: &str, cols: &[String]) -> Result<String, BongoError> {
+    let matches = cols.iter()
+        .filter(|col| match name.contains('.') {
+            true => col.as_str() == name,
+            false => col.split_once('.').is_some_and(|(_, col_name)| col_name == name),
+        })
+        .collect::<Vec<&String>>();
+
+    match matches.len() {
+        0 => Err(BongoError::SqlRuntimeError(format!("Column '{name}' does not exist."))),
+        1 => Ok(matches[0].clone()),
+        _ => Err(BongoError::SqlRuntimeError(format!(
+            "Column '{}' is ambiguous. Qualify it with the name of its table, e.g. '{}'.",
+            name, matches[0]
+        ))),
+    }
+}
+
+///
+/// Removes the qualifier from the column name `name` of a statement that only accesses the table `table`.
+///
+pub fn unqualify_col(table: &str, name: &str) -> Result<String, BongoError> {
+    match name.split_once('.') {
+        Some((qualifier, col)) if qualifier == table => Ok(col.to_string()),
+        Some((qualifier, _)) => Err(BongoError::SqlRuntimeError(format!(
+            "Column '{name}' cannot be used, because the table '{qualifier}' is not accessed by the statement."
+        ))),
+        None => Ok(name.to_string()),
+    }
+}
+
+///
+/// Splits `expr` into the operands of its top level `AND` operators.
+///
+fn conjuncts(expr: Expr, parts: &mut Vec<Expr>) {
+    match expr {
+        Expr::BinaryExpr { left, op: BinOp::And, right } => {
+            conjuncts(*left, parts);
+            conjuncts(*right, parts);
+        }
+        _ => parts.push(expr),
+    }
+}
+
+///
+/// Combines `parts` with `AND` operators. Returns `None` if there are no parts.
+///
+fn conjunction(parts: Vec<Expr>) -> Option<Expr> {
+    parts.into_iter().reduce(|left, right| Expr::BinaryExpr {
+        left: Box::new(left),
+        op: BinOp::And,
+        right: Box::new(right),
+    })
+}
+
+///
+/// `JoinQuery` is a `Select` statement whose column names have been resolved against the columns
+/// of all tables it accesses.
+///
+/// The rows of a join are combined by appending the columns of each joined table to the columns
+/// of the rows joined so far, so the columns of joined rows are the columns of all tables in the
+/// order in which they are joined. A `Select` without joins is a `JoinQuery` with a single table.
+///
+#[derive(Debug)]
+pub struct JoinQuery {
+    ///
+    /// The names of the columns of each table qualified by the name of the table in the order in
+    /// which the tables are joined.
+    ///
+    pub cols: Vec<Vec<String>>,
+    ///
+    /// The parts of the condition that only refer to columns of the first table. Their column
+    /// names are unqualified, so that they can be planned like the condition of a single table.
+    ///
+    pub condition: Option<Expr>,
+    ///
+    /// The joins, whose conditions refer to qualified column names.
+    ///
+    pub joins: Vec<Join>,
+    ///
+    /// The parts of the condition that have to be evaluated for the joined rows.
+    ///
+    pub filter: Option<Expr>,
+    ///
+    /// The computed select items, which are appended to the joined rows by `compute`.
+    ///
+    pub computed: Vec<Expr>,
+    ///
+    /// The positions of the selected columns in the joined rows with the computed values appended.
+    ///
+    pub projection: Vec<usize>,
+    ///
+    /// The keys to order the joined rows by.
+    ///
+    pub order: Vec<SortKey>,
+    ///
+    /// The unqualified name of the column to order by if the rows are only ordered by a column of
+    /// the first table in the order of a B-tree index or its reverse. Joining keeps the order of
+    /// the rows of the first table, so they can be read in order from an index on that column.
+    ///
+    pub order_col: Option<String>,
+}
+
+impl JoinQuery {
+    ///
+    /// Resolves `select` against the definitions `tables` of the columns of all tables it accesses,
+    /// given in the order in which they are joined.
+    ///
+    pub fn new(select: &Select, tables: &[(String, Vec<ColumnDef>)]) -> Result<Self, BongoError> {
+        for (i, (name, _)) in tables.iter().enumerate() {
+            if tables[..i].iter().any(|(other, _)| other == name) {
+                return Err(BongoError::UnsupportedFeatureError(format!(
+                    "Table '{}' is accessed more than once. BongoDB does not support aliases for tables.", name
+                )));
+            }
+        }
+
+        let cols = tables.iter()
+            .map(|(table, col_defs)| col_defs.iter().map(|col_def| format!("{}.{}", table, col_def.name)).collect())
+            .collect::<Vec<Vec<String>>>();
+        let all_cols = cols.concat();
+
+        // the condition of a join can only refer to the tables that have been joined up to then
+        let joins = select.joins.iter().enumerate()
+            .map(|(i, join)| {
+                let visible = cols[..i + 2].concat();
+                Ok(Join {
+                    kind: join.kind,
+                    table: join.table.clone(),
+                    condition: join.condition.clone().rename_identifiers(&|name| resolve_col(name, &visible))?,
+                })
+            })
+            .collect::<Result<Vec<Join>, BongoError>>()?;
+
+        let prefix = format!("{}.", select.table);
+        let mut pushed = vec![];
+        let mut filter = vec![];
+        if let Some(condition) = &select.condition {
+            let mut parts = vec![];
+            conjuncts(condition.clone().rename_identifiers(&|name| resolve_col(name, &all_cols))?, &mut parts);
+
+            for part in parts {
+                let mut only_first_table = true;
+                part.visit_identifiers(&mut |name| only_first_table &= name.starts_with(&prefix));
+
+                match only_first_table {
+                    true => pushed.push(part.rename_identifiers(&|name| unqualify_col(&select.table, name))?),
+                    false => filter.push(part),
+                }
+            }
+        }
+
+        let mut computed = vec![];
+        let mut projection = vec![];
+        for item in &select.cols {
+            match item {
+                SelectItem::ColumnName(name) => {
+                    let col = resolve_col(name, &all_cols)?;
+                    // unwrap safe, because the column has been resolved among all columns
+                    projection.push(all_cols.iter().position(|c| c == &col).unwrap());
+                }
+                SelectItem::Wildcard => projection.extend(0..all_cols.len()),
+                SelectItem::QualifiedWildcard(table) => {
+                    let i = match tables.iter().position(|(name, _)| name == table) {
+                        Some(i) => i,
+                        None => return Err(BongoError::SqlRuntimeError(format!(
+                            "Cannot select '{table}.*', because the table '{table}' is not accessed by the statement."
+                        ))),
+                    };
+                    let start = cols[..i].iter().map(|table_cols| table_cols.len()).sum::<usize>();
+                    projection.extend(start..start + cols[i].len());
+                }
+                // aggregates are computed for groups of rows, see `GroupQuery`
+                SelectItem::Aggregate(_) => {}
+                SelectItem::Expr { .. } if select.is_aggregation() => {}
+                SelectItem::Expr { expr, .. } => {
+                    computed.push(expr.clone().rename_identifiers(&|name| resolve_col(name, &all_cols))?);
+                    projection.push(all_cols.len() + computed.len() - 1);
+                }
+            }
+        }
+
+        // the combined rows of groups are ordered by `GroupQuery`
+        let mut order = vec![];
+        for Order { col, asc, nulls_first } in select.order.iter().filter(|_| !select.is_aggregation()) {
+            let col = match select.alias_position(col) {
+                Some(i) => all_cols.len() + i,
+                // unwrap safe, because the column has been resolved among all columns
+                None => {
+                    let col = resolve_col(col, &all_cols)?;
+                    all_cols.iter().position(|c| c == &col).unwrap()
+                }
+            };
+            order.push(SortKey { col, asc: *asc, nulls_first: *nulls_first });
+        }
+
+        // the order of the rows of the first table is lost when they are grouped
+        let order_col = match (select.order.as_slice(), order.as_slice()) {
+            ([order], [key]) if order.is_index_order() && !select.is_aggregation() => {
+                all_cols.get(key.col).and_then(|col| col.strip_prefix(&prefix)).map(|col| col.to_string())
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            cols,
+            condition: conjunction(pushed),
+            joins,
+            filter: conjunction(filter),
+            computed,
+            projection,
+            order,
+            order_col,
+        })
+    }
+
+    ///
+    /// Appends the values of the computed select items to each of the joined `rows`, whose columns
+    /// are `cols`, with the `NullLogic` `logic`.
+    ///
+    pub fn compute(&self, rows: Vec<Row>, cols: &[String], logic: NullLogic) -> Result<Vec<Row>, BongoError> {
+        if self.computed.is_empty() {
+            return Ok(rows);
+        }
+
+        rows.into_iter()
+            .map(|mut row| {
+                let values = self.computed.iter().map(|expr| expr.value(&row, cols, logic)).collect::<Result<Row, BongoError>>()?;
+                row.extend(values);
+                Ok(row)
+            })
+            .collect()
+    }
+}
+
+///
+/// `JoinStrategy` describes how the rows of a joined table that match a row on the left side
+/// of the join are found.
+///
+#[derive(Debug, PartialEq)]
+pub enum JoinStrategy {
+    ///
+    /// The rows are looked up in the index `index` on the column of the joined table that is
+    /// compared with `=` to a column of the left side.
+    ///
+    IndexLookup { index: String },
+    ///
+    /// All rows of the joined table are put into a hash table by the column that is compared
+    /// with `=` to a column of the left side.
+    ///
+    Hash,
+    ///
+    /// Each row of the left side is compared to all rows of the joined table.
+    ///
+    NestedLoop,
+}
+
+///
+/// `JoinPlan` determines how a table is joined to the rows joined so far.
+///
+#[derive(Debug)]
+pub struct JoinPlan {
+    pub strategy: JoinStrategy,
+    ///
+    /// The position of a column of the left side and of a column of the joined table that are
+    /// compared with `=` in the condition of the join. Only rows with equal values in both
+    /// columns can match.
+    ///
+    pub key: Option<(usize, usize)>,
+}
+
+impl JoinPlan {
+    ///
+    /// Plans the join `join`, whose condition has been resolved by a `JoinQuery`, of `left_rows`
+    /// rows with the columns `left_cols` to a table with the columns `cols` and the indexes `indexes`.
+    ///
+    /// An index on the joined table is used if the condition compares an indexed column to a column
+    /// of the left side. Otherwise a hash join is used for larger inputs and a nested loop otherwise.
+    ///
+    pub fn new(join: &Join, left_cols: &[String], cols: &[ColumnDef], indexes: &[Index], left_rows: usize) -> Self {
+        let mut parts = vec![];
+        conjuncts(join.condition.clone(), &mut parts);
+
+        let left_col = |name: &str| left_cols.iter().position(|col| col == name);
+        let joined_col = |name: &str| {
+            let col = name.strip_prefix(&join.table)?.strip_prefix('.')?;
+            cols.iter().position(|col_def| col_def.name == col)
+        };
+
+        let keys = parts.iter()
+            .filter_map(|part| match part {
+                Expr::BinaryExpr { left, op: BinOp::Eq, right } => match (left.as_ref(), right.as_ref()) {
+                    (Expr::Identifier(left), Expr::Identifier(right)) => {
+                        match (left_col(left), joined_col(right), left_col(right), joined_col(left)) {
+                            (Some(left), Some(right), _, _) | (_, _, Some(left), Some(right)) => Some((left, right)),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<(usize, usize)>>();
+
+        let lookup = keys.iter().find_map(|(left, right)| {
+            let index = indexes.iter().find(|index| index.def.col == cols[*right].name)?;
+            Some((JoinStrategy::IndexLookup { index: index.def.name.clone() }, (*left, *right)))
+        });
+
+        match (lookup, keys.first()) {
+            (Some((strategy, key)), _) => Self { strategy, key: Some(key) },
+            (None, Some(key)) if left_rows >= HASH_JOIN_MIN_ROWS => Self { strategy: JoinStrategy::Hash, key: Some(*key) },
+            (None, key) => Self { strategy: JoinStrategy::NestedLoop, key: key.copied() },
+        }
+    }
+
+    ///
+    /// Returns a description of the join, e.g. `Left join table_2 using hash join on table_2.col_1`.
+    ///
+    pub fn describe(&self, join: &Join, cols: &[ColumnDef]) -> String {
+        let kind = match join.kind {
+            JoinKind::Inner => "Inner",
+            JoinKind::Left => "Left",
+        };
+        let strategy = match &self.strategy {
+            JoinStrategy::IndexLookup { index } => format!("index lookup using {}", index),
+            JoinStrategy::Hash => "hash join".to_string(),
+            JoinStrategy::NestedLoop => "nested loop".to_string(),
+        };
+
+        match self.key {
+            Some((_, col)) => format!("{} join {} using {} on {}.{}", kind, join.table, strategy, join.table, cols[col].name),
+            None => format!("{} join {} using {}", kind, join.table, strategy),
+        }
+    }
+}
+
+///
+/// `Candidates` are the rows of a joined table that might match the rows of the left side.
+///
+pub enum Candidates {
+    ///
+    /// All rows of the joined table are candidates for each row of the left side.
+    ///
+    All(Vec<Row>),
+    ///
+    /// The candidates of a row of the left side are the rows stored for the `join_key` of its value
+    /// in the column `col`.
+    ///
+    ByKey { col: usize, rows: HashMap<BongoLiteral, Vec<Row>> },
+}
+
+impl Candidates {
+    fn get(&self, row: &Row) -> &[Row] {
+        match self {
+            Candidates::All(rows) => rows,
+            Candidates::ByKey { col, rows } => rows.get(&join_key(&row[*col])).map_or(&[], |rows| rows.as_slice()),
+        }
+    }
+}
+
+///
+/// Returns the key under which the candidates for the value `val` of a join column are stored.
+///
+/// Numbers of different types can be equal, so all numbers are stored as floating point values.
+/// Numbers that are equal have the same key, numbers with the same key are not necessarily equal.
+/// Dates are stored as the timestamps at the start of their days, to which they are equal.
+///
+pub fn join_key(val: &BongoLiteral) -> BongoLiteral {
+    match (val, val.as_f64()) {
+        (_, Some(val)) => BongoLiteral::Float(val),
+        (BongoLiteral::Date(date), _) => BongoLiteral::Timestamp(Timestamp::from(*date)),
+        _ => val.clone(),
+    }
+}
+
+///
+/// Joins the rows of a table to the rows `left` by appending each candidate that fulfills the
+/// condition of `join` with the `NullLogic` `logic` to a copy of the row of the left side. `cols` are
+/// the columns of the joined rows.
+///
+/// Rows of the left side without a matching row are dropped in an inner join. In a left join
+/// they are kept once with `width` NULL values for the columns of the joined table.
+///
+pub fn join_rows(left: Vec<Row>, cols: &[String], join: &Join, width: usize, candidates: &Candidates,
+                 logic: NullLogic) -> Result<Vec<Row>, BongoError> {
+    let mut joined = vec![];
+
+    for left_row in left {
+        let mut matched = false;
+
+        for candidate in candidates.get(&left_row) {
+            let mut row = left_row.clone();
+            row.extend(candidate.iter().cloned());
+
+            if join.condition.eval(&row, cols, logic)? {
+                joined.push(row);
+                matched = true;
+            }
+        }
+
+        if !matched && join.kind == JoinKind::Left {
+            let mut row = left_row;
+            row.resize(row.len() + width, BongoLiteral::Null);
+            joined.push(row);
+        }
+    }
+
+    Ok(joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bongo_core::types::{BongoDataType, BongoLiteral, ColumnDef};
+
+    use crate::join::{Candidates, join_key, join_rows, JoinQuery, resolve_col};
+    use crate::sort::SortKey;
+    use crate::statement::{BinOp, Expr, Join, JoinKind, NullLogic, Order, Select, SelectItem};
+
+    fn cols(names: &[&str]) -> Vec<ColumnDef> {
+        names.iter().map(|name| ColumnDef { name: name.to_string(), data_type: BongoDataType::Int, not_null: false, default: None, serial: false }).collect()
+    }
+
+    fn eq(left: &str, right: &str) -> Expr {
+        Expr::BinaryExpr {
+            left: Box::new(Expr::Identifier(left.to_string())),
+            op: BinOp::Eq,
+            right: Box::new(Expr::Identifier(right.to_string())),
+        }
+    }
+
+    #[test]
+    fn resolve_qualified_and_unqualified() {
+        let cols = vec!["t1.id".to_string(), "t1.a".to_string(), "t2.id".to_string()];
+
+        assert_eq!(Ok("t1.a".to_string()), resolve_col("a", &cols));
+        assert_eq!(Ok("t2.id".to_string()), resolve_col("t2.id", &cols));
+        // both tables have a column id
+        assert!(resolve_col("id", &cols).is_err());
+        assert!(resolve_col("t2.a", &cols).is_err());
+    }
+
+    #[test]
+    fn condition_is_pushed_to_first_table() {
+        let select = Select {
+            cols: vec![SelectItem::ColumnName("b".to_string()), SelectItem::QualifiedWildcard("t1".to_string())],
+            table: "t1".to_string(),
+            joins: vec![Join { kind: JoinKind::Left, table: "t2".to_string(), condition: eq("t1.id", "t2.id") }],
+            condition: Some(Expr::BinaryExpr {
+                left: Box::new(eq("a", "t1.id")),
+                op: BinOp::And,
+                right: Box::new(eq("b", "t1.id")),
+            }),
+            group_by: vec![],
+            having: None,
+            order: vec![Order { col: "a".to_string(), asc: false, nulls_first: true }],
+            limit: None,
+            offset: None,
+        };
+
+        let query = JoinQuery::new(&select, &[("t1".to_string(), cols(&["id", "a"])), ("t2".to_string(), cols(&["id", "b"]))]).unwrap();
+
+        assert_eq!(Some(eq("a", "id")), query.condition);
+        assert_eq!(Some(eq("t2.b", "t1.id")), query.filter);
+        assert_eq!(vec![3, 0, 1], query.projection);
+        assert_eq!(vec![SortKey { col: 1, asc: false, nulls_first: true }], query.order);
+        assert_eq!(Some("a".to_string()), query.order_col);
+    }
+
+    #[test]
+    fn inner_and_left_join_rows() {
+        let names = vec!["t1.id".to_string(), "t2.id".to_string()];
+        let left = vec![vec![BongoLiteral::Int(1)], vec![BongoLiteral::Int(2)]];
+        let right = vec![vec![BongoLiteral::Int(2)], vec![BongoLiteral::Int(3)]];
+        let mut by_key = HashMap::new();
+        by_key.insert(join_key(&BongoLiteral::Int(2)), vec![vec![BongoLiteral::Int(2)]]);
+
+        let inner = Join { kind: JoinKind::Inner, table: "t2".to_string(), condition: eq("t1.id", "t2.id") };
+        let left_join = Join { kind: JoinKind::Left, ..inner.clone() };
+
+        let nested = join_rows(left.clone(), &names, &inner, 1, &Candidates::All(right), NullLogic::default()).unwrap();
+        let hashed = join_rows(left, &names, &left_join, 1, &Candidates::ByKey { col: 0, rows: by_key }, NullLogic::default()).unwrap();
+
+        assert_eq!(vec![vec![BongoLiteral::Int(2), BongoLiteral::Int(2)]], nested);
+        assert_eq!(vec![
+            vec![BongoLiteral::Int(1), BongoLiteral::Null],
+            vec![BongoLiteral::Int(2), BongoLiteral::Int(2)],
+        ], hashed);
+    }
+}
@@ -1,18 +1,35 @@
 #![feature(iter_intersperse)]
 
+mod aggregate;
+mod btree;
+mod compactor;
 mod executor;
+mod format;
+mod heap;
+mod index;
+mod join;
+mod planner;
+mod sequence;
+mod sort;
 pub mod sql_parser;
 mod statement;
+mod transaction;
 mod unsafe_sync_cell;
+mod wal;
 
 use std::path::Path;
+use std::sync::Arc;
+use std::thread;
 use bongo_core::bongo_request::{BongoRequest, BongoRequestParser};
 use bongo_core::bongo_result::{ToJson};
 use bongo_core::types::{BongoError};
-use webserver::Webserver;
+use webserver::{ConnectionId, Webserver};
+use crate::compactor::{AUTO_VACUUM_INTERVAL, Compactor};
 use crate::executor::Executor;
 use crate::unsafe_sync_cell::UnsafeSyncCell;
 
+pub use crate::statement::NullLogic;
+
 pub struct BongoServer {}
 
 impl BongoServer {
@@ -25,13 +42,25 @@ impl BongoServer {
     /// will return an error if the directory `create_db` does not exist.
     /// * `auto_flush` - Setting this to true causes `BongoServer` to execute a `FLUSH` statement after
     /// each statement automatically.
+    /// * `auto_vacuum` - Setting this to `Some(ratio)` starts a background compactor which periodically
+    /// executes `VACUUM` on each table in which the share of deleted rows among all rows on disc is at
+    /// least `ratio`, see `Compactor`. The ratio must be greater than 0 and at most 1. `None` disables the background compactor.
+    /// * `null_logic` - `Some(logic)` changes how `NULL` values are treated in expressions of the database, see `NullLogic`.
+    ///
+    /// The NULL logic is stored with the database, so `None` keeps the one used before. Databases
+    /// created by earlier versions of BongoDB keep `NullLogic::Legacy`, new databases use `NullLogic::ThreeValued`.
     ///
-    pub async fn start_new<P>(address: &str, db_root: &P, create_db: bool, auto_flush: bool) -> Result<(), BongoError>
+    pub async fn start_new<P>(address: &str, db_root: &P, create_db: bool, auto_flush: bool, auto_vacuum: Option<f64>,
+                              null_logic: Option<NullLogic>) -> Result<(), BongoError>
         where P: AsRef<Path> + std::convert::AsRef<std::ffi::OsStr> + ?Sized {
         if AsRef::<Path>::as_ref(db_root).to_str().is_none() {
             return Err(BongoError::InvalidArgumentError("only paths that are valid unicode are allowed \
             to be used as DB root directory for BongoDB".to_string()));
         }
+        if auto_vacuum.is_some_and(|ratio| ratio <= 0.0 || ratio > 1.0) {
+            return Err(BongoError::InvalidArgumentError("the ghost ratio of the background compactor \
+            must be greater than 0 and at most 1".to_string()));
+        }
 
         println!(
             "Starting BongoServer on {} with database at '{}'",
@@ -39,18 +68,39 @@ impl BongoServer {
         );
 
         // NOTE: Executor itself ensures synchronization of accesses by using RwLock where needed.
-        let ex = UnsafeSyncCell::new(Executor::new(db_root, create_db, auto_flush)?);
+        let mut ex = Executor::new(db_root, create_db, auto_flush)?;
+        if let Some(null_logic) = null_logic {
+            ex.set_null_logic(null_logic)?;
+        }
+        let ex = Arc::new(UnsafeSyncCell::new(ex));
+
+        if let Some(ratio) = auto_vacuum {
+            let ex = ex.clone();
+            let mut compactor = Compactor::new(ratio);
+            thread::spawn(move || loop {
+                thread::sleep(AUTO_VACUUM_INTERVAL);
+                compactor.run(ex.get());
+            });
+        }
+
+        let disconnect_ex = ex.clone();
 
         Err(BongoError::WebServerError(
             Webserver::new(
                 address,
                 BongoRequestParser::new(),
-                move |request: BongoRequest| -> String {
-                    let serialized_response = ex.get().execute(&request).to_json();
+                move |connection_id: ConnectionId, request: BongoRequest| -> String {
+                    let serialized_response = ex.get().execute_for(connection_id, &request).to_json();
                     println!("request: '{}'", request.sql);
                     println!("response: '{serialized_response}'");
                     serialized_response
                 },
+                // a transaction that has not been finished by its connection is rolled back
+                move |connection_id: ConnectionId| {
+                    if let Err(err) = disconnect_ex.get().disconnect(connection_id) {
+                        println!("rollback on disconnect failed: '{:?}'", err);
+                    }
+                },
             )
                 .start()
                 .await,
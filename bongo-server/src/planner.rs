@@ -0,0 +1,728 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::ops::Bound;
+
+use bongo_core::types::{BongoDataType, BongoError, BongoLiteral, ColumnDef};
+
+use crate::btree::compare_keys;
+use crate::index::{Index, IndexKind};
+use crate::statement::{BinOp, Expr, fmt_literal, NullLogic};
+
+///
+/// The share of rows that a lookup of a single key in a B-tree index is estimated to return.
+/// Unlike hash indexes, B-tree indexes do not know how many entries a key has without reading them.
+///
+const BTREE_EQ_SELECTIVITY: f64 = 0.1;
+
+///
+/// The share of rows that a lookup of a range with one bound in a B-tree index is estimated to return.
+///
+const BTREE_OPEN_RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+
+///
+/// The share of rows that a lookup of a range with two bounds in a B-tree index is estimated to return.
+///
+const BTREE_CLOSED_RANGE_SELECTIVITY: f64 = 0.1;
+
+///
+/// An expression that can trivially indexed.
+///
+/// The definition of trivial is here that the expression must be a binary expression where one of the
+/// operands is the indexable, the other operand is a literal and the operator is a comparison, a
+/// `BETWEEN` expression of the indexable and two literals or an `IN` expression of the indexable
+/// and a list of literals.
+///
+struct TrivialIdxExpr {
+    ///
+    /// The column to be indexed.
+    /// Not used yet, might be useful later.
+    ///
+    pub _col: String,
+    ///
+    /// The lookup in the index that returns exactly the rows fulfilling the expression.
+    ///
+    pub lookup: IdxLookup,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IdxLookup {
+    Eq(BongoLiteral),
+    NotEq(BongoLiteral),
+    ///
+    /// All keys equal to one of the values, which are sorted in ascending order without duplicates,
+    /// so that the entries are returned in the order of the keys.
+    ///
+    In(Vec<BongoLiteral>),
+    ///
+    /// All keys between a lower and an upper bound. Ranges can only be looked up in B-tree indexes.
+    ///
+    Range(Bound<BongoLiteral>, Bound<BongoLiteral>),
+}
+
+impl IdxLookup {
+    ///
+    /// Returns the lookup for the expression `<col> <op> <val>`.
+    ///
+    fn from_comparison(op: &BinOp, val: &BongoLiteral) -> Result<Self, ()> {
+        let val = val.clone();
+        match op {
+            BinOp::Eq => Ok(IdxLookup::Eq(val)),
+            BinOp::NotEq => Ok(IdxLookup::NotEq(val)),
+            BinOp::Lt => Ok(IdxLookup::Range(Bound::Unbounded, Bound::Excluded(val))),
+            BinOp::LtEq => Ok(IdxLookup::Range(Bound::Unbounded, Bound::Included(val))),
+            BinOp::Gt => Ok(IdxLookup::Range(Bound::Excluded(val), Bound::Unbounded)),
+            BinOp::GtEq => Ok(IdxLookup::Range(Bound::Included(val), Bound::Unbounded)),
+            _ => Err(())
+        }
+    }
+
+    ///
+    /// Returns the lookup as condition on the column `col`, e.g. `col_1 >= 3 AND col_1 < 5`.
+    ///
+    fn describe(&self, col: &str) -> String {
+        match self {
+            IdxLookup::Eq(val) => format!("{} = {}", col, fmt_literal(val)),
+            IdxLookup::NotEq(val) => format!("{} != {}", col, fmt_literal(val)),
+            IdxLookup::In(vals) => {
+                format!("{} IN ({})", col, vals.iter().map(fmt_literal).collect::<Vec<String>>().join(", "))
+            }
+            IdxLookup::Range(lower, upper) => {
+                let lower = match lower {
+                    Bound::Included(val) => Some(format!("{} >= {}", col, fmt_literal(val))),
+                    Bound::Excluded(val) => Some(format!("{} > {}", col, fmt_literal(val))),
+                    Bound::Unbounded => None,
+                };
+                let upper = match upper {
+                    Bound::Included(val) => Some(format!("{} <= {}", col, fmt_literal(val))),
+                    Bound::Excluded(val) => Some(format!("{} < {}", col, fmt_literal(val))),
+                    Bound::Unbounded => None,
+                };
+
+                match (lower, upper) {
+                    (Some(lower), Some(upper)) => format!("{} AND {}", lower, upper),
+                    (Some(bound), None) | (None, Some(bound)) => bound,
+                    (None, None) => "any key".to_string(),
+                }
+            }
+        }
+    }
+
+    ///
+    /// Returns the lookup with its values converted to the type `d_type` of the indexed column or
+    /// `None` if `index` cannot answer the lookup exactly.
+    ///
+    /// Values are only looked up if their converted value is equal to them, e.g. `1.5` is not
+    /// looked up in an index on an `INT` column.
+    ///
+    fn for_column(self, index: &Index, d_type: &BongoDataType) -> Option<Self> {
+        let convert = |val: BongoLiteral| match d_type.convert(val.clone()) {
+            Ok(converted) if val == BongoLiteral::Null || converted.compare(&val) == Some(Ordering::Equal) => Some(converted),
+            _ => None,
+        };
+
+        match self {
+            IdxLookup::Eq(val) => convert(val).map(IdxLookup::Eq),
+            IdxLookup::NotEq(val) => convert(val).map(IdxLookup::NotEq),
+            IdxLookup::In(vals) => {
+                let mut vals = vals.into_iter().map(convert).collect::<Option<Vec<BongoLiteral>>>()?;
+                // values of different types can be converted to the same value
+                vals.sort_by(compare_keys);
+                vals.dedup();
+                Some(IdxLookup::In(vals))
+            }
+            IdxLookup::Range(lower, upper) => {
+                // NULL values are compared as false with `NullLogic::Legacy`, so only ranges of
+                // numbers, strings, dates and times yield exactly the values fulfilling the expression
+                let orderable = |val: &BongoLiteral| !matches!(val, BongoLiteral::Null | BongoLiteral::Bool(_));
+                let bound = |bound| match bound {
+                    Bound::Included(val) if orderable(&val) => convert(val).map(Bound::Included),
+                    Bound::Excluded(val) if orderable(&val) => convert(val).map(Bound::Excluded),
+                    Bound::Unbounded => Some(Bound::Unbounded),
+                    _ => None,
+                };
+
+                match index.def.kind {
+                    IndexKind::BTree => Some(IdxLookup::Range(bound(lower)?, bound(upper)?)),
+                    IndexKind::Hash => None,
+                }
+            }
+        }
+    }
+}
+
+///
+/// Returns the operator that yields the same result if the operands of `op` are swapped.
+///
+fn swap_operands(op: &BinOp) -> &BinOp {
+    match op {
+        BinOp::Lt => &BinOp::Gt,
+        BinOp::Gt => &BinOp::Lt,
+        BinOp::LtEq => &BinOp::GtEq,
+        BinOp::GtEq => &BinOp::LtEq,
+        _ => op,
+    }
+}
+
+impl TryFrom<(&str, &Expr)> for TrivialIdxExpr {
+    type Error = ();
+    ///
+    /// If an `Expr` can be converted to a `TrivialIdxExpr` all elements fulfilling the expression
+    /// can be calculated using an index on `idx_col`. Composed expressions are decomposed into
+    /// trivially indexable expressions by the `QueryPlan`.
+    ///
+    fn try_from((idx_col, expr): (&str, &Expr)) -> Result<Self, Self::Error> {
+        let (name, lookup) = match expr {
+            Expr::BinaryExpr { left, op, right } => {
+                match (&(**left), &(**right)) {
+                    (Expr::Identifier(name), Expr::Value(val)) => (name, IdxLookup::from_comparison(op, val)?),
+                    // the column is compared to the value from the right
+                    (Expr::Value(val), Expr::Identifier(name)) => (name, IdxLookup::from_comparison(swap_operands(op), val)?),
+                    // not trivially indexable, requires a concrete value and an identifier
+                    _ => return Err(()),
+                }
+            }
+            Expr::Between { expr, negated: false, low, high } => {
+                match (&(**expr), &(**low), &(**high)) {
+                    (Expr::Identifier(name), Expr::Value(low), Expr::Value(high)) => {
+                        (name, IdxLookup::Range(Bound::Included(low.clone()), Bound::Included(high.clone())))
+                    }
+                    // not trivially indexable, requires concrete values and an identifier
+                    _ => return Err(()),
+                }
+            }
+            Expr::InList { expr, list, negated: false } => {
+                let name = match &(**expr) {
+                    Expr::Identifier(name) => name,
+                    _ => return Err(()),
+                };
+                let mut vals = list.iter()
+                    .map(|item| match item {
+                        Expr::Value(val) => Ok(val.clone()),
+                        // not trivially indexable, requires concrete values
+                        _ => Err(()),
+                    })
+                    .collect::<Result<Vec<BongoLiteral>, ()>>()?;
+                vals.sort_by(compare_keys);
+                vals.dedup();
+
+                (name, IdxLookup::In(vals))
+            }
+            // not trivially indexable
+            _ => return Err(()),
+        };
+
+        if name != idx_col {
+            // the identifier is not the indexed column
+            return Err(());
+        }
+
+        Ok(TrivialIdxExpr { _col: name.to_string(), lookup })
+    }
+}
+
+///
+/// `Access` describes how the positions of the row versions that might fulfill a condition are
+/// determined.
+///
+#[derive(Debug, PartialEq)]
+pub enum Access {
+    ///
+    /// All slots of the table are read.
+    ///
+    FullScan,
+    ///
+    /// All entries of the B-tree index `index` are read in the order of the indexed column.
+    ///
+    IndexScan { index: String },
+    ///
+    /// The entries of the index `index` that fulfill `lookup` are read.
+    ///
+    IndexLookup { index: String, lookup: IdxLookup },
+    ///
+    /// The positions that both accesses return. The positions keep the order of the left access.
+    ///
+    Intersect(Box<Access>, Box<Access>),
+    ///
+    /// The positions that any of both accesses return.
+    ///
+    Union(Box<Access>, Box<Access>),
+}
+
+///
+/// A `QueryPlan` determines how the rows that fulfill the condition of a `SELECT`, `UPDATE` or
+/// `DELETE` statement are found.
+///
+/// The condition is decomposed along its `AND` and `OR` operators. Every part that can be looked
+/// up in an index is answered by the index. The positions of the parts of an `AND` are intersected
+/// and the positions of the parts of an `OR` are united. An `OR` can only be answered by indexes if
+/// both of its parts can, because otherwise all rows might fulfill it. Only the residual expression,
+/// i.e. the parts of the condition that are not answered exactly by the indexes, has to be evaluated
+/// for the rows at the resulting positions.
+///
+#[derive(Debug)]
+pub struct QueryPlan {
+    pub access: Access,
+    ///
+    /// The expression that has to be evaluated for each row read from disc. `None` if all rows
+    /// returned by `access` fulfill the condition.
+    ///
+    pub residual: Option<Expr>,
+    ///
+    /// `ordered` is true if the positions returned by `access` are in ascending order of the column
+    /// the rows shall be ordered by, because they have been taken from a B-tree index on that column.
+    ///
+    pub ordered: bool,
+    ///
+    /// The `NullLogic` the condition is evaluated with, which determines whether `!=` is fulfilled
+    /// by `NULL` values.
+    ///
+    logic: NullLogic,
+}
+
+impl QueryPlan {
+    ///
+    /// Plans how to find the rows that fulfill `condition` with the `NullLogic` `logic` in a table
+    /// with the columns `cols` and the indexes `indexes`.
+    ///
+    /// If `order_col` is given and no index can be used for the condition, the positions are taken
+    /// from a B-tree index on `order_col` if there is one, so that the rows do not have to be sorted.
+    ///
+    pub fn new(indexes: &[Index], cols: &[ColumnDef], condition: Option<Expr>, order_col: Option<&str>, logic: NullLogic) -> Self {
+        let order_index = indexes.iter()
+            .find(|index| index.def.kind == IndexKind::BTree && Some(index.def.col.as_str()) == order_col)
+            .map(|index| index.def.name.as_str());
+
+        let planner = Planner { indexes, cols, order_index, logic };
+
+        let access = condition.as_ref().and_then(|condition| planner.access(condition));
+        let residual = condition.and_then(|condition| planner.residual(condition));
+
+        let access = match (access, order_index) {
+            (Some(access), _) => access,
+            (None, Some(index)) => Access::IndexScan { index: index.to_string() },
+            (None, None) => Access::FullScan,
+        };
+        let ordered = planner.is_ordered(&access);
+
+        Self { access, residual, ordered, logic }
+    }
+
+    ///
+    /// Returns the positions of the row versions that might fulfill the condition. `all_positions`
+    /// returns the positions of all slots of the table, which are read if no index can be used.
+    ///
+    pub fn positions<F>(&self, indexes: &[Index], all_positions: F) -> Result<Vec<u64>, BongoError>
+        where F: FnOnce() -> Vec<u64> {
+        match self.access {
+            Access::FullScan => Ok(all_positions()),
+            _ => Self::access_positions(&self.access, indexes, self.logic),
+        }
+    }
+
+    fn access_positions(access: &Access, indexes: &[Index], logic: NullLogic) -> Result<Vec<u64>, BongoError> {
+        match access {
+            Access::FullScan => Err(BongoError::InternalError("A full scan cannot be part of an index access.".to_string())),
+            Access::IndexScan { index } => {
+                // the B-tree index contains all row versions of the table
+                Ok(Self::index(indexes, index)?.range(Bound::Unbounded, Bound::Unbounded)?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(_, pos)| pos)
+                    .collect())
+            }
+            Access::IndexLookup { index, lookup } => {
+                let index = Self::index(indexes, index)?;
+                match lookup {
+                    IdxLookup::Eq(val) => index.get(val),
+                    // all entries except the ones that match the value
+                    IdxLookup::NotEq(val) => {
+                        let positions = index.get_except(val)?;
+                        match logic {
+                            // `NULL != val` is unknown
+                            NullLogic::ThreeValued => {
+                                let nulls = index.get(&BongoLiteral::Null)?.into_iter().collect::<HashSet<u64>>();
+                                Ok(positions.into_iter().filter(|pos| !nulls.contains(pos)).collect())
+                            }
+                            NullLogic::Legacy => Ok(positions),
+                        }
+                    }
+                    IdxLookup::In(vals) => {
+                        let mut positions = vec![];
+                        for val in vals {
+                            positions.append(&mut index.get(val)?);
+                        }
+                        Ok(positions)
+                    }
+                    IdxLookup::Range(lower, upper) => {
+                        let entries = index.range(lower.as_ref(), upper.as_ref())?.unwrap_or_default();
+                        // NULL values are ordered after all other values, but are never within a range
+                        Ok(entries.into_iter()
+                            .filter(|(key, _)| *key != BongoLiteral::Null)
+                            .map(|(_, pos)| pos)
+                            .collect())
+                    }
+                }
+            }
+            Access::Intersect(left, right) => {
+                let right = Self::access_positions(right, indexes, logic)?.into_iter().collect::<HashSet<u64>>();
+                let mut left = Self::access_positions(left, indexes, logic)?;
+                left.retain(|pos| right.contains(pos));
+                Ok(left)
+            }
+            Access::Union(left, right) => {
+                let mut positions = Self::access_positions(left, indexes, logic)?;
+                positions.append(&mut Self::access_positions(right, indexes, logic)?);
+                // rows are read in the order they are stored on disc and each row only once
+                positions.sort_unstable();
+                positions.dedup();
+                Ok(positions)
+            }
+        }
+    }
+
+    ///
+    /// Estimates the amount of rows that are examined, without looking up any row position.
+    /// `row_count` is the amount of live rows in the table.
+    ///
+    pub fn estimate(&self, indexes: &[Index], row_count: usize) -> Result<usize, BongoError> {
+        Self::access_estimate(&self.access, indexes, row_count)
+    }
+
+    fn access_estimate(access: &Access, indexes: &[Index], row_count: usize) -> Result<usize, BongoError> {
+        let share = |selectivity: f64| (row_count as f64 * selectivity).ceil() as usize;
+
+        match access {
+            Access::FullScan | Access::IndexScan { .. } => Ok(row_count),
+            Access::IndexLookup { index, lookup } => {
+                let index = Self::index(indexes, index)?;
+                // hash indexes know the exact amount of entries of each key
+                let key_count = |val: &BongoLiteral| match index.def.kind {
+                    IndexKind::Hash => index.entries.get(val).map_or(0, |positions| positions.len()),
+                    IndexKind::BTree => share(BTREE_EQ_SELECTIVITY),
+                };
+
+                // the entries of the index include expired versions until the table is compacted
+                Ok(match lookup {
+                    IdxLookup::Eq(val) => key_count(val).min(row_count),
+                    IdxLookup::NotEq(val) => row_count.saturating_sub(key_count(val)),
+                    IdxLookup::In(vals) => vals.iter().map(key_count).sum::<usize>().min(row_count),
+                    IdxLookup::Range(Bound::Unbounded, Bound::Unbounded) => row_count,
+                    IdxLookup::Range(Bound::Unbounded, _) | IdxLookup::Range(_, Bound::Unbounded) => share(BTREE_OPEN_RANGE_SELECTIVITY),
+                    IdxLookup::Range(..) => share(BTREE_CLOSED_RANGE_SELECTIVITY),
+                })
+            }
+            Access::Intersect(left, right) => {
+                Ok(Self::access_estimate(left, indexes, row_count)?.min(Self::access_estimate(right, indexes, row_count)?))
+            }
+            Access::Union(left, right) => {
+                let sum = Self::access_estimate(left, indexes, row_count)? + Self::access_estimate(right, indexes, row_count)?;
+                Ok(sum.min(row_count))
+            }
+        }
+    }
+
+    ///
+    /// Returns a description of the access and of the residual expression with one line per step.
+    /// The steps of composed accesses are indented below them.
+    ///
+    pub fn describe(&self, indexes: &[Index]) -> Result<Vec<String>, BongoError> {
+        let mut lines = vec![];
+        Self::describe_access(&self.access, indexes, 0, &mut lines)?;
+
+        match &self.residual {
+            Some(residual) => lines.push(format!("Filter: {}", residual)),
+            None => lines.push("Filter: none".to_string()),
+        }
+
+        Ok(lines)
+    }
+
+    fn describe_access(access: &Access, indexes: &[Index], depth: usize, lines: &mut Vec<String>) -> Result<(), BongoError> {
+        let indent = "  ".repeat(depth);
+
+        match access {
+            Access::FullScan => lines.push(format!("{}Full scan", indent)),
+            Access::IndexScan { index } => lines.push(format!("{}Index scan using {}", indent, index)),
+            Access::IndexLookup { index, lookup } => {
+                let def = &Self::index(indexes, index)?.def;
+                let kind = match def.kind {
+                    IndexKind::Hash => "hash",
+                    IndexKind::BTree => "B-tree",
+                };
+                lines.push(format!("{}Index lookup using {} ({}) where {}", indent, index, kind, lookup.describe(&def.col)));
+            }
+            Access::Intersect(left, right) | Access::Union(left, right) => {
+                let name = match access {
+                    Access::Intersect(..) => "Intersection",
+                    _ => "Union",
+                };
+                lines.push(format!("{}{}", indent, name));
+                Self::describe_access(left, indexes, depth + 1, lines)?;
+                Self::describe_access(right, indexes, depth + 1, lines)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn index<'a>(indexes: &'a [Index], name: &str) -> Result<&'a Index, BongoError> {
+        match indexes.iter().find(|index| index.def.name == name) {
+            Some(index) => Ok(index),
+            None => Err(BongoError::InternalError(format!("The planned index '{}' does not exist.", name))),
+        }
+    }
+}
+
+struct Planner<'a> {
+    indexes: &'a [Index],
+    cols: &'a [ColumnDef],
+    ///
+    /// The name of the B-tree index on the column the rows shall be ordered by.
+    ///
+    order_index: Option<&'a str>,
+    logic: NullLogic,
+}
+
+impl<'a> Planner<'a> {
+    ///
+    /// Returns the access that answers the trivially indexable `expr` exactly. The first index
+    /// that supports the lookup is used.
+    ///
+    fn lookup(&self, expr: &Expr) -> Option<Access> {
+        self.indexes.iter().find_map(|index| {
+            let idx_expr = TrivialIdxExpr::try_from((index.def.col.as_str(), expr)).ok()?;
+            let col = self.cols.iter().find(|col_def| col_def.name == index.def.col)?;
+
+            let lookup = match (idx_expr.lookup, self.logic) {
+                // comparisons with NULL are unknown, so no row fulfills them
+                (IdxLookup::Eq(BongoLiteral::Null) | IdxLookup::NotEq(BongoLiteral::Null), NullLogic::ThreeValued) => return None,
+                (IdxLookup::In(vals), NullLogic::ThreeValued) => {
+                    IdxLookup::In(vals.into_iter().filter(|val| *val != BongoLiteral::Null).collect())
+                }
+                (lookup, _) => lookup,
+            };
+
+            let lookup = lookup.for_column(index, &col.data_type)?;
+            Some(Access::IndexLookup { index: index.def.name.clone(), lookup })
+        })
+    }
+
+    ///
+    /// Returns the access that returns a superset of the positions of all rows fulfilling `expr`
+    /// or `None` if all rows have to be read.
+    ///
+    fn access(&self, expr: &Expr) -> Option<Access> {
+        match expr {
+            Expr::BinaryExpr { left, op: BinOp::And, right } => {
+                match (self.access(left), self.access(right)) {
+                    (Some(left), Some(right)) => {
+                        // the ordered positions are kept by using them as left side of the intersection
+                        match self.is_ordered(&right) && !self.is_ordered(&left) {
+                            true => Some(Access::Intersect(Box::new(right), Box::new(left))),
+                            false => Some(Access::Intersect(Box::new(left), Box::new(right))),
+                        }
+                    }
+                    (Some(access), None) | (None, Some(access)) => Some(access),
+                    (None, None) => None,
+                }
+            }
+            Expr::BinaryExpr { left, op: BinOp::Or, right } => {
+                match (self.access(left), self.access(right)) {
+                    (Some(left), Some(right)) => Some(Access::Union(Box::new(left), Box::new(right))),
+                    // rows that are not returned by an index might fulfill the other side
+                    _ => None,
+                }
+            }
+            _ => self.lookup(expr),
+        }
+    }
+
+    ///
+    /// Returns whether the access of `expr` returns exactly the positions of the rows that fulfill it.
+    ///
+    fn is_exact(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::BinaryExpr { left, op: BinOp::And | BinOp::Or, right } => self.is_exact(left) && self.is_exact(right),
+            _ => self.lookup(expr).is_some(),
+        }
+    }
+
+    ///
+    /// Returns the part of `expr` that still has to be evaluated for the rows returned by the access
+    /// of `expr`. The parts of an `AND` that are answered exactly are left out.
+    ///
+    fn residual(&self, expr: Expr) -> Option<Expr> {
+        if self.is_exact(&expr) {
+            return None;
+        }
+
+        match expr {
+            Expr::BinaryExpr { left, op: BinOp::And, right } => {
+                match (self.residual(*left), self.residual(*right)) {
+                    (Some(left), Some(right)) => Some(Expr::BinaryExpr { left: Box::new(left), op: BinOp::And, right: Box::new(right) }),
+                    (Some(residual), None) | (None, Some(residual)) => Some(residual),
+                    (None, None) => None,
+                }
+            }
+            // the parts of an OR can not be separated, because a row returned for one part might
+            // fulfill the other part
+            _ => Some(expr),
+        }
+    }
+
+    ///
+    /// Returns whether `access` returns the positions in the order of the column the rows shall be ordered by.
+    ///
+    fn is_ordered(&self, access: &Access) -> bool {
+        match access {
+            Access::IndexScan { index } | Access::IndexLookup { index, .. } => Some(index.as_str()) == self.order_index,
+            Access::Intersect(left, _) => self.is_ordered(left),
+            Access::FullScan | Access::Union(..) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use bongo_core::types::{BongoDataType, BongoLiteral, ColumnDef};
+
+    use crate::index::{Index, IndexDef, IndexKind};
+    use crate::planner::{Access, IdxLookup, QueryPlan};
+    use crate::statement::{BinOp, Expr, NullLogic};
+
+    fn cols() -> Vec<ColumnDef> {
+        vec![
+            ColumnDef { name: "col_1".to_string(), data_type: BongoDataType::Int, not_null: false, default: None, serial: false },
+            ColumnDef { name: "col_2".to_string(), data_type: BongoDataType::Int, not_null: false, default: None, serial: false },
+            ColumnDef { name: "col_3".to_string(), data_type: BongoDataType::Int, not_null: false, default: None, serial: false },
+        ]
+    }
+
+    ///
+    /// Returns hash indexes on col_1 and col_2 with the keys 0 to 9 at the positions 0 to 9.
+    ///
+    fn indexes() -> Vec<Index> {
+        ["col_1", "col_2"].iter().enumerate().map(|(i, col)| {
+            let def = IndexDef { name: format!("idx_{}", i + 1), col: col.to_string(), kind: IndexKind::Hash };
+            let mut index = Index::create(def, Path::new("unused")).unwrap();
+            for pos in 0..10 {
+                index.insert(BongoLiteral::Int(pos as i64), pos).unwrap();
+            }
+            index
+        }).collect()
+    }
+
+    fn comparison(col: &str, op: BinOp, val: i64) -> Expr {
+        Expr::BinaryExpr {
+            left: Box::new(Expr::Identifier(col.to_string())),
+            op,
+            right: Box::new(Expr::Value(BongoLiteral::Int(val))),
+        }
+    }
+
+    fn and_or(left: Expr, op: BinOp, right: Expr) -> Expr {
+        Expr::BinaryExpr { left: Box::new(left), op, right: Box::new(right) }
+    }
+
+    fn lookup(index: &str, val: i64) -> Access {
+        Access::IndexLookup { index: index.to_string(), lookup: IdxLookup::Eq(BongoLiteral::Int(val)) }
+    }
+
+    #[test]
+    fn and_intersects_lookups() {
+        let indexes = indexes();
+        let condition = and_or(comparison("col_1", BinOp::Eq, 3), BinOp::And, comparison("col_2", BinOp::Eq, 3));
+
+        let plan = QueryPlan::new(&indexes, &cols(), Some(condition), None, NullLogic::default());
+
+        assert_eq!(Access::Intersect(Box::new(lookup("idx_1", 3)), Box::new(lookup("idx_2", 3))), plan.access);
+        assert_eq!(None, plan.residual);
+        assert_eq!(vec![3], plan.positions(&indexes, Vec::new).unwrap());
+    }
+
+    #[test]
+    fn or_unites_lookups() {
+        let indexes = indexes();
+        let condition = and_or(comparison("col_1", BinOp::Eq, 7), BinOp::Or, comparison("col_2", BinOp::Eq, 2));
+
+        let plan = QueryPlan::new(&indexes, &cols(), Some(condition), None, NullLogic::default());
+
+        assert_eq!(Access::Union(Box::new(lookup("idx_1", 7)), Box::new(lookup("idx_2", 2))), plan.access);
+        assert_eq!(None, plan.residual);
+        assert_eq!(vec![2, 7], plan.positions(&indexes, Vec::new).unwrap());
+    }
+
+    #[test]
+    fn residual_contains_parts_without_index() {
+        let indexes = indexes();
+        // (col_1 = 1 OR col_3 = 1) AND col_2 = 1 AND col_3 > 0
+        let condition = and_or(
+            and_or(
+                and_or(comparison("col_1", BinOp::Eq, 1), BinOp::Or, comparison("col_3", BinOp::Eq, 1)),
+                BinOp::And,
+                comparison("col_2", BinOp::Eq, 1),
+            ),
+            BinOp::And,
+            comparison("col_3", BinOp::Gt, 0),
+        );
+
+        let plan = QueryPlan::new(&indexes, &cols(), Some(condition), None, NullLogic::default());
+
+        assert_eq!(lookup("idx_2", 1), plan.access);
+        assert_eq!(Some(and_or(
+            and_or(comparison("col_1", BinOp::Eq, 1), BinOp::Or, comparison("col_3", BinOp::Eq, 1)),
+            BinOp::And,
+            comparison("col_3", BinOp::Gt, 0),
+        )), plan.residual);
+    }
+
+    #[test]
+    fn or_with_unindexed_part_scans_table() {
+        let indexes = indexes();
+        let condition = and_or(comparison("col_1", BinOp::Eq, 1), BinOp::Or, comparison("col_3", BinOp::Eq, 1));
+
+        let plan = QueryPlan::new(&indexes, &cols(), Some(condition), None, NullLogic::default());
+
+        assert_eq!(Access::FullScan, plan.access);
+        assert!(plan.residual.is_some());
+        assert_eq!(vec![0, 1], plan.positions(&indexes, || vec![0, 1]).unwrap());
+    }
+
+    #[test]
+    fn range_requires_btree_index() {
+        let indexes = indexes();
+        let condition = comparison("col_1", BinOp::Lt, 5);
+
+        let plan = QueryPlan::new(&indexes, &cols(), Some(condition), Some("col_1"), NullLogic::default());
+
+        assert_eq!(Access::FullScan, plan.access);
+        assert!(!plan.ordered);
+        assert_eq!(Some(comparison("col_1", BinOp::Lt, 5)), plan.residual);
+    }
+
+    #[test]
+    fn in_list_is_looked_up() {
+        let indexes = indexes();
+        let in_list = |list: Vec<Expr>| Expr::InList { expr: Box::new(Expr::Identifier("col_2".to_string())), list, negated: false };
+        let vals = |vals: &[i64]| vals.iter().map(|val| Expr::Value(BongoLiteral::Int(*val))).collect::<Vec<Expr>>();
+
+        let plan = QueryPlan::new(&indexes, &cols(), Some(in_list(vals(&[8, 2, 8, 42]))), None, NullLogic::default());
+
+        assert_eq!(Access::IndexLookup {
+            index: "idx_2".to_string(),
+            lookup: IdxLookup::In(vec![BongoLiteral::Int(2), BongoLiteral::Int(8), BongoLiteral::Int(42)]),
+        }, plan.access);
+        assert_eq!(None, plan.residual);
+        assert_eq!(vec![2, 8], plan.positions(&indexes, Vec::new).unwrap());
+
+        // only lists of values can be looked up
+        let mut list = vals(&[1]);
+        list.push(Expr::Identifier("col_3".to_string()));
+        let plan = QueryPlan::new(&indexes, &cols(), Some(in_list(list)), None, NullLogic::default());
+
+        assert_eq!(Access::FullScan, plan.access);
+        assert!(plan.residual.is_some());
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bongo_core::types::BongoError;
+use serde::{Deserialize, Serialize};
+
+use crate::executor::Executor;
+
+///
+/// Name of the file inside the DB root directory that holds the sequences of the database.
+///
+pub const SEQUENCES_FILE_NAME: &str = "sequences.bongo";
+
+///
+/// Number of values of a sequence that are reserved on disc at once.
+///
+const RESERVED_VALUES: i64 = 32;
+
+///
+/// `Sequence` is a counter created with `CREATE SEQUENCE`, whose values are given out by `nextval`.
+///
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Sequence {
+    ///
+    /// The value the next call of `nextval` returns.
+    ///
+    pub next: i64,
+    pub increment: i64,
+}
+
+///
+/// `Sequences` holds all sequences of a database and stores them in sequences.bongo.
+///
+/// Sequences are not transactional: A value that has been given out is never given out again,
+/// even if the statement that used it fails or its transaction is rolled back. To avoid writing
+/// the file for every value, values are reserved in blocks of `RESERVED_VALUES`. The file holds
+/// the first value after the reserved ones, so that after a crash the sequence continues behind
+/// all values that might have been given out. The values that have been reserved but not given
+/// out are skipped then. `sync` writes the exact state, which avoids the gap after a regular shutdown.
+///
+pub struct Sequences {
+    path: PathBuf,
+    sequences: BTreeMap<String, Sequence>,
+    ///
+    /// `reserved` is the number of values of each sequence that can still be given out before the
+    /// next block has to be reserved on disc.
+    ///
+    reserved: HashMap<String, i64>,
+}
+
+impl Sequences {
+    ///
+    /// Loads the sequences of the database inside of `db_root`. A database without sequences has no
+    /// sequences.bongo file.
+    ///
+    pub fn open(db_root: &Path) -> Result<Self, BongoError> {
+        let path = db_root.join(SEQUENCES_FILE_NAME);
+        let sequences = match path.exists() {
+            true => match fs::read(&path).ok().and_then(|bytes| bincode::deserialize(&bytes).ok()) {
+                Some(sequences) => sequences,
+                None => return Err(BongoError::ReadFileError(format!(
+                    "Could not read the sequences of the database at '{}'", path.to_str().unwrap()
+                ))),
+            },
+            false => BTreeMap::new(),
+        };
+
+        Ok(Self { path, sequences, reserved: HashMap::new() })
+    }
+
+    ///
+    /// Adds the sequence `name` and writes it to disc. Returns an error if it already exists.
+    ///
+    pub fn create(&mut self, name: &str, sequence: Sequence) -> Result<(), BongoError> {
+        if self.sequences.contains_key(name) {
+            return Err(BongoError::SqlRuntimeError(format!("Sequence '{}' already exists", name)));
+        }
+        self.sequences.insert(name.to_string(), sequence);
+
+        self.write()
+    }
+
+    ///
+    /// Removes the sequence `name` and returns whether it has existed.
+    ///
+    pub fn remove(&mut self, name: &str) -> Result<bool, BongoError> {
+        if self.sequences.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.reserved.remove(name);
+        self.write()?;
+
+        Ok(true)
+    }
+
+    ///
+    /// Returns the next value of the sequence `name` and advances the sequence.
+    ///
+    pub fn next_value(&mut self, name: &str) -> Result<i64, BongoError> {
+        let sequence = match self.sequences.get(name) {
+            Some(sequence) => sequence,
+            None => return Err(BongoError::SqlRuntimeError(format!("Sequence '{}' does not exist", name))),
+        };
+        let value = sequence.next;
+        let next = match value.checked_add(sequence.increment) {
+            Some(next) => next,
+            None => return Err(BongoError::SqlRuntimeError(format!("Sequence '{}' has no values left", name))),
+        };
+
+        if self.reserved.get(name).copied().unwrap_or(0) == 0 {
+            self.reserved.insert(name.to_string(), RESERVED_VALUES);
+            self.write()?;
+        }
+        // unwraps safe, because the sequence exists and a block of values is reserved
+        *self.reserved.get_mut(name).unwrap() -= 1;
+        self.sequences.get_mut(name).unwrap().next = next;
+
+        Ok(value)
+    }
+
+    ///
+    /// Writes the exact state of all sequences to disc, which releases all reserved values.
+    ///
+    pub fn sync(&mut self) -> Result<(), BongoError> {
+        if self.reserved.values().all(|reserved| *reserved == 0) {
+            return Ok(());
+        }
+        self.reserved.clear();
+
+        self.write()
+    }
+
+    fn write(&self) -> Result<(), BongoError> {
+        let persisted = self.sequences.iter()
+            .map(|(name, sequence)| {
+                let reserved = self.reserved.get(name).copied().unwrap_or(0);
+                let next = sequence.next.saturating_add(sequence.increment.saturating_mul(reserved));
+                (name, Sequence { next, increment: sequence.increment })
+            })
+            .collect::<BTreeMap<&String, Sequence>>();
+
+        match bincode::serialize(&persisted) {
+            Ok(bytes) => Executor::write_file_atomically(&self.path, &bytes),
+            Err(_) => Err(BongoError::InternalError("Could not serialize the sequences of the database.".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use bongo_core::types::BongoError;
+
+    use crate::sequence::{Sequence, Sequences};
+
+    #[test]
+    fn values_survive_restarts() {
+        let dir = PathBuf::from("test_temp/sequence_values_survive_restarts");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut sequences = Sequences::open(&dir).unwrap();
+        sequences.create("up", Sequence { next: 1, increment: 1 }).unwrap();
+        sequences.create("down", Sequence { next: 10, increment: -5 }).unwrap();
+        assert!(matches!(sequences.create("up", Sequence { next: 1, increment: 1 }), Err(BongoError::SqlRuntimeError(_))));
+        assert_eq!(vec![1, 2], vec![sequences.next_value("up").unwrap(), sequences.next_value("up").unwrap()]);
+        assert_eq!(vec![10, 5], vec![sequences.next_value("down").unwrap(), sequences.next_value("down").unwrap()]);
+
+        // without a sync the reserved values are skipped as after a crash
+        let mut reopened = Sequences::open(&dir).unwrap();
+        assert_eq!(33, reopened.next_value("up").unwrap());
+        assert_eq!(-150, reopened.next_value("down").unwrap());
+
+        sequences.sync().unwrap();
+        let mut reopened = Sequences::open(&dir).unwrap();
+        assert_eq!(3, reopened.next_value("up").unwrap());
+        assert!(reopened.remove("down").unwrap());
+        assert!(!reopened.remove("down").unwrap());
+        assert!(matches!(reopened.next_value("down"), Err(BongoError::SqlRuntimeError(_))));
+
+        let mut last = Sequences::open(&dir).unwrap();
+        last.create("last", Sequence { next: i64::MAX - 1, increment: 1 }).unwrap();
+        assert_eq!(i64::MAX - 1, last.next_value("last").unwrap());
+        assert!(matches!(last.next_value("last"), Err(BongoError::SqlRuntimeError(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+use std::cmp::Ordering;
+
+use bongo_core::types::{BongoLiteral, Row};
+
+use crate::btree::compare_keys;
+
+///
+/// `SortKey` is a column of `ORDER BY` whose position in the sorted rows has been resolved.
+///
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SortKey {
+    pub col: usize,
+    pub asc: bool,
+    pub nulls_first: bool,
+}
+
+///
+/// Compares the rows `r1` and `r2` by the first of the `keys` in which they differ.
+///
+/// NULL values are equal to each other and are ordered before or after all other values as given
+/// by `nulls_first`, independent of the direction of the key. Other values of the same type are
+/// compared by their value, so the order is a total order.
+///
+pub fn compare_rows(r1: &Row, r2: &Row, keys: &[SortKey]) -> Ordering {
+    keys.iter()
+        .map(|key| match (&r1[key.col], &r2[key.col]) {
+            (BongoLiteral::Null, BongoLiteral::Null) => Ordering::Equal,
+            (BongoLiteral::Null, _) if key.nulls_first => Ordering::Less,
+            (BongoLiteral::Null, _) => Ordering::Greater,
+            (_, BongoLiteral::Null) if key.nulls_first => Ordering::Greater,
+            (_, BongoLiteral::Null) => Ordering::Less,
+            (v1, v2) if key.asc => compare_keys(v1, v2),
+            (v1, v2) => compare_keys(v2, v1),
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+///
+/// Sorts `rows` by `cmp` and returns at most the first `limit` rows.
+///
+/// If only some of the rows are needed, the `limit` smallest rows are selected with a bounded
+/// max-heap, which only keeps `limit` rows at a time, before they are sorted. This avoids sorting
+/// all rows for e.g. `ORDER BY col_1 LIMIT 10`. Rows that are equal according to `cmp` keep their
+/// relative order in both cases.
+///
+pub fn sort_rows<F>(mut rows: Vec<Row>, limit: Option<usize>, cmp: F) -> Vec<Row>
+    where F: Fn(&Row, &Row) -> Ordering {
+    let limit = match limit {
+        Some(limit) if limit < rows.len() => limit,
+        _ => {
+            rows.sort_by(cmp);
+            return rows;
+        }
+    };
+
+    // rows are compared by their position in `rows` if they are equal to keep the sort stable
+    let cmp = |(i, r1): &(usize, Row), (j, r2): &(usize, Row)| cmp(r1, r2).then(i.cmp(j));
+
+    // the largest row of `heap` is at index 0
+    let mut heap: Vec<(usize, Row)> = Vec::with_capacity(limit);
+    for row in rows.into_iter().enumerate() {
+        if heap.len() < limit {
+            heap.push(row);
+            sift_up(&mut heap, &cmp);
+        } else if limit > 0 && cmp(&row, &heap[0]) == Ordering::Less {
+            heap[0] = row;
+            sift_down(&mut heap, &cmp);
+        }
+    }
+
+    heap.sort_by(cmp);
+    heap.into_iter().map(|(_, row)| row).collect()
+}
+
+///
+/// Moves the last element of the max-heap `heap` up to its position.
+///
+fn sift_up<T, F>(heap: &mut [T], cmp: &F) where F: Fn(&T, &T) -> Ordering {
+    let mut i = heap.len() - 1;
+    while i > 0 {
+        let parent = (i - 1) / 2;
+        if cmp(&heap[i], &heap[parent]) != Ordering::Greater {
+            break;
+        }
+        heap.swap(i, parent);
+        i = parent;
+    }
+}
+
+///
+/// Moves the first element of the max-heap `heap` down to its position.
+///
+fn sift_down<T, F>(heap: &mut [T], cmp: &F) where F: Fn(&T, &T) -> Ordering {
+    let mut i = 0;
+    loop {
+        let mut largest = i;
+        for child in [2 * i + 1, 2 * i + 2] {
+            if child < heap.len() && cmp(&heap[child], &heap[largest]) == Ordering::Greater {
+                largest = child;
+            }
+        }
+        if largest == i {
+            break;
+        }
+        heap.swap(i, largest);
+        i = largest;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bongo_core::types::BongoLiteral::{Int, Null, Varchar};
+    use bongo_core::types::Row;
+
+    use crate::btree::compare_keys;
+    use crate::sort::{compare_rows, sort_rows, SortKey};
+
+    fn rows() -> Vec<Row> {
+        [5, 3, 9, 1, 3, 7, 0, 3, 8].iter().enumerate()
+            .map(|(i, val)| vec![Int(*val), Varchar(i.to_string())])
+            .collect()
+    }
+
+    #[test]
+    fn top_k_equals_sorted_prefix() {
+        let cmp = |r1: &Row, r2: &Row| compare_keys(&r1[0], &r2[0]);
+        let sorted = sort_rows(rows(), None, cmp);
+
+        for limit in 0..=rows().len() + 1 {
+            let expected = sorted.iter().take(limit).cloned().collect::<Vec<Row>>();
+            assert_eq!(expected, sort_rows(rows(), Some(limit), cmp));
+        }
+    }
+
+    #[test]
+    fn multiple_keys_and_nulls() {
+        let rows = vec![
+            vec![Int(1), Null],
+            vec![Null, Int(2)],
+            vec![Int(1), Int(3)],
+            vec![Int(2), Int(1)],
+            vec![Null, Null],
+        ];
+        let keys = [
+            SortKey { col: 0, asc: false, nulls_first: false },
+            SortKey { col: 1, asc: true, nulls_first: true },
+        ];
+
+        assert_eq!(vec![
+            vec![Int(2), Int(1)],
+            vec![Int(1), Null],
+            vec![Int(1), Int(3)],
+            vec![Null, Null],
+            vec![Null, Int(2)],
+        ], sort_rows(rows, None, |r1, r2| compare_rows(r1, r2, &keys)));
+    }
+
+    #[test]
+    fn descending_top_k_is_stable() {
+        let top = sort_rows(rows(), Some(4), |r1, r2| compare_keys(&r2[0], &r1[0]));
+
+        assert_eq!(vec![
+            vec![Int(9), Varchar("2".to_string())],
+            vec![Int(8), Varchar("8".to_string())],
+            vec![Int(7), Varchar("5".to_string())],
+            vec![Int(5), Varchar("0".to_string())],
+        ], top);
+
+        let top = sort_rows(rows(), Some(4), |r1, r2| compare_keys(&r1[0], &r2[0]));
+        // equal rows keep the order in which they have been read
+        assert_eq!(vec!["6", "3", "1", "4"], top.iter().map(|row| match &row[1] {
+            Varchar(i) => i.as_str(),
+            _ => panic!("unexpected value"),
+        }).collect::<Vec<&str>>());
+    }
+}
@@ -22,19 +22,13 @@ pub fn unsupported_feature_err<T>(err_message: &str) -> Result<T, BongoError> {
 pub fn only_single_table_from_err<T>() -> Result<T, BongoError> {
     unsupported_feature_err(
         "Only single identifiers are supported in a list of tables \
-    i.e. multiple tables are not supported. Example 1: Select col_1 FROM table_1; Example 2: \
-    UPDATE table_name SET col_1 = 5;",
-    )
-}
-
-pub fn order_by_only_one_column_err<T>() -> Result<T, BongoError> {
-    unsupported_feature_err(
-        "ORDER BY is only supported with exactly one argument which must be a column name.",
+    i.e. multiple tables are not supported. Tables can only be combined with JOIN in a SELECT \
+    statement. Example 1: Select col_1 FROM table_1; Example 2: UPDATE table_name SET col_1 = 5;",
     )
 }
 
 pub fn insert_list_only_literals<T>() -> Result<T, BongoError> {
-    syntax_error("Only literals can appear in VALUES lists of insert statements")
+    syntax_error("Only literals and expressions without columns can appear in VALUES lists of insert statements")
 }
 
 pub fn generic_write_error<T>() -> Result<T, BongoError> {
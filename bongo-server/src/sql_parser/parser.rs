@@ -1,18 +1,22 @@
 use std::convert::TryFrom;
 
 use bongo_core::conversions::{TryConvertAllExt, TryConvertOption};
-use bongo_core::types::{BongoError, ColumnDef as BongoColDef, Row};
+use bongo_core::types::{BongoDataType, BongoError, BongoLiteral, ColumnDef as BongoColDef, Row};
 use sqlparser::ast::{
-    ColumnDef, Expr, Ident, ObjectName, ObjectType, OrderByExpr, Query, SetExpr, Statement as Ast,
-    TableFactor, TableWithJoins,
+    AlterTableOperation, ColumnDef, ColumnOption, Expr, FunctionArg, Ident, Join as SqlParserJoin, JoinConstraint, JoinOperator, ObjectName,
+    ObjectType, Query, ReferentialAction, SetExpr, Statement as Ast, TableConstraint, TableFactor, TableWithJoins, Value,
 };
 use sqlparser::dialect::GenericDialect;
+use sqlparser::dialect::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::{Token, Tokenizer};
 
+use crate::index::IndexKind;
 use crate::sql_parser::err_messages::*;
 use crate::statement::{
-    Assignment as BongoAssignment, CreateTable, Delete, DropTable, Expr as BongoExpr, Insert,
-    Order, Select, SelectItem as BongoSelectItem, Statement, Update,
+    AlterTable, AlterTableOperation as BongoAlterTableOperation, Assignment as BongoAssignment, CreateIndex, CreateSequence, CreateTable, Delete,
+    DropIndex, DropSequence, DropTable, Explain, Expr as BongoExpr, ForeignKey, Insert, Join, JoinKind, NextVal, NullLogic, Order,
+    ReferentialAction as BongoReferentialAction, Select, SelectItem as BongoSelectItem, Statement, UniqueConstraint, Update, Vacuum,
 };
 
 ///
@@ -35,33 +39,133 @@ impl SqlParser {
     pub fn parse(sql: &str) -> Result<Statement, BongoError> {
         let dialect = GenericDialect {};
 
-        match Self::parse_custom_statement(sql) {
+        match Self::parse_custom_statement(sql)? {
             None => {}
             Some(stmt) => {
                 return Ok(stmt);
             }
         }
 
-        let parse_result: Result<Vec<Ast>, ParserError> = Parser::parse_sql(&dialect, sql);
+        // the used version of `sqlparser` does not support USING in CREATE INDEX statements
+        let (sql, index_kind) = match Self::strip_index_kind(sql)? {
+            Some((stripped, kind)) => (stripped, Some(kind)),
+            None => (sql.to_string(), None),
+        };
+        // nor ALTER COLUMN in ALTER TABLE statements
+        let sql = Self::rewrite_alter_column_type(&sql).unwrap_or(sql);
+
+        let parse_result: Result<Vec<Ast>, ParserError> = Parser::parse_sql(&dialect, &sql);
 
         match parse_result {
             Ok(mut statements) => {
                 if statements.is_empty() {
                     return Err(BongoError::EmptySqlStatementError);
                 }
-                Self::ast_to_statement(statements.remove(0))
+                match (Self::ast_to_statement(statements.remove(0))?, index_kind) {
+                    (Statement::CreateIndex(mut create_index), Some(kind)) => {
+                        create_index.kind = kind;
+                        Ok(Statement::CreateIndex(create_index))
+                    }
+                    (statement, _) => Ok(statement),
+                }
             }
             Err(err) => Err(BongoError::from(err)),
         }
     }
 
     ///
-    /// Parses BongoDB specific statements.
+    /// Removes the clause `USING <method>` from a `CREATE [UNIQUE] INDEX` statement and returns
+    /// the remaining statement together with the kind of index given by the method.
+    ///
+    /// Returns `None` if `sql` is no `CREATE INDEX` statement or does not contain the clause.
+    ///
+    fn strip_index_kind(sql: &str) -> Result<Option<(String, IndexKind)>, BongoError> {
+        let words = sql.split_whitespace()
+            .take(3)
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<String>>();
+        let is_create_index = words.len() == 3 && words[0] == "create" &&
+            (words[1] == "index" || (words[1] == "unique" && words[2] == "index"));
+        if !is_create_index {
+            return Ok(None);
+        }
+
+        // ASCII lowercase keeps the positions of all characters
+        let lower = sql.to_ascii_lowercase();
+        let start = lower.match_indices("using").map(|(i, _)| i).find(|i| {
+            let before = lower[..*i].chars().last();
+            let after = lower[i + "using".len()..].chars().next();
+            matches!(before, Some(c) if c.is_whitespace() || c == ')') && matches!(after, Some(c) if c.is_whitespace())
+        });
+        let start = match start {
+            Some(start) => start,
+            None => return Ok(None),
+        };
+
+        let rest = &lower[start + "using".len()..];
+        let method_start = start + "using".len() + (rest.len() - rest.trim_start().len());
+        let method_len = lower[method_start..]
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(lower.len() - method_start);
+
+        let kind = match &lower[method_start..method_start + method_len] {
+            "btree" => IndexKind::BTree,
+            "hash" => IndexKind::Hash,
+            method => return unsupported_feature_err(&format!(
+                "BongoDB does not support the index method '{}'. Supported methods are BTREE and HASH.",
+                method
+            )),
+        };
+
+        Ok(Some((format!("{} {}", &sql[..start], &sql[method_start + method_len..]), kind)))
+    }
+
+    ///
+    /// Rewrites `ALTER TABLE <table> ALTER [COLUMN] <column> [SET DATA] TYPE <datatype>` to the
+    /// equivalent `ALTER TABLE <table> CHANGE COLUMN <column> <column> <datatype>`, which can be parsed.
+    ///
+    /// Returns `None` if `sql` is no such statement.
+    ///
+    fn rewrite_alter_column_type(sql: &str) -> Option<String> {
+        // the start of each of the first words in `sql`
+        let mut starts = vec![];
+        let mut prev = ' ';
+        for (i, c) in sql.char_indices() {
+            if starts.len() > 9 {
+                break;
+            }
+            if !c.is_whitespace() && prev.is_whitespace() {
+                starts.push(i);
+            }
+            prev = c;
+        }
+        let word = |n: usize| starts.get(n).map(|start| sql[*start..].split_whitespace().next().unwrap().to_lowercase());
+
+        if word(0)? != "alter" || word(1)? != "table" || word(3)? != "alter" {
+            return None;
+        }
+        let col = if word(4)? == "column" { 5 } else { 4 };
+        let data_type = match (word(col + 1)?.as_str(), word(col + 2)?.as_str(), word(col + 3)) {
+            ("type", ..) => col + 2,
+            ("set", "data", Some(word)) if word == "type" => col + 4,
+            _ => return None,
+        };
+        let col_name = sql[starts[col]..].split_whitespace().next()?;
+
+        Some(format!(
+            "{} CHANGE COLUMN {} {} {}",
+            &sql[..starts[3]], col_name, col_name, &sql[*starts.get(data_type)?..]
+        ))
+    }
+
+    ///
+    /// Parses BongoDB specific statements and statements the used version of `sqlparser` does
+    /// not support.
     ///
-    /// Currently the following BongoDB specific statements exist:
-    /// Flush
+    /// Currently the following such statements exist:
+    /// Flush, Vacuum, Insert with DEFAULT VALUES, CreateSequence, DropSequence
     ///
-    fn parse_custom_statement(sql: &str) -> Option<Statement> {
+    fn parse_custom_statement(sql: &str) -> Result<Option<Statement>, BongoError> {
         if sql
             .chars()
             .filter(|c| !c.is_whitespace())
@@ -69,10 +173,121 @@ impl SqlParser {
             .to_lowercase()
             == "flush;"
         {
-            return Some(Statement::Flush);
+            return Ok(Some(Statement::Flush));
+        }
+
+        match Self::parse_vacuum(sql).or_else(|| Self::parse_default_values(sql)) {
+            Some(statement) => Ok(Some(statement)),
+            None => Self::parse_sequence(sql),
+        }
+    }
+
+    ///
+    /// Parses `INSERT INTO <table> DEFAULT VALUES;`, which inserts one row with the default values
+    /// of all columns.
+    ///
+    fn parse_default_values(sql: &str) -> Option<Statement> {
+        let dialect = GenericDialect {};
+        let tokens = Tokenizer::new(&dialect, sql).tokenize().ok()?;
+        let mut tokens = tokens.iter().filter(|token| !matches!(token, Token::Whitespace(_)));
+
+        // quoted identifiers are no keywords, so the words have to be unquoted
+        let mut next_word = || match tokens.next() {
+            Some(Token::Word(word)) if word.quote_style.is_none() => Some(word),
+            _ => None,
+        };
+        if next_word()?.keyword != Keyword::INSERT || next_word()?.keyword != Keyword::INTO {
+            return None;
+        }
+        let table = next_word()?.value.clone();
+        if next_word()?.keyword != Keyword::DEFAULT || next_word()?.keyword != Keyword::VALUES {
+            return None;
         }
 
-        None
+        match (tokens.next(), tokens.next()) {
+            (None, _) | (Some(Token::SemiColon), None) => Some(Statement::Insert(Insert {
+                table,
+                cols: vec![],
+                rows: vec![vec![]],
+                nextvals: vec![],
+            })),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Parses `VACUUM [<table>];`.
+    ///
+    fn parse_vacuum(sql: &str) -> Option<Statement> {
+        let words = sql.trim().strip_suffix(';')?.split_whitespace().collect::<Vec<&str>>();
+
+        if words.is_empty() || words[0].to_lowercase() != "vacuum" {
+            return None;
+        }
+
+        match words.len() {
+            1 => Some(Statement::Vacuum(Vacuum { table: None })),
+            2 if words[1].chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                Some(Statement::Vacuum(Vacuum { table: Some(words[1].to_string()) }))
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// Parses `CREATE SEQUENCE <name> [INCREMENT [BY] <n>] [START [WITH] <n>];` and
+    /// `DROP SEQUENCE [IF EXISTS] <name>;`.
+    ///
+    /// Returns `None` if `sql` is no such statement.
+    ///
+    fn parse_sequence(sql: &str) -> Result<Option<Statement>, BongoError> {
+        let words = sql.trim().trim_end_matches(';').split_whitespace().collect::<Vec<&str>>();
+        let keyword = |i: usize| words.get(i).map(|word| word.to_lowercase());
+        let is_name = |word: &&str| word.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if keyword(1).as_deref() != Some("sequence") {
+            return Ok(None);
+        }
+
+        match keyword(0).as_deref() {
+            Some("drop") => {
+                let if_exists = keyword(2).as_deref() == Some("if") && keyword(3).as_deref() == Some("exists");
+                match &words[if if_exists { 4 } else { 2 }..] {
+                    [name] if is_name(name) => Ok(Some(Statement::DropSequence(DropSequence { name: name.to_string(), if_exists }))),
+                    _ => syntax_error("Expected a statement like DROP SEQUENCE [IF EXISTS] <name>;"),
+                }
+            }
+            Some("create") => {
+                let name = match words.get(2) {
+                    Some(name) if is_name(name) => name.to_string(),
+                    _ => return syntax_error("Expected the name of the sequence after CREATE SEQUENCE."),
+                };
+                let (mut start, mut increment) = (None, None);
+                let mut i = 3;
+                while i < words.len() {
+                    let (option, filler) = match keyword(i).as_deref() {
+                        Some("start") => (&mut start, "with"),
+                        Some("increment") => (&mut increment, "by"),
+                        _ => return unsupported_feature_err("BongoDB only supports the options START [WITH] and INCREMENT [BY] of a sequence."),
+                    };
+                    i += if keyword(i + 1).as_deref() == Some(filler) { 2 } else { 1 };
+                    match words.get(i).and_then(|word| word.parse::<i64>().ok()) {
+                        Some(value) if option.is_none() => *option = Some(value),
+                        _ => return syntax_error("Each option of a sequence has to be given once with an integer."),
+                    }
+                    i += 1;
+                }
+                if increment == Some(0) {
+                    return syntax_error("The increment of a sequence cannot be 0.");
+                }
+
+                Ok(Some(Statement::CreateSequence(CreateSequence {
+                    name,
+                    start: start.unwrap_or(1),
+                    increment: increment.unwrap_or(1),
+                })))
+            }
+            _ => Ok(None),
+        }
     }
 
     fn ast_to_statement(ast: Ast) -> Result<Statement, BongoError> {
@@ -83,48 +298,123 @@ impl SqlParser {
             Ast::Delete { .. } => Self::delete_to_statement(ast),
             Ast::CreateDatabase { mut db_name, .. } => Self::obj_name_to_create_db(&mut db_name),
             Ast::CreateTable { .. } => Self::create_table_to_statement(ast),
+            Ast::AlterTable { .. } => Self::alter_table_to_statement(ast),
+            Ast::CreateIndex { .. } => Self::create_index_to_statement(ast),
             Ast::Drop { .. } => Self::drop_to_statement(ast),
+            Ast::StartTransaction { modes } if modes.is_empty() => Ok(Statement::Begin),
+            Ast::Commit { chain: false } => Ok(Statement::Commit),
+            Ast::Rollback { chain: false } => Ok(Statement::Rollback),
+            Ast::Explain { .. } => Self::explain_to_statement(ast),
             _ => unsupported_feature_err(
                 "Only the following statements are supported \
             by BongoDB: SELECT, INSERT, UPDATE, DELETE, CREATE TABLE, CREATE DATABASE, DROP TABLE, \
-            DROP DATABASE.",
+            DROP DATABASE, ALTER TABLE, CREATE INDEX, DROP INDEX, BEGIN, COMMIT, ROLLBACK, EXPLAIN.",
             ),
         }
     }
 
+    fn explain_to_statement(explain: Ast) -> Result<Statement, BongoError> {
+        match explain {
+            Ast::Explain { describe_alias: false, analyze, verbose: false, statement } => {
+                let statement = Self::ast_to_statement(*statement)?;
+                match statement {
+                    Statement::Select(_) | Statement::Update(_) | Statement::Delete(_) => {
+                        Ok(Statement::Explain(Explain { analyze, statement: Box::new(statement) }))
+                    }
+                    _ => unsupported_feature_err("EXPLAIN is only supported for SELECT, UPDATE and DELETE statements."),
+                }
+            }
+            Ast::Explain { .. } => unsupported_feature_err("BongoDB does not support DESCRIBE and EXPLAIN VERBOSE."),
+            _ => internal_error("explain_to_statement should only be called with the Explain variant."),
+        }
+    }
+
     fn query_to_statement(query: Query) -> Result<Statement, BongoError> {
+        if query.fetch.is_some() {
+            return unsupported_feature_err("BongoDB does not support FETCH. Use LIMIT instead.");
+        }
+
         match query.body {
-            SetExpr::Select(select) => Ok(Statement::Select(Select {
-                cols: select
-                    .projection
-                    .try_convert_all(BongoSelectItem::try_from)?,
-                table: Self::select_extract_table(&select.from)?,
-                order: Self::select_extract_order(query.order_by)?,
-                condition: select.selection.try_convert_option(BongoExpr::try_from)?,
-            })),
+            SetExpr::Select(mut select) => {
+                let (table, joins) = Self::select_extract_tables(&mut select.from)?;
+
+                Ok(Statement::Select(Select {
+                    cols: select
+                        .projection
+                        .try_convert_all(BongoSelectItem::try_from)?,
+                    table,
+                    joins,
+                    order: query.order_by.try_convert_all(Order::try_from)?,
+                    condition: select.selection.try_convert_option(Self::condition_from_expr)?,
+                    group_by: select.group_by.try_convert_all(Self::column_from_expr)?,
+                    having: select.having.try_convert_option(BongoExpr::try_from)?,
+                    limit: query.limit.try_convert_option(Self::amount_of_rows_from_expr)?,
+                    offset: query.offset.try_convert_option(|offset| Self::amount_of_rows_from_expr(offset.value))?,
+                }))
+            }
             _ => unsupported_feature_err("This query syntax is not supported."),
         }
     }
 
-    fn select_extract_table(tables_with_joins: &[TableWithJoins]) -> Result<String, BongoError> {
+    ///
+    /// Returns the table of the FROM clause together with the tables joined to it.
+    ///
+    fn select_extract_tables(tables_with_joins: &mut Vec<TableWithJoins>) -> Result<(String, Vec<Join>), BongoError> {
         if tables_with_joins.len() != 1 {
             return only_single_table_from_err();
         }
 
-        Self::table_name_from_table_with_joins(&tables_with_joins[0])
+        let table_with_joins = tables_with_joins.remove(0);
+        let table = Self::table_name_from_table_factor(&table_with_joins.relation)?;
+        let joins = table_with_joins.joins.try_convert_all(Self::join_from_sql_parser_join)?;
+
+        Ok((table, joins))
     }
 
-    fn select_extract_order(
-        mut order_by_exprs: Vec<OrderByExpr>,
-    ) -> Result<Option<Order>, BongoError> {
-        if order_by_exprs.is_empty() {
-            return Ok(None);
+    fn join_from_sql_parser_join(join: SqlParserJoin) -> Result<Join, BongoError> {
+        let table = Self::table_name_from_table_factor(&join.relation)?;
+
+        let (kind, constraint) = match join.join_operator {
+            JoinOperator::Inner(constraint) => (JoinKind::Inner, constraint),
+            JoinOperator::LeftOuter(constraint) => (JoinKind::Left, constraint),
+            _ => return unsupported_feature_err("BongoDB only supports INNER JOIN and LEFT JOIN."),
+        };
+
+        match constraint {
+            JoinConstraint::On(expr) => Ok(Join { kind, table, condition: Self::condition_from_expr(expr)? }),
+            _ => unsupported_feature_err("Joins are only supported with an ON condition. Example: \
+            SELECT * FROM table_1 JOIN table_2 ON table_1.col_1 = table_2.col_1;"),
         }
-        if order_by_exprs.len() > 1 {
-            return order_by_only_one_column_err();
+    }
+
+    ///
+    /// Converts a condition that is evaluated for single rows, i.e. a `WHERE` or `ON` condition,
+    /// which cannot contain aggregate functions.
+    ///
+    fn condition_from_expr(expr: Expr) -> Result<BongoExpr, BongoError> {
+        let condition = BongoExpr::try_from(expr)?;
+        if condition.contains_aggregate() {
+            return unsupported_feature_err("Aggregate functions can only be used in select items and HAVING.");
         }
 
-        Ok(Some(Order::try_from(order_by_exprs.remove(0))?))
+        Ok(condition)
+    }
+
+    fn column_from_expr(expr: Expr) -> Result<String, BongoError> {
+        match BongoExpr::try_from(expr)? {
+            BongoExpr::Identifier(name) => Ok(name),
+            _ => unsupported_feature_err("GROUP BY is only supported with column names."),
+        }
+    }
+
+    fn amount_of_rows_from_expr(expr: Expr) -> Result<usize, BongoError> {
+        match expr {
+            Expr::Value(Value::Number(val, _)) => match val.parse::<usize>() {
+                Ok(val) => Ok(val),
+                Err(_) => syntax_error(&format!("'{val}' is not a valid amount of rows for LIMIT or OFFSET.")),
+            },
+            _ => unsupported_feature_err("LIMIT and OFFSET only support non-negative integer literals."),
+        }
     }
 
     fn insert_to_statement(insert: Ast) -> Result<Statement, BongoError> {
@@ -134,11 +424,15 @@ impl SqlParser {
                 columns,
                 source,
                 ..
-            } => Ok(Statement::Insert(Insert {
-                table: Self::insert_extract_table(table_name)?,
-                cols: Self::insert_extract_cols(columns)?,
-                rows: Self::insert_extract_rows(*source)?,
-            })),
+            } => {
+                let (rows, nextvals) = Self::insert_extract_rows(*source)?;
+                Ok(Statement::Insert(Insert {
+                    table: Self::insert_extract_table(table_name)?,
+                    cols: Self::insert_extract_cols(columns)?,
+                    rows,
+                    nextvals,
+                }))
+            }
             _ => {
                 internal_error("insert_to_statement should only be called with the Insert variant.")
             }
@@ -162,7 +456,7 @@ impl SqlParser {
         if idents.is_empty() {
             return unsupported_feature_err(
                 "BongoDB does only support INSERT statements \
-            with explicit column lists. Example: INSERT INTO table_1 (col_1, col_2) ..",
+            with explicit column lists or DEFAULT VALUES. Example: INSERT INTO table_1 (col_1, col_2) ..",
             );
         }
         Ok(idents
@@ -171,24 +465,37 @@ impl SqlParser {
             .collect())
     }
 
-    fn insert_extract_rows(query: Query) -> Result<Vec<Row>, BongoError> {
+    ///
+    /// Returns the rows of the VALUES list together with the positions of the values that are
+    /// given by `nextval('<sequence>')`, see `Insert::nextvals`.
+    ///
+    fn insert_extract_rows(query: Query) -> Result<(Vec<Row>, Vec<NextVal>), BongoError> {
+        let mut nextvals = vec![];
         match query.body {
-            SetExpr::Values(values) => values
-                .0
-                .into_iter()
-                .map(|exprs: Vec<Expr>| {
-                    exprs
-                        .into_iter()
-                        .map(|expr: Expr| {
-                            let bongo_expr = BongoExpr::try_from(expr)?;
-                            match bongo_expr {
-                                BongoExpr::Value(data) => Ok(data),
-                                _ => insert_list_only_literals(),
-                            }
-                        })
-                        .collect()
-                })
-                .collect(),
+            SetExpr::Values(values) => {
+                let rows = values
+                    .0
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, exprs): (usize, Vec<Expr>)| {
+                        exprs
+                            .into_iter()
+                            .enumerate()
+                            .map(|(j, expr): (usize, Expr)| {
+                                if let Some(sequence) = Self::nextval_sequence(&expr)? {
+                                    nextvals.push((i, j, sequence));
+                                    return Ok(BongoLiteral::Null);
+                                }
+                                match Self::constant_from_expr(expr)? {
+                                    Some(data) => Ok(data),
+                                    None => insert_list_only_literals(),
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect::<Result<Vec<Row>, BongoError>>()?;
+                Ok((rows, nextvals))
+            }
             _ => unsupported_feature_err(
                 "In INSERT statements only value lists \
             are supported by BongoDB. Example: INSERT INTO table_1 (col_1, col_2) VALUES \
@@ -198,6 +505,34 @@ impl SqlParser {
         }
     }
 
+    ///
+    /// Returns the name of the sequence if `expr` is a call of `nextval('<sequence>')`.
+    ///
+    fn nextval_sequence(expr: &Expr) -> Result<Option<String>, BongoError> {
+        match expr {
+            Expr::Function(function) if function.name.to_string().to_lowercase() == "nextval" => match function.args.as_slice() {
+                [FunctionArg::Unnamed(Expr::Value(Value::SingleQuotedString(sequence)))] => Ok(Some(sequence.clone())),
+                _ => syntax_error("nextval expects the name of a sequence as string, e.g. nextval('sequence_1')."),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    ///
+    /// Evaluates an expression that does not refer to any column, e.g. `NOW()` or
+    /// `DATE '2024-01-31' + 1`. Returns `None` if the expression refers to columns.
+    ///
+    fn constant_from_expr(expr: Expr) -> Result<Option<BongoLiteral>, BongoError> {
+        let bongo_expr = BongoExpr::try_from(expr)?;
+        let mut has_cols = bongo_expr.contains_aggregate();
+        bongo_expr.visit_identifiers(&mut |_| has_cols = true);
+        match bongo_expr {
+            BongoExpr::Value(data) => Ok(Some(data)),
+            _ if !has_cols => Ok(Some(bongo_expr.value(&Row::new(), &[], NullLogic::ThreeValued)?)),
+            _ => Ok(None),
+        }
+    }
+
     fn update_to_statement(update: Ast) -> Result<Statement, BongoError> {
         match update {
             Ast::Update {
@@ -207,7 +542,7 @@ impl SqlParser {
             } => Ok(Statement::Update(Update {
                 table: Self::table_name_from_table_with_joins(&table)?,
                 assignments: assignments.try_convert_all(BongoAssignment::try_from)?,
-                condition: selection.try_convert_option(BongoExpr::try_from)?,
+                condition: selection.try_convert_option(Self::condition_from_expr)?,
             })),
             _ => {
                 internal_error("update_to_statement should only be called with the Update variant.")
@@ -218,10 +553,19 @@ impl SqlParser {
     fn table_name_from_table_with_joins(
         table_with_joins: &TableWithJoins,
     ) -> Result<String, BongoError> {
-        let table_factor: &TableFactor = &table_with_joins.relation;
+        if !table_with_joins.joins.is_empty() {
+            return only_single_table_from_err();
+        }
+
+        Self::table_name_from_table_factor(&table_with_joins.relation)
+    }
 
-        match &table_factor {
-            TableFactor::Table { name, .. } => {
+    fn table_name_from_table_factor(table_factor: &TableFactor) -> Result<String, BongoError> {
+        match table_factor {
+            TableFactor::Table { name, alias, .. } => {
+                if alias.is_some() {
+                    return unsupported_feature_err("BongoDB does not support aliases for tables.");
+                }
                 if name.0.len() != 1 {
                     return only_single_table_from_err();
                 }
@@ -250,7 +594,7 @@ impl SqlParser {
                 mut table_name,
             } => Ok(Statement::Delete(Delete {
                 table: Self::string_from_obj_name(&mut table_name)?,
-                condition: selection.try_convert_option(|expr| BongoExpr::try_from(expr))?,
+                condition: selection.try_convert_option(Self::condition_from_expr)?,
             })),
             _ => {
                 internal_error("delete_to_statement should only be called with the Delete variant.")
@@ -266,14 +610,40 @@ impl SqlParser {
     fn create_table_to_statement(create_table: Ast) -> Result<Statement, BongoError> {
         match create_table {
             Ast::CreateTable {
-                mut name, columns, ..
+                mut name, columns, constraints, ..
             } => {
-                Ok(Statement::CreateTable(CreateTable {
-                    table: Self::string_from_obj_name(&mut name)?,
-                    cols: columns
-                        .try_convert_all(|col_def: ColumnDef| BongoColDef::try_from(&col_def))?,
-                    // cols: Self::vec_col_def_from_vec_col_def(&mut columns)?,
-                }))
+                let table = Self::string_from_obj_name(&mut name)?;
+                let mut checks = vec![];
+                let mut uniques = vec![];
+                let mut foreign_keys = vec![];
+                let cols = columns.iter()
+                    .map(|col_def| Self::col_def_from_col_def(col_def, &table, &mut checks, &mut uniques, &mut foreign_keys))
+                    .collect::<Result<Vec<BongoColDef>, BongoError>>()?;
+                for constraint in constraints {
+                    match constraint {
+                        TableConstraint::Check { expr, .. } => checks.push(Self::condition_from_expr(*expr)?),
+                        TableConstraint::Unique { name, columns, is_primary } => {
+                            let cols = columns.into_iter().map(|col| col.value).collect::<Vec<String>>();
+                            uniques.push(UniqueConstraint {
+                                name: name.map(|name| name.value).unwrap_or_else(|| Self::unique_name(&table, &cols, is_primary)),
+                                cols,
+                                primary: is_primary,
+                            });
+                        }
+                        TableConstraint::ForeignKey { name, columns, mut foreign_table, referred_columns, on_delete, on_update } => {
+                            let cols = columns.into_iter().map(|col| col.value).collect::<Vec<String>>();
+                            foreign_keys.push(ForeignKey {
+                                name: name.map(|name| name.value).unwrap_or_else(|| format!("{}_{}_fkey", table, cols.join("_"))),
+                                cols,
+                                table: Self::string_from_obj_name(&mut foreign_table)?,
+                                ref_cols: referred_columns.into_iter().map(|col| col.value).collect(),
+                                on_delete: Self::referential_action(on_delete, on_update)?,
+                            });
+                        }
+                    }
+                }
+
+                Ok(Statement::CreateTable(CreateTable { table, cols, checks, uniques, foreign_keys }))
             }
             _ => internal_error(
                 "create_table_to_statement should only be called with the CreateTable variant.",
@@ -281,6 +651,170 @@ impl SqlParser {
         }
     }
 
+    ///
+    /// Converts the definition of a column of the table `table` including its constraints. The
+    /// conditions of `CHECK` constraints are appended to `checks`, `PRIMARY KEY` and `UNIQUE`
+    /// constraints to `uniques` and `REFERENCES` constraints to `foreign_keys`, because they belong
+    /// to the table.
+    ///
+    fn col_def_from_col_def(column_def: &ColumnDef, table: &str, checks: &mut Vec<BongoExpr>, uniques: &mut Vec<UniqueConstraint>,
+                            foreign_keys: &mut Vec<ForeignKey>) -> Result<BongoColDef, BongoError> {
+        let mut col = BongoColDef::try_from(column_def)?;
+        for option in &column_def.options {
+            match &option.option {
+                ColumnOption::Null => col.not_null = false,
+                ColumnOption::NotNull => col.not_null = true,
+                ColumnOption::Default(expr) => {
+                    let default = BongoExpr::try_from(expr.clone())?;
+                    // functions like NOW() would only be evaluated once instead of for every row
+                    if default.contains_function() {
+                        return unsupported_feature_err("The default value of a column cannot contain function calls.");
+                    }
+                    match Self::constant_from_expr(expr.clone())? {
+                        Some(value) => col.default = Some(value),
+                        None => return syntax_error("The default value of a column cannot refer to columns."),
+                    }
+                }
+                ColumnOption::Check(expr) => checks.push(Self::condition_from_expr(expr.clone())?),
+                ColumnOption::Unique { is_primary } => {
+                    let cols = vec![col.name.clone()];
+                    uniques.push(UniqueConstraint {
+                        name: option.name.as_ref()
+                            .map(|name| name.value.clone())
+                            .unwrap_or_else(|| Self::unique_name(table, &cols, *is_primary)),
+                        cols,
+                        primary: *is_primary,
+                    });
+                }
+                ColumnOption::ForeignKey { foreign_table, referred_columns, on_delete, on_update } => {
+                    foreign_keys.push(ForeignKey {
+                        name: option.name.as_ref()
+                            .map(|name| name.value.clone())
+                            .unwrap_or_else(|| format!("{}_{}_fkey", table, col.name)),
+                        cols: vec![col.name.clone()],
+                        table: Self::string_from_obj_name(&mut foreign_table.clone())?,
+                        ref_cols: referred_columns.iter().map(|col| col.value.clone()).collect(),
+                        on_delete: Self::referential_action(on_delete.clone(), on_update.clone())?,
+                    });
+                }
+                // MySQL's AUTO_INCREMENT and SQLite's AUTOINCREMENT
+                ColumnOption::DialectSpecific(tokens) if tokens.len() == 1 &&
+                    matches!(tokens[0].to_string().to_uppercase().as_str(), "AUTO_INCREMENT" | "AUTOINCREMENT") => {
+                    if !col.data_type.is_integer() {
+                        return syntax_error("AUTO_INCREMENT can only be used for columns of the types SMALLINT, INT and BIGINT.");
+                    }
+                    col.serial = true;
+                    col.not_null = true;
+                }
+                _ => return unsupported_feature_err(
+                    "BongoDB only supports the column constraints NULL, NOT NULL, DEFAULT, CHECK, PRIMARY KEY, UNIQUE, REFERENCES and AUTO_INCREMENT.",
+                ),
+            }
+        }
+        if col.serial && (col.default.is_some() || !col.not_null) {
+            return syntax_error("SERIAL and AUTO_INCREMENT columns cannot have a DEFAULT value and cannot be NULL.");
+        }
+
+        Ok(col)
+    }
+
+    ///
+    /// Converts the `ON DELETE` action of a foreign key. `NO ACTION` is the same as `RESTRICT`,
+    /// because constraints are not deferred. Referenced rows can only be updated if they are not
+    /// referenced, so only `RESTRICT` and `NO ACTION` are supported for `ON UPDATE`.
+    ///
+    fn referential_action(on_delete: Option<ReferentialAction>, on_update: Option<ReferentialAction>) -> Result<BongoReferentialAction, BongoError> {
+        if !matches!(on_update, None | Some(ReferentialAction::Restrict) | Some(ReferentialAction::NoAction)) {
+            return unsupported_feature_err("BongoDB only supports RESTRICT and NO ACTION as ON UPDATE action of a foreign key.");
+        }
+
+        match on_delete {
+            None | Some(ReferentialAction::Restrict) | Some(ReferentialAction::NoAction) => Ok(BongoReferentialAction::Restrict),
+            Some(ReferentialAction::Cascade) => Ok(BongoReferentialAction::Cascade),
+            Some(ReferentialAction::SetNull) => Ok(BongoReferentialAction::SetNull),
+            Some(ReferentialAction::SetDefault) => unsupported_feature_err(
+                "BongoDB only supports RESTRICT, NO ACTION, CASCADE and SET NULL as ON DELETE action of a foreign key.",
+            ),
+        }
+    }
+
+    ///
+    /// Returns the name of a `PRIMARY KEY` or `UNIQUE` constraint without an explicit name, e.g.
+    /// `table_1_pkey` or `table_1_col_1_col_2_key`.
+    ///
+    fn unique_name(table: &str, cols: &[String], primary: bool) -> String {
+        match primary {
+            true => format!("{}_pkey", table),
+            false => format!("{}_{}_key", table, cols.join("_")),
+        }
+    }
+
+    fn alter_table_to_statement(alter_table: Ast) -> Result<Statement, BongoError> {
+        match alter_table {
+            Ast::AlterTable { mut name, operation } => {
+                let table = Self::string_from_obj_name(&mut name)?;
+                let operation = match operation {
+                    AlterTableOperation::AddColumn { column_def } => {
+                        let mut checks = vec![];
+                        let mut uniques = vec![];
+                        let mut foreign_keys = vec![];
+                        let col = Self::col_def_from_col_def(&column_def, &table, &mut checks, &mut uniques, &mut foreign_keys)?;
+                        BongoAlterTableOperation::AddColumn { col, checks, uniques, foreign_keys }
+                    }
+                    AlterTableOperation::DropColumn { column_name, if_exists, .. } => {
+                        BongoAlterTableOperation::DropColumn { col: column_name.value, if_exists }
+                    }
+                    AlterTableOperation::RenameColumn { old_column_name, new_column_name } => {
+                        BongoAlterTableOperation::RenameColumn { col: old_column_name.value, new_name: new_column_name.value }
+                    }
+                    // ALTER COLUMN TYPE is rewritten to CHANGE COLUMN, see `rewrite_alter_column_type`
+                    AlterTableOperation::ChangeColumn { old_name, new_name, data_type, options }
+                    if old_name == new_name && options.is_empty() => {
+                        BongoAlterTableOperation::AlterColumnType {
+                            col: old_name.value,
+                            data_type: BongoDataType::try_from(&data_type)?,
+                        }
+                    }
+                    _ => return unsupported_feature_err(
+                        "BongoDB only supports ADD COLUMN, DROP COLUMN, RENAME COLUMN and ALTER COLUMN TYPE \
+                        in ALTER TABLE statements.",
+                    ),
+                };
+
+                Ok(Statement::AlterTable(AlterTable { table, operation }))
+            }
+            _ => internal_error("alter_table_to_statement should only be called with the AlterTable variant."),
+        }
+    }
+
+    fn create_index_to_statement(create_index: Ast) -> Result<Statement, BongoError> {
+        match create_index {
+            Ast::CreateIndex {
+                mut name, mut table_name, mut columns, unique, if_not_exists,
+            } => {
+                if unique || if_not_exists {
+                    return unsupported_feature_err("BongoDB does not support UNIQUE and IF NOT EXISTS for indexes.");
+                }
+                if columns.len() != 1 {
+                    return unsupported_feature_err("BongoDB only supports indexes on exactly one column.");
+                }
+
+                match columns.remove(0).expr {
+                    Expr::Identifier(ident) => Ok(Statement::CreateIndex(CreateIndex {
+                        name: Self::string_from_obj_name(&mut name)?,
+                        table: Self::string_from_obj_name(&mut table_name)?,
+                        col: ident.value,
+                        kind: IndexKind::Hash,
+                    })),
+                    _ => unsupported_feature_err("Indexes can only be created on columns."),
+                }
+            }
+            _ => internal_error(
+                "create_index_to_statement should only be called with the CreateIndex variant.",
+            ),
+        }
+    }
+
     fn drop_to_statement(drop: Ast) -> Result<Statement, BongoError> {
         match drop {
             Ast::Drop {
@@ -294,8 +828,15 @@ impl SqlParser {
                             })?, // names: Self::vec_string_from_vec_obj_names(names)?
                         }))
                     }
+                    ObjectType::Index => {
+                        Ok(Statement::DropIndex(DropIndex {
+                            names: names.try_convert_all(|mut obj_name| {
+                                Self::string_from_obj_name(&mut obj_name)
+                            })?,
+                        }))
+                    }
                     _ => {
-                        unsupported_feature_err("BongoDB only supports DROP statements for TABLEs.")
+                        unsupported_feature_err("BongoDB only supports DROP statements for TABLEs and INDEXes.")
                     }
                 }
             }
@@ -309,7 +850,8 @@ mod tests {
     mod select {
         use crate::sql_parser::parser::SqlParser;
         use crate::statement::{
-            BinOp as BongoBinOp, Expr as BongoExpr, Order, Select, SelectItem, Statement,
+            Aggregate, AggregateFunc, BinOp as BongoBinOp, Expr as BongoExpr, Join, JoinKind, Order, Select,
+            SelectItem, Statement,
         };
         use bongo_core::types::BongoLiteral;
 
@@ -327,7 +869,12 @@ mod tests {
                     SelectItem::ColumnName(String::from("col_2")),
                 ],
                 table: String::from("table_1"),
-                order: Some(Order::Asc(String::from("col_1"))),
+                joins: vec![],
+                group_by: vec![],
+                having: None,
+                order: vec![Order { col: "col_1".to_string(), asc: true, nulls_first: false }],
+                limit: None,
+                offset: None,
                 condition: Some(BongoExpr::BinaryExpr {
                     left: Box::new(BongoExpr::BinaryExpr {
                         left: Box::new(BongoExpr::Identifier(String::from("a"))),
@@ -359,7 +906,12 @@ mod tests {
                     SelectItem::ColumnName(String::from("col_2")),
                 ],
                 table: String::from("table_1"),
-                order: None,
+                joins: vec![],
+                group_by: vec![],
+                having: None,
+                order: vec![],
+                limit: None,
+                offset: None,
                 condition: None,
             });
 
@@ -379,8 +931,13 @@ mod tests {
             let expected_statement = Statement::Select(Select {
                 cols: vec![SelectItem::Wildcard],
                 table: String::from("table_1"),
+                joins: vec![],
                 condition: None,
-                order: Some(Order::Asc("col_1".to_string())),
+                group_by: vec![],
+                having: None,
+                order: vec![Order { col: "col_1".to_string(), asc: true, nulls_first: false }],
+                limit: None,
+                offset: None,
             });
 
             assert_eq!(statement, Ok(expected_statement));
@@ -397,8 +954,13 @@ mod tests {
             let expected_statement = Statement::Select(Select {
                 cols: vec![SelectItem::Wildcard],
                 table: String::from("table_1"),
+                joins: vec![],
                 condition: None,
-                order: Some(Order::Desc("col_1".to_string())),
+                group_by: vec![],
+                having: None,
+                order: vec![Order { col: "col_1".to_string(), asc: false, nulls_first: true }],
+                limit: None,
+                offset: None,
             });
 
             assert_eq!(statement, Ok(expected_statement));
@@ -413,18 +975,258 @@ mod tests {
             let expected_statement = Statement::Select(Select {
                 cols: vec![SelectItem::Wildcard],
                 table: String::from("table_1"),
+                joins: vec![],
+                condition: None,
+                group_by: vec![],
+                having: None,
+                order: vec![],
+                limit: None,
+                offset: None,
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+        }
+
+        #[test]
+        fn joins() {
+            let sql = "SELECT table_1.*, table_2.col_2 \
+            FROM table_1 \
+            JOIN table_2 ON table_1.col_1 = table_2.col_1 \
+            LEFT JOIN table_3 ON col_3 = table_3.col_1;";
+
+            let statement = SqlParser::parse(sql);
+
+            let expected_statement = Statement::Select(Select {
+                cols: vec![
+                    SelectItem::QualifiedWildcard("table_1".to_string()),
+                    SelectItem::ColumnName("table_2.col_2".to_string()),
+                ],
+                table: String::from("table_1"),
+                joins: vec![
+                    Join {
+                        kind: JoinKind::Inner,
+                        table: "table_2".to_string(),
+                        condition: BongoExpr::BinaryExpr {
+                            left: Box::new(BongoExpr::Identifier("table_1.col_1".to_string())),
+                            op: BongoBinOp::Eq,
+                            right: Box::new(BongoExpr::Identifier("table_2.col_1".to_string())),
+                        },
+                    },
+                    Join {
+                        kind: JoinKind::Left,
+                        table: "table_3".to_string(),
+                        condition: BongoExpr::BinaryExpr {
+                            left: Box::new(BongoExpr::Identifier("col_3".to_string())),
+                            op: BongoBinOp::Eq,
+                            right: Box::new(BongoExpr::Identifier("table_3.col_1".to_string())),
+                        },
+                    },
+                ],
+                condition: None,
+                group_by: vec![],
+                having: None,
+                order: vec![],
+                limit: None,
+                offset: None,
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+        }
+
+        #[test]
+        fn unsupported_joins() {
+            assert!(SqlParser::parse("SELECT * FROM table_1 CROSS JOIN table_2;").is_err());
+            assert!(SqlParser::parse("SELECT * FROM table_1 JOIN table_2 USING (col_1);").is_err());
+            assert!(SqlParser::parse("SELECT * FROM table_1 t JOIN table_2 ON t.col_1 = table_2.col_1;").is_err());
+            assert!(SqlParser::parse("SELECT * FROM table_1, table_2;").is_err());
+        }
+
+        #[test]
+        fn group_by_and_having() {
+            let sql = "SELECT col_1, COUNT(*), sum(table_1.col_2) \
+            FROM table_1 \
+            GROUP BY col_1 \
+            HAVING MAX(col_2) > 5;";
+
+            let statement = SqlParser::parse(sql);
+
+            let expected_statement = Statement::Select(Select {
+                cols: vec![
+                    SelectItem::ColumnName("col_1".to_string()),
+                    SelectItem::Aggregate(Aggregate { func: AggregateFunc::Count, col: None }),
+                    SelectItem::Aggregate(Aggregate { func: AggregateFunc::Sum, col: Some("table_1.col_2".to_string()) }),
+                ],
+                table: String::from("table_1"),
+                joins: vec![],
+                condition: None,
+                group_by: vec!["col_1".to_string()],
+                having: Some(BongoExpr::BinaryExpr {
+                    left: Box::new(BongoExpr::Aggregate(Aggregate { func: AggregateFunc::Max, col: Some("col_2".to_string()) })),
+                    op: BongoBinOp::Gt,
+                    right: Box::new(BongoExpr::Value(BongoLiteral::Int(5))),
+                }),
+                order: vec![],
+                limit: None,
+                offset: None,
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+        }
+
+        #[test]
+        fn multiple_orders() {
+            let statement = SqlParser::parse("SELECT * FROM table_1 \
+            ORDER BY col_1 DESC NULLS LAST, table_1.col_2, col_3 ASC NULLS FIRST;");
+
+            let expected_statement = Statement::Select(Select {
+                cols: vec![SelectItem::Wildcard],
+                table: String::from("table_1"),
+                joins: vec![],
+                condition: None,
+                group_by: vec![],
+                having: None,
+                order: vec![
+                    Order { col: "col_1".to_string(), asc: false, nulls_first: false },
+                    Order { col: "table_1.col_2".to_string(), asc: true, nulls_first: false },
+                    Order { col: "col_3".to_string(), asc: true, nulls_first: true },
+                ],
+                limit: None,
+                offset: None,
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+        }
+
+        #[test]
+        fn limit_and_offset() {
+            let statement = SqlParser::parse("SELECT * FROM table_1 ORDER BY col_1 LIMIT 10 OFFSET 20;");
+
+            let expected_statement = Statement::Select(Select {
+                cols: vec![SelectItem::Wildcard],
+                table: String::from("table_1"),
+                joins: vec![],
                 condition: None,
-                order: None,
+                group_by: vec![],
+                having: None,
+                order: vec![Order { col: "col_1".to_string(), asc: true, nulls_first: false }],
+                limit: Some(10),
+                offset: Some(20),
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+            assert!(SqlParser::parse("SELECT * FROM table_1 LIMIT -1;").is_err());
+            assert!(SqlParser::parse("SELECT * FROM table_1 LIMIT col_1;").is_err());
+            assert!(SqlParser::parse("SELECT * FROM table_1 FETCH FIRST 10 ROWS ONLY;").is_err());
+        }
+
+        #[test]
+        fn computed_items() {
+            let sql = "SELECT col_1 % 3 AS rest, (col_1 + col_2) * 2 FROM table_1 WHERE col_1 - 1 > 0 ORDER BY rest";
+
+            let statement = SqlParser::parse(sql);
+
+            let expected_statement = Statement::Select(Select {
+                cols: vec![
+                    SelectItem::Expr {
+                        expr: BongoExpr::BinaryExpr {
+                            left: Box::new(BongoExpr::Identifier("col_1".to_string())),
+                            op: BongoBinOp::Modulo,
+                            right: Box::new(BongoExpr::Value(BongoLiteral::Int(3))),
+                        },
+                        alias: Some("rest".to_string()),
+                    },
+                    SelectItem::Expr {
+                        expr: BongoExpr::BinaryExpr {
+                            left: Box::new(BongoExpr::BinaryExpr {
+                                left: Box::new(BongoExpr::Identifier("col_1".to_string())),
+                                op: BongoBinOp::Plus,
+                                right: Box::new(BongoExpr::Identifier("col_2".to_string())),
+                            }),
+                            op: BongoBinOp::Multiply,
+                            right: Box::new(BongoExpr::Value(BongoLiteral::Int(2))),
+                        },
+                        alias: None,
+                    },
+                ],
+                table: String::from("table_1"),
+                joins: vec![],
+                condition: Some(BongoExpr::BinaryExpr {
+                    left: Box::new(BongoExpr::BinaryExpr {
+                        left: Box::new(BongoExpr::Identifier("col_1".to_string())),
+                        op: BongoBinOp::Minus,
+                        right: Box::new(BongoExpr::Value(BongoLiteral::Int(1))),
+                    }),
+                    op: BongoBinOp::Gt,
+                    right: Box::new(BongoExpr::Value(BongoLiteral::Int(0))),
+                }),
+                group_by: vec![],
+                having: None,
+                order: vec![Order { col: "rest".to_string(), asc: true, nulls_first: false }],
+                limit: None,
+                offset: None,
             });
 
             assert_eq!(statement, Ok(expected_statement));
         }
+
+        #[test]
+        fn predicates() {
+            let condition = |sql: &str| match SqlParser::parse(sql) {
+                Ok(Statement::Select(select)) => select.condition.unwrap().to_string(),
+                other => panic!("unexpected result {:?}", other),
+            };
+
+            assert_eq!("(NOT (col_1 IN (1, 2 + 1)) AND col_2 NOT LIKE 'a%') OR col_3 IS NOT NULL",
+                       condition("SELECT * FROM table_1 \
+                       WHERE NOT col_1 IN (1, 2 + 1) AND col_2 NOT LIKE 'a%' OR col_3 IS NOT NULL;"));
+            assert_eq!("col_1 NOT IN (-1) AND (col_2 IS NULL OR col_1 NOT BETWEEN -(col_3 * 2) AND 10)",
+                       condition("SELECT * FROM table_1 \
+                       WHERE col_1 NOT IN (-1) AND (col_2 IS NULL OR col_1 NOT BETWEEN -(col_3 * 2) AND 10);"));
+            assert!(SqlParser::parse("SELECT * FROM table_1 WHERE col_2 ILIKE 'a%';").is_err());
+            assert!(SqlParser::parse("SELECT * FROM table_1 WHERE col_1 IN (SELECT col_1 FROM table_2);").is_err());
+        }
+
+        #[test]
+        fn unsupported_aggregates() {
+            assert!(SqlParser::parse("SELECT * FROM table_1 WHERE COUNT(*) > 1;").is_err());
+            assert!(SqlParser::parse("SELECT SUM(*) FROM table_1;").is_err());
+            assert!(SqlParser::parse("SELECT COUNT(DISTINCT col_1) FROM table_1;").is_err());
+            assert!(SqlParser::parse("SELECT UPPER(col_1) FROM table_1;").is_err());
+            assert!(SqlParser::parse("SELECT COUNT(*) FROM table_1 GROUP BY 1;").is_err());
+        }
     }
 
     mod insert {
+        use std::str::FromStr;
+
         use super::super::SqlParser;
         use crate::statement::{Insert, Statement};
-        use bongo_core::types::BongoLiteral;
+        use bongo_core::datetime::{Date, Time, Timestamp};
+        use bongo_core::types::{BongoError, BongoLiteral};
+
+        #[test]
+        fn dates_and_constant_expressions() {
+            let sql = "INSERT INTO table_1 (col_1, col_2, col_3) VALUES \
+                (DATE '2024-02-29' + 1, TIME '12:30', TIMESTAMP '2024-01-01 10:00:00+01:00');";
+
+            let expected_statement = Statement::Insert(Insert {
+                table: "table_1".to_string(),
+                cols: vec!["col_1".to_string(), "col_2".to_string(), "col_3".to_string()],
+                rows: vec![vec![
+                    BongoLiteral::Date(Date::from_str("2024-03-01").unwrap()),
+                    BongoLiteral::Time(Time::from_str("12:30").unwrap()),
+                    BongoLiteral::Timestamp(Timestamp::from_str("2024-01-01 09:00").unwrap()),
+                ]],
+                nextvals: vec![],
+            });
+
+            assert_eq!(SqlParser::parse(sql), Ok(expected_statement));
+            assert!(matches!(SqlParser::parse("INSERT INTO table_1 (col_1) VALUES (NOW());"), Ok(Statement::Insert(_))));
+            assert!(matches!(SqlParser::parse("INSERT INTO table_1 (col_1) VALUES (col_1 + 1);"),
+                             Err(BongoError::SqlSyntaxError(_))));
+            assert!(matches!(SqlParser::parse("INSERT INTO table_1 (col_1) VALUES (DATE '2024-02-30');"),
+                             Err(BongoError::SqlRuntimeError(_))));
+        }
 
         #[test]
         fn multiple_rows() {
@@ -459,15 +1261,61 @@ mod tests {
                         BongoLiteral::Null,
                     ],
                 ],
+                nextvals: vec![],
             });
 
             assert_eq!(statement, Ok(expected_statement));
         }
+
+        #[test]
+        fn nextval() {
+            let sql = "INSERT INTO table_1 (col_1, col_2) VALUES (nextval('seq_1'), 1), (2, NEXTVAL('seq_2'));";
+
+            let expected_statement = Statement::Insert(Insert {
+                table: "table_1".to_string(),
+                cols: vec!["col_1".to_string(), "col_2".to_string()],
+                rows: vec![
+                    vec![BongoLiteral::Null, BongoLiteral::Int(1)],
+                    vec![BongoLiteral::Int(2), BongoLiteral::Null],
+                ],
+                nextvals: vec![(0, 0, "seq_1".to_string()), (1, 1, "seq_2".to_string())],
+            });
+
+            assert_eq!(SqlParser::parse(sql), Ok(expected_statement));
+            assert!(matches!(SqlParser::parse("INSERT INTO table_1 (col_1) VALUES (nextval(seq_1));"),
+                             Err(BongoError::SqlSyntaxError(_))));
+        }
+
+        #[test]
+        fn default_values() {
+            let expected_statement = Statement::Insert(Insert {
+                table: "table_1".to_string(),
+                cols: vec![],
+                rows: vec![vec![]],
+                nextvals: vec![],
+            });
+
+            assert_eq!(SqlParser::parse("INSERT INTO table_1 DEFAULT VALUES;"), Ok(expected_statement));
+            assert!(matches!(SqlParser::parse("insert into table_1 default values"), Ok(Statement::Insert(_))));
+            assert!(matches!(SqlParser::parse("INSERT INTO table_1 VALUES (1, 2);"),
+                             Err(BongoError::UnsupportedFeatureError(_))));
+            assert!(matches!(SqlParser::parse("/* one row */ INSERT\n  INTO table_1 -- all columns\n  DEFAULT /* of */ VALUES ;"),
+                             Ok(Statement::Insert(_))));
+        }
+
+        #[test]
+        fn default_values_only_as_keywords() {
+            assert!(SqlParser::parse(r#"INSERT INTO table_1 "DEFAULT" VALUES;"#).is_err());
+            assert!(matches!(SqlParser::parse(r#"INSERT INTO table_1 ("default values") VALUES (1);"#),
+                             Ok(Statement::Insert(Insert { rows, .. })) if rows == vec![vec![BongoLiteral::Int(1)]]));
+            assert!(SqlParser::parse("INSERT INTO table_1 -- DEFAULT VALUES\n;").is_err());
+            assert!(SqlParser::parse("INSERT INTO table_1 DEFAULT VALUES; DELETE FROM table_1;").is_err());
+        }
     }
 
     mod update {
         use super::super::SqlParser;
-        use crate::statement::{Assignment, Statement, Update};
+        use crate::statement::{Assignment, BinOp as BongoBinOp, Expr as BongoExpr, Statement, UnaryOp, Update};
         use bongo_core::types::BongoLiteral;
 
         #[test]
@@ -483,11 +1331,46 @@ mod tests {
                 assignments: vec![
                     Assignment {
                         col_name: "col_1".to_string(),
-                        val: BongoLiteral::Int(2),
+                        val: BongoExpr::Value(BongoLiteral::Int(2)),
                     },
                     Assignment {
                         col_name: "col_2".to_string(),
-                        val: BongoLiteral::Varchar("new_value".to_string()),
+                        val: BongoExpr::Value(BongoLiteral::Varchar("new_value".to_string())),
+                    },
+                ],
+                condition: None,
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+        }
+
+        #[test]
+        fn arithmetic_set_expr() {
+            let sql = "UPDATE table_1 SET col_1 = -(col_1 + 1) - 2, col_2 = -5;";
+
+            let statement = SqlParser::parse(sql);
+
+            let expected_statement = Statement::Update(Update {
+                table: "table_1".to_string(),
+                assignments: vec![
+                    Assignment {
+                        col_name: "col_1".to_string(),
+                        val: BongoExpr::BinaryExpr {
+                            left: Box::new(BongoExpr::Unary {
+                                op: UnaryOp::Minus,
+                                expr: Box::new(BongoExpr::BinaryExpr {
+                                    left: Box::new(BongoExpr::Identifier("col_1".to_string())),
+                                    op: BongoBinOp::Plus,
+                                    right: Box::new(BongoExpr::Value(BongoLiteral::Int(1))),
+                                }),
+                            }),
+                            op: BongoBinOp::Minus,
+                            right: Box::new(BongoExpr::Value(BongoLiteral::Int(2))),
+                        },
+                    },
+                    Assignment {
+                        col_name: "col_2".to_string(),
+                        val: BongoExpr::Value(BongoLiteral::Int(-5)),
                     },
                 ],
                 condition: None,
@@ -545,9 +1428,9 @@ mod tests {
     }
 
     mod create_table {
-        use bongo_core::types::{BongoDataType, ColumnDef as BongoColDef};
+        use bongo_core::types::{BongoDataType, BongoLiteral, ColumnDef as BongoColDef};
 
-        use crate::statement::{CreateTable, Statement};
+        use crate::statement::{BinOp, CreateTable, Expr, ForeignKey, ReferentialAction, Statement, UniqueConstraint};
 
         use super::super::SqlParser;
 
@@ -568,19 +1451,184 @@ mod tests {
                     BongoColDef {
                         name: "col_1".to_string(),
                         data_type: BongoDataType::Int,
+                        not_null: false,
+                        default: None,
+                        serial: false,
                     },
                     BongoColDef {
                         name: "col_2".to_string(),
                         data_type: BongoDataType::Bool,
+                        not_null: false,
+                        default: None,
+                        serial: false,
                     },
                     BongoColDef {
                         name: "col_3".to_string(),
                         data_type: BongoDataType::Varchar(256),
+                        not_null: false,
+                        default: None,
+                        serial: false,
+                    },
+                ],
+                checks: vec![],
+                uniques: vec![],
+                foreign_keys: vec![],
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+        }
+
+        #[test]
+        fn constraints() {
+            let sql = "CREATE TABLE table_1 ( \
+                            col_1 INT NOT NULL CHECK (col_1 > 0), \
+                            col_2 VARCHAR(10) NULL DEFAULT 'a', \
+                            CONSTRAINT positive CHECK (col_1 < 100 OR col_2 IS NULL) \
+                        );";
+
+            let statement = SqlParser::parse(sql);
+
+            let expected_statement = Statement::CreateTable(CreateTable {
+                table: "table_1".to_string(),
+                cols: vec![
+                    BongoColDef {
+                        name: "col_1".to_string(),
+                        data_type: BongoDataType::Int,
+                        not_null: true,
+                        default: None,
+                        serial: false,
+                    },
+                    BongoColDef {
+                        name: "col_2".to_string(),
+                        data_type: BongoDataType::Varchar(10),
+                        not_null: false,
+                        default: Some(BongoLiteral::Varchar("a".to_string())),
+                        serial: false,
                     },
                 ],
+                checks: vec![
+                    Expr::BinaryExpr {
+                        left: Box::new(Expr::Identifier("col_1".to_string())),
+                        op: BinOp::Gt,
+                        right: Box::new(Expr::Value(BongoLiteral::Int(0))),
+                    },
+                    Expr::BinaryExpr {
+                        left: Box::new(Expr::BinaryExpr {
+                            left: Box::new(Expr::Identifier("col_1".to_string())),
+                            op: BinOp::Lt,
+                            right: Box::new(Expr::Value(BongoLiteral::Int(100))),
+                        }),
+                        op: BinOp::Or,
+                        right: Box::new(Expr::IsNull { expr: Box::new(Expr::Identifier("col_2".to_string())), negated: false }),
+                    },
+                ],
+                uniques: vec![],
+                foreign_keys: vec![],
             });
 
             assert_eq!(statement, Ok(expected_statement));
+            assert!(SqlParser::parse("CREATE TABLE table_1 (col_1 TIMESTAMP DEFAULT NOW());").is_err());
+            assert!(SqlParser::parse("CREATE TABLE table_1 (col_1 INT CHECK (COUNT(*) > 0));").is_err());
+            assert!(SqlParser::parse("CREATE TABLE table_1 (col_1 INT REFERENCES table_2 (col_1) ON DELETE SET DEFAULT);").is_err());
+        }
+
+        #[test]
+        fn unique_constraints() {
+            let sql = "CREATE TABLE table_1 ( \
+                            col_1 INT PRIMARY KEY, \
+                            col_2 INT CONSTRAINT col_2_unique UNIQUE, \
+                            col_3 INT, \
+                            UNIQUE (col_2, col_3) \
+                        );";
+
+            let uniques = match SqlParser::parse(sql) {
+                Ok(Statement::CreateTable(create_table)) => create_table.uniques,
+                statement => panic!("unexpected statement {:?}", statement),
+            };
+
+            assert_eq!(vec![
+                UniqueConstraint { name: "table_1_pkey".to_string(), cols: vec!["col_1".to_string()], primary: true },
+                UniqueConstraint { name: "col_2_unique".to_string(), cols: vec!["col_2".to_string()], primary: false },
+                UniqueConstraint {
+                    name: "table_1_col_2_col_3_key".to_string(),
+                    cols: vec!["col_2".to_string(), "col_3".to_string()],
+                    primary: false,
+                },
+            ], uniques);
+
+            let uniques = match SqlParser::parse("CREATE TABLE table_1 (col_1 INT, CONSTRAINT pk PRIMARY KEY (col_1));") {
+                Ok(Statement::CreateTable(create_table)) => create_table.uniques,
+                statement => panic!("unexpected statement {:?}", statement),
+            };
+            assert_eq!(vec![UniqueConstraint { name: "pk".to_string(), cols: vec!["col_1".to_string()], primary: true }], uniques);
+        }
+
+        #[test]
+        fn foreign_keys() {
+            let sql = "CREATE TABLE table_1 ( \
+                            col_1 INT REFERENCES table_2, \
+                            col_2 INT CONSTRAINT parent REFERENCES table_1 (col_1) ON DELETE CASCADE, \
+                            col_3 INT, \
+                            FOREIGN KEY (col_2, col_3) REFERENCES table_3 (col_1, col_2) ON DELETE SET NULL ON UPDATE NO ACTION \
+                        );";
+
+            let foreign_keys = match SqlParser::parse(sql) {
+                Ok(Statement::CreateTable(create_table)) => create_table.foreign_keys,
+                statement => panic!("unexpected statement {:?}", statement),
+            };
+
+            assert_eq!(vec![
+                ForeignKey {
+                    name: "table_1_col_1_fkey".to_string(),
+                    cols: vec!["col_1".to_string()],
+                    table: "table_2".to_string(),
+                    ref_cols: vec![],
+                    on_delete: ReferentialAction::Restrict,
+                },
+                ForeignKey {
+                    name: "parent".to_string(),
+                    cols: vec!["col_2".to_string()],
+                    table: "table_1".to_string(),
+                    ref_cols: vec!["col_1".to_string()],
+                    on_delete: ReferentialAction::Cascade,
+                },
+                ForeignKey {
+                    name: "table_1_col_2_col_3_fkey".to_string(),
+                    cols: vec!["col_2".to_string(), "col_3".to_string()],
+                    table: "table_3".to_string(),
+                    ref_cols: vec!["col_1".to_string(), "col_2".to_string()],
+                    on_delete: ReferentialAction::SetNull,
+                },
+            ], foreign_keys);
+
+            assert!(SqlParser::parse("CREATE TABLE table_1 (col_1 INT REFERENCES table_2 ON UPDATE CASCADE);").is_err());
+        }
+
+        #[test]
+        fn serial_columns() {
+            let sql = "CREATE TABLE table_1 (col_1 SERIAL, col_2 bigserial, col_3 SMALLINT AUTO_INCREMENT, col_4 INT);";
+
+            let cols = match SqlParser::parse(sql) {
+                Ok(Statement::CreateTable(create_table)) => create_table.cols,
+                statement => panic!("unexpected statement {:?}", statement),
+            };
+
+            let serial = |name: &str, data_type: BongoDataType| BongoColDef {
+                name: name.to_string(),
+                data_type,
+                not_null: true,
+                default: None,
+                serial: true,
+            };
+            assert_eq!(vec![
+                serial("col_1", BongoDataType::Int),
+                serial("col_2", BongoDataType::BigInt),
+                serial("col_3", BongoDataType::SmallInt),
+                BongoColDef { name: "col_4".to_string(), data_type: BongoDataType::Int, not_null: false, default: None, serial: false },
+            ], cols);
+            assert!(SqlParser::parse("CREATE TABLE table_1 (col_1 VARCHAR(10) AUTO_INCREMENT);").is_err());
+            assert!(SqlParser::parse("CREATE TABLE table_1 (col_1 SERIAL DEFAULT 1);").is_err());
+            assert!(SqlParser::parse("CREATE TABLE table_1 (col_1 SERIAL NULL);").is_err());
         }
     }
 
@@ -603,6 +1651,124 @@ mod tests {
         }
     }
 
+    mod alter_table {
+        use bongo_core::types::{BongoDataType, BongoLiteral, ColumnDef};
+
+        use crate::statement::{AlterTable, AlterTableOperation, Statement};
+
+        use super::super::SqlParser;
+
+        fn alter_table(operation: AlterTableOperation) -> Statement {
+            Statement::AlterTable(AlterTable { table: "table_1".to_string(), operation })
+        }
+
+        #[test]
+        fn add_drop_and_rename_column() {
+            assert_eq!(SqlParser::parse("ALTER TABLE table_1 ADD COLUMN col_3 DECIMAL(5, 2) NOT NULL DEFAULT 1 + 2;"), Ok(alter_table(
+                AlterTableOperation::AddColumn {
+                    col: ColumnDef {
+                        name: "col_3".to_string(),
+                        data_type: BongoDataType::Decimal(5, 2),
+                        not_null: true,
+                        default: Some(BongoLiteral::Int(3)),
+                        serial: false,
+                    },
+                    checks: vec![],
+                    uniques: vec![],
+                    foreign_keys: vec![],
+                }
+            )));
+            assert_eq!(SqlParser::parse("ALTER TABLE table_1 ADD col_3 TEXT;"), Ok(alter_table(
+                AlterTableOperation::AddColumn {
+                    col: ColumnDef { name: "col_3".to_string(), data_type: BongoDataType::Text, not_null: false, default: None, serial: false },
+                    checks: vec![],
+                    uniques: vec![],
+                    foreign_keys: vec![],
+                }
+            )));
+            assert_eq!(SqlParser::parse("ALTER TABLE table_1 DROP COLUMN IF EXISTS col_1;"), Ok(alter_table(
+                AlterTableOperation::DropColumn { col: "col_1".to_string(), if_exists: true }
+            )));
+            assert_eq!(SqlParser::parse("ALTER TABLE table_1 RENAME COLUMN col_1 TO col_2;"), Ok(alter_table(
+                AlterTableOperation::RenameColumn { col: "col_1".to_string(), new_name: "col_2".to_string() }
+            )));
+
+            assert!(SqlParser::parse("ALTER TABLE table_1 ADD COLUMN col_3 INT DEFAULT col_1;").is_err());
+            assert!(SqlParser::parse("ALTER TABLE table_1 RENAME TO table_2;").is_err());
+        }
+
+        #[test]
+        fn alter_column_type() {
+            let expected_statement = alter_table(AlterTableOperation::AlterColumnType {
+                col: "col_1".to_string(),
+                data_type: BongoDataType::Varchar(20),
+            });
+
+            assert_eq!(SqlParser::parse("ALTER TABLE table_1 ALTER COLUMN col_1 TYPE VARCHAR(20);"), Ok(expected_statement));
+            assert_eq!(SqlParser::parse("alter table table_1\n alter col_1 set data type varchar(20);"), Ok(alter_table(
+                AlterTableOperation::AlterColumnType { col: "col_1".to_string(), data_type: BongoDataType::Varchar(20) }
+            )));
+            assert_eq!(SqlParser::parse("ALTER TABLE table_1 ALTER COLUMN col_1 SET DATA TYPE VARCHAR(20);"), Ok(alter_table(
+                AlterTableOperation::AlterColumnType { col: "col_1".to_string(), data_type: BongoDataType::Varchar(20) }
+            )));
+            assert!(SqlParser::parse("ALTER TABLE table_1 ALTER COLUMN col_1 SET DEFAULT 5;").is_err());
+        }
+    }
+
+    mod index {
+        use crate::index::IndexKind;
+        use crate::statement::{CreateIndex, DropIndex, Statement};
+
+        use super::super::SqlParser;
+
+        #[test]
+        fn create_index() {
+            let statement = SqlParser::parse("CREATE INDEX idx_1 ON table_1 (col_2);");
+
+            let expected_statement = Statement::CreateIndex(CreateIndex {
+                name: "idx_1".to_string(),
+                table: "table_1".to_string(),
+                col: "col_2".to_string(),
+                kind: IndexKind::Hash,
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+            assert!(SqlParser::parse("CREATE INDEX idx_1 ON table_1 (col_1, col_2);").is_err());
+            assert!(SqlParser::parse("CREATE UNIQUE INDEX idx_1 ON table_1 (col_1);").is_err());
+        }
+
+        #[test]
+        fn create_index_using() {
+            let expected_statement = Statement::CreateIndex(CreateIndex {
+                name: "idx_1".to_string(),
+                table: "table_1".to_string(),
+                col: "col_2".to_string(),
+                kind: IndexKind::BTree,
+            });
+
+            assert_eq!(SqlParser::parse("CREATE INDEX idx_1 ON table_1 USING BTREE (col_2);"), Ok(expected_statement));
+            assert_eq!(
+                SqlParser::parse("create index idx_1 on table_1(col_2) using hash;").map(|statement| match statement {
+                    Statement::CreateIndex(create_index) => create_index.kind,
+                    _ => panic!("not a CREATE INDEX statement"),
+                }),
+                Ok(IndexKind::Hash)
+            );
+            assert!(SqlParser::parse("CREATE INDEX idx_1 ON table_1 USING GIST (col_2);").is_err());
+        }
+
+        #[test]
+        fn drop_index() {
+            let statement = SqlParser::parse("DROP INDEX idx_1, idx_2;");
+
+            let expected_statement = Statement::DropIndex(DropIndex {
+                names: vec!["idx_1".to_string(), "idx_2".to_string()],
+            });
+
+            assert_eq!(statement, Ok(expected_statement));
+        }
+    }
+
     mod flush {
         use bongo_core::types::BongoError::SqlSyntaxError;
 
@@ -636,6 +1802,91 @@ mod tests {
         }
     }
 
+    mod transaction {
+        use crate::sql_parser::parser::SqlParser;
+        use crate::statement::Statement;
+
+        #[test]
+        fn begin_commit_rollback() {
+            assert_eq!(Ok(Statement::Begin), SqlParser::parse("BEGIN;"));
+            assert_eq!(Ok(Statement::Begin), SqlParser::parse("START TRANSACTION;"));
+            assert_eq!(Ok(Statement::Commit), SqlParser::parse("COMMIT;"));
+            assert_eq!(Ok(Statement::Rollback), SqlParser::parse("ROLLBACK;"));
+            assert!(SqlParser::parse("COMMIT AND CHAIN;").is_err());
+        }
+    }
+
+    mod explain {
+        use crate::sql_parser::parser::SqlParser;
+        use crate::statement::{Delete, Explain, Select, SelectItem, Statement};
+
+        #[test]
+        fn explain() {
+            let response_select = SqlParser::parse("EXPLAIN SELECT * FROM table_1;");
+            let response_analyze = SqlParser::parse("EXPLAIN ANALYZE DELETE FROM table_1;");
+            let response_insert = SqlParser::parse("EXPLAIN INSERT INTO table_1 (col_1) VALUES (1);");
+            let response_verbose = SqlParser::parse("EXPLAIN VERBOSE SELECT * FROM table_1;");
+
+            assert_eq!(Ok(Statement::Explain(Explain {
+                analyze: false,
+                statement: Box::new(Statement::Select(Select {
+                    cols: vec![SelectItem::Wildcard],
+                    table: "table_1".to_string(),
+                    joins: vec![],
+                    condition: None,
+                    group_by: vec![],
+                    having: None,
+                    order: vec![],
+                    limit: None,
+                    offset: None,
+                })),
+            })), response_select);
+            assert_eq!(Ok(Statement::Explain(Explain {
+                analyze: true,
+                statement: Box::new(Statement::Delete(Delete { table: "table_1".to_string(), condition: None })),
+            })), response_analyze);
+            assert!(response_insert.is_err());
+            assert!(response_verbose.is_err());
+        }
+    }
+
+    mod vacuum {
+        use crate::sql_parser::parser::SqlParser;
+        use crate::statement::{Statement, Vacuum};
+
+        #[test]
+        fn vacuum() {
+            let response_all = SqlParser::parse("VACUUM;");
+            let response_table = SqlParser::parse("vacuum table_1 ;");
+            let response_two_tables = SqlParser::parse("VACUUM table_1 table_2;");
+
+            assert_eq!(Ok(Statement::Vacuum(Vacuum { table: None })), response_all);
+            assert_eq!(Ok(Statement::Vacuum(Vacuum { table: Some("table_1".to_string()) })), response_table);
+            assert!(response_two_tables.is_err());
+        }
+    }
+
+    mod sequence {
+        use crate::sql_parser::parser::SqlParser;
+        use crate::statement::{CreateSequence, DropSequence, Statement};
+
+        #[test]
+        fn create_and_drop_sequence() {
+            let response_default = SqlParser::parse("CREATE SEQUENCE seq_1;");
+            let response_options = SqlParser::parse("create sequence seq_1 INCREMENT BY -2 START 10;");
+            let response_zero = SqlParser::parse("CREATE SEQUENCE seq_1 INCREMENT 0;");
+            let response_unknown_option = SqlParser::parse("CREATE SEQUENCE seq_1 CACHE 10;");
+            let response_drop = SqlParser::parse("DROP SEQUENCE IF EXISTS seq_1;");
+
+            let create = |start, increment| Statement::CreateSequence(CreateSequence { name: "seq_1".to_string(), start, increment });
+            assert_eq!(Ok(create(1, 1)), response_default);
+            assert_eq!(Ok(create(10, -2)), response_options);
+            assert!(response_zero.is_err());
+            assert!(response_unknown_option.is_err());
+            assert_eq!(Ok(Statement::DropSequence(DropSequence { name: "seq_1".to_string(), if_exists: true })), response_drop);
+        }
+    }
+
     mod drop_db {
         use crate::statement::{DropDB, Statement};
 
@@ -1,10 +1,19 @@
-use bongo_core::types::{BongoError, BongoLiteral, ColumnDef, Row};
+use bongo_core::datetime::{Date, Interval, Time, Timestamp};
+use bongo_core::decimal::Decimal;
+use bongo_core::types::{BongoDataType, BongoError, BongoLiteral, ColumnDef, parse_hex, Row};
+use serde::{Deserialize, Serialize};
 use sqlparser::ast::{
-    Assignment as SqlParserAssignment, BinaryOperator as SqlParserBinOp, BinaryOperator,
-    Expr as SqlParserExpr, OrderByExpr, SelectItem as SqlParserSelectItem, Value,
+    Assignment as SqlParserAssignment, BinaryOperator as SqlParserBinOp, BinaryOperator, DataType,
+    DateTimeField, Expr as SqlParserExpr, Function, FunctionArg, OrderByExpr,
+    SelectItem as SqlParserSelectItem, UnaryOperator, Value,
 };
 use std::convert::TryFrom;
+use std::fmt;
+use std::iter;
 use std::mem;
+use std::str::FromStr;
+
+use crate::index::{IndexDef, IndexKind};
 
 ///
 /// ApplyAssignments is an extension trait that is implemented by the Row type.
@@ -17,16 +26,19 @@ pub trait ApplyAssignments {
     ///
     /// `assignments` defines assignments to identifiers. The location of the identifiers inside of the
     /// row itself i.e. their index is defined through the vector of column names `cols`.
+    /// The values of all assignments are evaluated for the row with the `NullLogic` `logic` before
+    /// any of them is assigned.
     ///
-    fn apply_assignments(self, assignments: &[Assignment], cols: &[String])
+    fn apply_assignments(self, assignments: &[Assignment], cols: &[String], logic: NullLogic)
                          -> Result<Self, BongoError> where Self: Sized;
 }
 
 impl ApplyAssignments for Row {
-    fn apply_assignments(mut self, assignments: &[Assignment], cols: &[String]) -> Result<Self, BongoError> {
+    fn apply_assignments(mut self, assignments: &[Assignment], cols: &[String], logic: NullLogic) -> Result<Self, BongoError> {
         if self.len() != cols.len() {
             return Err(BongoError::InternalError("Cannot assign to row because column definition has a different size than row.".to_string()));
         }
+        let mut values = vec![];
         for a in assignments {
             let index = cols.iter().position(|c| c == &a.col_name.as_str());
             if index.is_none() {
@@ -36,7 +48,11 @@ impl ApplyAssignments for Row {
                 )));
             }
 
-            self[index.unwrap()] = a.val.clone();
+            values.push((index.unwrap(), a.val.value(&self, cols, logic)?));
+        }
+
+        for (index, val) in values {
+            self[index] = val;
         }
 
         Ok(self)
@@ -47,10 +63,13 @@ impl ApplyAssignments for Row {
 /// `Assignment` is a structure that represents an assignment of a value to a column.
 /// So far assignments are only supported and used in SQL UPDATE statements.
 ///
+/// The value is an expression, which is evaluated for the row the value is assigned to, e.g.
+/// `balance = balance - 10`.
+///
 #[derive(Debug, PartialEq)]
 pub struct Assignment {
     pub col_name: String,
-    pub val: BongoLiteral,
+    pub val: Expr,
 }
 
 ///
@@ -89,29 +108,60 @@ impl TryFrom<SqlParserAssignment> for Assignment {
             ));
         }
 
-        let expr = Expr::try_from(parser_assignment.value)?;
+        let val = Expr::try_from(parser_assignment.value)?;
 
-        match expr {
-            Expr::Value(val) => Ok(Self {
-                col_name: String::from(&parser_assignment.id[0].value),
-                val,
-            }),
-            _ => Err(BongoError::UnsupportedFeatureError(
-                "Only values are supported in assignments".to_string(),
-            )),
+        if val.contains_aggregate() {
+            return Err(BongoError::UnsupportedFeatureError(
+                "Aggregate functions cannot be used in assignments.".to_string(),
+            ));
         }
+
+        Ok(Self {
+            col_name: String::from(&parser_assignment.id[0].value),
+            val,
+        })
     }
 }
 
 ///
-/// `Order` represents the semantics of an SQL ORDER BY clause.
-/// However, in SQL ORDER BY clauses can be specified with multiple orders while `BongoDB` so far
-/// only supports ordering by exactly one column.
+/// `Order` represents one column of an SQL ORDER BY clause. The rows are ordered by the first
+/// `Order` of the clause and rows that are equal in that column are ordered by the next one.
 ///
-#[derive(Debug, PartialEq)]
-pub enum Order {
-    Asc(String),
-    Desc(String),
+#[derive(Debug, PartialEq, Clone)]
+pub struct Order {
+    pub col: String,
+    pub asc: bool,
+    ///
+    /// Whether NULL values are ordered before all other values. If neither `NULLS FIRST` nor
+    /// `NULLS LAST` is given, NULL values are ordered as if they were greater than all other values,
+    /// i.e. last for `ASC` and first for `DESC`.
+    ///
+    pub nulls_first: bool,
+}
+
+impl Order {
+    ///
+    /// Returns true if the values are ordered like in a B-tree index, in which NULL values are
+    /// greater than all other values, or in the reverse order.
+    ///
+    pub fn is_index_order(&self) -> bool {
+        self.asc != self.nulls_first
+    }
+}
+
+///
+/// Formats the order as SQL. `NULLS FIRST` and `NULLS LAST` are only given if they differ from the
+/// default of the direction.
+///
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.col, if self.asc { "ASC" } else { "DESC" })?;
+        match (self.is_index_order(), self.nulls_first) {
+            (true, _) => Ok(()),
+            (false, true) => write!(f, " NULLS FIRST"),
+            (false, false) => write!(f, " NULLS LAST"),
+        }
+    }
 }
 
 ///
@@ -122,22 +172,13 @@ impl TryFrom<OrderByExpr> for Order {
     type Error = BongoError;
 
     fn try_from(order_expr: OrderByExpr) -> Result<Self, Self::Error> {
-        match order_expr.expr {
-            SqlParserExpr::Identifier(ident) => {
-                let column = String::from(&ident.value);
-
-                Ok(match order_expr.asc {
-                    None => Order::Asc(column),
-                    Some(is_asc) => match is_asc {
-                        true => Order::Asc(column),
-                        false => Order::Desc(column),
-                    },
-                })
+        match Expr::try_from(order_expr.expr) {
+            Ok(Expr::Identifier(col)) => {
+                let asc = order_expr.asc.unwrap_or(true);
+                Ok(Order { col, asc, nulls_first: order_expr.nulls_first.unwrap_or(!asc) })
             }
             _ => Err(BongoError::UnsupportedFeatureError(
-                "ORDER BY is only supported with exactly \
-                one argument which must be a column name."
-                    .to_string(),
+                "ORDER BY is only supported with column names.".to_string(),
             )),
         }
     }
@@ -146,18 +187,31 @@ impl TryFrom<OrderByExpr> for Order {
 ///
 /// `SelectItem` represents an item in a projection in SQL.
 ///
-/// Although SQL is more powerful, `BongoDB` so far only supports column names and unqualified
-/// wildcard (asterisks *) as `SelectItem`s.
+/// Although SQL is more powerful, `BongoDB` so far only supports column names, which might be
+/// qualified by their table, wildcards (asterisks *) and aggregate functions as `SelectItem`s.
 ///
 /// # Examples
-/// In the statement `SELECT col_1, col_2 FROM table_1`
-/// `col_1` and `col_2` are select items.
+/// In the statement `SELECT col_1, table_2.col_2, table_2.* FROM table_1 JOIN table_2 ON ...`
+/// `col_1`, `table_2.col_2` and `table_2.*` are select items.
+/// In the statement `SELECT col_1, COUNT(*) FROM table_1 GROUP BY col_1` `col_1` and `COUNT(*)`
+/// are select items.
+/// In the statement `SELECT col_1 * 2 AS double FROM table_1` `col_1 * 2 AS double` is a select item.
 ///
 ///
 #[derive(Debug, PartialEq)]
 pub enum SelectItem {
     ColumnName(String),
     Wildcard,
+    ///
+    /// All columns of the contained table.
+    ///
+    QualifiedWildcard(String),
+    Aggregate(Aggregate),
+    ///
+    /// A value computed from the columns of a row, which can be named by an alias with `AS`. The
+    /// alias can be used in `ORDER BY`.
+    ///
+    Expr { expr: Expr, alias: Option<String> },
 }
 
 ///
@@ -168,17 +222,227 @@ impl TryFrom<SqlParserSelectItem> for SelectItem {
     type Error = BongoError;
 
     fn try_from(item: SqlParserSelectItem) -> Result<Self, Self::Error> {
-        let error = Err(BongoError::UnsupportedFeatureError(
-            "Only identifiers and unqualified wildcards \
-            are supported as select items by BongoDB."
-                .to_string(),
-        ));
         match item {
-            SqlParserSelectItem::UnnamedExpr(SqlParserExpr::Identifier(ident)) =>
-                { Ok(SelectItem::ColumnName(String::from(&ident.value))) }
-
+            SqlParserSelectItem::UnnamedExpr(expr) => {
+                match Expr::try_from(expr)? {
+                    Expr::Identifier(name) => Ok(SelectItem::ColumnName(name)),
+                    Expr::Aggregate(aggregate) => Ok(SelectItem::Aggregate(aggregate)),
+                    expr => Ok(SelectItem::Expr { expr, alias: None }),
+                }
+            }
+            SqlParserSelectItem::ExprWithAlias { expr, alias } => {
+                Ok(SelectItem::Expr { expr: Expr::try_from(expr)?, alias: Some(alias.value) })
+            }
+            SqlParserSelectItem::QualifiedWildcard(name) if name.0.len() == 1 => {
+                Ok(SelectItem::QualifiedWildcard(String::from(&name.0[0].value)))
+            }
             SqlParserSelectItem::Wildcard => Ok(SelectItem::Wildcard),
-            _ => error
+            _ => Err(BongoError::UnsupportedFeatureError(
+                "Wildcards can only be qualified by a table name, e.g. table_1.*.".to_string(),
+            )),
+        }
+    }
+}
+
+///
+/// `AggregateFunc` is a function that combines the values of a column in all rows of a group to a
+/// single value.
+///
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum AggregateFunc {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl fmt::Display for AggregateFunc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AggregateFunc::Count => "COUNT",
+            AggregateFunc::Sum => "SUM",
+            AggregateFunc::Avg => "AVG",
+            AggregateFunc::Min => "MIN",
+            AggregateFunc::Max => "MAX",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+///
+/// `Aggregate` is the call of an aggregate function on a column, e.g. `SUM(col_1)`.
+///
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Aggregate {
+    pub func: AggregateFunc,
+    ///
+    /// The column whose values are aggregated. `None` stands for `*` in `COUNT(*)`, which counts
+    /// all rows including the rows with NULL values.
+    ///
+    pub col: Option<String>,
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.col {
+            Some(col) => write!(f, "{}({})", self.func, col),
+            None => write!(f, "{}(*)", self.func),
+        }
+    }
+}
+
+///
+/// Tries to convert a function call of the used `sqlparser`-library into an `Aggregate`.
+///
+impl TryFrom<Function> for Aggregate {
+    type Error = BongoError;
+
+    fn try_from(function: Function) -> Result<Self, Self::Error> {
+        let name = function.name.to_string().to_uppercase();
+        let func = match name.as_str() {
+            "COUNT" => AggregateFunc::Count,
+            "SUM" => AggregateFunc::Sum,
+            "AVG" => AggregateFunc::Avg,
+            "MIN" => AggregateFunc::Min,
+            "MAX" => AggregateFunc::Max,
+            _ => return Err(BongoError::UnsupportedFeatureError(format!(
+                "The function '{name}' is not supported by BongoDB. Supported functions are COUNT, SUM, AVG, MIN, MAX, \
+                NOW, CURRENT_DATE, CURRENT_TIME, CURRENT_TIMESTAMP and EXTRACT."
+            ))),
+        };
+        if function.distinct || function.over.is_some() {
+            return Err(BongoError::UnsupportedFeatureError(
+                "BongoDB does not support DISTINCT and OVER in aggregate functions.".to_string()
+            ));
+        }
+
+        let mut args = function.args;
+        let col = match (args.pop(), args.is_empty()) {
+            (Some(FunctionArg::Unnamed(SqlParserExpr::Wildcard)), true) if func == AggregateFunc::Count => None,
+            (Some(FunctionArg::Unnamed(expr @ (SqlParserExpr::Identifier(_) | SqlParserExpr::CompoundIdentifier(_)))), true) => {
+                match Expr::try_from(expr)? {
+                    Expr::Identifier(col) => Some(col),
+                    _ => unreachable!("identifiers are always converted to identifiers"),
+                }
+            }
+            _ => return Err(BongoError::UnsupportedFeatureError(format!(
+                "The argument of {func} must be a single column name{}.",
+                if func == AggregateFunc::Count { " or *" } else { "" }
+            ))),
+        };
+
+        Ok(Self { func, col })
+    }
+}
+
+///
+/// `DateField` is a field of a date, time or timestamp that can be extracted with `EXTRACT`.
+///
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum DateField {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DateField {
+    ///
+    /// Returns the field of `val`. The seconds are a `BongoLiteral::Decimal` with their fraction,
+    /// all other fields are integers.
+    ///
+    fn extract(&self, val: &BongoLiteral) -> Result<BongoLiteral, BongoError> {
+        let (date, time) = match val {
+            BongoLiteral::Date(date) => (Some(*date), None),
+            BongoLiteral::Time(time) => (None, Some(*time)),
+            BongoLiteral::Timestamp(timestamp) => (Some(timestamp.date()), Some(timestamp.time())),
+            _ => (None, None),
+        };
+
+        let field = match (self, date, time) {
+            (DateField::Year, Some(date), _) => Some(BongoLiteral::Int(date.ymd().0)),
+            (DateField::Month, Some(date), _) => Some(BongoLiteral::Int(date.ymd().1 as i64)),
+            (DateField::Day, Some(date), _) => Some(BongoLiteral::Int(date.ymd().2 as i64)),
+            (DateField::Hour, _, Some(time)) => Some(BongoLiteral::Int(time.hms_micro().0 as i64)),
+            (DateField::Minute, _, Some(time)) => Some(BongoLiteral::Int(time.hms_micro().1 as i64)),
+            (DateField::Second, _, Some(time)) => Decimal::new(time.hms_micro().2 as i128, 6).map(BongoLiteral::Decimal),
+            _ => None,
+        };
+        field.ok_or_else(|| BongoError::SqlRuntimeError(format!("Cannot extract {} from '{:?}'.", self, val)))
+    }
+}
+
+impl fmt::Display for DateField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DateField::Year => "YEAR",
+            DateField::Month => "MONTH",
+            DateField::Day => "DAY",
+            DateField::Hour => "HOUR",
+            DateField::Minute => "MINUTE",
+            DateField::Second => "SECOND",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl From<DateTimeField> for DateField {
+    fn from(field: DateTimeField) -> Self {
+        match field {
+            DateTimeField::Year => DateField::Year,
+            DateTimeField::Month => DateField::Month,
+            DateTimeField::Day => DateField::Day,
+            DateTimeField::Hour => DateField::Hour,
+            DateTimeField::Minute => DateField::Minute,
+            DateTimeField::Second => DateField::Second,
+        }
+    }
+}
+
+///
+/// `ScalarFunc` is a function that computes a single value from the values of its arguments in a
+/// row, e.g. `EXTRACT(YEAR FROM col_1)`.
+///
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ScalarFunc {
+    ///
+    /// `NOW()` and `CURRENT_TIMESTAMP`, the current date and time in UTC when the function is
+    /// evaluated.
+    ///
+    Now,
+    CurrentDate,
+    CurrentTime,
+    Extract(DateField),
+}
+
+impl ScalarFunc {
+    ///
+    /// Returns the function without arguments with the name `name`, e.g. `NOW`.
+    ///
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "NOW" | "CURRENT_TIMESTAMP" => Some(ScalarFunc::Now),
+            "CURRENT_DATE" => Some(ScalarFunc::CurrentDate),
+            "CURRENT_TIME" => Some(ScalarFunc::CurrentTime),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Applies the function to the values of its arguments. `EXTRACT` from `BongoLiteral::Null` is
+    /// `BongoLiteral::Null`.
+    ///
+    pub fn apply(&self, args: &[BongoLiteral]) -> Result<BongoLiteral, BongoError> {
+        match (self, args) {
+            (ScalarFunc::Now, []) => Ok(BongoLiteral::Timestamp(Timestamp::now())),
+            (ScalarFunc::CurrentDate, []) => Ok(BongoLiteral::Date(Timestamp::now().date())),
+            (ScalarFunc::CurrentTime, []) => Ok(BongoLiteral::Time(Timestamp::now().time())),
+            (ScalarFunc::Extract(_), [BongoLiteral::Null]) => Ok(BongoLiteral::Null),
+            (ScalarFunc::Extract(field), [val]) => field.extract(val),
+            _ => Err(BongoError::InternalError(format!("Wrong number of arguments for {:?}.", self))),
         }
     }
 }
@@ -187,7 +451,7 @@ impl TryFrom<SqlParserSelectItem> for SelectItem {
 /// `BinOp` represents a binary operator which can appear inside an expression.
 /// `BongoDB` does not support all binary operators that exist in SQL
 ///
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum BinOp {
     Gt,
     Lt,
@@ -197,20 +461,254 @@ pub enum BinOp {
     NotEq,
     And,
     Or,
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Modulo,
 }
 
 impl BinOp {
     ///
-    /// Applies the `BinOp` to two `BongoLiteral`s.
+    /// Returns how strongly the operator binds its operands. Operators with a higher precedence
+    /// are applied first.
+    ///
+    fn precedence(&self) -> u8 {
+        match self {
+            BinOp::Or => 0,
+            BinOp::And => 1,
+            // `NOT` binds more strongly than `AND` and less strongly than comparisons
+            BinOp::Gt | BinOp::Lt | BinOp::GtEq | BinOp::LtEq | BinOp::Eq | BinOp::NotEq => 3,
+            BinOp::Plus | BinOp::Minus => 4,
+            BinOp::Multiply | BinOp::Divide | BinOp::Modulo => 5,
+        }
+    }
+
+    ///
+    /// Applies an arithmetic operator to two `BongoLiteral`s. If one of them is `BongoLiteral::Null`
+    /// the result is `BongoLiteral::Null`. Overflows and divisions by zero result in an error.
+    ///
+    /// The result is a `BongoLiteral::Float` if one of the operands is a `BongoLiteral::Float`,
+    /// otherwise a `BongoLiteral::Decimal` if one of them is a `BongoLiteral::Decimal`. Integers are
+    /// only combined to integers. Dates and times are combined as described in `apply_datetime`.
+    ///
+    pub fn apply_arithmetic(&self, left: &BongoLiteral, right: &BongoLiteral) -> Result<BongoLiteral, BongoError> {
+        let (left, right) = match (left, right) {
+            (BongoLiteral::Null, _) | (_, BongoLiteral::Null) => return Ok(BongoLiteral::Null),
+            (BongoLiteral::Date(_) | BongoLiteral::Time(_) | BongoLiteral::Timestamp(_) | BongoLiteral::Interval(_), _)
+            | (_, BongoLiteral::Date(_) | BongoLiteral::Time(_) | BongoLiteral::Timestamp(_) | BongoLiteral::Interval(_)) => {
+                return self.apply_datetime(left, right);
+            }
+            (BongoLiteral::Int(left), BongoLiteral::Int(right)) => (*left, *right),
+            (BongoLiteral::Float(_), _) | (_, BongoLiteral::Float(_)) if left.as_f64().is_some() && right.as_f64().is_some() => {
+                // unwraps safe, because both literals are numbers
+                return self.apply_float(left.as_f64().unwrap(), right.as_f64().unwrap());
+            }
+            (BongoLiteral::Int(_) | BongoLiteral::Decimal(_), BongoLiteral::Int(_) | BongoLiteral::Decimal(_)) => {
+                let as_decimal = |lit: &BongoLiteral| match lit {
+                    BongoLiteral::Int(val) => Decimal::from(*val),
+                    BongoLiteral::Decimal(val) => *val,
+                    _ => unreachable!("only integers and decimals are converted"),
+                };
+                return self.apply_decimal(as_decimal(left), as_decimal(right));
+            }
+            _ => return Err(BongoError::SqlRuntimeError(format!(
+                "Cannot apply '{}' to '{:?}' and '{:?}'. Arithmetic operators can only be applied to numbers.",
+                self, left, right
+            ))),
+        };
+
+        if right == 0 && matches!(self, BinOp::Divide | BinOp::Modulo) {
+            return Err(BongoError::SqlRuntimeError("Division by zero.".to_string()));
+        }
+        let result = match self {
+            BinOp::Plus => left.checked_add(right),
+            BinOp::Minus => left.checked_sub(right),
+            BinOp::Multiply => left.checked_mul(right),
+            BinOp::Divide => left.checked_div(right),
+            BinOp::Modulo => left.checked_rem(right),
+            _ => return Err(BongoError::InternalError(format!("'{}' is not an arithmetic operator.", self))),
+        };
+
+        match result {
+            Some(result) => Ok(BongoLiteral::Int(result)),
+            None => Err(BongoError::SqlRuntimeError(format!("Integer overflow in {} {} {}.", left, self, right))),
+        }
+    }
+
+    fn apply_float(&self, left: f64, right: f64) -> Result<BongoLiteral, BongoError> {
+        if right == 0.0 && matches!(self, BinOp::Divide | BinOp::Modulo) {
+            return Err(BongoError::SqlRuntimeError("Division by zero.".to_string()));
+        }
+        let result = match self {
+            BinOp::Plus => left + right,
+            BinOp::Minus => left - right,
+            BinOp::Multiply => left * right,
+            BinOp::Divide => left / right,
+            BinOp::Modulo => left % right,
+            _ => return Err(BongoError::InternalError(format!("'{}' is not an arithmetic operator.", self))),
+        };
+
+        match result.is_finite() {
+            true => Ok(BongoLiteral::Float(result)),
+            false => Err(BongoError::SqlRuntimeError(format!("Floating point overflow in {} {} {}.", left, self, right))),
+        }
+    }
+
+    fn apply_decimal(&self, left: Decimal, right: Decimal) -> Result<BongoLiteral, BongoError> {
+        if right.is_zero() && matches!(self, BinOp::Divide | BinOp::Modulo) {
+            return Err(BongoError::SqlRuntimeError("Division by zero.".to_string()));
+        }
+        let result = match self {
+            BinOp::Plus => left.checked_add(&right),
+            BinOp::Minus => left.checked_sub(&right),
+            BinOp::Multiply => left.checked_mul(&right),
+            BinOp::Divide => left.checked_div(&right),
+            BinOp::Modulo => left.checked_rem(&right),
+            _ => return Err(BongoError::InternalError(format!("'{}' is not an arithmetic operator.", self))),
+        };
+
+        match result {
+            Some(result) => Ok(BongoLiteral::Decimal(result)),
+            None => Err(BongoError::SqlRuntimeError(format!("Decimal overflow in {} {} {}.", left, self, right))),
+        }
+    }
+
+    ///
+    /// Applies `+` or `-` to dates, times, timestamps and intervals or `*` to an interval and an
+    /// integer:
+    /// - a date plus or minus an integer is the date that many days later or earlier and the
+    ///   difference of two dates is the amount of days between them.
+    /// - a date or timestamp plus or minus an interval is a timestamp, the difference of two
+    ///   timestamps is an interval. Dates are used as the timestamps at the start of their days.
+    /// - a time plus or minus an interval is a time, which wraps around midnight. Only the hours,
+    ///   minutes and seconds of the interval are added. The difference of two times is an interval.
+    /// - intervals can be added, subtracted and multiplied by integers.
+    ///
+    fn apply_datetime(&self, left: &BongoLiteral, right: &BongoLiteral) -> Result<BongoLiteral, BongoError> {
+        let timestamp = |lit: &BongoLiteral| match lit {
+            BongoLiteral::Date(date) => Some(Timestamp::from(*date)),
+            BongoLiteral::Timestamp(timestamp) => Some(*timestamp),
+            _ => None,
+        };
+
+        let result = match (self, left, right) {
+            (BinOp::Plus, BongoLiteral::Date(date), BongoLiteral::Int(days))
+            | (BinOp::Plus, BongoLiteral::Int(days), BongoLiteral::Date(date)) => {
+                date.checked_add_days(*days).map(BongoLiteral::Date)
+            }
+            (BinOp::Minus, BongoLiteral::Date(date), BongoLiteral::Int(days)) => {
+                days.checked_neg().and_then(|days| date.checked_add_days(days)).map(BongoLiteral::Date)
+            }
+            (BinOp::Minus, BongoLiteral::Date(left), BongoLiteral::Date(right)) => {
+                Some(BongoLiteral::Int(left.days() as i64 - right.days() as i64))
+            }
+            (BinOp::Plus, val, BongoLiteral::Interval(interval))
+            | (BinOp::Plus, BongoLiteral::Interval(interval), val) if timestamp(val).is_some() => {
+                timestamp(val).unwrap().checked_add(interval).map(BongoLiteral::Timestamp)
+            }
+            (BinOp::Minus, val, BongoLiteral::Interval(interval)) if timestamp(val).is_some() => {
+                interval.checked_neg()
+                    .and_then(|interval| timestamp(val).unwrap().checked_add(&interval))
+                    .map(BongoLiteral::Timestamp)
+            }
+            (BinOp::Minus, left, right) if timestamp(left).is_some() && timestamp(right).is_some() => {
+                timestamp(left).unwrap().checked_sub(&timestamp(right).unwrap()).map(BongoLiteral::Interval)
+            }
+            (BinOp::Plus, BongoLiteral::Time(time), BongoLiteral::Interval(interval))
+            | (BinOp::Plus, BongoLiteral::Interval(interval), BongoLiteral::Time(time)) => {
+                Some(BongoLiteral::Time(time.wrapping_add_micros(interval.micros())))
+            }
+            (BinOp::Minus, BongoLiteral::Time(time), BongoLiteral::Interval(interval)) => {
+                Some(BongoLiteral::Time(time.wrapping_add_micros(-interval.micros())))
+            }
+            (BinOp::Minus, BongoLiteral::Time(left), BongoLiteral::Time(right)) => {
+                Interval::new(0, 0, left.micros() - right.micros()).map(BongoLiteral::Interval)
+            }
+            (BinOp::Plus, BongoLiteral::Interval(left), BongoLiteral::Interval(right)) => {
+                left.checked_add(right).map(BongoLiteral::Interval)
+            }
+            (BinOp::Minus, BongoLiteral::Interval(left), BongoLiteral::Interval(right)) => {
+                left.checked_sub(right).map(BongoLiteral::Interval)
+            }
+            (BinOp::Multiply, BongoLiteral::Interval(interval), BongoLiteral::Int(factor))
+            | (BinOp::Multiply, BongoLiteral::Int(factor), BongoLiteral::Interval(interval)) => {
+                interval.checked_mul(*factor).map(BongoLiteral::Interval)
+            }
+            _ => return Err(BongoError::SqlRuntimeError(format!(
+                "Cannot apply '{}' to '{:?}' and '{:?}'. Dates and times can only be combined with intervals, \
+                days and each other.", self, left, right
+            ))),
+        };
+
+        result.ok_or_else(|| BongoError::SqlRuntimeError(format!(
+            "The result of '{:?}' {} '{:?}' is out of range.", left, self, right
+        )))
+    }
+
+    ///
+    /// Applies the `BinOp` to two `BongoLiteral`s with the `NullLogic` `logic`.
     ///
     /// All literals != `BongoLiteral::Null` will be compared using the comparison operators of their
-    /// contained values.
-    /// `BongoLiteral::Null` values in logical expressions evaluate to false.
+    /// contained values. With `NullLogic::ThreeValued`, `BongoLiteral::Null` stands for an unknown
+    /// value: comparisons with it result in `BongoLiteral::Null` and `AND` and `OR` follow the
+    /// truth tables of SQL, e.g. `NULL AND false` is false and `NULL OR false` is `NULL`.
+    ///
+    /// Numbers are compared by their values, independent of their variants. Comparing literals of
+    /// other different variants (both of them not null) will result in an error as they are not
+    /// comparable.
+    ///
+    pub fn apply(&self, left: &BongoLiteral, right: &BongoLiteral, logic: NullLogic) -> Result<BongoLiteral, BongoError> {
+        if self.precedence() > BinOp::Eq.precedence() {
+            return self.apply_arithmetic(left, right);
+        }
+        if logic == NullLogic::Legacy {
+            return self.apply_legacy(left, right);
+        }
+
+        if let BinOp::And | BinOp::Or = self {
+            let (left, right) = (truth_value(left)?, truth_value(right)?);
+            let result = match (self, left, right) {
+                (BinOp::And, Some(false), _) | (BinOp::And, _, Some(false)) => Some(false),
+                (BinOp::Or, Some(true), _) | (BinOp::Or, _, Some(true)) => Some(true),
+                (_, Some(left), Some(right)) => Some(left && right),
+                _ => None,
+            };
+            return Ok(result.map_or(BongoLiteral::Null, BongoLiteral::Bool));
+        }
+
+        if *left == BongoLiteral::Null || *right == BongoLiteral::Null {
+            return Ok(BongoLiteral::Null);
+        }
+        self.compare(left, right)
+    }
+
     ///
-    /// Comparing literals of different variants (both of them not null) will result in an error as
-    /// they are not comparable.
+    /// Applies a comparison operator to two literals that are not `BongoLiteral::Null`.
     ///
-    pub fn apply(&self, left: &BongoLiteral, right: &BongoLiteral) -> Result<BongoLiteral, BongoError> {
+    fn compare(&self, left: &BongoLiteral, right: &BongoLiteral) -> Result<BongoLiteral, BongoError> {
+        let ordering = match left.compare(right) {
+            Some(ordering) => ordering,
+            None => return Err(BongoError::SqlRuntimeError(
+                format!("Cannot compare '{:?}' and '{:?}'. Can only compare instances of the same datatype.",
+                        left, right))),
+        };
+
+        Ok(BongoLiteral::Bool(match self {
+            BinOp::Lt => ordering.is_lt(),
+            BinOp::Gt => ordering.is_gt(),
+            BinOp::GtEq => ordering.is_ge(),
+            BinOp::LtEq => ordering.is_le(),
+            BinOp::Eq => ordering.is_eq(),
+            _ => ordering.is_ne(),
+        }))
+    }
+
+    ///
+    /// Applies a comparison or logical operator with `NullLogic::Legacy`, which treats
+    /// `BongoLiteral::Null` as false except in comparisons with `=` and `!=`.
+    ///
+    fn apply_legacy(&self, left: &BongoLiteral, right: &BongoLiteral) -> Result<BongoLiteral, BongoError> {
         // only equal discriminants can be compared.
         // The exception is Null
         // 1. Null can be compared in with Eq and NotEq operator to other values
@@ -234,22 +732,15 @@ impl BinOp {
             right = &BongoLiteral::Bool(false);
         }
 
+        if let BinOp::Lt | BinOp::Gt | BinOp::GtEq | BinOp::LtEq | BinOp::Eq | BinOp::NotEq = self {
+            return self.compare(left, right);
+        }
         if mem::discriminant(left) != mem::discriminant(right) {
             return Err(BongoError::SqlRuntimeError(
                 format!("Cannot compare '{:?}' and '{:?}'. Can only compare instances of the same datatype.",
                         left, right)));
         }
 
-        match self {
-            BinOp::Lt => { return Ok(BongoLiteral::Bool(left < right)); }
-            BinOp::Gt => { return Ok(BongoLiteral::Bool(left > right)); }
-            BinOp::GtEq => { return Ok(BongoLiteral::Bool(left >= right)); }
-            BinOp::LtEq => { return Ok(BongoLiteral::Bool(left <= right)); }
-            BinOp::Eq => { return Ok(BongoLiteral::Bool(left == right)); }
-            BinOp::NotEq => { return Ok(BongoLiteral::Bool(left != right)); }
-            _ => {}
-        }
-
         if let BongoLiteral::Bool(val_left) = left {
             // right is implicitly the same as left we tested in the beginning of the method -> no else needed
             if let BongoLiteral::Bool(val_right) = right {
@@ -292,6 +783,27 @@ impl BinOp {
     }
 }
 
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            BinOp::Gt => ">",
+            BinOp::Lt => "<",
+            BinOp::GtEq => ">=",
+            BinOp::LtEq => "<=",
+            BinOp::Eq => "=",
+            BinOp::NotEq => "!=",
+            BinOp::And => "AND",
+            BinOp::Or => "OR",
+            BinOp::Plus => "+",
+            BinOp::Minus => "-",
+            BinOp::Multiply => "*",
+            BinOp::Divide => "/",
+            BinOp::Modulo => "%",
+        };
+        write!(f, "{}", op)
+    }
+}
+
 ///
 /// Tries to convert a `BinaryOperator` of the used `sqlparser`-library into an object of the custom
 /// `BinOp` type paying attention to what features are supported by BongoDB.
@@ -309,15 +821,100 @@ impl TryFrom<SqlParserBinOp> for BinOp {
             BinaryOperator::NotEq => Ok(BinOp::NotEq),
             BinaryOperator::And => Ok(BinOp::And),
             BinaryOperator::Or => Ok(BinOp::Or),
+            BinaryOperator::Plus => Ok(BinOp::Plus),
+            BinaryOperator::Minus => Ok(BinOp::Minus),
+            BinaryOperator::Multiply => Ok(BinOp::Multiply),
+            BinaryOperator::Divide => Ok(BinOp::Divide),
+            BinaryOperator::Modulo => Ok(BinOp::Modulo),
             _ => Err(BongoError::UnsupportedFeatureError(
-                "Only the Operators >, <, >=, <=, =, !=, \
-                AND, OR are supported by BongoDB"
+                "Only the Operators >, <, >=, <=, =, !=, +, -, *, /, %, \
+                AND, OR, LIKE are supported by BongoDB"
                     .to_string(),
             )),
         }
     }
 }
 
+///
+/// `NullLogic` determines how `NULL` values are treated when expressions are evaluated.
+///
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum NullLogic {
+    ///
+    /// The three-valued logic of SQL, in which `NULL` is an unknown value. Comparisons with `NULL`
+    /// are unknown, so `NULL = NULL` and `NOT (NULL = 5)` are both unknown and rows for which a
+    /// condition is unknown are not returned.
+    ///
+    #[default]
+    ThreeValued,
+    ///
+    /// The logic of earlier versions of BongoDB, which treats `NULL` as false in logical operators,
+    /// `NOT` and ordering comparisons. `NULL = NULL` is true and `NULL = 5` is false. Databases whose
+    /// queries rely on this can keep using it.
+    ///
+    Legacy,
+}
+
+///
+/// Returns the boolean value of `val` or `None` if `val` is `BongoLiteral::Null`, i.e. unknown.
+///
+fn truth_value(val: &BongoLiteral) -> Result<Option<bool>, BongoError> {
+    match val {
+        BongoLiteral::Null => Ok(None),
+        val => val.as_bool().map(Some),
+    }
+}
+
+///
+/// `UnaryOp` represents an operator with a single operand.
+///
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum UnaryOp {
+    Minus,
+    Not,
+}
+
+impl UnaryOp {
+    ///
+    /// Applies the operator to a `BongoLiteral`. Negating `BongoLiteral::Null` with `-` results in
+    /// `BongoLiteral::Null`. `NOT NULL` is unknown with `NullLogic::ThreeValued` and true with
+    /// `NullLogic::Legacy`, which treats `BongoLiteral::Null` as false.
+    ///
+    pub fn apply(&self, val: &BongoLiteral, logic: NullLogic) -> Result<BongoLiteral, BongoError> {
+        match (self, val) {
+            (UnaryOp::Not, BongoLiteral::Bool(val)) => Ok(BongoLiteral::Bool(!val)),
+            (UnaryOp::Not, BongoLiteral::Null) if logic == NullLogic::Legacy => Ok(BongoLiteral::Bool(true)),
+            (UnaryOp::Not, BongoLiteral::Null) => Ok(BongoLiteral::Null),
+            (UnaryOp::Not, val) => Err(BongoError::SqlRuntimeError(format!(
+                "Cannot apply 'NOT' to '{:?}'. Logical operators can only be applied to BOOLEAN values.", val
+            ))),
+            (UnaryOp::Minus, BongoLiteral::Null) => Ok(BongoLiteral::Null),
+            (UnaryOp::Minus, BongoLiteral::Int(val)) => val.checked_neg().map(BongoLiteral::Int).ok_or_else(|| {
+                BongoError::SqlRuntimeError(format!("Integer overflow in -{val}."))
+            }),
+            (UnaryOp::Minus, BongoLiteral::Float(val)) => Ok(BongoLiteral::Float(-val)),
+            (UnaryOp::Minus, BongoLiteral::Decimal(val)) => val.checked_neg().map(BongoLiteral::Decimal).ok_or_else(|| {
+                BongoError::SqlRuntimeError(format!("Decimal overflow in -{val}."))
+            }),
+            (UnaryOp::Minus, BongoLiteral::Interval(val)) => val.checked_neg().map(BongoLiteral::Interval).ok_or_else(|| {
+                BongoError::SqlRuntimeError(format!("The result of -'{:?}' is out of range.", val))
+            }),
+            (UnaryOp::Minus, val) => Err(BongoError::SqlRuntimeError(format!(
+                "Cannot apply '-' to '{:?}'. Arithmetic operators can only be applied to numbers and intervals.", val
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryOp::Minus => write!(f, "-"),
+            UnaryOp::Not => write!(f, "NOT"),
+        }
+    }
+}
+
 ///
 /// `Expr` represents an expression in SQL.
 ///
@@ -326,49 +923,266 @@ impl TryFrom<SqlParserBinOp> for BinOp {
 /// `Value(BongoLiteral)` variant. Using a recursive definition allows to evaluating the expression
 /// in a natural way starting from its root.
 ///
+/// The name of an `Identifier` is a column name, which can be qualified by the name of its table
+/// in the form `<table>.<column>`.
+///
 /// # Examples
 ///
 /// In the statement `SELECT * FROM table_1 WHERE (a < b) AND (c = 5)`
 /// `(a < b) AND (c = 5)` is the variant `BinaryExpr` where each operand each is a `BinaryExpr`
 /// variant. The operands of these Expressions then are `Identifier`s ore `Value`s
 ///
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Expr {
     BinaryExpr {
         left: Box<Expr>,
         op: BinOp,
         right: Box<Expr>,
     },
+    Unary {
+        op: UnaryOp,
+        expr: Box<Expr>,
+    },
     Identifier(String),
     Value(BongoLiteral),
+    ///
+    /// `expr [NOT] BETWEEN low AND high`, which is equivalent to `expr >= low AND expr <= high`
+    /// or its negation.
+    ///
+    Between {
+        expr: Box<Expr>,
+        negated: bool,
+        low: Box<Expr>,
+        high: Box<Expr>,
+    },
+    ///
+    /// `expr [NOT] IN (list)`, which is true if `expr` is equal to any expression of `list`.
+    ///
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    ///
+    /// `expr [NOT] LIKE pattern`, where `%` in the pattern matches any sequence of characters
+    /// and `_` matches a single character.
+    ///
+    Like {
+        expr: Box<Expr>,
+        negated: bool,
+        pattern: Box<Expr>,
+    },
+    ///
+    /// `expr IS [NOT] NULL`
+    ///
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    ///
+    /// The value of an aggregate function for a group of rows, which can only be used in `HAVING`.
+    /// It has to be replaced by the value computed for the group before the expression is evaluated.
+    ///
+    Aggregate(Aggregate),
+    ///
+    /// The call of a scalar function, e.g. `NOW()`.
+    ///
+    Function {
+        func: ScalarFunc,
+        args: Vec<Expr>,
+    },
 }
 
 impl Expr {
     ///
-    /// Evaluates the expression recursively for a specific `Row`.
+    /// Replaces the name of every identifier in the expression by the name returned by `rename`.
+    ///
+    /// This is used to resolve the column names of a statement against the columns of the tables
+    /// it accesses.
+    ///
+    pub fn rename_identifiers<F>(self, rename: &F) -> Result<Expr, BongoError>
+        where F: Fn(&str) -> Result<String, BongoError> {
+        Ok(match self {
+            Expr::BinaryExpr { left, op, right } => Expr::BinaryExpr {
+                left: Box::new(left.rename_identifiers(rename)?),
+                op,
+                right: Box::new(right.rename_identifiers(rename)?),
+            },
+            Expr::Unary { op, expr } => Expr::Unary { op, expr: Box::new(expr.rename_identifiers(rename)?) },
+            Expr::Identifier(name) => Expr::Identifier(rename(&name)?),
+            Expr::Value(val) => Expr::Value(val),
+            Expr::Between { expr, negated, low, high } => Expr::Between {
+                expr: Box::new(expr.rename_identifiers(rename)?),
+                negated,
+                low: Box::new(low.rename_identifiers(rename)?),
+                high: Box::new(high.rename_identifiers(rename)?),
+            },
+            Expr::InList { expr, list, negated } => Expr::InList {
+                expr: Box::new(expr.rename_identifiers(rename)?),
+                list: list.into_iter().map(|item| item.rename_identifiers(rename)).collect::<Result<Vec<Expr>, BongoError>>()?,
+                negated,
+            },
+            Expr::Like { expr, negated, pattern } => Expr::Like {
+                expr: Box::new(expr.rename_identifiers(rename)?),
+                negated,
+                pattern: Box::new(pattern.rename_identifiers(rename)?),
+            },
+            Expr::IsNull { expr, negated } => Expr::IsNull { expr: Box::new(expr.rename_identifiers(rename)?), negated },
+            Expr::Aggregate(Aggregate { func, col }) => Expr::Aggregate(Aggregate {
+                func,
+                col: col.map(|col| rename(&col)).transpose()?,
+            }),
+            Expr::Function { func, args } => Expr::Function {
+                func,
+                args: args.into_iter().map(|arg| arg.rename_identifiers(rename)).collect::<Result<Vec<Expr>, BongoError>>()?,
+            },
+        })
+    }
+
+    ///
+    /// Calls `visit` with the name of every identifier in the expression.
+    ///
+    pub fn visit_identifiers<F>(&self, visit: &mut F) where F: FnMut(&str) {
+        match self {
+            Expr::BinaryExpr { left, right, .. } => {
+                left.visit_identifiers(visit);
+                right.visit_identifiers(visit);
+            }
+            Expr::Unary { expr, .. } => expr.visit_identifiers(visit),
+            Expr::Identifier(name) => visit(name),
+            Expr::Value(_) => {}
+            Expr::Between { expr, low, high, .. } => {
+                expr.visit_identifiers(visit);
+                low.visit_identifiers(visit);
+                high.visit_identifiers(visit);
+            }
+            Expr::InList { expr, list, .. } => {
+                expr.visit_identifiers(visit);
+                list.iter().for_each(|item| item.visit_identifiers(visit));
+            }
+            Expr::Like { expr, pattern, .. } => {
+                expr.visit_identifiers(visit);
+                pattern.visit_identifiers(visit);
+            }
+            Expr::IsNull { expr, .. } => expr.visit_identifiers(visit),
+            Expr::Aggregate(Aggregate { col, .. }) => {
+                if let Some(col) = col {
+                    visit(col);
+                }
+            }
+            Expr::Function { args, .. } => args.iter().for_each(|arg| arg.visit_identifiers(visit)),
+        }
+    }
+
+    ///
+    /// Replaces every aggregate function in the expression by the expression returned by `replace`.
+    ///
+    pub fn replace_aggregates<F>(self, replace: &mut F) -> Result<Expr, BongoError>
+        where F: FnMut(Aggregate) -> Result<Expr, BongoError> {
+        Ok(match self {
+            Expr::BinaryExpr { left, op, right } => Expr::BinaryExpr {
+                left: Box::new(left.replace_aggregates(replace)?),
+                op,
+                right: Box::new(right.replace_aggregates(replace)?),
+            },
+            Expr::Unary { op, expr } => Expr::Unary { op, expr: Box::new(expr.replace_aggregates(replace)?) },
+            Expr::Identifier(name) => Expr::Identifier(name),
+            Expr::Value(val) => Expr::Value(val),
+            Expr::Between { expr, negated, low, high } => Expr::Between {
+                expr: Box::new(expr.replace_aggregates(replace)?),
+                negated,
+                low: Box::new(low.replace_aggregates(replace)?),
+                high: Box::new(high.replace_aggregates(replace)?),
+            },
+            Expr::InList { expr, list, negated } => Expr::InList {
+                expr: Box::new(expr.replace_aggregates(replace)?),
+                list: list.into_iter().map(|item| item.replace_aggregates(replace)).collect::<Result<Vec<Expr>, BongoError>>()?,
+                negated,
+            },
+            Expr::Like { expr, negated, pattern } => Expr::Like {
+                expr: Box::new(expr.replace_aggregates(replace)?),
+                negated,
+                pattern: Box::new(pattern.replace_aggregates(replace)?),
+            },
+            Expr::IsNull { expr, negated } => Expr::IsNull { expr: Box::new(expr.replace_aggregates(replace)?), negated },
+            Expr::Aggregate(aggregate) => replace(aggregate)?,
+            Expr::Function { func, args } => Expr::Function {
+                func,
+                args: args.into_iter().map(|arg| arg.replace_aggregates(replace)).collect::<Result<Vec<Expr>, BongoError>>()?,
+            },
+        })
+    }
+
+    ///
+    /// Returns true if the expression contains an aggregate function.
+    ///
+    pub fn contains_aggregate(&self) -> bool {
+        match self {
+            Expr::BinaryExpr { left, right, .. } => left.contains_aggregate() || right.contains_aggregate(),
+            Expr::Unary { expr, .. } => expr.contains_aggregate(),
+            Expr::Identifier(_) | Expr::Value(_) => false,
+            Expr::Between { expr, low, high, .. } => {
+                expr.contains_aggregate() || low.contains_aggregate() || high.contains_aggregate()
+            }
+            Expr::InList { expr, list, .. } => expr.contains_aggregate() || list.iter().any(Expr::contains_aggregate),
+            Expr::Like { expr, pattern, .. } => expr.contains_aggregate() || pattern.contains_aggregate(),
+            Expr::IsNull { expr, .. } => expr.contains_aggregate(),
+            Expr::Aggregate(_) => true,
+            Expr::Function { args, .. } => args.iter().any(Expr::contains_aggregate),
+        }
+    }
+
+    ///
+    /// Returns true if the expression contains a call of a scalar function, e.g. `NOW()`.
+    ///
+    pub fn contains_function(&self) -> bool {
+        match self {
+            Expr::BinaryExpr { left, right, .. } => left.contains_function() || right.contains_function(),
+            Expr::Unary { expr, .. } => expr.contains_function(),
+            Expr::Identifier(_) | Expr::Value(_) | Expr::Aggregate(_) => false,
+            Expr::Between { expr, low, high, .. } => {
+                expr.contains_function() || low.contains_function() || high.contains_function()
+            }
+            Expr::InList { expr, list, .. } => expr.contains_function() || list.iter().any(Expr::contains_function),
+            Expr::Like { expr, pattern, .. } => expr.contains_function() || pattern.contains_function(),
+            Expr::IsNull { expr, .. } => expr.contains_function(),
+            Expr::Function { .. } => true,
+        }
+    }
+
+    ///
+    /// Evaluates the expression recursively for a specific `Row` with the `NullLogic` `logic`.
     ///
     /// Evaluating an expression requires a row to evaluate it as well as the knowledge of what
-    /// identifier i.e. column name is mapped to what index of the `row`.
+    /// identifier i.e. column name is mapped to what index of the `row`. An unknown result, i.e.
+    /// `NULL`, is false, so that a row for which a condition is unknown is not returned.
+    ///
+    pub fn eval(&self, row: &Row, cols: &[String], logic: NullLogic) -> Result<bool, BongoError> {
+        Ok(truth_value(&self.value(row, cols, logic)?)?.unwrap_or(false))
+    }
+
     ///
-    pub fn eval(&self, row: &Row, cols: &[String]) -> Result<bool, BongoError> {
-        // Expressions are evaluated from the leaves by evaluating them using the `BinOp::eval` method.
-        // As the final result is a boolean we have to use a helper function to convert the `BongoLiteral::Bool`
-        // to a `bool` in the end.
+    /// Computes the value of the expression for a specific `Row`, e.g. the value of `col_1 * 2` for
+    /// a computed select item.
+    ///
+    pub fn value(&self, row: &Row, cols: &[String], logic: NullLogic) -> Result<BongoLiteral, BongoError> {
+        // Expressions are evaluated from the leaves by evaluating them using the `BinOp::apply` method.
 
         if row.len() != cols.len() {
             return Err(BongoError::InternalError("Column size and row size are different".to_string()));
         }
 
-        self.eval_helper(row, cols)?.as_bool()
+        self.eval_helper(row, cols, logic)
     }
-    fn eval_helper(&self, row: &Row, cols: &[String]) -> Result<BongoLiteral, BongoError> {
+    fn eval_helper(&self, row: &Row, cols: &[String], logic: NullLogic) -> Result<BongoLiteral, BongoError> {
         match self {
             Expr::BinaryExpr { left, op, right } => {
-                let left_val = left.eval_helper(row, cols)?;
-                let right_val = right.eval_helper(row, cols)?;
+                let left_val = left.eval_helper(row, cols, logic)?;
+                let right_val = right.eval_helper(row, cols, logic)?;
 
-                op.apply(&left_val, &right_val)
+                op.apply(&left_val, &right_val, logic)
             }
+            Expr::Unary { op, expr } => op.apply(&expr.eval_helper(row, cols, logic)?, logic),
             Expr::Identifier(name) => {
                 let pos = cols.iter().position(|n| { n == name });
                 if pos.is_none() {
@@ -377,7 +1191,209 @@ impl Expr {
                 // return value in this column
                 Ok(row[pos.unwrap()].clone())
             }
+            Expr::Aggregate(aggregate) => Err(BongoError::SqlRuntimeError(format!(
+                "The aggregate function '{aggregate}' can only be used in select items and HAVING."
+            ))),
             Expr::Value(val) => { Ok(val.clone()) }
+            Expr::Between { expr, negated, low, high } => {
+                let val = expr.eval_helper(row, cols, logic)?;
+                let above_low = BinOp::GtEq.apply(&val, &low.eval_helper(row, cols, logic)?, logic)?;
+                let below_high = BinOp::LtEq.apply(&val, &high.eval_helper(row, cols, logic)?, logic)?;
+                let between = BinOp::And.apply(&above_low, &below_high, logic)?;
+
+                match negated {
+                    true => UnaryOp::Not.apply(&between, logic),
+                    false => Ok(between),
+                }
+            }
+            Expr::InList { expr, list, negated } => {
+                let val = expr.eval_helper(row, cols, logic)?;
+                // the result is unknown if no item is equal, but an item might be equal to an unknown value
+                let mut found = BongoLiteral::Bool(false);
+                for item in list {
+                    match BinOp::Eq.apply(&val, &item.eval_helper(row, cols, logic)?, logic)? {
+                        BongoLiteral::Null => found = BongoLiteral::Null,
+                        equal => if equal.as_bool()? {
+                            found = equal;
+                            break;
+                        },
+                    }
+                }
+
+                match negated {
+                    true => UnaryOp::Not.apply(&found, logic),
+                    false => Ok(found),
+                }
+            }
+            Expr::Like { expr, negated, pattern } => {
+                match (expr.eval_helper(row, cols, logic)?, pattern.eval_helper(row, cols, logic)?) {
+                    (BongoLiteral::Null, _) | (_, BongoLiteral::Null) => match logic {
+                        NullLogic::ThreeValued => Ok(BongoLiteral::Null),
+                        // like comparisons with NULL, NULL never matches a pattern
+                        NullLogic::Legacy => Ok(BongoLiteral::Bool(*negated)),
+                    },
+                    (BongoLiteral::Varchar(val), BongoLiteral::Varchar(pattern)) => {
+                        Ok(BongoLiteral::Bool(like_matches(&val, &pattern) != *negated))
+                    }
+                    (val, pattern) => Err(BongoError::SqlRuntimeError(format!(
+                        "Cannot match '{:?}' against the pattern '{:?}'. LIKE can only be applied to VARCHAR values.",
+                        val, pattern
+                    ))),
+                }
+            }
+            Expr::IsNull { expr, negated } => {
+                Ok(BongoLiteral::Bool((expr.eval_helper(row, cols, logic)? == BongoLiteral::Null) != *negated))
+            }
+            Expr::Function { func, args } => {
+                let args = args.iter()
+                    .map(|arg| arg.eval_helper(row, cols, logic))
+                    .collect::<Result<Vec<BongoLiteral>, BongoError>>()?;
+                func.apply(&args)
+            }
+        }
+    }
+
+    ///
+    /// Returns how strongly the expression binds its operands, see `BinOp::precedence`.
+    ///
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::BinaryExpr { op, .. } => op.precedence(),
+            Expr::Unary { op: UnaryOp::Not, .. } => 2,
+            Expr::Between { .. } | Expr::InList { .. } | Expr::Like { .. } | Expr::IsNull { .. } => 3,
+            Expr::Unary { op: UnaryOp::Minus, .. } => 6,
+            Expr::Identifier(_) | Expr::Value(_) | Expr::Aggregate(_) | Expr::Function { .. } => 7,
+        }
+    }
+}
+
+///
+/// Returns true if `val` matches the `pattern` of `LIKE`.
+///
+/// `%` matches any sequence of characters including the empty one and `_` matches exactly one
+/// character. A backslash matches the following character literally, e.g. `\%` matches `%`.
+///
+pub fn like_matches(val: &str, pattern: &str) -> bool {
+    // `None` stands for `%`, `Some(None)` for `_` and `Some(Some(c))` for the character `c`
+    let mut tokens = vec![];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '%' => None,
+            '_' => Some(None),
+            '\\' => Some(Some(chars.next().unwrap_or('\\'))),
+            c => Some(Some(c)),
+        });
+    }
+    let val = val.chars().collect::<Vec<char>>();
+
+    // the last `%` and the position in `val` it has been matched up to, so that it can be
+    // extended by one character if the rest of the pattern does not match
+    let mut backtrack = None;
+    let (mut i, mut j) = (0, 0);
+    while i < val.len() {
+        match tokens.get(j) {
+            Some(None) => {
+                backtrack = Some((j, i));
+                j += 1;
+            }
+            Some(Some(c)) if c.is_none() || *c == Some(val[i]) => {
+                i += 1;
+                j += 1;
+            }
+            _ => match backtrack {
+                Some((percent, matched)) => {
+                    backtrack = Some((percent, matched + 1));
+                    i = matched + 1;
+                    j = percent + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    tokens[j..].iter().all(|token| token.is_none())
+}
+
+///
+/// Formats a literal the way it is written in SQL.
+///
+pub fn fmt_literal(literal: &BongoLiteral) -> String {
+    match literal {
+        BongoLiteral::Int(val) => val.to_string(),
+        BongoLiteral::Bool(val) => val.to_string(),
+        BongoLiteral::Varchar(val) => format!("'{}'", val.replace('\'', "''")),
+        BongoLiteral::Null => "NULL".to_string(),
+        BongoLiteral::Float(val) => format!("{:?}", val),
+        BongoLiteral::Decimal(val) => val.to_string(),
+        BongoLiteral::Date(val) => format!("DATE '{}'", val),
+        BongoLiteral::Time(val) => format!("TIME '{}'", val),
+        BongoLiteral::Timestamp(val) => format!("TIMESTAMP '{}'", val),
+        BongoLiteral::Interval(val) => format!("INTERVAL '{}'", val),
+        BongoLiteral::Blob(val) => format!("X'{}'", val.iter().map(|b| format!("{:02X}", b)).collect::<String>()),
+    }
+}
+
+///
+/// Formats the expression as SQL. Operands that are composed by another logical operator or by an
+/// operator that binds less strongly than their parent are put into parentheses, so that the
+/// formatted expression has the same meaning.
+///
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn operand(expr: &Expr, parent: &BinOp, is_right: bool) -> String {
+            match expr {
+                Expr::BinaryExpr { op: op @ (BinOp::And | BinOp::Or), .. } if op != parent => format!("({})", expr),
+                _ if expr.precedence() < parent.precedence() => format!("({})", expr),
+                // e.g. `a - (b - c)` differs from `a - b - c`
+                _ if is_right && expr.precedence() == parent.precedence()
+                    && !matches!(parent, BinOp::And | BinOp::Or) => format!("({})", expr),
+                _ => expr.to_string(),
+            }
+        }
+        // operands of predicates and of `NOT` are put into parentheses unless they bind more strongly
+        // than comparisons, e.g. `NOT (a = b)`
+        fn predicate_operand(expr: &Expr) -> String {
+            match expr.precedence() > BinOp::Eq.precedence() {
+                true => expr.to_string(),
+                false => format!("({})", expr),
+            }
+        }
+        let not = |negated: &bool| if *negated { "NOT " } else { "" };
+
+        match self {
+            Expr::BinaryExpr { left, op, right } => {
+                write!(f, "{} {} {}", operand(left, op, false), op, operand(right, op, true))
+            }
+            // `--` would start a comment
+            Expr::Unary { op: UnaryOp::Minus, expr } if expr.precedence() < self.precedence() ||
+                matches!(**expr, Expr::Unary { op: UnaryOp::Minus, .. } | Expr::Value(BongoLiteral::Int(i64::MIN..=-1))) => {
+                write!(f, "-({})", expr)
+            }
+            Expr::Unary { op: UnaryOp::Minus, expr } => write!(f, "-{}", expr),
+            Expr::Unary { op: UnaryOp::Not, expr } => write!(f, "NOT {}", predicate_operand(expr)),
+            Expr::Identifier(name) => write!(f, "{}", name),
+            Expr::Value(val) => write!(f, "{}", fmt_literal(val)),
+            Expr::Between { expr, negated, low, high } => {
+                write!(f, "{} {}BETWEEN {} AND {}", predicate_operand(expr), not(negated),
+                       predicate_operand(low), predicate_operand(high))
+            }
+            Expr::InList { expr, list, negated } => {
+                let list = list.iter().map(|item| item.to_string()).collect::<Vec<String>>();
+                write!(f, "{} {}IN ({})", predicate_operand(expr), not(negated), list.join(", "))
+            }
+            Expr::Like { expr, negated, pattern } => {
+                write!(f, "{} {}LIKE {}", predicate_operand(expr), not(negated), predicate_operand(pattern))
+            }
+            Expr::IsNull { expr, negated } => write!(f, "{} IS {}NULL", predicate_operand(expr), not(negated)),
+            Expr::Aggregate(aggregate) => write!(f, "{}", aggregate),
+            Expr::Function { func: ScalarFunc::Now, .. } => write!(f, "NOW()"),
+            Expr::Function { func: ScalarFunc::CurrentDate, .. } => write!(f, "CURRENT_DATE"),
+            Expr::Function { func: ScalarFunc::CurrentTime, .. } => write!(f, "CURRENT_TIME"),
+            Expr::Function { func: ScalarFunc::Extract(field), args } => {
+                let args = args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+                write!(f, "EXTRACT({} FROM {})", field, args.join(", "))
+            }
         }
     }
 }
@@ -391,27 +1407,67 @@ impl TryFrom<SqlParserExpr> for Expr {
 
     fn try_from(expr: SqlParserExpr) -> Result<Self, Self::Error> {
         match expr {
-            SqlParserExpr::Identifier(ident) => Ok(Expr::Identifier(ident.value)),
+            // `CURRENT_DATE` etc. are functions without parentheses, unless they are quoted
+            SqlParserExpr::Identifier(ident) => match ScalarFunc::from_name(&ident.value) {
+                Some(func) if ident.quote_style.is_none() && ident.value.to_uppercase().starts_with("CURRENT_") => {
+                    Ok(Expr::Function { func, args: vec![] })
+                }
+                _ => Ok(Expr::Identifier(ident.value)),
+            },
+            SqlParserExpr::CompoundIdentifier(idents) => {
+                if idents.len() != 2 {
+                    return Err(BongoError::UnsupportedFeatureError(
+                        "Column names can only be qualified by a table name, e.g. table_1.col_1.".to_string()
+                    ));
+                }
+                Ok(Expr::Identifier(format!("{}.{}", idents[0].value, idents[1].value)))
+            }
             SqlParserExpr::Value(value) => {
                 match value {
-                    Value::Number(lit, ..) => match str::parse::<i64>(&lit) {
-                        Ok(val) => Ok(Expr::Value(BongoLiteral::Int(val))),
-                        Err(_) => Err(BongoError::InternalError(
-                            "Failed to parse int value.".to_string(),
-                        )),
+                    // numbers with a decimal point and integers that do not fit into a BIGINT are
+                    // decimals, numbers with an exponent floating point values
+                    Value::Number(lit, ..) => match (str::parse::<i64>(&lit), Decimal::from_str(&lit), str::parse::<f64>(&lit)) {
+                        (Ok(val), ..) => Ok(Expr::Value(BongoLiteral::Int(val))),
+                        (_, Ok(val), _) => Ok(Expr::Value(BongoLiteral::Decimal(val))),
+                        (_, _, Ok(val)) if val.is_finite() => Ok(Expr::Value(BongoLiteral::Float(val))),
+                        _ => Err(BongoError::SqlRuntimeError(format!(
+                            "The number '{}' is out of range.", lit
+                        ))),
                     },
                     Value::SingleQuotedString(lit) | Value::DoubleQuotedString(lit) => Ok(
                         Expr::Value(BongoLiteral::Varchar(lit.clone())),
                     ),
+                    // e.g. `X'00FF'`
+                    Value::HexStringLiteral(lit) => match parse_hex(&lit) {
+                        Some(val) => Ok(Expr::Value(BongoLiteral::Blob(val))),
+                        None => Err(BongoError::SqlSyntaxError(format!(
+                            "'{}' is not a valid hexadecimal string.", lit
+                        ))),
+                    },
                     Value::Boolean(val) => Ok(Expr::Value(BongoLiteral::Bool(val))),
                     Value::Null => Ok(Expr::Value(BongoLiteral::Null)),
+                    Value::Interval { value, leading_field, last_field: None, .. } => {
+                        let interval = match leading_field {
+                            // e.g. `INTERVAL '3' DAY`
+                            Some(field) => format!("{} {}", value, field),
+                            None => value,
+                        };
+                        Ok(Expr::Value(BongoLiteral::Interval(Interval::from_str(&interval)?)))
+                    }
                     _ => Err(BongoError::UnsupportedFeatureError(
-                        "Only integers, single quoted strings \
-                    booleans and NULL values are supported as literals by BongoDB."
+                        "Only numbers, single quoted strings, hexadecimal strings, booleans, NULL values, \
+                    DATE, TIME and TIMESTAMP strings and intervals are supported as literals by BongoDB."
                             .to_string(),
                     )),
                 }
             }
+            SqlParserExpr::BinaryOp { left, op: op @ (BinaryOperator::Like | BinaryOperator::NotLike), right } => {
+                Ok(Expr::Like {
+                    expr: Box::new(Expr::try_from(*left)?),
+                    negated: op == BinaryOperator::NotLike,
+                    pattern: Box::new(Expr::try_from(*right)?),
+                })
+            }
             SqlParserExpr::BinaryOp { left, op, right } => {
                 // Each operand of a binary operation must also itself be a supported expression
                 // and the operation of a binary expression must be convertible.
@@ -422,9 +1478,55 @@ impl TryFrom<SqlParserExpr> for Expr {
                     right: Box::new(Expr::try_from(*right)?),
                 })
             }
+            SqlParserExpr::UnaryOp { op: UnaryOperator::Minus, expr } => match *expr {
+                // negative numbers are literals, which e.g. allows to insert them
+                SqlParserExpr::Value(Value::Number(lit, long)) => {
+                    Expr::try_from(SqlParserExpr::Value(Value::Number(format!("-{lit}"), long)))
+                }
+                expr => Ok(Expr::Unary { op: UnaryOp::Minus, expr: Box::new(Expr::try_from(expr)?) }),
+            },
+            SqlParserExpr::UnaryOp { op: UnaryOperator::Plus, expr } => Expr::try_from(*expr),
+            SqlParserExpr::UnaryOp { op: UnaryOperator::Not, expr } => {
+                Ok(Expr::Unary { op: UnaryOp::Not, expr: Box::new(Expr::try_from(*expr)?) })
+            }
+            SqlParserExpr::InList { expr, list, negated } => {
+                Ok(Expr::InList {
+                    expr: Box::new(Expr::try_from(*expr)?),
+                    list: list.into_iter().map(Expr::try_from).collect::<Result<Vec<Expr>, BongoError>>()?,
+                    negated,
+                })
+            }
+            SqlParserExpr::IsNull(expr) => Ok(Expr::IsNull { expr: Box::new(Expr::try_from(*expr)?), negated: false }),
+            SqlParserExpr::IsNotNull(expr) => Ok(Expr::IsNull { expr: Box::new(Expr::try_from(*expr)?), negated: true }),
+            SqlParserExpr::Nested(expr) => Expr::try_from(*expr),
+            SqlParserExpr::Between { expr, negated, low, high } => {
+                Ok(Expr::Between {
+                    expr: Box::new(Expr::try_from(*expr)?),
+                    negated,
+                    low: Box::new(Expr::try_from(*low)?),
+                    high: Box::new(Expr::try_from(*high)?),
+                })
+            }
+            SqlParserExpr::TypedString { data_type, value } => match data_type {
+                DataType::Date => Ok(Expr::Value(BongoLiteral::Date(Date::from_str(&value)?))),
+                DataType::Time => Ok(Expr::Value(BongoLiteral::Time(Time::from_str(&value)?))),
+                DataType::Timestamp => Ok(Expr::Value(BongoLiteral::Timestamp(Timestamp::from_str(&value)?))),
+                data_type => Err(BongoError::UnsupportedFeatureError(format!(
+                    "Typed strings are only supported for DATE, TIME and TIMESTAMP, not for {}.", data_type
+                ))),
+            },
+            SqlParserExpr::Function(function) => match ScalarFunc::from_name(&function.name.to_string()) {
+                Some(func) if function.args.is_empty() => Ok(Expr::Function { func, args: vec![] }),
+                _ => Ok(Expr::Aggregate(Aggregate::try_from(function)?)),
+            },
+            SqlParserExpr::Extract { field, expr } => Ok(Expr::Function {
+                func: ScalarFunc::Extract(DateField::from(field)),
+                args: vec![Expr::try_from(*expr)?],
+            }),
             _ => Err(BongoError::UnsupportedFeatureError(
-                "Only identifiers, values, and binary \
-            operations are supported as expressions by BongoDB."
+                "Only identifiers, values, unary and binary \
+            operations, BETWEEN, IN, LIKE, IS NULL, aggregate functions and the functions NOW, CURRENT_DATE, \
+            CURRENT_TIME, CURRENT_TIMESTAMP and EXTRACT are supported as expressions by BongoDB."
                     .to_string(),
             )),
         }
@@ -447,27 +1549,157 @@ pub enum Statement {
     Delete(Delete),
     CreateTable(CreateTable),
     DropTable(DropTable),
+    // Changes the columns of a table and rewrites its rows to the new layout
+    AlterTable(AlterTable),
+    CreateIndex(CreateIndex),
+    DropIndex(DropIndex),
+    // Creates a sequence, whose values are generated with nextval('<name>')
+    CreateSequence(CreateSequence),
+    DropSequence(DropSequence),
     // Forces BongoDB to write all information that is currently kept in the cache (RAM) to disk
     Flush,
+    // Removes unused rows (ghosts) from the files of one or all tables
+    Vacuum(Vacuum),
+    // Starts a transaction on the connection the statement has been sent on
+    Begin,
+    // Ends the transaction of the connection and keeps all its changes
+    Commit,
+    // Ends the transaction of the connection and reverts all its changes
+    Rollback,
+    // Describes how a SELECT, UPDATE or DELETE statement is executed
+    Explain(Explain),
     // NOTE: currently not supported, because BongoDB asserts having only exactly one DB
     CreateDB(CreateDB),
     // NOTE: currently not supported, because BongoDB asserts having only exactly one DB
     DropDB(DropDB),
 }
 
+#[derive(Debug, PartialEq)]
+pub struct Vacuum {
+    ///
+    /// The table to compact. `None` means that all tables are compacted.
+    ///
+    pub table: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Explain {
+    ///
+    /// `analyze` is true for `EXPLAIN ANALYZE`, which also executes the explained statement.
+    ///
+    pub analyze: bool,
+    ///
+    /// The explained statement, which is a `Select`, `Update` or `Delete`.
+    ///
+    pub statement: Box<Statement>,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Select {
     pub cols: Vec<SelectItem>,
     pub table: String,
+    ///
+    /// The tables joined to `table` in the order in which they are joined.
+    ///
+    pub joins: Vec<Join>,
     pub condition: Option<Expr>,
-    pub order: Option<Order>,
+    ///
+    /// The columns given by `GROUP BY`. Rows with equal values in all of these columns are
+    /// combined to one row.
+    ///
+    pub group_by: Vec<String>,
+    ///
+    /// The condition given by `HAVING`, which is evaluated for the combined rows.
+    ///
+    pub having: Option<Expr>,
+    ///
+    /// The columns given by `ORDER BY`, which is empty if the rows are not ordered.
+    ///
+    pub order: Vec<Order>,
+    ///
+    /// The maximum amount of rows to return given by `LIMIT`.
+    ///
+    pub limit: Option<usize>,
+    ///
+    /// The amount of rows to skip before rows are returned given by `OFFSET`.
+    ///
+    pub offset: Option<usize>,
+}
+
+impl Select {
+    ///
+    /// Returns the names of all tables the statement accesses in the order in which they are joined.
+    ///
+    pub fn table_names(&self) -> Vec<String> {
+        iter::once(&self.table)
+            .chain(self.joins.iter().map(|join| &join.table))
+            .cloned()
+            .collect()
+    }
+
+    ///
+    /// Returns true if the rows of the statement are combined by `GROUP BY` or aggregate functions.
+    ///
+    pub fn is_aggregation(&self) -> bool {
+        !self.group_by.is_empty() ||
+            self.having.is_some() ||
+            self.cols.iter().any(|item| match item {
+                SelectItem::Aggregate(_) => true,
+                SelectItem::Expr { expr, .. } => expr.contains_aggregate(),
+                _ => false,
+            })
+    }
+
+    ///
+    /// Returns the position among the computed select items of the item named `name` by `AS`.
+    ///
+    pub fn alias_position(&self, name: &str) -> Option<usize> {
+        self.cols.iter()
+            .filter_map(|item| match item {
+                SelectItem::Expr { alias, .. } => Some(alias),
+                _ => None,
+            })
+            .position(|alias| alias.as_deref() == Some(name))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum JoinKind {
+    Inner,
+    ///
+    /// `LEFT [OUTER] JOIN` keeps the rows without a matching row in the joined table and fills the
+    /// columns of the joined table with NULL values.
+    ///
+    Left,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Join {
+    pub kind: JoinKind,
+    pub table: String,
+    ///
+    /// The condition given by `ON`, which can refer to the columns of the joined table and of all
+    /// tables that have been joined before.
+    ///
+    pub condition: Expr,
 }
 
+///
+/// A value of an `INSERT` statement given by `nextval('<sequence>')`: index of the row, index of the
+/// value and name of the sequence.
+///
+pub type NextVal = (usize, usize, String);
+
 #[derive(Debug, PartialEq)]
 pub struct Insert {
     pub table: String,
     pub cols: Vec<String>,
     pub rows: Vec<Row>,
+    ///
+    /// `nextvals` are the values of `rows` given by `nextval('<sequence>')`. They are NULL in
+    /// `rows` until the executor fills in the next values of the sequences.
+    ///
+    pub nextvals: Vec<NextVal>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -487,6 +1719,88 @@ pub struct Delete {
 pub struct CreateTable {
     pub table: String,
     pub cols: Vec<ColumnDef>,
+    ///
+    /// The conditions of the `CHECK` constraints of the columns and of the table. Every row of the
+    /// table has to satisfy them.
+    ///
+    pub checks: Vec<Expr>,
+    ///
+    /// The `PRIMARY KEY` and `UNIQUE` constraints of the columns and of the table.
+    ///
+    pub uniques: Vec<UniqueConstraint>,
+    ///
+    /// The `FOREIGN KEY` constraints of the columns and of the table.
+    ///
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+///
+/// `UniqueConstraint` is a `PRIMARY KEY` or `UNIQUE` constraint on one or more columns of a table.
+/// No two rows of the table may have equal values in all of these columns. Rows with NULL in one of
+/// the columns are not compared to other rows, so a `UNIQUE` column can hold NULL any number of times.
+///
+/// The constraint is backed by a hash index on its first column, which has the name of the
+/// constraint and is used to find the rows that might have the same values.
+///
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UniqueConstraint {
+    pub name: String,
+    pub cols: Vec<String>,
+    ///
+    /// `primary` is true for a `PRIMARY KEY`, whose columns are `NOT NULL` as well.
+    ///
+    pub primary: bool,
+}
+
+impl UniqueConstraint {
+    ///
+    /// Returns the definition of the index that backs this constraint.
+    ///
+    pub fn index_def(&self) -> IndexDef {
+        IndexDef { name: self.name.clone(), col: self.cols[0].clone(), kind: IndexKind::Hash }
+    }
+}
+
+///
+/// `ForeignKey` is a `FOREIGN KEY` constraint of a table. The values of every row in the columns
+/// `cols` have to be the values of a row of the table `table` in the columns `ref_cols`, which
+/// are the columns of a `PRIMARY KEY` or `UNIQUE` constraint of that table. Rows with NULL in
+/// one of `cols` do not reference any row.
+///
+/// `ref_cols` is empty if the statement did not name the referenced columns. They are the columns
+/// of the primary key of `table` then, which the executor fills in when it creates the constraint.
+///
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ForeignKey {
+    pub name: String,
+    pub cols: Vec<String>,
+    pub table: String,
+    pub ref_cols: Vec<String>,
+    ///
+    /// `on_delete` determines what happens to the rows that reference a row which is deleted.
+    ///
+    pub on_delete: ReferentialAction,
+}
+
+///
+/// `ReferentialAction` is the action of a `FOREIGN KEY` constraint for rows that reference a
+/// deleted row.
+///
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ReferentialAction {
+    ///
+    /// The deletion fails. This is also the action for `NO ACTION`, because the constraints are
+    /// checked at the end of each statement anyway.
+    ///
+    Restrict,
+    ///
+    /// The referencing rows are deleted as well.
+    ///
+    Cascade,
+    ///
+    /// The referencing columns of the referencing rows are set to NULL.
+    ///
+    SetNull,
 }
 
 #[derive(Debug, PartialEq)]
@@ -497,6 +1811,76 @@ pub struct DropTable {
     pub names: Vec<String>,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct AlterTable {
+    pub table: String,
+    pub operation: AlterTableOperation,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AlterTableOperation {
+    ///
+    /// Appends the column `col` to the table. Existing rows get the default value of `col` or NULL
+    /// if it has no default. `checks`, `uniques` and `foreign_keys` are the constraints of the new column.
+    ///
+    AddColumn { col: ColumnDef, checks: Vec<Expr>, uniques: Vec<UniqueConstraint>, foreign_keys: Vec<ForeignKey> },
+    ///
+    /// Removes the column `col` and all indexes on it. Dropping a column that does not exist is
+    /// no error if `if_exists` is true.
+    ///
+    DropColumn { col: String, if_exists: bool },
+    RenameColumn { col: String, new_name: String },
+    ///
+    /// Converts the values of the column `col` to `data_type`, see `BongoDataType::convert`.
+    ///
+    AlterColumnType { col: String, data_type: BongoDataType },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CreateIndex {
+    pub name: String,
+    pub table: String,
+    ///
+    /// The indexed column. Indexes on multiple columns are not supported.
+    ///
+    pub col: String,
+    ///
+    /// The data structure given by `USING`, which is `IndexKind::Hash` by default.
+    ///
+    pub kind: IndexKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DropIndex {
+    ///
+    /// multiple indexes could be dropped here
+    ///
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CreateSequence {
+    pub name: String,
+    ///
+    /// The value that the first call of `nextval` returns, which is 1 by default.
+    ///
+    pub start: i64,
+    ///
+    /// The difference between two consecutive values, which is 1 by default. A negative increment
+    /// creates a descending sequence.
+    ///
+    pub increment: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DropSequence {
+    pub name: String,
+    ///
+    /// Dropping a sequence that does not exist is no error if `if_exists` is true.
+    ///
+    pub if_exists: bool,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct CreateDB {
     pub name: String,
@@ -510,96 +1894,302 @@ pub struct DropDB {
 #[cfg(test)]
 mod tests {
     mod bin_op {
+        use std::str::FromStr;
+
+        use bongo_core::datetime::{Date, Interval, Time, Timestamp};
+        use bongo_core::decimal::Decimal;
         use bongo_core::types::BongoLiteral;
-        use crate::statement::BinOp;
+        use crate::statement::{BinOp, NullLogic, UnaryOp};
 
         #[test]
         fn apply_lt() {
             let op = BinOp::Lt;
-            assert_eq!(op.apply(&BongoLiteral::Int(3), &BongoLiteral::Int(5)).unwrap(), BongoLiteral::Bool(true));
-            assert_eq!(op.apply(&BongoLiteral::Int(5), &BongoLiteral::Int(3)).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Int(3), &BongoLiteral::Int(5), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(op.apply(&BongoLiteral::Int(5), &BongoLiteral::Int(3), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
             // equal
-            assert_eq!(op.apply(&BongoLiteral::Int(3), &BongoLiteral::Int(3)).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Int(3), &BongoLiteral::Int(3), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
 
             // alphabetic order
-            assert_eq!(op.apply(&BongoLiteral::Varchar("a".to_string()), &BongoLiteral::Varchar("b".to_string())).unwrap(), BongoLiteral::Bool(true));
-            assert_eq!(op.apply(&BongoLiteral::Varchar("b".to_string()), &BongoLiteral::Varchar("a".to_string())).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Varchar("a".to_string()), &BongoLiteral::Varchar("b".to_string()), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(op.apply(&BongoLiteral::Varchar("b".to_string()), &BongoLiteral::Varchar("a".to_string()), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
             // equal
-            assert_eq!(op.apply(&BongoLiteral::Varchar("a".to_string()), &BongoLiteral::Varchar("a".to_string())).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Varchar("a".to_string()), &BongoLiteral::Varchar("a".to_string()), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
 
-            assert_eq!(op.apply(&BongoLiteral::Bool(false), &BongoLiteral::Bool(true)).unwrap(), BongoLiteral::Bool(true));
-            assert_eq!(op.apply(&BongoLiteral::Bool(true), &BongoLiteral::Bool(false)).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Bool(false), &BongoLiteral::Bool(true), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(op.apply(&BongoLiteral::Bool(true), &BongoLiteral::Bool(false), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
             // equal
-            assert_eq!(op.apply(&BongoLiteral::Bool(true), &BongoLiteral::Bool(true)).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Bool(true), &BongoLiteral::Bool(true), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
 
             // Null shall return false on any comparison unless the other operand is also Null
-            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Null).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Null, NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
 
             // types that are not equal should not be comparable:
-            assert!(op.apply(&BongoLiteral::Int(3), &BongoLiteral::Bool(true)).is_err());
-            assert!(op.apply(&BongoLiteral::Int(3), &BongoLiteral::Varchar("oh no!".to_string())).is_err());
+            assert!(op.apply(&BongoLiteral::Int(3), &BongoLiteral::Bool(true), NullLogic::Legacy).is_err());
+            assert!(op.apply(&BongoLiteral::Int(3), &BongoLiteral::Varchar("oh no!".to_string()), NullLogic::Legacy).is_err());
         }
 
         #[test]
         fn apply_and() {
             let op = BinOp::And;
-            assert_eq!(op.apply(&BongoLiteral::Bool(false), &BongoLiteral::Bool(false)).unwrap(), BongoLiteral::Bool(false));
-            assert_eq!(op.apply(&BongoLiteral::Bool(false), &BongoLiteral::Bool(true)).unwrap(), BongoLiteral::Bool(false));
-            assert_eq!(op.apply(&BongoLiteral::Bool(true), &BongoLiteral::Bool(false)).unwrap(), BongoLiteral::Bool(false));
-            assert_eq!(op.apply(&BongoLiteral::Bool(true), &BongoLiteral::Bool(true)).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(op.apply(&BongoLiteral::Bool(false), &BongoLiteral::Bool(false), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Bool(false), &BongoLiteral::Bool(true), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Bool(true), &BongoLiteral::Bool(false), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Bool(true), &BongoLiteral::Bool(true), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(true));
 
             // null should evaluate to false in logical expressions
-            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Null).unwrap(), BongoLiteral::Bool(false));
-            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Bool(true)).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Null, NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Bool(true), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
 
 
             // other types should not have logical operators
-            assert!(op.apply(&BongoLiteral::Int(1), &BongoLiteral::Int(1)).is_err());
-            assert!(op.apply(&BongoLiteral::Varchar("a".to_string()), &BongoLiteral::Varchar("b".to_string())).is_err());
+            assert!(op.apply(&BongoLiteral::Int(1), &BongoLiteral::Int(1), NullLogic::Legacy).is_err());
+            assert!(op.apply(&BongoLiteral::Varchar("a".to_string()), &BongoLiteral::Varchar("b".to_string()), NullLogic::Legacy).is_err());
         }
 
         #[test]
         fn equality_for_null() {
             let op = BinOp::Eq;
             // comparing null to something else is false
-            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Int(42)).unwrap(), BongoLiteral::Bool(false));
-            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Varchar("something".to_string())).unwrap(), BongoLiteral::Bool(false));
-            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Bool(false)).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Int(42), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Varchar("something".to_string()), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
+            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Bool(false), NullLogic::Legacy).unwrap(), BongoLiteral::Bool(false));
             // comparing null to null is true
-            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Null).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(op.apply(&BongoLiteral::Null, &BongoLiteral::Null, NullLogic::Legacy).unwrap(), BongoLiteral::Bool(true));
+        }
+
+        #[test]
+        fn three_valued_logic() {
+            let logic = NullLogic::ThreeValued;
+            let (t, f, null) = (BongoLiteral::Bool(true), BongoLiteral::Bool(false), BongoLiteral::Null);
+
+            // comparisons with NULL are unknown
+            assert_eq!(BinOp::Eq.apply(&null, &null, logic).unwrap(), null);
+            assert_eq!(BinOp::NotEq.apply(&BongoLiteral::Int(5), &null, logic).unwrap(), null);
+            assert_eq!(BinOp::Lt.apply(&null, &BongoLiteral::Int(5), logic).unwrap(), null);
+            assert_eq!(BinOp::Lt.apply(&BongoLiteral::Int(3), &BongoLiteral::Int(5), logic).unwrap(), t);
+
+            // truth tables of AND and OR
+            assert_eq!(BinOp::And.apply(&null, &f, logic).unwrap(), f);
+            assert_eq!(BinOp::And.apply(&t, &null, logic).unwrap(), null);
+            assert_eq!(BinOp::And.apply(&null, &null, logic).unwrap(), null);
+            assert_eq!(BinOp::Or.apply(&null, &t, logic).unwrap(), t);
+            assert_eq!(BinOp::Or.apply(&f, &null, logic).unwrap(), null);
+            assert_eq!(BinOp::Or.apply(&f, &f, logic).unwrap(), f);
+
+            assert_eq!(UnaryOp::Not.apply(&null, logic).unwrap(), null);
+            assert_eq!(UnaryOp::Not.apply(&null, NullLogic::Legacy).unwrap(), t);
+
+            assert!(BinOp::And.apply(&null, &BongoLiteral::Int(1), logic).is_err());
+            assert!(BinOp::Eq.apply(&BongoLiteral::Int(1), &t, logic).is_err());
+        }
+
+        #[test]
+        fn apply_arithmetic() {
+            assert_eq!(BinOp::Plus.apply(&BongoLiteral::Int(3), &BongoLiteral::Int(5), NullLogic::default()).unwrap(), BongoLiteral::Int(8));
+            assert_eq!(BinOp::Minus.apply(&BongoLiteral::Int(3), &BongoLiteral::Int(5), NullLogic::default()).unwrap(), BongoLiteral::Int(-2));
+            assert_eq!(BinOp::Multiply.apply(&BongoLiteral::Int(3), &BongoLiteral::Int(-5), NullLogic::default()).unwrap(), BongoLiteral::Int(-15));
+            // integer division truncates towards zero
+            assert_eq!(BinOp::Divide.apply(&BongoLiteral::Int(-7), &BongoLiteral::Int(2), NullLogic::default()).unwrap(), BongoLiteral::Int(-3));
+            assert_eq!(BinOp::Modulo.apply(&BongoLiteral::Int(-7), &BongoLiteral::Int(2), NullLogic::default()).unwrap(), BongoLiteral::Int(-1));
+
+            // arithmetic with null results in null
+            assert_eq!(BinOp::Plus.apply(&BongoLiteral::Null, &BongoLiteral::Int(5), NullLogic::default()).unwrap(), BongoLiteral::Null);
+            assert_eq!(BinOp::Divide.apply(&BongoLiteral::Int(5), &BongoLiteral::Null, NullLogic::default()).unwrap(), BongoLiteral::Null);
+
+            assert!(BinOp::Divide.apply(&BongoLiteral::Int(5), &BongoLiteral::Int(0), NullLogic::default()).is_err());
+            assert!(BinOp::Modulo.apply(&BongoLiteral::Int(5), &BongoLiteral::Int(0), NullLogic::default()).is_err());
+            assert!(BinOp::Plus.apply(&BongoLiteral::Int(i64::MAX), &BongoLiteral::Int(1), NullLogic::default()).is_err());
+            assert!(BinOp::Divide.apply(&BongoLiteral::Int(i64::MIN), &BongoLiteral::Int(-1), NullLogic::default()).is_err());
+            assert!(BinOp::Plus.apply(&BongoLiteral::Int(1), &BongoLiteral::Varchar("1".to_string()), NullLogic::default()).is_err());
+        }
+
+        #[test]
+        fn apply_to_floats_and_decimals() {
+            let logic = NullLogic::default();
+            let dec = |s: &str| BongoLiteral::Decimal(Decimal::from_str(s).unwrap());
+
+            // decimals are exact and integers are combined with them to decimals
+            assert_eq!(BinOp::Plus.apply(&dec("0.1"), &dec("0.2"), logic).unwrap(), dec("0.3"));
+            assert_eq!(BinOp::Multiply.apply(&dec("1.5"), &BongoLiteral::Int(3), logic).unwrap(), dec("4.5"));
+            assert_eq!(BinOp::Divide.apply(&BongoLiteral::Int(2), &dec("3.0"), logic).unwrap(), dec("0.6666667"));
+            assert!(BinOp::Modulo.apply(&dec("1.5"), &dec("0.0"), logic).is_err());
+            // floating point values are combined with all numbers to floating point values
+            assert_eq!(BinOp::Minus.apply(&BongoLiteral::Float(0.5), &dec("0.25"), logic).unwrap(), BongoLiteral::Float(0.25));
+            assert_eq!(BinOp::Divide.apply(&BongoLiteral::Int(1), &BongoLiteral::Float(4.0), logic).unwrap(), BongoLiteral::Float(0.25));
+            assert!(BinOp::Divide.apply(&BongoLiteral::Float(1.0), &BongoLiteral::Int(0), logic).is_err());
+            assert!(BinOp::Multiply.apply(&BongoLiteral::Float(1e300), &BongoLiteral::Float(1e300), logic).is_err());
+            assert_eq!(UnaryOp::Minus.apply(&dec("1.5"), logic).unwrap(), dec("-1.5"));
+
+            // numbers are compared by their values
+            assert_eq!(BinOp::Eq.apply(&BongoLiteral::Int(1), &dec("1.00"), logic).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(BinOp::Lt.apply(&dec("0.5"), &BongoLiteral::Float(0.75), logic).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(BinOp::NotEq.apply(&BongoLiteral::Float(2.0), &BongoLiteral::Int(2), NullLogic::Legacy).unwrap(),
+                       BongoLiteral::Bool(false));
+            assert!(BinOp::Eq.apply(&dec("1"), &BongoLiteral::Bool(true), logic).is_err());
+        }
+
+        #[test]
+        fn apply_to_dates_and_times() {
+            let logic = NullLogic::default();
+            let date = |s: &str| BongoLiteral::Date(Date::from_str(s).unwrap());
+            let time = |s: &str| BongoLiteral::Time(Time::from_str(s).unwrap());
+            let timestamp = |s: &str| BongoLiteral::Timestamp(Timestamp::from_str(s).unwrap());
+            let interval = |s: &str| BongoLiteral::Interval(Interval::from_str(s).unwrap());
+
+            assert_eq!(BinOp::Plus.apply(&date("2024-02-28"), &BongoLiteral::Int(2), logic).unwrap(), date("2024-03-01"));
+            assert_eq!(BinOp::Minus.apply(&date("2024-03-01"), &date("2023-03-01"), logic).unwrap(), BongoLiteral::Int(366));
+            assert_eq!(BinOp::Plus.apply(&interval("1 month"), &date("2024-01-31"), logic).unwrap(), timestamp("2024-02-29"));
+            assert_eq!(BinOp::Minus.apply(&timestamp("2024-03-01 01:00"), &interval("2 hours"), logic).unwrap(),
+                       timestamp("2024-02-29 23:00"));
+            assert_eq!(BinOp::Minus.apply(&timestamp("2024-03-01 01:00"), &date("2024-02-28"), logic).unwrap(),
+                       interval("2 days 1 hour"));
+            assert_eq!(BinOp::Plus.apply(&time("23:00"), &interval("1 day 2 hours"), logic).unwrap(), time("01:00"));
+            assert_eq!(BinOp::Minus.apply(&time("08:00"), &time("09:30"), logic).unwrap(), interval("-01:30"));
+            assert_eq!(BinOp::Multiply.apply(&BongoLiteral::Int(3), &interval("1 day 1 hour"), logic).unwrap(),
+                       interval("3 days 3 hours"));
+            assert_eq!(UnaryOp::Minus.apply(&interval("1 day"), logic).unwrap(), interval("-1 day"));
+            assert!(BinOp::Plus.apply(&date("2024-01-01"), &date("2024-01-01"), logic).is_err());
+            assert!(BinOp::Plus.apply(&date("9999-12-31"), &BongoLiteral::Int(1), logic).is_err());
+            assert!(BinOp::Multiply.apply(&time("01:00"), &BongoLiteral::Int(2), logic).is_err());
+            assert_eq!(BinOp::Plus.apply(&date("2024-01-01"), &BongoLiteral::Null, logic).unwrap(), BongoLiteral::Null);
+
+            // dates are compared to timestamps as the start of their days
+            assert_eq!(BinOp::Eq.apply(&date("2024-01-01"), &timestamp("2024-01-01 00:00"), logic).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(BinOp::Lt.apply(&timestamp("2023-12-31 23:59"), &date("2024-01-01"), logic).unwrap(), BongoLiteral::Bool(true));
+            assert_eq!(BinOp::Gt.apply(&interval("1 day"), &interval("23 hours"), logic).unwrap(), BongoLiteral::Bool(true));
+            assert!(BinOp::Eq.apply(&date("2024-01-01"), &time("00:00"), logic).is_err());
         }
     }
 
     mod expr {
+        use std::str::FromStr;
+
+        use bongo_core::datetime::{Date, Timestamp};
+        use bongo_core::decimal::Decimal;
         use bongo_core::types::BongoLiteral;
-        use crate::statement::{BinOp, Expr};
+        use crate::statement::{BinOp, DateField, Expr, like_matches, NullLogic, ScalarFunc, UnaryOp};
+
+        #[test]
+        fn value_and_display_of_functions() {
+            let extract = |field: DateField| Expr::Function {
+                func: ScalarFunc::Extract(field),
+                args: vec![Expr::Identifier("a".to_string())],
+            };
+            let cols = &["a".to_string()];
+            let row = vec![BongoLiteral::Timestamp(Timestamp::from_str("2024-02-29 13:45:30.25").unwrap())];
+            let value = |expr: &Expr, row: &Vec<BongoLiteral>| expr.value(row, cols, NullLogic::default());
+
+            assert_eq!("EXTRACT(YEAR FROM a)", extract(DateField::Year).to_string());
+            assert_eq!(BongoLiteral::Int(2024), value(&extract(DateField::Year), &row).unwrap());
+            assert_eq!(BongoLiteral::Int(29), value(&extract(DateField::Day), &row).unwrap());
+            assert_eq!(BongoLiteral::Int(45), value(&extract(DateField::Minute), &row).unwrap());
+            assert_eq!(BongoLiteral::Decimal(Decimal::from_str("30.250000").unwrap()), value(&extract(DateField::Second), &row).unwrap());
+            assert_eq!(BongoLiteral::Null, value(&extract(DateField::Month), &vec![BongoLiteral::Null]).unwrap());
+            let date = vec![BongoLiteral::Date(Date::from_str("2024-02-29").unwrap())];
+            assert!(value(&extract(DateField::Hour), &date).is_err());
+
+            // NOW() - INTERVAL '1 day' <= CURRENT_DATE
+            let expr = Expr::BinaryExpr {
+                left: Box::new(Expr::BinaryExpr {
+                    left: Box::new(Expr::Function { func: ScalarFunc::Now, args: vec![] }),
+                    op: BinOp::Minus,
+                    right: Box::new(Expr::Value(BongoLiteral::Interval(FromStr::from_str("1 day").unwrap()))),
+                }),
+                op: BinOp::LtEq,
+                right: Box::new(Expr::Function { func: ScalarFunc::CurrentDate, args: vec![] }),
+            };
+            assert_eq!("NOW() - INTERVAL '1 day' <= CURRENT_DATE", expr.to_string());
+            assert_eq!(BongoLiteral::Bool(true), value(&expr, &row).unwrap());
+        }
+
+        #[test]
+        fn like_patterns() {
+            assert!(like_matches("bongo", "bongo"));
+            assert!(like_matches("bongo", "b%"));
+            assert!(like_matches("bongo", "%o%o"));
+            assert!(like_matches("bongo", "_o_g_"));
+            assert!(like_matches("b❤ngo", "b_ngo"));
+            assert!(like_matches("", "%"));
+            assert!(like_matches("50%", "50\\%"));
+            assert!(!like_matches("500", "50\\%"));
+            assert!(!like_matches("bongo", "bong"));
+            assert!(!like_matches("bongo", "%x%"));
+            assert!(!like_matches("bongo", "b_go"));
+            // matching is case sensitive
+            assert!(!like_matches("Bongo", "bongo"));
+        }
+
+        #[test]
+        fn eval_predicates() {
+            let cols = &["a".to_string(), "b".to_string()];
+            let row = &vec![BongoLiteral::Int(2), BongoLiteral::Null];
+            let ident = |name: &str| Box::new(Expr::Identifier(name.to_string()));
+            let list = vec![Expr::Value(BongoLiteral::Int(1)), Expr::Value(BongoLiteral::Int(2))];
+
+            assert!(Expr::InList { expr: ident("a"), list: list.clone(), negated: false }.eval(row, cols, NullLogic::default()).unwrap());
+            assert!(!Expr::InList { expr: ident("a"), list: list.clone(), negated: true }.eval(row, cols, NullLogic::default()).unwrap());
+            assert!(!Expr::InList { expr: ident("b"), list, negated: false }.eval(row, cols, NullLogic::default()).unwrap());
+            assert!(Expr::IsNull { expr: ident("b"), negated: false }.eval(row, cols, NullLogic::default()).unwrap());
+            assert!(Expr::IsNull { expr: ident("a"), negated: true }.eval(row, cols, NullLogic::default()).unwrap());
+            assert!(!Expr::Unary { op: UnaryOp::Not, expr: Box::new(Expr::IsNull { expr: ident("b"), negated: false }) }
+                .eval(row, cols, NullLogic::default()).unwrap());
+            // LIKE can only be applied to strings
+            let pattern = Box::new(Expr::Value(BongoLiteral::Varchar("2%".to_string())));
+            assert!(Expr::Like { expr: ident("a"), negated: false, pattern }.eval(row, cols, NullLogic::default()).is_err());
+        }
+
+        #[test]
+        fn value_and_display_of_arithmetic() {
+            // -(a - (b - 1)) * 2
+            let expr = Expr::BinaryExpr {
+                left: Box::new(Expr::Unary {
+                    op: UnaryOp::Minus,
+                    expr: Box::new(Expr::BinaryExpr {
+                        left: Box::new(Expr::Identifier("a".to_string())),
+                        op: BinOp::Minus,
+                        right: Box::new(Expr::BinaryExpr {
+                            left: Box::new(Expr::Identifier("b".to_string())),
+                            op: BinOp::Minus,
+                            right: Box::new(Expr::Value(BongoLiteral::Int(1))),
+                        }),
+                    }),
+                }),
+                op: BinOp::Multiply,
+                right: Box::new(Expr::Value(BongoLiteral::Int(2))),
+            };
+            let cols = &["a".to_string(), "b".to_string()];
+
+            assert_eq!("-(a - (b - 1)) * 2", expr.to_string());
+            assert_eq!(BongoLiteral::Int(-4), expr.value(&vec![BongoLiteral::Int(5), BongoLiteral::Int(4)], cols, NullLogic::default()).unwrap());
+            assert_eq!(BongoLiteral::Null, expr.value(&vec![BongoLiteral::Int(5), BongoLiteral::Null], cols, NullLogic::default()).unwrap());
+        }
 
         #[test]
         fn eval_err() {
-            assert!(Expr::Value(BongoLiteral::Int(1)).eval(&vec![], &[]).is_err());
-            assert!(Expr::Value(BongoLiteral::Varchar("oh no!".to_string())).eval(&vec![], &[]).is_err());
+            assert!(Expr::Value(BongoLiteral::Int(1)).eval(&vec![], &[], NullLogic::default()).is_err());
+            assert!(Expr::Value(BongoLiteral::Varchar("oh no!".to_string())).eval(&vec![], &[], NullLogic::default()).is_err());
             // literals for row and the definition of columns have different sizes
             assert!(Expr::Value(BongoLiteral::Bool(true)).eval(
                 &vec![BongoLiteral::Bool(true), BongoLiteral::Bool(false)],
-                &["col_1".to_string()]).is_err());
+                &["col_1".to_string()], NullLogic::default()).is_err());
             // identifier is not a column
             assert!(Expr::Identifier("col_1".to_string()).eval(
                 &vec![BongoLiteral::Bool(true)],
-                &["col_2".to_string()]).is_err());
+                &["col_2".to_string()], NullLogic::default()).is_err());
         }
 
         #[test]
         fn eval_simple_valid_expr() {
-            assert!(Expr::Value(BongoLiteral::Bool(true)).eval(&vec![], &[]).unwrap());
-            assert!(!Expr::Value(BongoLiteral::Bool(false)).eval(&vec![], &[]).unwrap());
+            assert!(Expr::Value(BongoLiteral::Bool(true)).eval(&vec![], &[], NullLogic::default()).unwrap());
+            assert!(!Expr::Value(BongoLiteral::Bool(false)).eval(&vec![], &[], NullLogic::default()).unwrap());
             // with some columns given, but those are ignored, because expression does not contains identifier
             assert!(Expr::Value(BongoLiteral::Bool(true)).eval(
                 &vec![BongoLiteral::Bool(true), BongoLiteral::Bool(false)],
-                &["col_1".to_string(), "col_2".to_string()]).unwrap());
+                &["col_1".to_string(), "col_2".to_string()], NullLogic::default()).unwrap());
             // identifier that evaluates to true
             assert!(Expr::Identifier("col_1".to_string()).eval(
                 &vec![BongoLiteral::Bool(true)],
-                &["col_1".to_string()]).unwrap());
+                &["col_1".to_string()], NullLogic::default()).unwrap());
         }
 
         #[test]
@@ -641,9 +2231,9 @@ mod tests {
                 BongoLiteral::Null, // last column should never be evaluated
             ];
 
-            assert!(expr.eval(row_true_1, cols).unwrap());
-            assert!(expr.eval(row_true_2, cols).unwrap());
-            assert!(!expr.eval(row_false, cols).unwrap());
+            assert!(expr.eval(row_true_1, cols, NullLogic::default()).unwrap());
+            assert!(expr.eval(row_true_2, cols, NullLogic::default()).unwrap());
+            assert!(!expr.eval(row_false, cols, NullLogic::default()).unwrap());
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::wal::{WalOp, WalRecord};
+
+///
+/// `TransactionId` identifies a transaction in the write-ahead log.
+///
+pub type TransactionId = u64;
+
+///
+/// `NO_TXN` is never given out as id of a transaction. It is stored as deleting transaction of row
+/// versions that have not been deleted.
+///
+pub const NO_TXN: TransactionId = 0;
+
+///
+/// A `Transaction` is the state of a transaction that has been started with `BEGIN` on a
+/// connection and that has not been finished with `COMMIT` or `ROLLBACK` yet.
+///
+/// The statements of a transaction are applied to the tables immediately. All records the
+/// transaction has written to the write-ahead log are kept, so that the transaction can be undone on
+/// `ROLLBACK` by applying the inverse of all its operations in reverse order.
+///
+#[derive(Debug)]
+pub struct Transaction {
+    pub id: TransactionId,
+    ///
+    /// `records` are all records of the write-ahead log that belong to this transaction in the
+    /// order in which they have been appended.
+    ///
+    pub records: Vec<WalRecord>,
+    ///
+    /// `tables` are the names of all tables that this transaction has modified. Those tables are
+    /// locked for all other connections until the transaction ends.
+    ///
+    pub tables: HashSet<String>,
+}
+
+impl Transaction {
+    pub fn new(id: TransactionId) -> Self {
+        Self { id, records: vec![], tables: HashSet::new() }
+    }
+
+    ///
+    /// Returns the operations that revert all operations of this transaction.
+    ///
+    pub fn undo_ops(&self) -> Vec<WalOp> {
+        self.records.iter()
+            .rev()
+            .flat_map(|record| record.ops.iter().rev())
+            .filter_map(|op| op.inverse())
+            .collect()
+    }
+
+    ///
+    /// Returns all transactions of `records` that have not been ended by a record with `ends_txn`.
+    /// These transactions were still in progress when the write-ahead log was written last.
+    ///
+    pub fn unfinished(records: &[WalRecord]) -> Vec<Transaction> {
+        let mut transactions: HashMap<TransactionId, Transaction> = HashMap::new();
+
+        for record in records {
+            if let Some(id) = record.txn {
+                if record.ends_txn {
+                    transactions.remove(&id);
+                } else {
+                    transactions.entry(id)
+                        .or_insert_with(|| Transaction::new(id))
+                        .records
+                        .push(record.clone());
+                }
+            }
+        }
+
+        let mut unfinished = transactions.into_values().collect::<Vec<Transaction>>();
+        unfinished.sort_by_key(|txn| txn.id);
+
+        unfinished
+    }
+}
+
+///
+/// A `Snapshot` determines which row versions are visible to a statement.
+///
+/// Each row version on disc stores the id of the transaction that created it and of the transaction
+/// that deleted it. A version is visible if its creation is visible and its deletion is not.
+/// Rolled back transactions leave no versions behind, so the changes of a transaction are visible
+/// if the transaction is the own transaction or if it has been committed before the snapshot was taken.
+///
+#[derive(Debug)]
+pub struct Snapshot {
+    ///
+    /// All transactions with an id greater or equal to `bound` have been started after the snapshot was taken.
+    ///
+    pub bound: TransactionId,
+    ///
+    /// `active` are the transactions that were in progress when the snapshot was taken.
+    ///
+    pub active: HashSet<TransactionId>,
+    ///
+    /// `own` is the transaction of the statement the snapshot has been taken for, if any.
+    ///
+    pub own: Option<TransactionId>,
+}
+
+impl Snapshot {
+    ///
+    /// Returns a snapshot that sees the changes of all transactions. This is the view of statements
+    /// that modify a table, because they have exclusive access to it.
+    ///
+    pub fn latest() -> Self {
+        Self { bound: TransactionId::MAX, active: HashSet::new(), own: None }
+    }
+
+    ///
+    /// Returns whether the changes of the transaction `txn` are visible.
+    ///
+    pub fn sees(&self, txn: TransactionId) -> bool {
+        txn != NO_TXN && (self.own == Some(txn) || (txn < self.bound && !self.active.contains(&txn)))
+    }
+
+    ///
+    /// Returns whether a row version that has been created by `created` and deleted by `deleted` is visible.
+    ///
+    pub fn sees_version(&self, created: TransactionId, deleted: TransactionId) -> bool {
+        self.sees(created) && !self.sees(deleted)
+    }
+
+    ///
+    /// Returns the smallest transaction id whose changes might not be visible in this snapshot.
+    /// The changes of all transactions with a smaller id are visible.
+    ///
+    pub fn horizon(&self) -> TransactionId {
+        self.active.iter().fold(self.bound, |horizon, txn| horizon.min(*txn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bongo_core::types::BongoLiteral;
+
+    use std::collections::HashSet;
+
+    use crate::transaction::{NO_TXN, Snapshot, Transaction};
+    use crate::wal::{WalOp, WalRecord};
+
+    fn insert(pos: u64, append: bool) -> WalOp {
+        WalOp::Insert { table: "table_1".to_string(), pos, row: vec![BongoLiteral::Int(pos as i64)], txn: 1, append }
+    }
+
+    #[test]
+    fn undo_ops_are_reversed_inverses() {
+        let delete = WalOp::Delete { table: "table_1".to_string(), pos: 20, row: vec![BongoLiteral::Int(20)], txn: 1 };
+        let mut txn = Transaction::new(1);
+        txn.records.push(WalRecord { lsn: 1, txn: Some(1), ops: vec![insert(0, false), insert(10, true)], ends_txn: false });
+        txn.records.push(WalRecord { lsn: 2, txn: Some(1), ops: vec![delete], ends_txn: false });
+
+        assert_eq!(txn.undo_ops(), vec![
+            WalOp::Restore { table: "table_1".to_string(), pos: 20, row: vec![BongoLiteral::Int(20)] },
+            WalOp::Truncate { table: "table_1".to_string(), pos: 10, row: vec![BongoLiteral::Int(10)] },
+            WalOp::Remove { table: "table_1".to_string(), pos: 0, row: vec![BongoLiteral::Int(0)] },
+        ]);
+    }
+
+    #[test]
+    fn unfinished_transactions() {
+        let records = vec![
+            WalRecord { lsn: 1, txn: Some(1), ops: vec![insert(0, true)], ends_txn: false },
+            WalRecord { lsn: 2, txn: Some(2), ops: vec![insert(10, true)], ends_txn: false },
+            WalRecord { lsn: 3, txn: None, ops: vec![insert(20, true)], ends_txn: false },
+            WalRecord { lsn: 4, txn: Some(1), ops: vec![], ends_txn: true },
+        ];
+
+        let unfinished = Transaction::unfinished(&records);
+
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].id, 2);
+        assert_eq!(unfinished[0].records, vec![records[1].clone()]);
+    }
+
+    #[test]
+    fn snapshot_visibility() {
+        let snapshot = Snapshot { bound: 10, active: HashSet::from([5]), own: Some(7) };
+
+        // committed before the snapshot
+        assert!(snapshot.sees_version(3, NO_TXN));
+        // in progress or started after the snapshot
+        assert!(!snapshot.sees_version(5, NO_TXN));
+        assert!(!snapshot.sees_version(12, NO_TXN));
+        // deleted by a committed, an active and the own transaction
+        assert!(!snapshot.sees_version(3, 4));
+        assert!(snapshot.sees_version(3, 5));
+        assert!(!snapshot.sees_version(3, 7));
+        // the own transaction is seen although it is not committed yet
+        assert!(snapshot.sees_version(7, NO_TXN));
+        assert!(Snapshot::latest().sees_version(12, NO_TXN));
+        assert!(!Snapshot::latest().sees_version(3, 12));
+    }
+
+    #[test]
+    fn snapshot_horizon() {
+        assert_eq!(Snapshot { bound: 10, active: HashSet::from([5, 8]), own: Some(7) }.horizon(), 5);
+        assert_eq!(Snapshot { bound: 10, active: HashSet::new(), own: None }.horizon(), 10);
+    }
+}
@@ -0,0 +1,406 @@
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use bongo_core::types::{BongoError, ColumnDef, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::index::IndexDef;
+use crate::statement::{Expr, ForeignKey, UniqueConstraint};
+use crate::transaction::TransactionId;
+
+///
+/// Name of the file inside the DB root directory that holds the write-ahead log.
+///
+pub const WAL_FILE_NAME: &str = "wal.bongo";
+
+///
+/// Size of the header of a frame in the log file: 4 bytes payload length and 4 bytes crc32 checksum.
+///
+const FRAME_HEADER_SIZE: usize = 8;
+
+///
+/// `WalOp` is a single physical modification of the database.
+///
+/// The operations are physical in the sense that they already contain the exact location on disc
+/// (`pos`) that the `Executor` decided on when executing the statement. This makes replaying them
+/// deterministic: applying the same `WalOp` twice leaves `data.bongo` in the same state.
+///
+/// Rows are versioned: An update deletes the old version of a row and inserts a new version. Row
+/// versions are only physically removed when a transaction is rolled back or when the table is compacted.
+///
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum WalOp {
+    CreateTable { table: String, cols: Vec<ColumnDef>, checks: Vec<Expr>, uniques: Vec<UniqueConstraint>, foreign_keys: Vec<ForeignKey> },
+    DropTable { table: String },
+    CreateIndex { table: String, index: IndexDef },
+    DropIndex { table: String, name: String },
+    // Writes a new version created by `txn`. `append` is true if the version is written behind the
+    // end of data.bongo instead of reusing a ghost
+    Insert { table: String, pos: u64, row: Row, txn: TransactionId, append: bool },
+    // Marks the version at `pos` as deleted by `txn`
+    Delete { table: String, pos: u64, row: Row, txn: TransactionId },
+    // Marks the version at `pos` as not deleted again. Only used to undo deletions.
+    Restore { table: String, pos: u64, row: Row },
+    // Frees the slot of the version at `pos`, which becomes a ghost. Used to undo inserts that reused a ghost and
+    // to reclaim expired versions that no snapshot can see anymore
+    Remove { table: String, pos: u64, row: Row },
+    // Removes the last version of data.bongo at `pos` by cutting off the file. Only used to undo appending inserts.
+    Truncate { table: String, pos: u64, row: Row },
+}
+
+impl WalOp {
+    ///
+    /// Returns the name of the table this operation modifies.
+    ///
+    pub fn table(&self) -> &str {
+        match self {
+            WalOp::CreateTable { table, .. }
+            | WalOp::DropTable { table }
+            | WalOp::CreateIndex { table, .. }
+            | WalOp::DropIndex { table, .. }
+            | WalOp::Insert { table, .. }
+            | WalOp::Delete { table, .. }
+            | WalOp::Restore { table, .. }
+            | WalOp::Remove { table, .. }
+            | WalOp::Truncate { table, .. } => table,
+        }
+    }
+
+    ///
+    /// Returns whether this operation modifies the rows of a table as opposed to the definition of
+    /// a table or of its indexes.
+    ///
+    pub fn is_row_op(&self) -> bool {
+        !matches!(self, WalOp::CreateTable { .. } | WalOp::DropTable { .. } | WalOp::CreateIndex { .. } | WalOp::DropIndex { .. })
+    }
+
+    ///
+    /// Returns the operation that reverts the effects of this row level operation exactly,
+    /// including the ghosts of the table. `None` is returned for operations on whole tables and indexes.
+    ///
+    pub fn inverse(&self) -> Option<WalOp> {
+        match self {
+            WalOp::Insert { table, pos, row, append: true, .. } => {
+                Some(WalOp::Truncate { table: table.clone(), pos: *pos, row: row.clone() })
+            }
+            WalOp::Insert { table, pos, row, append: false, .. } => {
+                Some(WalOp::Remove { table: table.clone(), pos: *pos, row: row.clone() })
+            }
+            WalOp::Delete { table, pos, row, .. } => {
+                Some(WalOp::Restore { table: table.clone(), pos: *pos, row: row.clone() })
+            }
+            WalOp::CreateTable { .. }
+            | WalOp::DropTable { .. }
+            | WalOp::CreateIndex { .. }
+            | WalOp::DropIndex { .. }
+            | WalOp::Restore { .. }
+            | WalOp::Remove { .. }
+            | WalOp::Truncate { .. } => None,
+        }
+    }
+}
+
+///
+/// A `WalRecord` holds all operations of exactly one statement.
+///
+/// Writing all operations of a statement in one record makes statements atomic: A record is either
+/// completely in the log or it is discarded as torn tail when the log is read after a crash.
+///
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct WalRecord {
+    ///
+    /// The log sequence number which is strictly increasing for every appended record.
+    ///
+    pub lsn: u64,
+    ///
+    /// The transaction the operations belong to. `None` if the statement has been executed outside
+    /// of a transaction.
+    ///
+    pub txn: Option<TransactionId>,
+    pub ops: Vec<WalOp>,
+    ///
+    /// `ends_txn` is true for the last record of a transaction, which is written on COMMIT and
+    /// ROLLBACK. A transaction without such a record has to be rolled back after a crash.
+    ///
+    pub ends_txn: bool,
+}
+
+///
+/// `WriteAheadLog` is the per-database log that every mutating statement is appended to before
+/// the `Executor` touches any `data.bongo` file.
+///
+/// Each record is stored as a frame consisting of a 4 byte big endian length, a 4 byte big endian
+/// crc32 checksum of the payload and the bincode serialized `WalRecord` as payload.
+/// The log is truncated on every `FLUSH`, because at that point all meta data reflecting the
+/// logged operations has been written to disc. Only the records of transactions that are still in
+/// progress are kept, as they might have to be rolled back after a crash.
+///
+pub struct WriteAheadLog {
+    path: PathBuf,
+    file: File,
+    next_lsn: u64,
+}
+
+impl WriteAheadLog {
+    ///
+    /// Opens the log inside of `db_root` or creates it if it does not exist yet.
+    ///
+    /// Returns the log together with all records that are completely contained in it. A torn frame
+    /// at the end of the file (a crash during `append`) is cut off.
+    ///
+    pub fn open(db_root: &Path) -> Result<(Self, Vec<WalRecord>), BongoError> {
+        let mut path = db_root.to_path_buf();
+        path.push(WAL_FILE_NAME);
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path);
+        if file.is_err() {
+            return Err(BongoError::WriteFileError("Could not open write-ahead log.".to_string()));
+        }
+        let mut file = file.unwrap();
+
+        let (records, valid_len) = Self::read_records(&mut file)?;
+
+        // cut off a torn tail so that new records are appended directly after the last valid one
+        if file.set_len(valid_len).is_err() || file.seek(SeekFrom::End(0)).is_err() {
+            return Err(BongoError::WriteFileError("Could not repair write-ahead log.".to_string()));
+        }
+
+        let next_lsn = records.last().map_or(1, |r| r.lsn + 1);
+
+        Ok((Self { path, file, next_lsn }, records))
+    }
+
+    fn read_records(file: &mut File) -> Result<(Vec<WalRecord>, u64), BongoError> {
+        if file.seek(SeekFrom::Start(0)).is_err() {
+            return Err(BongoError::ReadFileError("Could not read write-ahead log.".to_string()));
+        }
+
+        let mut bytes = vec![];
+        if BufReader::new(file).read_to_end(&mut bytes).is_err() {
+            return Err(BongoError::ReadFileError("Could not read write-ahead log.".to_string()));
+        }
+
+        let mut records = vec![];
+        let mut offset = 0;
+
+        while offset + FRAME_HEADER_SIZE <= bytes.len() {
+            let len = u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize;
+            let checksum = u32::from_be_bytes(bytes[offset + 4..offset + 8].try_into().unwrap());
+            let start = offset + FRAME_HEADER_SIZE;
+
+            if start + len > bytes.len() || crc32fast::hash(&bytes[start..start + len]) != checksum {
+                break;
+            }
+            match bincode::deserialize::<WalRecord>(&bytes[start..start + len]) {
+                Ok(record) => records.push(record),
+                Err(_) => break,
+            }
+
+            offset = start + len;
+        }
+
+        Ok((records, offset as u64))
+    }
+
+    ///
+    /// Appends the operations of one statement as a new record and forces it to disc.
+    ///
+    /// Returns the appended record, which carries the assigned log sequence number.
+    ///
+    pub fn append(&mut self, txn: Option<TransactionId>, ops: Vec<WalOp>, ends_txn: bool) -> Result<WalRecord, BongoError> {
+        let record = WalRecord { lsn: self.next_lsn, txn, ops, ends_txn };
+
+        let frame = Self::frame(&record)?;
+
+        if self.file.write_all(&frame).is_err() || self.file.sync_data().is_err() {
+            return Err(BongoError::WriteFileError(format!(
+                "Could not append to write-ahead log at '{}'",
+                self.path.to_str().unwrap()
+            )));
+        }
+
+        self.next_lsn += 1;
+
+        Ok(record)
+    }
+
+    fn frame(record: &WalRecord) -> Result<Vec<u8>, BongoError> {
+        let payload = bincode::serialize(record);
+        if payload.is_err() {
+            return Err(BongoError::InternalError("Could not serialize write-ahead log record.".to_string()));
+        }
+        let payload = payload.unwrap();
+
+        let mut frame = Vec::with_capacity(FRAME_HEADER_SIZE + payload.len());
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&crc32fast::hash(&payload).to_be_bytes());
+        frame.extend_from_slice(&payload);
+
+        Ok(frame)
+    }
+
+    ///
+    /// Removes all records except `keep` from the log.
+    ///
+    /// This must only be called after all effects of the logged records are persisted on disc.
+    /// If records are kept, the log is rewritten next to the old log and atomically swapped in.
+    ///
+    pub fn truncate(&mut self, keep: &[&WalRecord]) -> Result<(), BongoError> {
+        if keep.is_empty() {
+            if self.file.set_len(0).is_err()
+                || self.file.seek(SeekFrom::Start(0)).is_err()
+                || self.file.sync_all().is_err() {
+                return Err(BongoError::WriteFileError("Could not truncate write-ahead log.".to_string()));
+            }
+
+            return Ok(());
+        }
+
+        let mut bytes = vec![];
+        for record in keep {
+            bytes.append(&mut Self::frame(record)?);
+        }
+
+        let mut tmp_name = self.path.file_name().unwrap().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = self.path.with_file_name(tmp_name);
+
+        let file = File::create(&tmp_path);
+        if file.is_err() {
+            return Err(BongoError::WriteFileError("Could not truncate write-ahead log.".to_string()));
+        }
+        let mut file = file.unwrap();
+        if file.write_all(&bytes).is_err() || file.sync_all().is_err() || fs::rename(&tmp_path, &self.path).is_err() {
+            return Err(BongoError::WriteFileError("Could not truncate write-ahead log.".to_string()));
+        }
+        let dir = File::open(self.path.parent().unwrap());
+        if dir.is_err() || dir.unwrap().sync_all().is_err() {
+            return Err(BongoError::WriteFileError("Could not truncate write-ahead log.".to_string()));
+        }
+
+        // the renamed file is now the log, so new records have to be appended to it
+        self.file = file;
+
+        Ok(())
+    }
+
+    ///
+    /// Makes sure that the next appended record gets a log sequence number greater than `lsn`.
+    ///
+    /// This is needed after a truncated log has been opened as the numbering would otherwise start
+    /// over although tables on disc already reflect records with higher numbers.
+    ///
+    pub fn advance_past(&mut self, lsn: u64) {
+        self.next_lsn = self.next_lsn.max(lsn + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use bongo_core::types::BongoLiteral;
+
+    use crate::wal::{WalOp, WriteAheadLog, WAL_FILE_NAME};
+
+    fn example_op(pos: u64) -> WalOp {
+        WalOp::Insert { table: "table_1".to_string(), pos, row: vec![BongoLiteral::Int(pos as i64)], txn: 1, append: true }
+    }
+
+    #[test]
+    fn append_and_reopen() {
+        let db_root = PathBuf::from("test_temp/wal_append_and_reopen");
+        fs::create_dir_all(&db_root).unwrap();
+
+        {
+            let (mut wal, records) = WriteAheadLog::open(&db_root).unwrap();
+            assert!(records.is_empty());
+            assert_eq!(wal.append(None, vec![example_op(0)], false).unwrap().lsn, 1);
+            assert_eq!(wal.append(None, vec![example_op(1), example_op(2)], false).unwrap().lsn, 2);
+        }
+
+        let (mut wal, records) = WriteAheadLog::open(&db_root).unwrap();
+        let next = wal.append(None, vec![], false).unwrap().lsn;
+
+        fs::remove_dir_all(&db_root).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].ops, vec![example_op(1), example_op(2)]);
+        assert_eq!(next, 3);
+    }
+
+    #[test]
+    fn torn_tail_is_discarded() {
+        let db_root = PathBuf::from("test_temp/wal_torn_tail_is_discarded");
+        fs::create_dir_all(&db_root).unwrap();
+
+        {
+            let (mut wal, _) = WriteAheadLog::open(&db_root).unwrap();
+            wal.append(None, vec![example_op(0)], false).unwrap();
+        }
+
+        // simulate a crash in the middle of writing the second frame
+        let mut file = OpenOptions::new().append(true).open(db_root.join(WAL_FILE_NAME)).unwrap();
+        file.write_all(&[0, 0, 0, 42, 1, 2]).unwrap();
+        drop(file);
+
+        let (mut wal, records) = WriteAheadLog::open(&db_root).unwrap();
+        wal.append(None, vec![example_op(1)], false).unwrap();
+        let (_, records_after_append) = WriteAheadLog::open(&db_root).unwrap();
+
+        fs::remove_dir_all(&db_root).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records_after_append.len(), 2);
+    }
+
+    #[test]
+    fn truncate() {
+        let db_root = PathBuf::from("test_temp/wal_truncate");
+        fs::create_dir_all(&db_root).unwrap();
+
+        {
+            let (mut wal, _) = WriteAheadLog::open(&db_root).unwrap();
+            wal.append(None, vec![example_op(0)], false).unwrap();
+            wal.truncate(&[]).unwrap();
+        }
+
+        let (_, records) = WriteAheadLog::open(&db_root).unwrap();
+
+        fs::remove_dir_all(&db_root).unwrap();
+
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn truncate_keeping_records() {
+        let db_root = PathBuf::from("test_temp/wal_truncate_keeping_records");
+        fs::create_dir_all(&db_root).unwrap();
+
+        {
+            let (mut wal, _) = WriteAheadLog::open(&db_root).unwrap();
+            wal.append(None, vec![example_op(0)], false).unwrap();
+            let kept = wal.append(Some(1), vec![example_op(1)], false).unwrap();
+            wal.truncate(&[&kept]).unwrap();
+            wal.append(Some(1), vec![], true).unwrap();
+        }
+
+        let (_, records) = WriteAheadLog::open(&db_root).unwrap();
+
+        fs::remove_dir_all(&db_root).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].ops, vec![example_op(1)]);
+        assert!(records[1].ends_txn);
+    }
+}
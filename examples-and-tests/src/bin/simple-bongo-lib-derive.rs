@@ -94,7 +94,7 @@ mod tests {
 
     #[test]
     fn create_table() {
-        let query = "CREATE TABLE Test (id INT, name VARCHAR(255), test BOOLEAN);";
+        let query = "CREATE TABLE Test (id BIGINT PRIMARY KEY, name VARCHAR(255), test BOOLEAN);";
 
         assert_eq!(query, Test::create_table_query());
     }
@@ -1,7 +1,14 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpListener;
 
+///
+/// `ConnectionId` identifies a TCP connection to the `Webserver`. Each accepted connection gets a
+/// new id, which is passed to the callbacks together with everything received on that connection.
+///
+pub type ConnectionId = u64;
+
 ///
 /// A `Webserver` handling tcp connections in an asynchronous multithreaded manner using the tokio library
 ///
@@ -15,6 +22,7 @@ use tokio::net::TcpListener;
 /// the `handle_request` callback. The result of the `handle_request` callback is transmitted the
 /// same way to the client via the TCP-stream. This means the response is evaluated, a header with the
 /// size of the response is assembled, and the header and response together are send over the TCP-stream.
+/// When a connection is closed the `handle_disconnect` callback is called with the id of the connection.
 ///
 pub struct Webserver<Request>
 where
@@ -24,7 +32,9 @@ where
     // as the size of RequestParser and Fn(Request) is unknown at compile time they have to be
     // stored on the heap using Box
     request_parser: Box<dyn RequestParser<Request> + Send + Sync>,
-    handle_request: Box<dyn (Fn(Request) -> String) + Send + Sync>,
+    handle_request: Box<dyn (Fn(ConnectionId, Request) -> String) + Send + Sync>,
+    handle_disconnect: Box<dyn Fn(ConnectionId) + Send + Sync>,
+    next_connection_id: AtomicU64,
 }
 
 // safe to implement, because Webserver only has read access to its fields and therefore no mutable
@@ -57,18 +67,23 @@ impl<Request: 'static + Send> Webserver<Request> {
     /// * `request_parser` - A parser that is used to parse individual `Request`s from a received String.
     /// * `handle_request` - A callback function or closure that is called every time a a request has
     /// been received on the TCP stream and has been parsed into a `Request`. This function
-    /// gets passed the parsed request as an argument.
+    /// gets passed the id of the connection and the parsed request as arguments.
     /// The returned string will be transmitted via the TCP-stream back to the client of this connection.
+    /// * `handle_disconnect` - A callback function or closure that is called with the id of a
+    /// connection after that connection has been closed.
     ///
-    pub fn new<F, P>(address: &str, request_parser: P, handle_request: F) -> Webserver<Request>
+    pub fn new<F, D, P>(address: &str, request_parser: P, handle_request: F, handle_disconnect: D) -> Webserver<Request>
     where
-        F: 'static + (Fn(Request) -> String) + Send + Sync,
+        F: 'static + (Fn(ConnectionId, Request) -> String) + Send + Sync,
+        D: 'static + Fn(ConnectionId) + Send + Sync,
         P: 'static + RequestParser<Request> + Send + Sync,
     {
         Self {
             address: String::from(address),
             request_parser: Box::new(request_parser),
             handle_request: Box::new(handle_request),
+            handle_disconnect: Box::new(handle_disconnect),
+            next_connection_id: AtomicU64::new(0),
         }
     }
 
@@ -107,6 +122,7 @@ impl<Request: 'static + Send> Webserver<Request> {
     ///
     async fn handle_connection(self: Arc<Self>, listener: &TcpListener) -> () {
         let (mut socket, _addr) = listener.accept().await.unwrap();
+        let connection_id = self.next_connection_id.fetch_add(1, Ordering::Relaxed);
 
         tokio::spawn(async move {
             println!("A connection has been opened.");
@@ -130,7 +146,7 @@ impl<Request: 'static + Send> Webserver<Request> {
                                 let response: String;
                                 match self.request_parser.parse(&buffer) {
                                     Some(request) => {
-                                        response = (self.handle_request)(request);
+                                        response = (self.handle_request)(connection_id, request);
                                     }
                                     None => {
                                         response = "Request format could not be parsed, request is ignored.".to_string();
@@ -155,6 +171,8 @@ impl<Request: 'static + Send> Webserver<Request> {
                     }
                 }
             }
+
+            (self.handle_disconnect)(connection_id);
         });
     }
 }
@@ -182,10 +200,11 @@ mod tests {
             Webserver::new(
                 "localhost:8080",        // connect to localhost
                 ExampleRequestParser {}, // parse a string from request
-                |request| -> String {
+                |_connection_id, request| -> String {
                     // just echo the request
                     request
                 },
+                |_connection_id| {},
             )
             .start(),
         );